@@ -10,6 +10,19 @@ QUERY addMemory(memory_id: String, user_id: String, content: String, memory_type
 QUERY getMemory(memory_id: String) =>
   memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
   RETURN memory
+QUERY getIdempotencyRecord(user_id: String, idempotency_key: String) =>
+  record <- N<IdempotencyRecord>::WHERE(AND(_::{user_id}::EQ(user_id), _::{idempotency_key}::EQ(idempotency_key)))::FIRST
+  RETURN record
+QUERY addIdempotencyRecord(user_id: String, idempotency_key: String, result: String, created_at: String) =>
+  record <- AddN<IdempotencyRecord>({ user_id: user_id, idempotency_key: idempotency_key, result: result, created_at: created_at })
+  RETURN record
+QUERY setMemoryArchivedAt(memory_id: String, archived_at: String) =>
+  memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
+  updated <- memory::UPDATE({ archived_at: archived_at })
+  RETURN updated
+QUERY getArchivedMemories(user_id: String) =>
+  memories <- N<Memory>::WHERE(AND(_::{user_id}::EQ(user_id), _::{archived_at}::NEQ("")))
+  RETURN memories
 QUERY getRecentMemories(limit: I64) =>
   memories <- N<Memory>::RANGE(0, limit)
   RETURN memories
@@ -34,12 +47,45 @@ QUERY updateMemory(memory_id: String, content: String, certainty: I64, importanc
 QUERY updateMemoryById(id: ID, content: String, certainty: I64, importance: I64, updated_at: String) =>
   updated <- N<Memory>(id)::UPDATE({ content: content, certainty: certainty, importance: importance, updated_at: updated_at })
   RETURN updated
+QUERY deleteMemory(memory_id: String) =>
+  memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
+  DROP memory::Out<HAS_CHUNK>::Out<CHUNK_HAS_EMBEDDING>
+  DROP memory::Out<HAS_CHUNK>
+  DROP memory::Out<HAS_EMBEDDING>
+  DROP memory
+  RETURN "deleted"
+QUERY deleteMemoryEdges(memory_id: String) =>
+  memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
+  DROP memory::OutE<IMPLIES>
+  DROP memory::InE<IMPLIES>
+  DROP memory::OutE<BECAUSE>
+  DROP memory::InE<BECAUSE>
+  DROP memory::OutE<CONTRADICTS>
+  DROP memory::InE<CONTRADICTS>
+  DROP memory::OutE<MEMORY_RELATION>
+  DROP memory::InE<MEMORY_RELATION>
+  RETURN "deleted"
+QUERY deleteMemoryEntityLinks(memory_id: String) =>
+  memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
+  DROP memory::OutE<EXTRACTED_ENTITY>
+  DROP memory::OutE<MENTIONS>
+  RETURN "deleted"
+QUERY findOrphanedMemoryEmbeddings() =>
+  embeddings <- V<MemoryEmbedding>::WHERE(AND(!EXISTS(_::In<HAS_EMBEDDING>), !EXISTS(_::In<CHUNK_HAS_EMBEDDING>)))
+  RETURN embeddings
+QUERY deleteEmbeddingById(embedding_id: ID) =>
+  DROP V<MemoryEmbedding>(embedding_id)
+  RETURN "deleted"
 QUERY deleteMemoryEmbedding(memory_id: ID) =>
   DROP N<Memory>(memory_id)::Out<HAS_EMBEDDING>
   RETURN "deleted"
 QUERY getMemoryEmbedding(memory_id: ID) =>
   embedding <- N<Memory>(memory_id)::Out<HAS_EMBEDDING>::FIRST
   RETURN embedding
+QUERY getMemoryEmbeddingByMemoryId(memory_id: String) =>
+  memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
+  embedding <- memory::Out<HAS_EMBEDDING>::FIRST
+  RETURN embedding
 QUERY addMemoryRelation(source_id: String, target_id: String, relation_type: String, strength: I64, created_at: String, metadata: String) =>
   source <- N<Memory>::WHERE(_::{memory_id}::EQ(source_id))::FIRST
   target <- N<Memory>::WHERE(_::{memory_id}::EQ(target_id))::FIRST
@@ -64,6 +110,70 @@ QUERY addMemoryContradiction(from_id: String, to_id: String, resolution: String,
   to_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(to_id))::FIRST
   contradiction <- AddE<CONTRADICTS>({ resolution: resolution, resolved: resolved, resolution_strategy: resolution_strategy })::From(from_memory)::To(to_memory)
   RETURN contradiction
+QUERY resolveMemoryContradiction(from_id: String, to_id: String, resolution: String, resolution_strategy: String) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<CONTRADICTS>::WHERE(_::ToN::{memory_id}::EQ(to_id))
+  updated <- edges::UPDATE({ resolution: resolution, resolved: 1, resolution_strategy: resolution_strategy })
+  RETURN updated
+QUERY getMemoryImplication(from_id: String, to_id: String) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<IMPLIES>::WHERE(_::ToN::{memory_id}::EQ(to_id))
+  target <- N<Memory>::WHERE(_::{memory_id}::EQ(to_id))::FIRST
+  RETURN edges, target
+QUERY getMemoryCausation(from_id: String, to_id: String) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<BECAUSE>::WHERE(_::ToN::{memory_id}::EQ(to_id))
+  target <- N<Memory>::WHERE(_::{memory_id}::EQ(to_id))::FIRST
+  RETURN edges, target
+QUERY getMemoryContradiction(from_id: String, to_id: String) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<CONTRADICTS>::WHERE(_::ToN::{memory_id}::EQ(to_id))
+  target <- N<Memory>::WHERE(_::{memory_id}::EQ(to_id))::FIRST
+  RETURN edges, target
+QUERY getReasoningRelation(from_id: String, to_id: String, relation_type: String) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<MEMORY_RELATION>::WHERE(AND(_::{relation_type}::EQ(relation_type), _::ToN::{memory_id}::EQ(to_id)))
+  target <- N<Memory>::WHERE(_::{memory_id}::EQ(to_id))::FIRST
+  RETURN edges, target
+QUERY updateMemoryImplication(from_id: String, to_id: String, strength: I64) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<IMPLIES>::WHERE(_::ToN::{memory_id}::EQ(to_id))
+  updated <- edges::UPDATE({ probability: strength })
+  RETURN updated
+QUERY updateMemoryCausation(from_id: String, to_id: String, strength: I64) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<BECAUSE>::WHERE(_::ToN::{memory_id}::EQ(to_id))
+  updated <- edges::UPDATE({ strength: strength })
+  RETURN updated
+QUERY updateReasoningRelation(from_id: String, to_id: String, relation_type: String, strength: I64) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<MEMORY_RELATION>::WHERE(AND(_::{relation_type}::EQ(relation_type), _::ToN::{memory_id}::EQ(to_id)))
+  updated <- edges::UPDATE({ strength: strength })
+  RETURN updated
+QUERY deleteMemoryImplication(from_id: String, to_id: String) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<IMPLIES>::WHERE(_::ToN::{memory_id}::EQ(to_id))
+  deleted <- edges::COUNT
+  DROP edges
+  RETURN deleted
+QUERY deleteMemoryCausation(from_id: String, to_id: String) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<BECAUSE>::WHERE(_::ToN::{memory_id}::EQ(to_id))
+  deleted <- edges::COUNT
+  DROP edges
+  RETURN deleted
+QUERY deleteMemoryContradiction(from_id: String, to_id: String) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<CONTRADICTS>::WHERE(_::ToN::{memory_id}::EQ(to_id))
+  deleted <- edges::COUNT
+  DROP edges
+  RETURN deleted
+QUERY deleteReasoningRelation(from_id: String, to_id: String, relation_type: String) =>
+  from_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(from_id))::FIRST
+  edges <- from_memory::OutE<MEMORY_RELATION>::WHERE(AND(_::{relation_type}::EQ(relation_type), _::ToN::{memory_id}::EQ(to_id)))
+  deleted <- edges::COUNT
+  DROP edges
+  RETURN deleted
 QUERY addMemorySupersession(new_id: String, old_id: String, reason: String, superseded_at: String, is_contradiction: I64) =>
   new_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(new_id))::FIRST
   old_memory <- N<Memory>::WHERE(_::{memory_id}::EQ(old_id))::FIRST
@@ -125,6 +235,37 @@ QUERY getEntity(entity_id: String) =>
 QUERY getEntityByName(name: String) =>
   entity <- N<Entity>::WHERE(_::{name}::EQ(name))::FIRST
   RETURN entity
+QUERY getEntitiesByName(name: String) =>
+  entities <- N<Entity>::WHERE(_::{name}::EQ(name))
+  RETURN entities
+QUERY getEntityMemories(entity_id: String) =>
+  entity <- N<Entity>::WHERE(_::{entity_id}::EQ(entity_id))::FIRST
+  memories <- entity::In<EXTRACTED_ENTITY>
+  memory_edges <- entity::InE<EXTRACTED_ENTITY>
+  mentioned <- entity::In<MENTIONS>
+  mention_edges <- entity::InE<MENTIONS>
+  RETURN entity, memories, memory_edges, mentioned, mention_edges
+QUERY updateEntityAliases(entity_id: String, aliases: String) =>
+  entity <- N<Entity>::WHERE(_::{entity_id}::EQ(entity_id))::FIRST
+  updated <- entity::UPDATE({ aliases: aliases })
+  RETURN updated
+QUERY addEntityAlias(alias: String, entity_id: String) =>
+  alias_node <- AddN<EntityAlias>({ alias: alias, entity_id: entity_id })
+  RETURN alias_node
+QUERY getEntityAlias(alias: String) =>
+  alias_node <- N<EntityAlias>::WHERE(_::{alias}::EQ(alias))::FIRST
+  RETURN alias_node
+QUERY repointEntityAliases(from_entity_id: String, to_entity_id: String) =>
+  aliases <- N<EntityAlias>::WHERE(_::{entity_id}::EQ(from_entity_id))
+  updated <- aliases::UPDATE({ entity_id: to_entity_id })
+  RETURN updated
+QUERY deleteEntity(entity_id: String) =>
+  entity <- N<Entity>::WHERE(_::{entity_id}::EQ(entity_id))::FIRST
+  DROP entity::InE<EXTRACTED_ENTITY>
+  DROP entity::InE<MENTIONS>
+  DROP entity::Out<ENTITY_HAS_EMBEDDING>
+  DROP entity
+  RETURN "deleted"
 QUERY createEntity(entity_id: String, name: String, entity_type: String, properties: String, aliases: String) =>
   entity <- AddN<Entity>({
     entity_id: entity_id,
@@ -163,7 +304,9 @@ QUERY vectorSearch(query_vector: [F64], user_id: String, limit: I64, min_score:
 QUERY smartVectorSearchWithChunks(query_vector: [F64], limit: I64) =>
   embeddings <- SearchV<MemoryEmbedding>(query_vector, limit)
   memories <- embeddings::In<HAS_EMBEDDING>
-  RETURN memories
+  chunks <- embeddings::In<CHUNK_HAS_EMBEDDING>
+  chunk_parents <- chunks::In<HAS_CHUNK>
+  RETURN memories, chunks, chunk_parents
 QUERY searchSimilarEntities(query_vector: [F64], limit: I64) =>
   embeddings <- SearchV<EntityEmbedding>(query_vector, limit)
   RETURN embeddings
@@ -466,6 +609,20 @@ QUERY getUserMemories(user_id: String, limit: I64) =>
   user <- N<User>::WHERE(_::{user_id}::EQ(user_id))::FIRST
   memories <- user::Out<HAS_MEMORY>::RANGE(0, limit)
   RETURN memories
+QUERY getUserMemoriesBefore(user_id: String, before: String, limit: I64) =>
+  user <- N<User>::WHERE(_::{user_id}::EQ(user_id))::FIRST
+  memories <- user::Out<HAS_MEMORY>::WHERE(AND(_::{created_at}::LT(before), _::{is_deleted}::EQ(0), _::{archived_at}::EQ("")))::ORDER<Desc>(_::{created_at})::RANGE(0, limit)
+  RETURN memories
+QUERY getUserMemoriesAt(user_id: String, created_at: String) =>
+  user <- N<User>::WHERE(_::{user_id}::EQ(user_id))::FIRST
+  memories <- user::Out<HAS_MEMORY>::WHERE(AND(_::{created_at}::EQ(created_at), _::{is_deleted}::EQ(0), _::{archived_at}::EQ("")))
+  RETURN memories
+
+QUERY getUserContradictions(user_id: String, limit: I64) =>
+  user <- N<User>::WHERE(_::{user_id}::EQ(user_id))::FIRST
+  memories <- user::Out<HAS_MEMORY>::RANGE(0, limit)
+  contradictions <- memories::OutE<CONTRADICTS>
+  RETURN memories, contradictions
 
 QUERY getMemoryEntities(memory_id: String) =>
   memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
@@ -479,6 +636,22 @@ QUERY getMemoryConcepts(memory_id: String) =>
   belongs_to <- memory::Out<BELONGS_TO_CATEGORY>
   RETURN instance_of, belongs_to
 
+QUERY getConceptMemories(concept_id: String) =>
+  concept <- N<Concept>::WHERE(_::{concept_id}::EQ(concept_id))::FIRST
+  memories <- concept::In<INSTANCE_OF>
+  RETURN concept, memories
+
+QUERY addMemoryHistoryEvent(event_id: String, memory_id: String, action: String, new_value: String, timestamp: String, actor: String, reason: String, confidence: I64, source_message_preview: String) =>
+  memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
+  event <- AddN<HistoryEvent>({ event_id: event_id, memory_id: memory_id, action: action, old_value: "", new_value: new_value, timestamp: timestamp, actor: actor, reason: reason, confidence: confidence, source_message_preview: source_message_preview })
+  link <- AddE<HAS_HISTORY>::From(memory)::To(event)
+  RETURN event
+
+QUERY getMemoryHistory(memory_id: String) =>
+  memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
+  events <- memory::Out<HAS_HISTORY>
+  RETURN events
+
 QUERY getMemoryReasoningRelations(memory_id: String) =>
   memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
   outgoing <- memory::Out<MEMORY_RELATION>
@@ -495,7 +668,19 @@ QUERY getMemoryLogicalConnections(memory_id: String) =>
   contradicts_in <- memory::In<CONTRADICTS>
   relation_out <- memory::Out<MEMORY_RELATION>
   relation_in <- memory::In<MEMORY_RELATION>
-  RETURN implies_out, implies_in, because_out, because_in, contradicts_out, contradicts_in, relation_out, relation_in
+  implies_out_edges <- memory::OutE<IMPLIES>
+  implies_in_edges <- memory::InE<IMPLIES>
+  because_out_edges <- memory::OutE<BECAUSE>
+  because_in_edges <- memory::InE<BECAUSE>
+  relation_out_edges <- memory::OutE<MEMORY_RELATION>
+  RETURN implies_out, implies_in, because_out, because_in, contradicts_out, contradicts_in, relation_out, relation_in, implies_out_edges, implies_in_edges, because_out_edges, because_in_edges, relation_out_edges
+
+QUERY getMemoryLogicalEdges(memory_id: String) =>
+  memory <- N<Memory>::WHERE(_::{memory_id}::EQ(memory_id))::FIRST
+  implies_out <- memory::OutE<IMPLIES>
+  because_in <- memory::InE<BECAUSE>
+  relation_out <- memory::OutE<MEMORY_RELATION>
+  RETURN implies_out, because_in, relation_out
 
 
 QUERY getMemoryGraphStats(memory_id: String) =>
@@ -534,8 +719,12 @@ QUERY countAllConcepts() =>
 
 QUERY countUserMemories(user_id: String) =>
   user <- N<User>::WHERE(_::{user_id}::EQ(user_id))::FIRST
-  count <- user::Out<HAS_MEMORY>::COUNT
-  RETURN count
+  memories <- user::Out<HAS_MEMORY>::WHERE(_::{is_deleted}::EQ(0))
+  count <- memories::COUNT
+  by_type <- memories::GROUP_BY(memory_type)
+  oldest <- memories::ORDER<Asc>(_::{created_at})::FIRST
+  newest <- memories::ORDER<Desc>(_::{created_at})::FIRST
+  RETURN count, by_type, oldest, newest
 
 QUERY searchByContextTag(tag: String, limit: I64) =>
   memories <- N<Memory>::WHERE(_::{context_tags}::EQ(tag))::RANGE(0, limit)
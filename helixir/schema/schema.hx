@@ -46,7 +46,8 @@ N::Memory {
   metadata: String DEFAULT "{}",
   is_deleted: I64 DEFAULT 0,
   deleted_at: String DEFAULT "",
-  deleted_by: String DEFAULT ""
+  deleted_by: String DEFAULT "",
+  archived_at: String DEFAULT ""
 }
 N::Entity {
   entity_id: String,
@@ -55,6 +56,10 @@ N::Entity {
   properties: String,
   aliases: String
 }
+N::EntityAlias {
+  alias: String,
+  entity_id: String
+}
 N::Concept {
   concept_id: String,
   name: String,
@@ -148,6 +153,12 @@ N::Reasoning {
   confidence: I64,
   created_at: String
 }
+N::IdempotencyRecord {
+  user_id: String,
+  idempotency_key: String,
+  result: String,
+  created_at: String
+}
 N::HistoryEvent {
   event_id: String,
   memory_id: String,
@@ -155,7 +166,10 @@ N::HistoryEvent {
   old_value: String,
   new_value: String,
   timestamp: String,
-  actor: String
+  actor: String,
+  reason: String DEFAULT "",
+  confidence: I64 DEFAULT 0,
+  source_message_preview: String DEFAULT ""
 }
 E::VALID_IN {
   From: Memory,
@@ -239,7 +253,8 @@ E::IMPLIES {
   To: Memory,
   Properties: {
     probability: I64,
-    reasoning_id: String
+    reasoning_id: String,
+    created_at: String DEFAULT "{{timestamp}}"
   }
 }
 E::BECAUSE {
@@ -247,7 +262,8 @@ E::BECAUSE {
   To: Memory,
   Properties: {
     strength: I64,
-    reasoning_id: String
+    reasoning_id: String,
+    created_at: String DEFAULT "{{timestamp}}"
   }
 }
 E::CONTRADICTS {
@@ -12,6 +12,11 @@ pub struct HelixirConfig {
     pub api_key: Option<String>,
     pub timeout: u64,
     pub max_retries: u32,
+    pub keep_alive_secs: Option<u64>,
+    /// Per-call deadlines in seconds; 0 disables the LLM and database ones
+    pub embedding_timeout: u64,
+    pub llm_timeout: u64,
+    pub db_timeout: u64,
 
     
     pub llm_provider: String,
@@ -19,17 +24,26 @@ pub struct HelixirConfig {
     pub llm_api_key: Option<String>,
     pub llm_base_url: Option<String>,
     pub llm_temperature: f32,
+    /// Context window requested from Ollama (`num_ctx`); unset keeps the server's default
+    pub llm_context_window: Option<u32>,
 
     
     pub llm_fallback_enabled: bool,
     pub llm_fallback_url: String,
     pub llm_fallback_model: String,
+    /// Entries in the prompt-keyed LLM response cache; 0 disables it
+    pub llm_cache_size: usize,
+    pub llm_cache_deterministic_only: bool,
+    /// Extraction results to keep for repeated messages; 0 disables. Only used at temperature 0
+    pub extraction_cache_size: usize,
 
     
     pub embedding_provider: String,
     pub embedding_model: String,
     pub embedding_url: String,
     pub embedding_api_key: Option<String>,
+    pub embedding_dimension: Option<usize>,
+    pub embedding_normalize: bool,
 
     
     pub embedding_fallback_enabled: bool,
@@ -46,6 +60,17 @@ pub struct HelixirConfig {
     pub vector_search_enabled: bool,
     pub graph_search_enabled: bool,
     pub bm25_search_enabled: bool,
+    pub lexical_index_path: Option<String>,
+    pub ontology_query_expansion: bool,
+    pub decision_similarity_floor: f64,
+    pub max_candidate_fetch: usize,
+    pub fallback_on_traversal_error: bool,
+    /// Opt-in LLM relevance pass over each search's final results
+    pub search_rerank: bool,
+    /// Reject unrecognized search modes instead of falling back to vector search
+    pub strict_search_modes: bool,
+    /// Half-life for reasoning-edge strength when ranking chains; `None` disables decay
+    pub reasoning_half_life_days: Option<f64>,
 }
 
 impl HelixirConfig {
@@ -58,21 +83,31 @@ impl HelixirConfig {
             api_key: None,
             timeout: 30,
             max_retries: 3,
+            keep_alive_secs: None,
+            embedding_timeout: 30,
+            llm_timeout: 120,
+            db_timeout: 60,
 
             llm_provider: "cerebras".to_string(),
             llm_model: "llama-3.3-70b".to_string(),
             llm_api_key: None,
             llm_base_url: None,
             llm_temperature: 0.3,
+            llm_context_window: None,
 
             llm_fallback_enabled: true,
             llm_fallback_url: "http://localhost:11434".to_string(),
             llm_fallback_model: "llama3.2".to_string(),
+            llm_cache_size: 0,
+            llm_cache_deterministic_only: false,
+            extraction_cache_size: 0,
 
             embedding_provider: "ollama".to_string(),
             embedding_model: "nomic-embed-text".to_string(),
             embedding_url: "http://localhost:11434".to_string(),
             embedding_api_key: None,
+            embedding_dimension: None,
+            embedding_normalize: false,
 
             embedding_fallback_enabled: true,
             embedding_fallback_url: "http://localhost:11434".to_string(),
@@ -86,6 +121,14 @@ impl HelixirConfig {
             vector_search_enabled: true,
             graph_search_enabled: true,
             bm25_search_enabled: true,
+            lexical_index_path: None,
+            ontology_query_expansion: false,
+            decision_similarity_floor: 0.6,
+            max_candidate_fetch: 2_000,
+            fallback_on_traversal_error: true,
+            search_rerank: false,
+            strict_search_modes: false,
+            reasoning_half_life_days: None,
         }
     }
 
@@ -116,6 +159,18 @@ impl HelixirConfig {
         if let Ok(key) = std::env::var("HELIX_LLM_API_KEY") {
             config.llm_api_key = Some(key);
         }
+        if let Some(tokens) = std::env::var("HELIX_LLM_CONTEXT_WINDOW").ok().and_then(|t| t.parse().ok()) {
+            config.llm_context_window = Some(tokens).filter(|&t| t > 0);
+        }
+        if let Some(size) = std::env::var("HELIX_LLM_CACHE_SIZE").ok().and_then(|s| s.parse().ok()) {
+            config.llm_cache_size = size;
+        }
+        if let Ok(only) = std::env::var("HELIX_LLM_CACHE_DETERMINISTIC_ONLY") {
+            config.llm_cache_deterministic_only = matches!(only.to_lowercase().as_str(), "1" | "true" | "yes");
+        }
+        if let Some(size) = std::env::var("HELIX_EXTRACTION_CACHE_SIZE").ok().and_then(|s| s.parse().ok()) {
+            config.extraction_cache_size = size;
+        }
         if let Ok(provider) = std::env::var("HELIX_EMBEDDING_PROVIDER") {
             config.embedding_provider = provider;
         }
@@ -128,6 +183,51 @@ impl HelixirConfig {
         if let Ok(key) = std::env::var("HELIX_EMBEDDING_API_KEY") {
             config.embedding_api_key = Some(key);
         }
+        if let Some(dim) = std::env::var("HELIX_EMBEDDING_DIM").ok().and_then(|d| d.parse().ok()) {
+            config.embedding_dimension = Some(dim);
+        }
+        if let Ok(normalize) = std::env::var("HELIX_EMBEDDING_NORMALIZE") {
+            config.embedding_normalize = matches!(normalize.to_lowercase().as_str(), "1" | "true" | "yes");
+        }
+        if let Some(retries) = std::env::var("HELIX_MAX_RETRIES").ok().and_then(|r| r.parse().ok()) {
+            config.max_retries = retries;
+        }
+        if let Some(secs) = std::env::var("HELIX_EMBEDDING_TIMEOUT").ok().and_then(|s| s.parse().ok()) {
+            config.embedding_timeout = secs;
+        }
+        if let Some(secs) = std::env::var("HELIX_LLM_TIMEOUT").ok().and_then(|s| s.parse().ok()) {
+            config.llm_timeout = secs;
+        }
+        if let Some(secs) = std::env::var("HELIX_DB_TIMEOUT").ok().and_then(|s| s.parse().ok()) {
+            config.db_timeout = secs;
+        }
+        if let Some(secs) = std::env::var("HELIX_KEEP_ALIVE_SECS").ok().and_then(|s| s.parse().ok()) {
+            config.keep_alive_secs = Some(secs).filter(|&s| s > 0);
+        }
+        if let Ok(path) = std::env::var("HELIX_LEXICAL_INDEX_PATH") {
+            config.lexical_index_path = Some(path);
+        }
+        if let Ok(expand) = std::env::var("HELIX_ONTOLOGY_EXPANSION") {
+            config.ontology_query_expansion = matches!(expand.to_lowercase().as_str(), "1" | "true" | "yes");
+        }
+        if let Some(floor) = std::env::var("HELIX_DECISION_SIMILARITY_FLOOR").ok().and_then(|f| f.parse().ok()) {
+            config.decision_similarity_floor = floor;
+        }
+        if let Some(cap) = std::env::var("HELIX_MAX_CANDIDATE_FETCH").ok().and_then(|c| c.parse().ok()) {
+            config.max_candidate_fetch = cap;
+        }
+        if let Ok(fallback) = std::env::var("HELIX_TRAVERSAL_FALLBACK") {
+            config.fallback_on_traversal_error = matches!(fallback.to_lowercase().as_str(), "1" | "true" | "yes");
+        }
+        if let Ok(rerank) = std::env::var("HELIX_SEARCH_RERANK") {
+            config.search_rerank = matches!(rerank.to_lowercase().as_str(), "1" | "true" | "yes");
+        }
+        if let Ok(strict) = std::env::var("HELIX_STRICT_SEARCH_MODES") {
+            config.strict_search_modes = matches!(strict.to_lowercase().as_str(), "1" | "true" | "yes");
+        }
+        if let Some(days) = std::env::var("HELIX_REASONING_HALF_LIFE_DAYS").ok().and_then(|d| d.parse::<f64>().ok()) {
+            config.reasoning_half_life_days = Some(days).filter(|&d| d > 0.0);
+        }
 
         config
     }
@@ -3,16 +3,24 @@
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::core::config::HelixirConfig;
-use crate::db::HelixClient;
+use crate::db::{HelixClient, RetryConfig};
 use crate::llm::EmbeddingGenerator;
-use crate::llm::providers::base::LlmProvider;
+use crate::llm::providers::base::{LlmProvider, ProviderCapabilities};
+use crate::llm::providers::cached::CachedLlmProvider;
+use crate::llm::providers::timeout::TimeoutProvider;
+use crate::llm::providers::usage::TokenUsage;
 use crate::llm::factory::LlmProviderFactory;
-use crate::toolkit::tooling_manager::ToolingManager;
+use crate::toolkit::mind_toolbox::reasoning::{ReasoningRelation, ReasoningType, StrengthDecay};
+use crate::toolkit::mind_toolbox::search::{LlmReranker, SearchEngineConfig};
+use crate::toolkit::tooling_manager::{GraphFormat, MemoryCursor, ToolingManager};
+
+pub use crate::toolkit::tooling_manager::{DeleteFilter, RepairReport, MemoryStats, ConsolidationReport, ConsolidationMerge, MemoryEvent, MemoryEventOp};
 
 
 #[derive(Debug, thiserror::Error)]
@@ -38,10 +46,35 @@ pub enum HelixirClientError {
 pub struct AddMemoryResult {
     pub memories_added: usize,
     pub memory_ids: Vec<String>,
+    pub memories_updated: usize,
+    pub updated_ids: Vec<String>,
+    pub memories_deleted: usize,
+    pub deleted_ids: Vec<String>,
+    pub skipped: usize,
+    pub entities_extracted: usize,
+    pub relations_created: usize,
     pub chunks_created: usize,
     pub stats: HashMap<String, serde_json::Value>,
 }
 
+impl From<crate::toolkit::AddMemoryResult> for AddMemoryResult {
+    fn from(result: crate::toolkit::AddMemoryResult) -> Self {
+        Self {
+            memories_added: result.added.len(),
+            memory_ids: result.added,
+            memories_updated: result.updated.len(),
+            updated_ids: result.updated,
+            memories_deleted: result.deleted.len(),
+            deleted_ids: result.deleted,
+            skipped: result.skipped,
+            entities_extracted: result.entities_extracted,
+            relations_created: result.reasoning_relations_created,
+            chunks_created: result.chunks_created,
+            stats: result.metadata,
+        }
+    }
+}
+
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -61,10 +94,27 @@ pub struct UpdateResult {
 }
 
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkDeleteResult {
+    pub deleted_count: usize,
+    pub deleted_ids: Vec<String>,
+    pub dry_run: bool,
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmInfo {
+    pub provider: String,
+    pub model: String,
+    pub capabilities: ProviderCapabilities,
+}
+
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphResult {
     pub nodes: Vec<GraphNode>,
     pub edges: Vec<GraphEdge>,
+    pub edge_type_counts: HashMap<String, usize>,
 }
 
 
@@ -109,37 +159,95 @@ pub struct GraphEdge {
 }
 
 
+/// Upper bound on each subsystem probe in `HelixirClient::health`.
+const HEALTH_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long `HelixirClient::health` reuses the last LLM probe, so readiness polling
+/// does not hit the provider on every tick.
+const LLM_HEALTH_TTL: Duration = Duration::from_secs(30);
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    /// Serving, but through a fallback path
+    Degraded,
+    Down,
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsystemHealth {
+    pub status: HealthStatus,
+    pub latency_ms: u64,
+    pub detail: Option<String>,
+}
+
+impl SubsystemHealth {
+    fn new(status: HealthStatus, started: Instant, detail: Option<String>) -> Self {
+        Self { status, latency_ms: started.elapsed().as_millis() as u64, detail }
+    }
+}
+
+
+/// Readiness summary; `ready` holds when no subsystem is down.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub ready: bool,
+    pub db: SubsystemHealth,
+    pub embeddings: SubsystemHealth,
+    pub llm: SubsystemHealth,
+    pub ontology: SubsystemHealth,
+}
+
+
 pub struct HelixirClient {
     config: HelixirConfig,
     db: Arc<HelixClient>,
     embedder: Arc<EmbeddingGenerator>,
     llm_provider: Arc<dyn LlmProvider>,
+    // The provider below the response cache, so health probes always reach the backend
+    llm_probe: Arc<dyn LlmProvider>,
+    llm_health: parking_lot::Mutex<Option<(Instant, SubsystemHealth)>>,
     tooling_manager: ToolingManager,
     is_initialized: Arc<AtomicBool>,
+    keep_alive: parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>,
 }
 
 impl HelixirClient {
     
     pub fn new(config: HelixirConfig) -> Result<Self, HelixirClientError> {
         
-        let db = Arc::new(HelixClient::new(&config.host, config.port)
-            .map_err(|e| HelixirClientError::Database(e.to_string()))?);
+        let retry = RetryConfig { max_retries: config.max_retries, ..RetryConfig::default() };
+        let mut db = HelixClient::new(&config.host, config.port)
+            .map_err(|e| HelixirClientError::Database(e.to_string()))?
+            .with_retry_config(retry);
+        if config.db_timeout > 0 {
+            db = db.with_query_timeout(Duration::from_secs(config.db_timeout));
+        }
+        let db = Arc::new(db);
 
         
         let is_openai_compat = config.embedding_provider == "openai";
-        let embedder = Arc::new(EmbeddingGenerator::new(
+        let mut embedder = EmbeddingGenerator::new(
             config.embedding_provider.clone(),
             if is_openai_compat { "http://localhost:11434".to_string() } else { config.embedding_url.clone() },
             config.embedding_model.clone(),
             config.embedding_api_key.clone(),
             if is_openai_compat { Some(config.embedding_url.clone()) } else { None },
-            config.timeout,
+            config.embedding_timeout,
             1000,
             300,
             config.embedding_fallback_enabled,
             Some(config.embedding_fallback_url.clone()),
             Some(config.embedding_fallback_model.clone()),
-        ));
+        )
+        .with_normalization(config.embedding_normalize);
+        if let Some(dim) = config.embedding_dimension {
+            embedder = embedder.with_expected_dim(dim);
+        }
+        let embedder = Arc::new(embedder);
 
         
         let llm_provider: Arc<dyn LlmProvider> = LlmProviderFactory::create(
@@ -148,14 +256,46 @@ impl HelixirClient {
             config.llm_api_key.as_deref(),
             config.llm_base_url.as_deref(),
             f64::from(config.llm_temperature),
+            config.llm_context_window,
         ).into();
+        let llm_provider: Arc<dyn LlmProvider> = if config.llm_timeout > 0 {
+            Arc::new(TimeoutProvider::new(llm_provider, Duration::from_secs(config.llm_timeout)))
+        } else {
+            llm_provider
+        };
+        let llm_probe = Arc::clone(&llm_provider);
+        let llm_provider: Arc<dyn LlmProvider> = if config.llm_cache_size > 0 {
+            Arc::new(
+                CachedLlmProvider::new(llm_provider, config.llm_cache_size, crate::DEFAULT_CACHE_TTL)
+                    .with_deterministic_only(config.llm_cache_deterministic_only),
+            )
+        } else {
+            llm_provider
+        };
 
         
-        let tooling_manager = ToolingManager::new(
+        let mut tooling_manager = ToolingManager::new(
             Arc::clone(&db),
             Arc::clone(&embedder),
             Arc::clone(&llm_provider),
         );
+        if let Some(path) = &config.lexical_index_path {
+            tooling_manager = tooling_manager.with_lexical_index_path(path);
+        }
+        tooling_manager = tooling_manager.with_search_config(SearchEngineConfig {
+            ontology_expansion: config.ontology_query_expansion,
+            decision_similarity_floor: config.decision_similarity_floor,
+            max_candidate_fetch: config.max_candidate_fetch,
+            fallback_on_traversal_error: config.fallback_on_traversal_error,
+            strict_modes: config.strict_search_modes,
+            ..SearchEngineConfig::default()
+        }).with_strength_decay(StrengthDecay { half_life_days: config.reasoning_half_life_days });
+        if config.extraction_cache_size > 0 {
+            tooling_manager = tooling_manager.with_extraction_cache(config.extraction_cache_size, crate::DEFAULT_CACHE_TTL);
+        }
+        if config.search_rerank {
+            tooling_manager = tooling_manager.with_reranker(Arc::new(LlmReranker::new(Arc::clone(&llm_provider))));
+        }
 
         info!("HelixirClient created with ToolingManager");
 
@@ -164,8 +304,11 @@ impl HelixirClient {
             db,
             embedder,
             llm_provider,
+            llm_probe,
+            llm_health: parking_lot::Mutex::new(None),
             tooling_manager,
             is_initialized: Arc::new(AtomicBool::new(false)),
+            keep_alive: parking_lot::Mutex::new(None),
         })
     }
 
@@ -184,6 +327,15 @@ impl HelixirClient {
         
         self.db.health_check().await
             .map_err(|e| HelixirClientError::Database(e.to_string()))?;
+        self.db.connect().await
+            .map_err(|e| HelixirClientError::Database(e.to_string()))?;
+
+        if let Some(secs) = self.config.keep_alive_secs {
+            let mut keep_alive = self.keep_alive.lock();
+            if keep_alive.is_none() {
+                *keep_alive = Some(self.db.spawn_keep_alive(Duration::from_secs(secs)));
+            }
+        }
 
         
         self.tooling_manager.initialize().await
@@ -200,11 +352,14 @@ impl HelixirClient {
         user_id: &str,
         agent_id: Option<&str>,
         metadata: Option<HashMap<String, serde_json::Value>>,
+        idempotency_key: Option<&str>,
+        chunk_threshold: Option<usize>,
     ) -> Result<AddMemoryResult, HelixirClientError> {
-        self.add_with_tags(message, user_id, agent_id, metadata, None).await
+        self.add_with_tags(message, user_id, agent_id, metadata, None, idempotency_key, chunk_threshold).await
     }
 
     /// Add memory with optional context tags that are inherited by all extracted facts
+    #[allow(clippy::too_many_arguments)]
     pub async fn add_with_tags(
         &self,
         message: &str,
@@ -212,23 +367,37 @@ impl HelixirClient {
         agent_id: Option<&str>,
         metadata: Option<HashMap<String, serde_json::Value>>,
         context_tags: Option<&str>,
+        idempotency_key: Option<&str>,
+        chunk_threshold: Option<usize>,
     ) -> Result<AddMemoryResult, HelixirClientError> {
         self.ensure_initialized().await?;
 
         let result = self.tooling_manager
-            .add_memory(message, user_id, agent_id, metadata, context_tags)
+            .add_memory(message, user_id, agent_id, metadata, context_tags, idempotency_key, chunk_threshold)
             .await
             .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
 
-        Ok(AddMemoryResult {
-            memories_added: result.added.len(),
-            memory_ids: result.added,
-            chunks_created: result.chunks_created,
-            stats: result.metadata,
-        })
+        Ok(result.into())
+    }
+
+    /// Add several messages at once; results are returned in input order
+    pub async fn add_batch(
+        &self,
+        messages: &[(&str, &str)],
+        context_tags: Option<&str>,
+    ) -> Result<Vec<AddMemoryResult>, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        let results = self.tooling_manager
+            .add_memories_batch(messages, context_tags)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
+
+        Ok(results.into_iter().map(Into::into).collect())
     }
 
     
+    #[allow(clippy::too_many_arguments)]
     pub async fn search(
         &self,
         query: &str,
@@ -237,12 +406,96 @@ impl HelixirClient {
         search_mode: Option<&str>,
         temporal_days: Option<f64>,
         graph_depth: Option<usize>,
+        include_archived: bool,
     ) -> Result<Vec<SearchResult>, HelixirClientError> {
         self.ensure_initialized().await?;
 
         let mode = search_mode.unwrap_or(&self.config.default_search_mode);
         let results = self.tooling_manager
-            .search_memory(query, user_id, limit, mode, temporal_days, graph_depth)
+            .search_memory(query, user_id, limit, mode, temporal_days, graph_depth, include_archived)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
+
+        Ok(results
+            .into_iter()
+            .map(|r| SearchResult {
+                id: r.memory_id,
+                content: r.content,
+                score: r.score as f32,
+                metadata: r.metadata,
+                created_at: r.created_at,
+            })
+            .collect())
+    }
+
+    /// List a user's memories newest first, skipping the newest `offset`; offsets shift as
+    /// memories are added, so use `list_page` where pages must stay stable
+    pub async fn list(
+        &self,
+        user_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<SearchResult>, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        let results = self.tooling_manager
+            .list_memories(user_id, limit, offset)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
+
+        Ok(results
+            .into_iter()
+            .map(|r| SearchResult {
+                id: r.memory_id,
+                content: r.content,
+                score: r.score as f32,
+                metadata: r.metadata,
+                created_at: r.created_at,
+            })
+            .collect())
+    }
+
+    /// List a user's memories newest first; pass the returned cursor back as `before` for the
+    /// next page, `None` marks the last one
+    pub async fn list_page(
+        &self,
+        user_id: &str,
+        limit: usize,
+        before: Option<&MemoryCursor>,
+    ) -> Result<(Vec<SearchResult>, Option<MemoryCursor>), HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        let (results, next) = self.tooling_manager
+            .list_memories_page(user_id, limit, before)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
+
+        let page = results
+            .into_iter()
+            .map(|r| SearchResult {
+                id: r.memory_id,
+                content: r.content,
+                score: r.score as f32,
+                metadata: r.metadata,
+                created_at: r.created_at,
+            })
+            .collect();
+        Ok((page, next))
+    }
+
+    
+    pub async fn search_multimode(
+        &self,
+        query: &str,
+        user_id: &str,
+        modes: &[&str],
+        weights: &[f64],
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchResult>, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        let results = self.tooling_manager
+            .search_multimode(query, user_id, modes, weights, limit.unwrap_or(self.config.default_search_limit))
             .await
             .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
 
@@ -290,37 +543,193 @@ impl HelixirClient {
     }
 
     
+    pub async fn archive(&self, memory_id: &str) -> Result<bool, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .archive_memory(memory_id)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    
+    pub async fn restore(&self, memory_id: &str) -> Result<bool, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .restore_memory(memory_id)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    
+    pub async fn list_archived(&self, user_id: &str) -> Result<Vec<SearchResult>, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        let results = self.tooling_manager
+            .list_archived(user_id)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
+
+        Ok(results
+            .into_iter()
+            .map(|r| SearchResult {
+                id: r.memory_id,
+                content: r.content,
+                score: r.score as f32,
+                metadata: r.metadata,
+                created_at: r.created_at,
+            })
+            .collect())
+    }
+
+    
+    pub async fn delete_where(
+        &self,
+        user_id: &str,
+        filter: DeleteFilter,
+    ) -> Result<BulkDeleteResult, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        let deleted_ids = self.tooling_manager
+            .delete_where(user_id, &filter)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
+
+        Ok(BulkDeleteResult {
+            deleted_count: deleted_ids.len(),
+            deleted_ids,
+            dry_run: filter.dry_run,
+        })
+    }
+
+    
+    pub async fn history(&self, memory_id: &str) -> Result<Vec<MemoryEvent>, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .get_memory_history(memory_id)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    
+    pub async fn repair(&self, user_id: &str, dry_run: bool) -> Result<RepairReport, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .repair(user_id, dry_run)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    /// Operator-only: removes embeddings with no memory or chunk parent across all users.
+    pub async fn purge_orphaned_embeddings(&self, dry_run: bool) -> Result<usize, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .purge_orphaned_embeddings(dry_run)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    /// Records that `from_id` IMPLIES, BECAUSE, CONTRADICTS or SUPPORTS `to_id`; unknown
+    /// relation types are rejected before anything is written.
+    pub async fn relate(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        relation_type: &str,
+        strength: Option<i32>,
+    ) -> Result<ReasoningRelation, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .relate(from_id, to_id, relation_type, strength)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    
+    pub async fn unrelate(&self, from_id: &str, to_id: &str, relation_type: &str) -> Result<bool, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .unrelate(from_id, to_id, relation_type)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    /// Merges near-duplicate memories; see `ToolingManager::consolidate`.
+    pub async fn consolidate(
+        &self,
+        user_id: &str,
+        similarity_threshold: f64,
+        dry_run: bool,
+    ) -> Result<ConsolidationReport, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .consolidate(user_id, similarity_threshold, dry_run)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    /// Memory totals per type for `user_id`, without fetching the memories themselves.
+    pub async fn memory_stats(&self, user_id: &str) -> Result<MemoryStats, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .memory_stats(user_id)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    
+    pub async fn export_graph(&self, user_id: &str, format: GraphFormat) -> Result<String, HelixirClientError> {
+        self.ensure_initialized().await?;
+
+        self.tooling_manager
+            .export_graph(user_id, format)
+            .await
+            .map_err(|e| HelixirClientError::Tooling(e.to_string()))
+    }
+
+    
     pub async fn get_graph(
         &self,
         user_id: &str,
         memory_id: Option<&str>,
         depth: Option<usize>,
+        max_seeds: Option<usize>,
     ) -> Result<GraphResult, HelixirClientError> {
         self.ensure_initialized().await?;
 
-        let (nodes, edges) = self.tooling_manager
-            .get_memory_graph(user_id, memory_id, depth.unwrap_or(2))
+        let graph = self.tooling_manager
+            .get_memory_graph(user_id, memory_id, depth.unwrap_or(2), max_seeds)
             .await
             .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
 
         
         Ok(GraphResult {
-            nodes: nodes.into_iter().map(|n| GraphNode {
+            nodes: graph.nodes.into_iter().map(|n| GraphNode {
                 id: n.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
                 content: n.get("content").and_then(|v| v.as_str()).unwrap_or("").to_string(),
                 node_type: n.get("type").and_then(|v| v.as_str()).unwrap_or("memory").to_string(),
                 metadata: HashMap::new(),
             }).collect(),
-            edges: edges.into_iter().map(|e| GraphEdge {
+            edges: graph.edges.into_iter().map(|e| GraphEdge {
                 source: e.get("source").and_then(|v| v.as_str()).unwrap_or("").to_string(),
                 target: e.get("target").and_then(|v| v.as_str()).unwrap_or("").to_string(),
                 edge_type: e.get("type").and_then(|v| v.as_str()).unwrap_or("").to_string(),
                 weight: e.get("weight").and_then(|v| v.as_f64()).unwrap_or(1.0) as f32,
             }).collect(),
+            edge_type_counts: graph.edge_type_counts,
         })
     }
 
     
+    #[allow(clippy::too_many_arguments)]
     pub async fn search_by_concept(
         &self,
         query: &str,
@@ -329,11 +738,20 @@ impl HelixirClient {
         tags: Option<&str>,
         mode: Option<&str>,
         limit: Option<usize>,
+        include_subconcepts: bool,
     ) -> Result<Vec<SearchResult>, HelixirClientError> {
         self.ensure_initialized().await?;
 
         let results = self.tooling_manager
-            .search_by_concept(query, user_id, concept_type, tags, mode.unwrap_or("contextual"), limit.unwrap_or(10))
+            .search_by_concept(
+                query,
+                user_id,
+                concept_type,
+                tags,
+                mode.unwrap_or("contextual"),
+                limit.unwrap_or(10),
+                include_subconcepts,
+            )
             .await
             .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
 
@@ -355,11 +773,12 @@ impl HelixirClient {
         chain_mode: Option<&str>,
         max_depth: Option<usize>,
         limit: Option<usize>,
+        edge_types: Option<&[ReasoningType]>,
     ) -> Result<ReasoningChainResult, HelixirClientError> {
         self.ensure_initialized().await?;
 
         let result = self.tooling_manager
-            .search_reasoning_chain(query, user_id, chain_mode.unwrap_or("both"), max_depth.unwrap_or(5), limit.unwrap_or(5))
+            .search_reasoning_chain(query, user_id, chain_mode.unwrap_or("both"), max_depth.unwrap_or(5), limit.unwrap_or(5), edge_types)
             .await
             .map_err(|e| HelixirClientError::Tooling(e.to_string()))?;
 
@@ -390,6 +809,76 @@ impl HelixirClient {
         })
     }
 
+    /// Probes every subsystem concurrently without initializing or otherwise changing
+    /// client state. Unreachable subsystems are reported as down rather than as errors.
+    pub async fn health(&self) -> HealthReport {
+        let (db, embeddings, llm) = tokio::join!(self.probe_db(), self.probe_embeddings(), self.probe_llm());
+
+        let started = Instant::now();
+        let ontology = if self.tooling_manager.ontology_loaded() {
+            SubsystemHealth::new(HealthStatus::Up, started, None)
+        } else {
+            SubsystemHealth::new(HealthStatus::Down, started, Some("ontology not loaded".to_string()))
+        };
+
+        let ready = [&db, &embeddings, &llm, &ontology].iter().all(|s| s.status != HealthStatus::Down);
+        if !ready {
+            warn!(
+                "Helixir not ready: db={:?} embeddings={:?} llm={:?} ontology={:?}",
+                db.status, embeddings.status, llm.status, ontology.status
+            );
+        }
+        HealthReport { ready, db, embeddings, llm, ontology }
+    }
+
+    async fn probe_db(&self) -> SubsystemHealth {
+        let started = Instant::now();
+        match tokio::time::timeout(HEALTH_PROBE_TIMEOUT, self.db.health_check()).await {
+            Ok(Ok(())) => SubsystemHealth::new(HealthStatus::Up, started, None),
+            Ok(Err(e)) => SubsystemHealth::new(HealthStatus::Down, started, Some(e.to_string())),
+            Err(_) => SubsystemHealth::new(HealthStatus::Down, started, Some("probe timed out".to_string())),
+        }
+    }
+
+    async fn probe_embeddings(&self) -> SubsystemHealth {
+        let started = Instant::now();
+        let Ok(health) = tokio::time::timeout(HEALTH_PROBE_TIMEOUT, self.embedder.health_check()).await else {
+            return SubsystemHealth::new(HealthStatus::Down, started, Some("probe timed out".to_string()));
+        };
+
+        let fallback_ok = health.fallback.as_ref().is_some_and(|f| f.ok);
+        if health.primary.ok && !health.dimension_mismatch {
+            SubsystemHealth::new(HealthStatus::Up, started, None)
+        } else if health.primary.ok || fallback_ok {
+            let detail = if health.dimension_mismatch {
+                "fallback dimension does not match primary".to_string()
+            } else {
+                format!("primary down: {}", health.primary.error.unwrap_or_default())
+            };
+            SubsystemHealth::new(HealthStatus::Degraded, started, Some(detail))
+        } else {
+            SubsystemHealth::new(HealthStatus::Down, started, health.primary.error)
+        }
+    }
+
+    /// A reachability check rather than a completion, reused for `LLM_HEALTH_TTL`.
+    async fn probe_llm(&self) -> SubsystemHealth {
+        if let Some((probed_at, health)) = self.llm_health.lock().as_ref() {
+            if probed_at.elapsed() < LLM_HEALTH_TTL {
+                return health.clone();
+            }
+        }
+
+        let started = Instant::now();
+        let health = match tokio::time::timeout(HEALTH_PROBE_TIMEOUT, self.llm_probe.health_check()).await {
+            Ok(Ok(())) => SubsystemHealth::new(HealthStatus::Up, started, None),
+            Ok(Err(e)) => SubsystemHealth::new(HealthStatus::Down, started, Some(e.to_string())),
+            Err(_) => SubsystemHealth::new(HealthStatus::Down, started, Some("probe timed out".to_string())),
+        };
+        *self.llm_health.lock() = Some((started, health.clone()));
+        health
+    }
+
     
     pub async fn close(&self) -> Result<(), HelixirClientError> {
         if !self.is_initialized.load(Ordering::Relaxed) {
@@ -397,6 +886,9 @@ impl HelixirClient {
         }
 
         self.is_initialized.store(false, Ordering::Relaxed);
+        if let Err(e) = self.tooling_manager.flush_lexical_index().await {
+            warn!("Failed to flush lexical index on close: {}", e);
+        }
         Ok(())
     }
 
@@ -429,6 +921,20 @@ impl HelixirClient {
     }
 
     
+    pub fn llm_info(&self) -> LlmInfo {
+        LlmInfo {
+            provider: self.llm_provider.provider_name().to_string(),
+            model: self.llm_provider.model_name().to_string(),
+            capabilities: self.llm_provider.capabilities(),
+        }
+    }
+
+    
+    pub fn llm_usage(&self) -> TokenUsage {
+        self.tooling_manager.llm_usage()
+    }
+
+    
     pub fn tooling(&self) -> &ToolingManager {
         &self.tooling_manager
     }
@@ -439,6 +945,9 @@ impl Drop for HelixirClient {
         if self.is_initialized.load(Ordering::Relaxed) {
             self.is_initialized.store(false, Ordering::Relaxed);
         }
+        if let Some(handle) = self.keep_alive.lock().take() {
+            handle.abort();
+        }
     }
 }
 
@@ -455,8 +964,10 @@ mod tests {
 
     #[test]
     fn test_client_from_env() {
-        std::env::set_var("HELIX_HOST", "localhost");
-        std::env::set_var("HELIX_PORT", "6969");
+        unsafe {
+            std::env::set_var("HELIX_HOST", "localhost");
+            std::env::set_var("HELIX_PORT", "6969");
+        }
         let client = HelixirClient::from_env();
         assert!(client.is_ok());
     }
@@ -469,4 +980,74 @@ mod tests {
         assert_eq!(client.config().host, "localhost");
         assert_eq!(client.config().port, 6969);
     }
+
+    #[test]
+    fn test_llm_info() {
+        let client = HelixirClient::new(HelixirConfig::default()).unwrap();
+        let info = client.llm_info();
+
+        assert_eq!(info.provider, "cerebras");
+        assert_eq!(info.model, "llama-3.3-70b");
+        assert!(info.capabilities.supports_json_mode);
+    }
+
+    #[test]
+    fn test_add_result_surfaces_updates() {
+        let tooling_result = crate::toolkit::AddMemoryResult {
+            added: vec![],
+            updated: vec!["mem_existing".to_string()],
+            deleted: vec![],
+            skipped: 1,
+            entities_extracted: 2,
+            reasoning_relations_created: 3,
+            chunks_created: 0,
+            metadata: HashMap::new(),
+        };
+
+        let result = AddMemoryResult::from(tooling_result);
+        assert_eq!(result.memories_added, 0);
+        assert_eq!(result.memories_updated, 1);
+        assert_eq!(result.updated_ids, vec!["mem_existing".to_string()]);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.entities_extracted, 2);
+        assert_eq!(result.relations_created, 3);
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_unreachable_subsystems_as_down() {
+        let mut config = HelixirConfig::new("127.0.0.1", 1);
+        config.max_retries = 0;
+        config.llm_provider = "ollama".to_string();
+        config.llm_base_url = Some("http://127.0.0.1:1".to_string());
+        config.embedding_url = "http://127.0.0.1:1".to_string();
+        config.embedding_fallback_enabled = false;
+        let client = HelixirClient::new(config).unwrap();
+
+        let report = client.health().await;
+        assert!(!report.ready);
+        assert_eq!(report.db.status, HealthStatus::Down);
+        assert_eq!(report.embeddings.status, HealthStatus::Down);
+        assert_eq!(report.llm.status, HealthStatus::Down);
+        assert_eq!(report.ontology.status, HealthStatus::Down);
+        assert!(report.llm.detail.is_some());
+        assert!(!client.is_initialized.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_health_checks_llm_reachability_once_per_ttl() {
+        let stub = crate::test_support::spawn_reply_stub(serde_json::json!({"models": []})).await;
+        let mut config = HelixirConfig::new("127.0.0.1", 1);
+        config.max_retries = 0;
+        config.llm_provider = "ollama".to_string();
+        config.llm_base_url = Some(stub.url());
+        config.embedding_url = "http://127.0.0.1:1".to_string();
+        config.embedding_fallback_enabled = false;
+        let client = HelixirClient::new(config).unwrap();
+
+        for _ in 0..3 {
+            assert_eq!(client.health().await.llm.status, HealthStatus::Up);
+        }
+        assert_eq!(stub.count("api/tags"), 1);
+        assert_eq!(stub.count("api/chat"), 0);
+    }
 }
@@ -14,6 +14,8 @@ pub enum SearchMode {
     Deep,
     
     Full,
+    
+    Hybrid,
 }
 
 impl SearchMode {
@@ -73,6 +75,19 @@ impl SearchMode {
                 min_vector_score: 0.0,
                 min_combined_score: 0.0,
             },
+            Self::Hybrid => SearchModeDefaults {
+                max_results: 20,
+                graph_depth: 0,
+                temporal_days: None,
+                vector_weight: 0.6,
+                bm25_weight: 0.4,
+                include_relations: false,
+                cost_estimate: 2.0,
+                use_smart_traversal: false,
+                vector_top_k: 20,
+                min_vector_score: 0.0,
+                min_combined_score: 0.15,
+            },
         }
     }
 
@@ -84,6 +99,7 @@ impl SearchMode {
             Self::Contextual => "Balanced search (30 days) + moderate graph",
             Self::Deep => "Deep search (90 days) + extensive graph",
             Self::Full => "Complete history + full graph traversal",
+            Self::Hybrid => "Vector similarity fused with BM25 keyword matching, no graph",
         }
     }
 
@@ -95,6 +111,7 @@ impl SearchMode {
             "contextual" => Self::Contextual,
             "deep" => Self::Deep,
             "full" => Self::Full,
+            "hybrid" => Self::Hybrid,
             _ => Self::Recent, 
         }
     }
@@ -209,6 +226,7 @@ mod tests {
         assert_eq!(SearchMode::from_str("CONTEXTUAL"), SearchMode::Contextual);
         assert_eq!(SearchMode::from_str("Deep"), SearchMode::Deep);
         assert_eq!(SearchMode::from_str("full"), SearchMode::Full);
+        assert_eq!(SearchMode::from_str("Hybrid"), SearchMode::Hybrid);
         assert_eq!(SearchMode::from_str("unknown"), SearchMode::Recent);
     }
 
@@ -221,6 +239,10 @@ mod tests {
         let full = SearchMode::Full.get_defaults();
         assert_eq!(full.max_results, 100);
         assert!(full.temporal_days.is_none());
+
+        let hybrid = SearchMode::Hybrid.get_defaults();
+        assert!(!hybrid.use_smart_traversal);
+        assert!(hybrid.min_combined_score > 0.0 && hybrid.min_combined_score < 0.3);
     }
 
     #[test]
@@ -245,7 +245,7 @@ impl ChunkingService {
         }
 
         let result: Output = client
-            .execute_query(
+            .execute_mutation(
                 "addMemoryChunk",
                 &Input {
                     chunk_id: chunk_id.clone(),
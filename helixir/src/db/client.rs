@@ -1,8 +1,11 @@
 
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::future::Future;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Weak};
 use std::time::Duration;
 use helix_rs::{HelixDB, HelixDBClient, HelixError};
+use parking_lot::{RwLock, RwLockReadGuard};
 use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
@@ -14,6 +17,70 @@ const INITIAL_RETRY_DELAY_MS: u64 = 100;
 
 const MAX_RETRY_DELAY_MS: u64 = 10000;
 
+const RECONNECT_ATTEMPTS: u32 = 3;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    Reconnecting,
+    Down,
+}
+
+impl ConnectionState {
+    fn as_u8(self) -> u8 {
+        match self {
+            ConnectionState::Connected => 0,
+            ConnectionState::Reconnecting => 1,
+            ConnectionState::Down => 2,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => ConnectionState::Connected,
+            1 => ConnectionState::Reconnecting,
+            _ => ConnectionState::Down,
+        }
+    }
+}
+
+
+/// Exponential backoff with jitter for queries that are safe to repeat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Attempts made after the first one fails
+    pub max_retries: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: MAX_RETRIES,
+            base_delay_ms: INITIAL_RETRY_DELAY_MS,
+            max_delay_ms: MAX_RETRY_DELAY_MS,
+        }
+    }
+}
+
+impl RetryConfig {
+    
+    pub fn no_retry() -> Self {
+        Self { max_retries: 0, ..Self::default() }
+    }
+
+    /// Equal jitter: half of the capped exponential delay is fixed, the other half random.
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let exp = self.base_delay_ms.saturating_mul(1u64 << retry.min(32));
+        let capped = exp.min(self.max_delay_ms);
+        let half = capped / 2;
+        let jitter = if half == 0 { 0 } else { (uuid::Uuid::new_v4().as_u128() % (half as u128 + 1)) as u64 };
+        Duration::from_millis(capped - half + jitter)
+    }
+}
+
 
 #[derive(Debug, Error)]
 pub enum HelixClientError {
@@ -29,16 +96,27 @@ pub enum HelixClientError {
     NotConnected,
     #[error("Retry exhausted after {0} attempts: {1}")]
     RetryExhausted(u32, String),
+    #[error("Query {0} timed out after {1:?}")]
+    Timeout(String, Duration),
 }
 
 
 pub struct HelixClient {
     
-    inner: HelixDB,
+    inner: RwLock<HelixDB>,
     
-    is_connected: AtomicBool,
+    state: AtomicU8,
+    
+    reconnect_lock: tokio::sync::Mutex<()>,
+    
+    endpoint: String,
+    port: u16,
     
     base_url: String,
+    
+    retry: RetryConfig,
+    /// Deadline for a whole query call, retries and reconnects included
+    query_timeout: Option<Duration>,
 }
 
 impl HelixClient {
@@ -58,13 +136,48 @@ impl HelixClient {
         info!("HelixClient created for {}", base_url);
 
         Ok(Self {
-            inner,
-            is_connected: AtomicBool::new(false),
+            inner: RwLock::new(inner),
+            state: AtomicU8::new(ConnectionState::Down.as_u8()),
+            reconnect_lock: tokio::sync::Mutex::new(()),
+            endpoint,
+            port,
             base_url,
+            retry: RetryConfig::default(),
+            query_timeout: None,
         })
     }
 
     
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    
+    pub fn retry_config(&self) -> RetryConfig {
+        self.retry
+    }
+
+    /// Fails queries that run longer than `timeout` with `HelixClientError::Timeout`.
+    pub fn with_query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = Some(timeout);
+        self
+    }
+
+    async fn with_deadline<T, F>(&self, query_name: &str, call: F) -> Result<T, HelixClientError>
+    where
+        F: Future<Output = Result<T, HelixClientError>>,
+    {
+        match self.query_timeout {
+            Some(limit) => tokio::time::timeout(limit, call).await.unwrap_or_else(|_| {
+                warn!("Query {} timed out after {:?}", query_name, limit);
+                Err(HelixClientError::Timeout(query_name.to_string(), limit))
+            }),
+            None => call.await,
+        }
+    }
+
+    
     pub fn from_env() -> Result<Self, HelixClientError> {
         let host = std::env::var("HELIX_HOST").unwrap_or_else(|_| "localhost".to_string());
         let port: u16 = std::env::var("HELIX_PORT")
@@ -77,66 +190,85 @@ impl HelixClient {
 
     
     pub async fn connect(&self) -> Result<(), HelixClientError> {
-        if self.is_connected.load(Ordering::Relaxed) {
+        if self.is_connected() {
             return Ok(());
         }
 
-        
-                self.is_connected.store(true, Ordering::Relaxed);
+        self.set_state(ConnectionState::Connected);
         info!("HelixClient ready for {}", self.base_url);
-                    Ok(())
+        Ok(())
     }
 
-    
+    /// Runs an idempotent query, retrying transient failures per the client's `RetryConfig`.
     pub async fn execute_query<T, P>(&self, query_name: &str, params: &P) -> Result<T, HelixClientError>
     where
         T: DeserializeOwned,
         P: Serialize + Sync,
     {
-        let mut last_error = None;
-        let mut delay = Duration::from_millis(INITIAL_RETRY_DELAY_MS);
-
-        for attempt in 1..=MAX_RETRIES {
-            debug!("Executing query: {} (attempt {})", query_name, attempt);
-
-            match self.inner.query::<P, T>(query_name, params).await {
-                Ok(result) => {
-                    if !self.is_connected.load(Ordering::Relaxed) {
-                        self.is_connected.store(true, Ordering::Relaxed);
-                    }
-                    debug!("Query {} succeeded", query_name);
-                    return Ok(result);
-                }
-                Err(e) => {
-                    let err_str = e.to_string();
-                    
-                    
-                    if err_str.contains("not found") || err_str.contains("No value") {
-                        debug!("Query {} returned not found (expected)", query_name);
-                        return Err(HelixClientError::Query(err_str));
-                    }
-
-                    
-                    if attempt == 1 {
-                    debug!("Query {} failed (attempt {}), retrying: {}", query_name, attempt, e);
-                } else {
-                    debug!("Query {} failed (final attempt {}): {}", query_name, attempt, e);
-                }
-                last_error = Some(err_str);
+        self.execute_query_with(query_name, params, true).await
+    }
+
+    /// Runs a query that must not be repeated blindly (e.g. node-creating writes).
+    pub async fn execute_mutation<T, P>(&self, query_name: &str, params: &P) -> Result<T, HelixClientError>
+    where
+        T: DeserializeOwned,
+        P: Serialize + Sync,
+    {
+        self.execute_query_with(query_name, params, false).await
+    }
+
+    
+    pub async fn execute_query_with<T, P>(
+        &self,
+        query_name: &str,
+        params: &P,
+        idempotent: bool,
+    ) -> Result<T, HelixClientError>
+    where
+        T: DeserializeOwned,
+        P: Serialize + Sync,
+    {
+        self.with_deadline(query_name, self.execute_with_recovery(query_name, params, idempotent)).await
+    }
+
+    async fn execute_with_recovery<T, P>(
+        &self,
+        query_name: &str,
+        params: &P,
+        idempotent: bool,
+    ) -> Result<T, HelixClientError>
+    where
+        T: DeserializeOwned,
+        P: Serialize + Sync,
+    {
+        let result = retry_with_backoff(&self.retry, idempotent, query_name, || async {
+            self.db().query::<P, T>(query_name, params).await.map_err(|e| e.to_string())
+        })
+        .await;
 
-                    if attempt < MAX_RETRIES {
-                        tokio::time::sleep(delay).await;
-                        
-                        delay = (delay * 2).min(Duration::from_millis(MAX_RETRY_DELAY_MS));
-                    }
+        match result {
+            Ok(value) => {
+                self.set_state(ConnectionState::Connected);
+                Ok(value)
+            }
+            Err(e) if is_connection_error(&e.to_string()) => {
+                if !self.reconnect().await {
+                    return Err(HelixClientError::Connection(format!(
+                        "{} unreachable after reconnect attempts: {}",
+                        self.base_url, e
+                    )));
                 }
+                if !idempotent {
+                    return Err(e);
+                }
+                debug!("Replaying query {} after reconnect", query_name);
+                self.db()
+                    .query::<P, T>(query_name, params)
+                    .await
+                    .map_err(|e| HelixClientError::Query(e.to_string()))
             }
+            Err(e) => Err(e),
         }
-
-        Err(HelixClientError::RetryExhausted(
-            MAX_RETRIES,
-            last_error.unwrap_or_else(|| "Unknown error".to_string()),
-        ))
     }
 
     
@@ -145,10 +277,13 @@ impl HelixClient {
         T: DeserializeOwned,
         P: Serialize + Sync,
     {
-        self.inner
-            .query::<P, T>(query_name, params)
-            .await
-            .map_err(|e| HelixClientError::Query(e.to_string()))
+        self.with_deadline(query_name, async {
+            self.db()
+                .query::<P, T>(query_name, params)
+                .await
+                .map_err(|e| HelixClientError::Query(e.to_string()))
+        })
+        .await
     }
 
     
@@ -174,8 +309,70 @@ impl HelixClient {
     }
 
     
+    pub async fn ping(&self) -> bool {
+        let alive = self.health_check().await.is_ok();
+        if alive {
+            self.set_state(ConnectionState::Connected);
+        } else if self.connection_state() == ConnectionState::Connected {
+            self.set_state(ConnectionState::Down);
+        }
+        alive
+    }
+
+    /// Rebuilds the HTTP transport and pings with backoff; concurrent callers share one attempt.
+    pub async fn reconnect(&self) -> bool {
+        let _guard = self.reconnect_lock.lock().await;
+        if self.connection_state() == ConnectionState::Connected && self.health_check().await.is_ok() {
+            return true;
+        }
+
+        self.set_state(ConnectionState::Reconnecting);
+        for attempt in 0..RECONNECT_ATTEMPTS {
+            *self.inner.write() = <HelixDB as HelixDBClient>::new(Some(&self.endpoint), Some(self.port), None);
+            if self.health_check().await.is_ok() {
+                info!("Reconnected to {} (attempt {})", self.base_url, attempt + 1);
+                self.set_state(ConnectionState::Connected);
+                return true;
+            }
+            tokio::time::sleep(self.retry.delay_for(attempt)).await;
+        }
+
+        error!("HelixDB at {} is down after {} reconnect attempts", self.base_url, RECONNECT_ATTEMPTS);
+        self.set_state(ConnectionState::Down);
+        false
+    }
+
+    /// Opt-in background ping; the task ends once the client is dropped.
+    pub fn spawn_keep_alive(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let client: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(client) = client.upgrade() else {
+                    break;
+                };
+                if !client.ping().await {
+                    warn!("Keep-alive ping to {} failed, reconnecting", client.base_url);
+                    client.reconnect().await;
+                }
+            }
+        })
+    }
+
+    
+    pub fn connection_state(&self) -> ConnectionState {
+        ConnectionState::from_u8(self.state.load(Ordering::Relaxed))
+    }
+
+    fn set_state(&self, state: ConnectionState) {
+        self.state.store(state.as_u8(), Ordering::Relaxed);
+    }
+
+    
     pub fn is_connected(&self) -> bool {
-        self.is_connected.load(Ordering::Relaxed)
+        self.connection_state() == ConnectionState::Connected
     }
 
     
@@ -183,9 +380,72 @@ impl HelixClient {
         &self.base_url
     }
 
-    
-    pub fn inner(&self) -> &HelixDB {
-        &self.inner
+    /// Borrows the current connection; a reconnect waits until the guard is dropped, so don't
+    /// hold it across an `.await`.
+    pub fn inner(&self) -> RwLockReadGuard<'_, HelixDB> {
+        self.inner.read()
+    }
+
+    /// A handle to the current connection that stays usable after a reconnect replaces it.
+    pub fn db(&self) -> HelixDB {
+        self.inner.read().clone()
+    }
+}
+
+fn is_connection_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["error sending request", "connection refused", "connection reset", "broken pipe", "timed out"]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
+/// Drives `op` until it succeeds, the error is terminal, or retries run out.
+///
+/// Cancellation-safe: all retry state lives in this future, so dropping it
+/// mid-backoff simply abandons the remaining attempts.
+pub(crate) async fn retry_with_backoff<T, F, Fut>(
+    config: &RetryConfig,
+    idempotent: bool,
+    query_name: &str,
+    mut op: F,
+) -> Result<T, HelixClientError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let max_retries = if idempotent { config.max_retries } else { 0 };
+    let mut retry = 0;
+
+    loop {
+        debug!("Executing query: {} (attempt {})", query_name, retry + 1);
+
+        let err_str = match op().await {
+            Ok(result) => {
+                debug!("Query {} succeeded", query_name);
+                return Ok(result);
+            }
+            Err(e) => e,
+        };
+
+        if err_str.contains("not found") || err_str.contains("No value") {
+            debug!("Query {} returned not found (expected)", query_name);
+            return Err(HelixClientError::Query(err_str));
+        }
+
+        if !idempotent {
+            warn!("Query {} failed and is not idempotent, not retrying: {}", query_name, err_str);
+            return Err(HelixClientError::Query(err_str));
+        }
+
+        if retry >= max_retries {
+            debug!("Query {} failed (final attempt {}): {}", query_name, retry + 1, err_str);
+            return Err(HelixClientError::RetryExhausted(retry + 1, err_str));
+        }
+
+        let delay = config.delay_for(retry);
+        debug!("Query {} failed (attempt {}), retrying in {:?}: {}", query_name, retry + 1, delay, err_str);
+        tokio::time::sleep(delay).await;
+        retry += 1;
     }
 }
 
@@ -201,10 +461,154 @@ mod tests {
 
     #[test]
     fn test_client_from_env() {
-        std::env::set_var("HELIX_HOST", "localhost");
-        std::env::set_var("HELIX_PORT", "6969");
-        
+        unsafe {
+            std::env::set_var("HELIX_HOST", "localhost");
+            std::env::set_var("HELIX_PORT", "6969");
+        }
+
         let client = HelixClient::from_env();
         assert!(client.is_ok());
     }
+
+    fn fast_retry() -> RetryConfig {
+        RetryConfig { max_retries: 3, base_delay_ms: 1, max_delay_ms: 4 }
+    }
+
+    fn flaky_transport(failures: u32, calls: &std::sync::atomic::AtomicU32) -> Result<&'static str, String> {
+        let call = calls.fetch_add(1, Ordering::SeqCst);
+        if call < failures {
+            Err("connection reset by peer".to_string())
+        } else {
+            Ok("ok")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_recovers_after_two_failures() {
+        let calls = std::sync::atomic::AtomicU32::new(0);
+        let result = retry_with_backoff(&fast_retry(), true, "getMemory", || async {
+            flaky_transport(2, &calls)
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_query_is_not_retried() {
+        let calls = std::sync::atomic::AtomicU32::new(0);
+        let result = retry_with_backoff(&fast_retry(), false, "addMemory", || async {
+            flaky_transport(2, &calls)
+        })
+        .await;
+
+        assert!(matches!(result, Err(HelixClientError::Query(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_exhaustion_and_cancellation() {
+        let calls = std::sync::atomic::AtomicU32::new(0);
+        let result = retry_with_backoff(&fast_retry(), true, "getMemory", || async {
+            flaky_transport(u32::MAX, &calls)
+        })
+        .await;
+        assert!(matches!(result, Err(HelixClientError::RetryExhausted(4, _))));
+
+        let calls = std::sync::atomic::AtomicU32::new(0);
+        let slow = RetryConfig { max_retries: 5, base_delay_ms: 200, max_delay_ms: 200 };
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(20),
+            retry_with_backoff(&slow, true, "getMemory", || async { flaky_transport(u32::MAX, &calls) }),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    async fn spawn_health_stub(drop_first: usize) -> u16 {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut served = 0;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                served += 1;
+                if served <= drop_first {
+                    drop(socket);
+                    continue;
+                }
+                let mut buf = vec![0u8; 8192];
+                let _ = socket.read(&mut buf).await;
+                let response = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}";
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_query_timeout_is_reported_distinctly() {
+        // Accepts connections but never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let client = HelixClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_query_timeout(Duration::from_millis(50));
+        let started = std::time::Instant::now();
+        let err = client
+            .execute_query::<serde_json::Value, _>("getMemory", &serde_json::json!({}))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, HelixClientError::Timeout(ref name, _) if name == "getMemory"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_ping_tracks_connection_state() {
+        let down = HelixClient::new("127.0.0.1", 1).unwrap().with_retry_config(fast_retry());
+        assert_eq!(down.connection_state(), ConnectionState::Down);
+        assert!(!down.ping().await);
+        let err = down.execute_query::<serde_json::Value, _>("getMemory", &serde_json::json!({})).await;
+        assert!(matches!(err, Err(HelixClientError::Connection(_))));
+        assert_eq!(down.connection_state(), ConnectionState::Down);
+
+        let port = spawn_health_stub(0).await;
+        let up = HelixClient::new("127.0.0.1", port).unwrap();
+        assert!(up.ping().await);
+        assert_eq!(up.connection_state(), ConnectionState::Connected);
+    }
+
+    #[tokio::test]
+    async fn test_query_reconnects_after_dropped_connections() {
+        let port = spawn_health_stub(2).await;
+        let retry = RetryConfig { max_retries: 1, base_delay_ms: 1, max_delay_ms: 2 };
+        let client = HelixClient::new("127.0.0.1", port).unwrap().with_retry_config(retry);
+
+        let result = client.execute_query::<serde_json::Value, _>("getMemory", &serde_json::json!({})).await;
+
+        assert_eq!(result.unwrap(), serde_json::json!({}));
+        assert_eq!(client.connection_state(), ConnectionState::Connected);
+    }
+
+    #[test]
+    fn test_backoff_delay_is_capped_with_jitter() {
+        let config = RetryConfig { max_retries: 10, base_delay_ms: 100, max_delay_ms: 1000 };
+        for retry in 0..10 {
+            let delay = config.delay_for(retry).as_millis() as u64;
+            let capped = (100u64 << retry).min(1000);
+            assert!(delay >= capped / 2 && delay <= capped, "retry {retry}: {delay}ms");
+        }
+    }
 }
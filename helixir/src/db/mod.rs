@@ -2,5 +2,5 @@
 
 mod client;
 
-pub use client::{HelixClient, HelixClientError};
+pub use client::{ConnectionState, HelixClient, HelixClientError, RetryConfig};
 
@@ -11,6 +11,9 @@ pub mod mcp;
 pub mod toolkit;
 pub mod utils;
 
+#[cfg(test)]
+pub(crate) mod test_support;
+
 pub use utils::{safe_truncate, safe_truncate_ellipsis};
 
 
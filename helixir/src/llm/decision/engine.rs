@@ -1,13 +1,17 @@
 
 
+use std::str::FromStr;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
 use super::models::{MemoryDecision, MemoryOperation, SimilarMemory};
-use super::prompt::{build_decision_prompt, SYSTEM_PROMPT};
+use super::prompt::{build_contradiction_prompt, build_decision_prompt, CONTRADICTION_SYSTEM_PROMPT, SYSTEM_PROMPT};
 use crate::llm::providers::base::LlmProvider;
 
 
+const FALLBACK_CONFIDENCE: u8 = 30;
+
+
 pub struct LLMDecisionEngine {
     
     llm: Arc<dyn LlmProvider>,
@@ -77,27 +81,42 @@ impl LLMDecisionEngine {
 
         debug!("Calling LLM for decision with {} candidates", highly_similar.len());
 
-        match self.llm.generate(SYSTEM_PROMPT, &prompt, Some("json_object")).await {
+        let response_format = self.llm.capabilities().supports_json_mode.then_some("json_object");
+
+        match self.llm.generate(SYSTEM_PROMPT, &prompt, response_format).await {
             Ok((response, _metadata)) => {
-                
-                match serde_json::from_str::<MemoryDecision>(&response) {
-                    Ok(decision) => {
-                        info!(
-                            "Decision made: operation={:?}, confidence={}, target={:?}",
-                            decision.operation, decision.confidence, decision.target_memory_id
-                        );
-                        decision
-                    }
-                    Err(e) => {
-                        warn!("Failed to parse LLM response as JSON: {}", e);
-                        warn!("Response was: {}", crate::safe_truncate(&response, 200));
-                        MemoryDecision::add(50, format!("JSON parse failed ({}), defaulting to ADD.", e))
-                    }
-                }
+                let decision = parse_decision(&response, &highly_similar);
+                info!(
+                    "Decision made: operation={:?}, confidence={}, target={:?}",
+                    decision.operation, decision.confidence, decision.target_memory_id
+                );
+                decision
             }
             Err(e) => {
                 warn!("LLM call failed: {}", e);
-                MemoryDecision::add(50, format!("LLM call failed ({}), defaulting to ADD.", e))
+                MemoryDecision::add(FALLBACK_CONFIDENCE, format!("LLM call failed ({}), defaulting to ADD.", e))
+            }
+        }
+    }
+
+    
+    /// Confidence (0-100) that the two memories contradict; `None` when they agree or the LLM gave no usable answer.
+    pub async fn check_contradiction(&self, first: &SimilarMemory, second: &SimilarMemory) -> Option<u8> {
+        let prompt = build_contradiction_prompt(first, second);
+        let response_format = self.llm.capabilities().supports_json_mode.then_some("json_object");
+
+        match self.llm.generate(CONTRADICTION_SYSTEM_PROMPT, &prompt, response_format).await {
+            Ok((response, _metadata)) => {
+                let confidence = parse_contradiction(&response);
+                debug!(
+                    "Contradiction check {} vs {}: {:?}",
+                    first.id, second.id, confidence
+                );
+                confidence
+            }
+            Err(e) => {
+                warn!("Contradiction check failed: {}", e);
+                None
             }
         }
     }
@@ -110,6 +129,107 @@ impl LLMDecisionEngine {
     }
 }
 
+
+/// The outermost `{...}` of an LLM response, dropping code fences or prose around it.
+pub(crate) fn extract_json_object(response: &str) -> Option<&str> {
+    match (response.find('{'), response.rfind('}')) {
+        (Some(start), Some(end)) if start < end => Some(&response[start..=end]),
+        _ => None,
+    }
+}
+
+
+fn parse_contradiction(response: &str) -> Option<u8> {
+    let json_text = extract_json_object(response)?;
+    let value: serde_json::Value = serde_json::from_str(json_text).ok()?;
+
+    if !value.get("contradicts").and_then(|v| v.as_bool())? {
+        return None;
+    }
+    value.get("confidence").and_then(confidence_percent)
+}
+
+
+/// Reads a 0-100 confidence. A non-integer in (0, 1] is taken as a fraction, so `0.9`
+/// is 90 while `1` stays 1.
+fn confidence_percent(value: &serde_json::Value) -> Option<u8> {
+    let c = value.as_f64()?;
+    let fraction = value.is_f64() && c > 0.0 && c <= 1.0;
+    let percent = if fraction { c * 100.0 } else { c };
+    Some(percent.clamp(0.0, 100.0).round() as u8)
+}
+
+
+fn parse_decision(response: &str, candidates: &[SimilarMemory]) -> MemoryDecision {
+    let fallback = |reason: String| {
+        warn!("{}; response was: {}", reason, crate::safe_truncate(response, 200));
+        MemoryDecision::add(FALLBACK_CONFIDENCE, format!("{}, defaulting to ADD.", reason))
+    };
+
+    let Some(json_text) = extract_json_object(response) else {
+        return fallback("LLM response contained no JSON object".to_string());
+    };
+    let value: serde_json::Value = match serde_json::from_str(json_text) {
+        Ok(value) => value,
+        Err(e) => return fallback(format!("JSON parse failed ({})", e)),
+    };
+
+    let operation = match value.get("operation").and_then(|v| v.as_str()) {
+        Some(op) => match MemoryOperation::from_str(&op.trim().to_uppercase()) {
+            Ok(operation) => operation,
+            Err(_) => return fallback(format!("Unknown operation '{}'", op)),
+        },
+        None => return fallback("Missing operation".to_string()),
+    };
+
+    let confidence = match value.get("confidence").and_then(confidence_percent) {
+        Some(c) => c,
+        None => return fallback("Missing confidence".to_string()),
+    };
+
+    let text_field = |key: &str| {
+        value.get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let is_candidate = |id: &Option<String>| {
+        id.as_ref().is_some_and(|id| candidates.iter().any(|m| &m.id == id))
+    };
+
+    let decision = MemoryDecision {
+        operation,
+        target_memory_id: text_field("target_memory_id"),
+        confidence,
+        reasoning: text_field("reasoning").unwrap_or_default(),
+        merged_content: text_field("merged_content"),
+        supersedes_memory_id: text_field("supersedes_memory_id"),
+        contradicts_memory_id: text_field("contradicts_memory_id"),
+        relates_to: value.get("relates_to")
+            .and_then(|v| serde_json::from_value(v.clone()).ok()),
+    };
+
+    let valid = match decision.operation {
+        MemoryOperation::Add | MemoryOperation::Noop => true,
+        MemoryOperation::Update => {
+            is_candidate(&decision.target_memory_id) && decision.merged_content.is_some()
+        }
+        MemoryOperation::Delete => is_candidate(&decision.target_memory_id),
+        MemoryOperation::Supersede => is_candidate(&decision.supersedes_memory_id),
+        MemoryOperation::Contradict => is_candidate(&decision.contradicts_memory_id),
+    };
+
+    if !valid {
+        return fallback(format!(
+            "{:?} decision without a valid target among {} candidates",
+            decision.operation,
+            candidates.len()
+        ));
+    }
+
+    decision
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,4 +252,80 @@ mod tests {
         assert_eq!(supersede.operation, MemoryOperation::Supersede);
         assert_eq!(supersede.supersedes_memory_id, Some("mem_old".to_string()));
     }
-}
\ No newline at end of file
+
+    fn candidates() -> Vec<SimilarMemory> {
+        vec![SimilarMemory {
+            id: "mem_existing".to_string(),
+            content: "User likes tea".to_string(),
+            score: 0.95,
+            created_at: None,
+        }]
+    }
+
+    #[test]
+    fn test_parse_decision_valid_update() {
+        let response = r#"{"operation": "UPDATE", "target_memory_id": "mem_existing", "merged_content": "User likes green tea", "confidence": 88, "reasoning": "refines"}"#;
+        let decision = parse_decision(response, &candidates());
+        assert_eq!(decision.operation, MemoryOperation::Update);
+        assert_eq!(decision.confidence, 88);
+        assert_eq!(decision.merged_content.as_deref(), Some("User likes green tea"));
+    }
+
+    #[test]
+    fn test_parse_decision_tolerates_fences_and_case() {
+        let response = "```json\n{\"operation\": \"noop\", \"confidence\": 0.9}\n```";
+        let decision = parse_decision(response, &candidates());
+        assert_eq!(decision.operation, MemoryOperation::Noop);
+        assert_eq!(decision.confidence, 90);
+    }
+
+    #[test]
+    fn test_integer_confidence_is_a_percentage() {
+        let response = r#"{"operation": "NOOP", "confidence": 1}"#;
+        assert_eq!(parse_decision(response, &candidates()).confidence, 1);
+        let response = r#"{"operation": "NOOP", "confidence": 1.0}"#;
+        assert_eq!(parse_decision(response, &candidates()).confidence, 100);
+        assert_eq!(parse_contradiction(r#"{"contradicts": true, "confidence": 1}"#), Some(1));
+    }
+
+    #[test]
+    fn test_parse_decision_malformed_defaults_to_add() {
+        for response in [
+            "not json at all",
+            "{\"operation\": \"DELETE\"",
+            r#"{"confidence": 90}"#,
+            r#"{"operation": "DELETE"}"#,
+            r#"{"operation": "EXPLODE", "confidence": 90}"#,
+        ] {
+            let decision = parse_decision(response, &candidates());
+            assert_eq!(decision.operation, MemoryOperation::Add, "response: {}", response);
+            assert_eq!(decision.confidence, FALLBACK_CONFIDENCE);
+        }
+    }
+
+    #[test]
+    fn test_parse_contradiction() {
+        assert_eq!(parse_contradiction(r#"{"contradicts": true, "confidence": 82}"#), Some(82));
+        assert_eq!(parse_contradiction("```json\n{\"contradicts\": true, \"confidence\": 0.7}\n```"), Some(70));
+        assert_eq!(parse_contradiction(r#"{"contradicts": false, "confidence": 95}"#), None);
+        assert_eq!(parse_contradiction(r#"{"contradicts": true}"#), None);
+        assert_eq!(parse_contradiction("no idea"), None);
+    }
+
+    #[test]
+    fn test_parse_decision_rejects_unknown_targets() {
+        let delete = r#"{"operation": "DELETE", "target_memory_id": "mem_other", "confidence": 95}"#;
+        assert_eq!(parse_decision(delete, &candidates()).operation, MemoryOperation::Add);
+
+        let supersede = r#"{"operation": "SUPERSEDE", "confidence": 95}"#;
+        assert_eq!(parse_decision(supersede, &candidates()).operation, MemoryOperation::Add);
+
+        let update = r#"{"operation": "UPDATE", "target_memory_id": "mem_existing", "confidence": 95}"#;
+        assert_eq!(parse_decision(update, &candidates()).operation, MemoryOperation::Add);
+
+        let delete_ok = r#"{"operation": "DELETE", "target_memory_id": "mem_existing", "confidence": 95}"#;
+        let decision = parse_decision(delete_ok, &candidates());
+        assert_eq!(decision.operation, MemoryOperation::Delete);
+        assert_eq!(decision.target_memory_id.as_deref(), Some("mem_existing"));
+    }
+}
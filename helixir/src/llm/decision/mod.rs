@@ -6,4 +6,5 @@ mod engine;
 
 pub use models::{MemoryDecision, MemoryOperation, SimilarMemory};
 pub use engine::LLMDecisionEngine;
+pub(crate) use engine::extract_json_object;
 
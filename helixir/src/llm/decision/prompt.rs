@@ -89,3 +89,36 @@ Decide what to do with the new memory. Choose ONE operation:
     )
 }
 
+
+
+pub const CONTRADICTION_SYSTEM_PROMPT: &str = r#"You are a memory consistency checker. Decide whether two memories about the same user make claims that cannot both be true at the same time.
+
+Always respond with valid JSON."#;
+
+
+pub fn build_contradiction_prompt(first: &SimilarMemory, second: &SimilarMemory) -> String {
+    format!(
+        r#"Do these two memories contradict each other?
+
+**Memory A** (created {first_created}):
+"{first_content}"
+
+**Memory B** (created {second_created}):
+"{second_content}"
+
+**Response Format (JSON):**
+{{
+  "contradicts": true|false,
+  "confidence": 0-100,
+  "reasoning": "Why"
+}}
+
+**Important:**
+- Differences in detail or scope are not contradictions
+- A changed preference over time still counts as a contradiction"#,
+        first_created = first.created_at.as_deref().unwrap_or("unknown"),
+        first_content = first.content,
+        second_created = second.created_at.as_deref().unwrap_or("unknown"),
+        second_content = second.content,
+    )
+}
@@ -2,9 +2,11 @@
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::RwLock;
+use lru::LruCache;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use thiserror::Error;
 use tracing::{debug, info, warn};
@@ -32,6 +34,12 @@ pub enum EmbeddingError {
 
     #[error("Both primary and fallback failed: primary={0}, fallback={1}")]
     BothFailed(String, String),
+
+    #[error("Embedding request timed out after {0:?}")]
+    Timeout(Duration),
+
+    #[error("Embedding has {actual} dimensions, expected {expected} (model={model})")]
+    DimensionMismatch { expected: usize, actual: usize, model: String },
 }
 
 
@@ -46,6 +54,23 @@ struct OllamaEmbeddingResponse {
     embedding: Vec<f32>,
 }
 
+#[derive(Serialize)]
+struct OllamaBatchEmbeddingRequest {
+    model: String,
+    input: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OllamaBatchEmbeddingResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+#[derive(Serialize)]
+struct OpenAIBatchEmbeddingRequest {
+    model: String,
+    input: Vec<String>,
+}
+
 #[derive(Serialize)]
 struct OpenAIEmbeddingRequest {
     model: String,
@@ -60,66 +85,149 @@ struct OpenAIEmbeddingResponse {
 #[derive(Deserialize)]
 struct OpenAIEmbeddingData {
     embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
 }
 
 
-struct CacheEntry {
-    embedding: Vec<f32>,
-    created_at: Instant,
+/// Cache namespace: some models embed queries and documents with different prefixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingKind {
+    Query,
+    Document,
+}
+
+impl EmbeddingKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EmbeddingKind::Query => "query",
+            EmbeddingKind::Document => "document",
+        }
+    }
+}
+
+
+/// Which backend produced an embedding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingBackend {
+    Cache,
+    Primary,
+    Fallback,
+}
+
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EmbeddingBackendStats {
+    /// Requests sent to the fallback after the primary failed, successful or not
+    pub fallback_invocations: u64,
+    pub fallback_successes: u64,
+    pub using_fallback: bool,
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendHealth {
+    pub provider: String,
+    pub model: String,
+    pub ok: bool,
+    pub dimension: Option<usize>,
+    pub error: Option<String>,
+}
+
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingHealth {
+    pub primary: BackendHealth,
+    /// `None` when no fallback would be tried for this provider
+    pub fallback: Option<BackendHealth>,
+    /// Fallback vectors differ in size from primary ones (or the expected dimension),
+    /// so anything it embeds cannot be searched alongside primary embeddings
+    pub dimension_mismatch: bool,
+}
+
+impl EmbeddingHealth {
+    
+    pub fn is_healthy(&self) -> bool {
+        self.primary.ok && !self.dimension_mismatch
+    }
+}
+
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EmbeddingCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub size: usize,
+    pub hit_rate: f64,
 }
 
 struct EmbeddingCache {
-    cache: RwLock<HashMap<String, CacheEntry>>,
-    max_size: usize,
+    cache: Mutex<LruCache<String, (Vec<f32>, Instant)>>,
     ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
 }
 
 impl EmbeddingCache {
     fn new(max_size: usize, ttl_secs: u64) -> Self {
         Self {
-            cache: RwLock::new(HashMap::new()),
-            max_size,
+            cache: Mutex::new(LruCache::new(NonZeroUsize::new(max_size).unwrap_or(NonZeroUsize::MIN))),
             ttl: Duration::from_secs(ttl_secs),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
         }
     }
 
-    fn get(&self, text: &str) -> Option<Vec<f32>> {
-        let cache = self.cache.read().unwrap();
-        if let Some(entry) = cache.get(text) {
-            if entry.created_at.elapsed() < self.ttl {
-                return Some(entry.embedding.clone());
-            }
+    // Keyed on the model too, so switching models never serves vectors from the old one.
+    fn key(model: &str, kind: EmbeddingKind, text: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [model, kind.as_str(), text] {
+            hasher.update(part.len().to_le_bytes());
+            hasher.update(part.as_bytes());
         }
-        None
-    }
-
-    fn set(&self, text: &str, embedding: Vec<f32>) {
-        let mut cache = self.cache.write().unwrap();
-        if cache.len() >= self.max_size {
-            
-            if let Some(oldest_key) = cache
-                .iter()
-                .min_by_key(|(_, v)| v.created_at)
-                .map(|(k, _)| k.clone())
-            {
-                cache.remove(&oldest_key);
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn get(&self, key: &str) -> Option<Vec<f32>> {
+        let mut cache = self.cache.lock();
+        let fresh = match cache.get(key) {
+            Some((embedding, created_at)) if created_at.elapsed() < self.ttl => Some(embedding.clone()),
+            Some(_) => {
+                cache.pop(key);
+                None
             }
-        }
-        cache.insert(
-            text.to_string(),
-            CacheEntry {
-                embedding,
-                created_at: Instant::now(),
-            },
-        );
+            None => None,
+        };
+        let counter = if fresh.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        fresh
+    }
+
+    fn set(&self, key: String, embedding: Vec<f32>) {
+        self.cache.lock().put(key, (embedding, Instant::now()));
     }
 
     fn clear(&self) {
-        self.cache.write().unwrap().clear();
+        self.cache.lock().clear();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
     }
 
     fn len(&self) -> usize {
-        self.cache.read().unwrap().len()
+        self.cache.lock().len()
+    }
+
+    fn stats(&self) -> EmbeddingCacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let total = hits + misses;
+        EmbeddingCacheStats {
+            hits,
+            misses,
+            size: self.len(),
+            hit_rate: if total > 0 { hits as f64 / total as f64 } else { 0.0 },
+        }
     }
 }
 
@@ -131,6 +239,7 @@ pub struct EmbeddingGenerator {
     api_key: Option<String>,
     base_url: Option<String>,
     client: Client,
+    request_timeout: Duration,
     cache: EmbeddingCache,
 
     
@@ -139,6 +248,9 @@ pub struct EmbeddingGenerator {
     fallback_model: String,
     using_fallback: AtomicBool,
     fallback_count: AtomicUsize,
+    fallback_invocations: AtomicU64,
+    expected_dim: Option<usize>,
+    normalize: bool,
 }
 
 impl EmbeddingGenerator {
@@ -177,48 +289,129 @@ impl EmbeddingGenerator {
                 .timeout(Duration::from_secs(timeout_secs))
                 .build()
                 .expect("Failed to create HTTP client"),
+            request_timeout: Duration::from_secs(timeout_secs),
             cache: EmbeddingCache::new(cache_size, cache_ttl),
             fallback_enabled,
             fallback_url,
             fallback_model,
             using_fallback: AtomicBool::new(false),
             fallback_count: AtomicUsize::new(0),
+            fallback_invocations: AtomicU64::new(0),
+            expected_dim: None,
+            normalize: false,
         }
     }
 
     
+    pub fn with_normalization(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    
+    pub fn is_normalized(&self) -> bool {
+        self.normalize
+    }
+
+    
+    pub fn with_expected_dim(mut self, dim: usize) -> Self {
+        self.expected_dim = Some(dim);
+        self
+    }
+
+    
+    pub fn expected_dim(&self) -> Option<usize> {
+        self.expected_dim
+    }
+
+    
+    pub fn validate_dimension(&self, embedding: &[f32]) -> Result<(), EmbeddingError> {
+        let mismatch = match self.expected_dim {
+            Some(expected) => embedding.len() != expected,
+            None => embedding.is_empty(),
+        };
+        if mismatch {
+            let model = if self.is_using_fallback() { &self.fallback_model } else { &self.model };
+            return Err(EmbeddingError::DimensionMismatch {
+                expected: self.expected_dim.unwrap_or(0),
+                actual: embedding.len(),
+                model: model.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    
     pub async fn generate(&self, text: &str, use_cache: bool) -> Result<Vec<f32>, EmbeddingError> {
+        self.generate_as(text, EmbeddingKind::Document, use_cache).await
+    }
+
+    
+    pub async fn generate_query(&self, text: &str, use_cache: bool) -> Result<Vec<f32>, EmbeddingError> {
+        self.generate_as(text, EmbeddingKind::Query, use_cache).await
+    }
+
+    /// Like `generate`, also reporting which backend served the request.
+    pub async fn generate_traced(&self, text: &str, use_cache: bool) -> Result<(Vec<f32>, EmbeddingBackend), EmbeddingError> {
+        let (mut embedding, backend) = self.generate_raw(text, EmbeddingKind::Document, use_cache).await?;
+        if self.normalize {
+            l2_normalize(&mut embedding);
+        }
+        Ok((embedding, backend))
+    }
+
+    async fn generate_as(&self, text: &str, kind: EmbeddingKind, use_cache: bool) -> Result<Vec<f32>, EmbeddingError> {
+        let (mut embedding, _) = self.generate_raw(text, kind, use_cache).await?;
+        if self.normalize {
+            l2_normalize(&mut embedding);
+        }
+        Ok(embedding)
+    }
+
+    
+    pub async fn generate_normalized(&self, text: &str, use_cache: bool) -> Result<Vec<f32>, EmbeddingError> {
+        let (mut embedding, _) = self.generate_raw(text, EmbeddingKind::Document, use_cache).await?;
+        l2_normalize(&mut embedding);
+        Ok(embedding)
+    }
+
+    // Lookups follow whichever model is currently answering.
+    fn cache_key(&self, kind: EmbeddingKind, text: &str) -> String {
+        let model = if self.is_using_fallback() { &self.fallback_model } else { &self.model };
+        EmbeddingCache::key(model, kind, text)
+    }
+
+    async fn generate_raw(
+        &self,
+        text: &str,
+        kind: EmbeddingKind,
+        use_cache: bool,
+    ) -> Result<(Vec<f32>, EmbeddingBackend), EmbeddingError> {
         if text.trim().is_empty() {
             return Err(EmbeddingError::EmptyText);
         }
 
         
         if use_cache {
-            if let Some(cached) = self.cache.get(text) {
+            if let Some(cached) = self.cache.get(&self.cache_key(kind, text)) {
                 debug!("Cache HIT for: {}...", crate::safe_truncate(text, 50));
-                return Ok(cached);
+                return Ok((cached, EmbeddingBackend::Cache));
             }
         }
 
-        
-        let result = match self.provider.as_str() {
-            "ollama" => self.generate_ollama(text).await,
-            "openai" => self.generate_openai(text).await,
-            other => Err(EmbeddingError::NotImplemented(other.to_string())),
-        };
-
-        match result {
+        match self.generate_primary(text).await {
             Ok(embedding) => {
                 if use_cache {
-                    self.cache.set(text, embedding.clone());
+                    self.cache.set(EmbeddingCache::key(&self.model, kind, text), embedding.clone());
                 }
                 self.using_fallback.store(false, Ordering::SeqCst);
-                Ok(embedding)
+                Ok((embedding, EmbeddingBackend::Primary))
             }
             Err(e) => {
                 debug!("Primary embedding provider unavailable, trying fallback: {}", e);
-                if self.fallback_enabled && self.provider != "ollama" {
-                    self.fallback_to_ollama(text, use_cache, &e).await
+                if self.fallback_active() {
+                    let embedding = self.fallback_to_ollama(text, kind, use_cache, &e).await?;
+                    Ok((embedding, EmbeddingBackend::Fallback))
                 } else {
                     Err(e)
                 }
@@ -226,6 +419,206 @@ impl EmbeddingGenerator {
         }
     }
 
+    async fn generate_primary(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        self.with_deadline(async {
+            match self.provider.as_str() {
+                "ollama" => self.generate_ollama(text).await,
+                "openai" => self.generate_openai(text).await,
+                other => Err(EmbeddingError::NotImplemented(other.to_string())),
+            }
+        })
+        .await
+    }
+
+    // Whichever fires first, the overall deadline or the HTTP client's own, reports `Timeout`.
+    async fn with_deadline<T>(
+        &self,
+        call: impl std::future::Future<Output = Result<T, EmbeddingError>>,
+    ) -> Result<T, EmbeddingError> {
+        match tokio::time::timeout(self.request_timeout, call).await {
+            Ok(Err(EmbeddingError::Http(e))) if e.is_timeout() => Err(EmbeddingError::Timeout(self.request_timeout)),
+            Ok(result) => result,
+            Err(_) => Err(EmbeddingError::Timeout(self.request_timeout)),
+        }
+    }
+
+    // The fallback is itself Ollama, so an Ollama primary has nothing to fall back to.
+    fn fallback_active(&self) -> bool {
+        self.fallback_enabled && self.provider != "ollama"
+    }
+
+    /// Embeds a probe text with the primary and, when one applies, the fallback backend,
+    /// bypassing the cache and leaving fallback state and counters untouched.
+    pub async fn health_check(&self) -> EmbeddingHealth {
+        const PROBE: &str = "embedding health check";
+
+        let probe = |provider: &str, model: &str, result: Result<Vec<f32>, EmbeddingError>| BackendHealth {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            ok: result.as_ref().is_ok_and(|v| !v.is_empty()),
+            dimension: result.as_ref().ok().map(Vec::len),
+            error: result.err().map(|e| e.to_string()),
+        };
+
+        let primary = probe(&self.provider, &self.model, self.generate_primary(PROBE).await);
+        let fallback = if self.fallback_active() {
+            Some(probe("ollama", &self.fallback_model, self.request_fallback(PROBE).await))
+        } else {
+            None
+        };
+
+        let reference = primary.dimension.filter(|_| primary.ok).or(self.expected_dim);
+        let dimension_mismatch = match (reference, fallback.as_ref().and_then(|f| f.dimension)) {
+            (Some(expected), Some(actual)) => expected != actual,
+            _ => false,
+        };
+        if dimension_mismatch {
+            warn!(
+                "Fallback embedding model {} is incompatible: {:?} dimensions vs {:?}",
+                self.fallback_model,
+                fallback.as_ref().and_then(|f| f.dimension),
+                reference
+            );
+        }
+
+        EmbeddingHealth { primary, fallback, dimension_mismatch }
+    }
+
+    
+    pub async fn generate_batch(&self, texts: &[&str], use_cache: bool) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        self.generate_batch_each(texts, use_cache).await.into_iter().collect()
+    }
+
+    /// One result per input, in input order. Cache misses go out as a single batch
+    /// request; if the backend rejects it they are retried concurrently one by one.
+    pub async fn generate_batch_each(&self, texts: &[&str], use_cache: bool) -> Vec<Result<Vec<f32>, EmbeddingError>> {
+        let mut results: Vec<Option<Result<Vec<f32>, EmbeddingError>>> = texts
+            .iter()
+            .map(|text| {
+                if text.trim().is_empty() {
+                    Some(Err(EmbeddingError::EmptyText))
+                } else if use_cache {
+                    self.cache.get(&self.cache_key(EmbeddingKind::Document, text)).map(Ok)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let misses: Vec<usize> = (0..texts.len()).filter(|&i| results[i].is_none()).collect();
+
+        if !misses.is_empty() {
+            let pending: Vec<String> = misses.iter().map(|&i| texts[i].to_string()).collect();
+            let batched = self
+                .with_deadline(async {
+                    match self.provider.as_str() {
+                        "ollama" => self.generate_ollama_batch(&pending).await,
+                        "openai" => self.generate_openai_batch(&pending).await,
+                        other => Err(EmbeddingError::NotImplemented(other.to_string())),
+                    }
+                })
+                .await;
+
+            let generated: Vec<Result<Vec<f32>, EmbeddingError>> = match batched {
+                Ok(vectors) if vectors.len() == pending.len() => {
+                    self.using_fallback.store(false, Ordering::SeqCst);
+                    if use_cache {
+                        for (text, embedding) in pending.iter().zip(&vectors) {
+                            self.cache.set(EmbeddingCache::key(&self.model, EmbeddingKind::Document, text), embedding.clone());
+                        }
+                    }
+                    vectors.into_iter().map(Ok).collect()
+                }
+                other => {
+                    // Fall back to one request per text so the fallback provider still applies.
+                    match other {
+                        Ok(vectors) => debug!(
+                            "Batch embedding returned {} vectors for {} texts, retrying individually",
+                            vectors.len(),
+                            pending.len()
+                        ),
+                        Err(e) => debug!("Batch embedding failed, retrying individually: {}", e),
+                    }
+                    futures::future::join_all(pending.iter().map(|text| self.generate_raw(text, EmbeddingKind::Document, use_cache)))
+                        .await
+                        .into_iter()
+                        .map(|result| result.map(|(embedding, _)| embedding))
+                        .collect()
+                }
+            };
+
+            for (idx, result) in misses.into_iter().zip(generated) {
+                results[idx] = Some(result);
+            }
+        }
+
+        debug!("Generated batch of {} embeddings", texts.len());
+        results
+            .into_iter()
+            .flatten()
+            .map(|result| {
+                result.map(|mut embedding| {
+                    if self.normalize {
+                        l2_normalize(&mut embedding);
+                    }
+                    embedding
+                })
+            })
+            .collect()
+    }
+
+    async fn generate_ollama_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let request = OllamaBatchEmbeddingRequest {
+            model: self.model.clone(),
+            input: texts.to_vec(),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/api/embed", self.ollama_url))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(EmbeddingError::Http)?
+            .json::<OllamaBatchEmbeddingResponse>()
+            .await?;
+
+        Ok(response.embeddings)
+    }
+
+    async fn generate_openai_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let api_key = self
+            .api_key
+            .as_ref()
+            .ok_or_else(|| EmbeddingError::InvalidResponse("API key required".to_string()))?;
+
+        let api_url = self
+            .base_url
+            .as_ref()
+            .map(|u| u.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| "https://api.openai.com/v1".to_string());
+
+        let request = OpenAIBatchEmbeddingRequest {
+            model: self.model.clone(),
+            input: texts.to_vec(),
+        };
+
+        let mut response = self
+            .client
+            .post(format!("{}/embeddings", api_url))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(EmbeddingError::Http)?
+            .json::<OpenAIEmbeddingResponse>()
+            .await?;
+
+        response.data.sort_by_key(|d| d.index);
+        Ok(response.data.into_iter().map(|d| d.embedding).collect())
+    }
+
     async fn generate_ollama(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
         let request = OllamaEmbeddingRequest {
             model: self.model.clone(),
@@ -285,6 +678,7 @@ impl EmbeddingGenerator {
     async fn fallback_to_ollama(
         &self,
         text: &str,
+        kind: EmbeddingKind,
         use_cache: bool,
         original_error: &EmbeddingError,
     ) -> Result<Vec<f32>, EmbeddingError> {
@@ -292,35 +686,15 @@ impl EmbeddingGenerator {
             "Using fallback Ollama ({}/{}) - primary unavailable",
             self.fallback_url, self.fallback_model
         );
+        self.fallback_invocations.fetch_add(1, Ordering::Relaxed);
 
-        let request = OllamaEmbeddingRequest {
-            model: self.fallback_model.clone(),
-            prompt: text.to_string(),
-        };
-
-        let response = self
-            .client
-            .post(format!("{}/api/embeddings", self.fallback_url))
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| {
-                EmbeddingError::BothFailed(original_error.to_string(), e.to_string())
-            })?
-            .error_for_status()
-            .map_err(|e| {
-                EmbeddingError::BothFailed(original_error.to_string(), e.to_string())
-            })?
-            .json::<OllamaEmbeddingResponse>()
+        let embedding = self
+            .request_fallback(text)
             .await
-            .map_err(|e| {
-                EmbeddingError::BothFailed(original_error.to_string(), e.to_string())
-            })?;
-
-        let embedding = response.embedding;
+            .map_err(|e| EmbeddingError::BothFailed(original_error.to_string(), e.to_string()))?;
 
         if use_cache {
-            self.cache.set(text, embedding.clone());
+            self.cache.set(EmbeddingCache::key(&self.fallback_model, kind, text), embedding.clone());
         }
 
         self.using_fallback.store(true, Ordering::SeqCst);
@@ -335,6 +709,37 @@ impl EmbeddingGenerator {
         Ok(embedding)
     }
 
+    async fn request_fallback(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        let request = OllamaEmbeddingRequest {
+            model: self.fallback_model.clone(),
+            prompt: text.to_string(),
+        };
+
+        self.with_deadline(async {
+            let response = self
+                .client
+                .post(format!("{}/api/embeddings", self.fallback_url))
+                .json(&request)
+                .send()
+                .await?
+                .error_for_status()
+                .map_err(EmbeddingError::Http)?
+                .json::<OllamaEmbeddingResponse>()
+                .await?;
+            Ok(response.embedding)
+        })
+        .await
+    }
+
+    
+    pub fn backend_stats(&self) -> EmbeddingBackendStats {
+        EmbeddingBackendStats {
+            fallback_invocations: self.fallback_invocations.load(Ordering::Relaxed),
+            fallback_successes: self.fallback_count.load(Ordering::SeqCst) as u64,
+            using_fallback: self.is_using_fallback(),
+        }
+    }
+
     
     pub fn is_using_fallback(&self) -> bool {
         self.using_fallback.load(Ordering::SeqCst)
@@ -351,6 +756,11 @@ impl EmbeddingGenerator {
     }
 
     
+    pub fn cache_stats(&self) -> EmbeddingCacheStats {
+        self.cache.stats()
+    }
+
+    
     pub fn clear_cache(&self) {
         self.cache.clear();
         info!("Embedding cache cleared");
@@ -372,3 +782,184 @@ impl EmbeddingGenerator {
         self.provider.clone()
     }
 }
+
+/// Scales `vector` to unit L2 length in place; all-zero vectors are left unchanged.
+pub fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > f32::EPSILON {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::test_support::{ollama_embedder, spawn_stub, StubServer};
+
+    fn generator() -> EmbeddingGenerator {
+        EmbeddingGenerator::new("ollama", "http://localhost:11434", "nomic-embed-text", None, None, 5, 10, 60, false, None, None)
+    }
+
+    #[test]
+    fn test_l2_normalize() {
+        let mut vector = vec![3.0, 4.0];
+        l2_normalize(&mut vector);
+        assert!((vector[0] - 0.6).abs() < 1e-6 && (vector[1] - 0.8).abs() < 1e-6);
+
+        let mut zeros = vec![0.0; 3];
+        l2_normalize(&mut zeros);
+        assert_eq!(zeros, vec![0.0; 3]);
+
+        assert!(!generator().is_normalized());
+        assert!(generator().with_normalization(true).is_normalized());
+    }
+
+    #[test]
+    fn test_validate_dimension_rejects_mismatch() {
+        let embedder = generator().with_expected_dim(768);
+        assert_eq!(embedder.expected_dim(), Some(768));
+        assert!(embedder.validate_dimension(&vec![0.1; 768]).is_ok());
+
+        let err = embedder.validate_dimension(&vec![0.1; 384]).unwrap_err();
+        assert!(matches!(err, EmbeddingError::DimensionMismatch { expected: 768, actual: 384, .. }));
+        assert!(err.to_string().contains("384 dimensions, expected 768"));
+    }
+
+    #[test]
+    fn test_validate_dimension_rejects_empty_without_expectation() {
+        let embedder = generator();
+        assert_eq!(embedder.expected_dim(), None);
+        assert!(embedder.validate_dimension(&[0.5, 0.5]).is_ok());
+        assert!(embedder.validate_dimension(&[]).is_err());
+    }
+
+    // Minimal Ollama stand-in: vectors are [text length, 1.0]; any text "boom" fails.
+    async fn spawn_embedding_stub() -> StubServer {
+        spawn_stub(|path, body| {
+            let vector = |text: &str| serde_json::json!([text.len() as f32, 1.0]);
+            match path {
+                "api/embed" => {
+                    let inputs: Vec<&str> = body["input"].as_array().into_iter().flatten().filter_map(|v| v.as_str()).collect();
+                    (!inputs.contains(&"boom"))
+                        .then(|| serde_json::json!({"embeddings": inputs.iter().map(|t| vector(t)).collect::<Vec<_>>()}))
+                }
+                "api/embeddings" => {
+                    let prompt = body["prompt"].as_str().unwrap_or_default();
+                    (prompt != "boom").then(|| serde_json::json!({"embedding": vector(prompt)}))
+                }
+                _ => None,
+            }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_generate_batch_sends_one_request_and_keeps_order() {
+        let stub = spawn_embedding_stub().await;
+        let embedder = ollama_embedder(stub.url());
+
+        let vectors = embedder.generate_batch(&["a", "ccc", "bb"], true).await.unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 1.0], vec![3.0, 1.0], vec![2.0, 1.0]]);
+        assert_eq!(stub.requests.lock().len(), 1);
+
+        embedder.generate_batch(&["ccc", "a"], true).await.unwrap();
+        assert_eq!(stub.requests.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_generate_batch_each_reports_partial_failures() {
+        let stub = spawn_embedding_stub().await;
+        let embedder = ollama_embedder(stub.url());
+
+        let results = embedder.generate_batch_each(&["dddd", "boom", "", "e"], false).await;
+        assert_eq!(results[0].as_ref().unwrap(), &vec![4.0, 1.0]);
+        assert!(results[1].is_err());
+        assert!(matches!(results[2], Err(EmbeddingError::EmptyText)));
+        assert_eq!(results[3].as_ref().unwrap(), &vec![1.0, 1.0]);
+        // One rejected batch, then one request per remaining text.
+        assert_eq!(stub.requests.lock().len(), 4);
+
+        assert!(embedder.generate_batch(&["dddd", "boom"], false).await.is_err());
+    }
+
+    // Primary points at a closed port, so every call has to go through the stub fallback.
+    fn failing_primary_generator(fallback_port: u16) -> EmbeddingGenerator {
+        EmbeddingGenerator::new(
+            "openai",
+            "http://127.0.0.1:1",
+            "text-embedding-3-small",
+            Some("key".to_string()),
+            Some("http://127.0.0.1:1".to_string()),
+            5,
+            10,
+            60,
+            true,
+            Some(format!("http://127.0.0.1:{}", fallback_port)),
+            Some("stub".to_string()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fallback_is_traced_and_counted() {
+        let stub = spawn_embedding_stub().await;
+        let embedder = failing_primary_generator(stub.port);
+
+        let (vector, backend) = embedder.generate_traced("abc", true).await.unwrap();
+        assert_eq!((vector, backend), (vec![3.0, 1.0], EmbeddingBackend::Fallback));
+        let (_, backend) = embedder.generate_traced("abc", true).await.unwrap();
+        assert_eq!(backend, EmbeddingBackend::Cache);
+        assert!(embedder.generate("boom", false).await.is_err());
+
+        let stats = embedder.backend_stats();
+        assert_eq!((stats.fallback_invocations, stats.fallback_successes), (2, 1));
+        assert!(stats.using_fallback);
+
+        let (_, backend) = ollama_embedder(stub.url()).generate_traced("abc", false).await.unwrap();
+        assert_eq!(backend, EmbeddingBackend::Primary);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_flags_fallback_dimension_mismatch() {
+        let stub = spawn_embedding_stub().await;
+
+        let healthy = ollama_embedder(stub.url()).health_check().await;
+        assert!(healthy.is_healthy());
+        assert_eq!(healthy.primary.dimension, Some(2));
+        assert!(healthy.fallback.is_none());
+
+        let embedder = failing_primary_generator(stub.port).with_expected_dim(1536);
+        let health = embedder.health_check().await;
+        assert!(!health.primary.ok && health.primary.error.is_some());
+        let fallback = health.fallback.as_ref().unwrap();
+        assert_eq!((fallback.ok, fallback.dimension), (true, Some(2)));
+        assert!(health.dimension_mismatch);
+        assert!(!health.is_healthy());
+        assert_eq!(embedder.backend_stats().fallback_invocations, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cache_separates_queries_documents_and_models() {
+        let stub = spawn_embedding_stub().await;
+        let embedder = ollama_embedder(stub.url());
+
+        embedder.generate("same text", true).await.unwrap();
+        embedder.generate("same text", true).await.unwrap();
+        assert_eq!(stub.requests.lock().len(), 1);
+
+        embedder.generate_query("same text", true).await.unwrap();
+        assert_eq!(stub.requests.lock().len(), 2);
+
+        let stats = embedder.cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.size), (1, 2, 2));
+        assert!((stats.hit_rate - 1.0 / 3.0).abs() < 1e-9);
+
+        assert_ne!(
+            EmbeddingCache::key("nomic-embed-text", EmbeddingKind::Document, "same text"),
+            EmbeddingCache::key("mxbai-embed-large", EmbeddingKind::Document, "same text"),
+        );
+
+        embedder.clear_cache();
+        assert_eq!(embedder.cache_stats().hits, 0);
+    }
+}
@@ -3,6 +3,7 @@
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, warn};
 
+use super::cache::{cache_key, TtlCache};
 use super::providers::base::{LlmProvider, LlmProviderError};
 
 
@@ -41,6 +42,29 @@ pub struct ExtractedEntity {
     
     #[serde(rename = "type")]
     pub entity_type: String,
+    /// How clearly the text is about this entity, 0-100
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<i32>,
+    /// The text's attitude toward the entity: positive, negative, neutral or mixed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment: Option<String>,
+}
+
+impl ExtractedEntity {
+    /// Link confidence, falling back to `DEFAULT_ENTITY_CONFIDENCE` when the extractor gave none.
+    pub fn link_confidence(&self) -> i32 {
+        self.confidence.map_or(DEFAULT_ENTITY_CONFIDENCE, |c| c.clamp(0, 100))
+    }
+
+    /// Normalized sentiment; anything unrecognized counts as neutral.
+    pub fn link_sentiment(&self) -> &'static str {
+        match self.sentiment.as_deref().map(|s| s.trim().to_lowercase()).as_deref() {
+            Some("positive") => "positive",
+            Some("negative") => "negative",
+            Some("mixed") => "mixed",
+            _ => "neutral",
+        }
+    }
 }
 
 
@@ -63,19 +87,58 @@ pub struct ExtractedRelation {
     pub explanation: String,
 }
 
+/// Entity link confidence when the extractor does not score the entity.
+pub const DEFAULT_ENTITY_CONFIDENCE: i32 = 80;
+
 fn default_strength() -> i32 { 80 }
 fn default_confidence() -> i32 { 80 }
 
 
+type ExtractionCache = TtlCache<ExtractionResult>;
+
+fn extraction_cache_key(text: &str, user_id: &str, extract_entities: bool, extract_relations: bool) -> String {
+    let flags = format!("{}{}", u8::from(extract_entities), u8::from(extract_relations));
+    cache_key(&[text, user_id, &flags])
+}
+
+
 pub struct LlmExtractor<P: LlmProvider> {
     provider: P,
+    cache: Option<ExtractionCache>,
 }
 
 impl<P: LlmProvider> LlmExtractor<P> {
     
     #[must_use]
     pub fn new(provider: P) -> Self {
-        Self { provider }
+        Self { provider, cache: None }
+    }
+
+    /// Reuses results for identical `(text, user_id, flags)` within `ttl_secs`. Only applies
+    /// while the provider reports a temperature of zero; sampled extractions always hit the LLM,
+    /// and configuring the cache for such a provider logs a warning.
+    #[must_use]
+    pub fn with_cache(mut self, capacity: usize, ttl_secs: u64) -> Self {
+        self.cache = Some(ExtractionCache::new(capacity, ttl_secs));
+        if self.active_cache().is_none() {
+            warn!(
+                "Extraction cache configured but {} samples at temperature {:?}; extractions will not be cached",
+                self.provider.provider_name(),
+                self.provider.temperature()
+            );
+        }
+        self
+    }
+
+    /// `(hits, misses)` of the extraction cache; zero when caching is off.
+    pub fn cache_stats(&self) -> (u64, u64) {
+        self.cache.as_ref().map_or((0, 0), |c| (c.hits(), c.misses()))
+    }
+
+    fn active_cache(&self) -> Option<&ExtractionCache> {
+        self.cache
+            .as_ref()
+            .filter(|_| matches!(self.provider.temperature(), Some(t) if t <= 0.0))
     }
 
     
@@ -93,6 +156,15 @@ impl<P: LlmProvider> LlmExtractor<P> {
             user_id
         );
 
+        let cache = self.active_cache();
+        let cache_key = cache.map(|_| extraction_cache_key(text, user_id, extract_entities, extract_relations));
+        if let (Some(cache), Some(key)) = (cache, cache_key.as_deref()) {
+            if let Some(cached) = cache.get(key) {
+                debug!("Extraction cache hit ({})", crate::safe_truncate(key, 12));
+                return Ok(cached);
+            }
+        }
+
         let system_prompt = self.build_system_prompt(extract_entities, extract_relations);
         let user_prompt = format!("Extract information from this text:\n\n{}", text);
 
@@ -110,6 +182,9 @@ impl<P: LlmProvider> LlmExtractor<P> {
                     result.entities.len(),
                     result.relations.len()
                 );
+                if let (Some(cache), Some(key)) = (cache, cache_key) {
+                    cache.put(key, result.clone());
+                }
                 Ok(result)
             }
             Err(e) => {
@@ -149,7 +224,9 @@ Output JSON with this structure:
     {
       "id": "unique_id",
       "name": "Entity Name",
-      "type": "person|organization|location|concept|system"
+      "type": "person|organization|location|concept|system",
+      "confidence": 80,
+      "sentiment": "positive|negative|neutral|mixed"
     }
   ]"#,
             );
@@ -188,6 +265,8 @@ CRITICAL for relations: Both from_memory_content and to_memory_content MUST be t
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::StubLlm;
+    use std::sync::Arc;
 
     #[test]
     fn test_extraction_result_serialization() {
@@ -203,6 +282,8 @@ mod tests {
                 id: "rust".to_string(),
                 name: "Rust".to_string(),
                 entity_type: "concept".to_string(),
+                confidence: None,
+                sentiment: None,
             }],
             relations: vec![],
         };
@@ -210,4 +291,47 @@ mod tests {
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("preference"));
     }
+
+    fn counting(temperature: f64) -> Arc<StubLlm> {
+        let response = r#"{"memories": [{"text": "User prefers Rust", "memory_type": "preference",
+            "certainty": 90, "importance": 70, "entities": []}], "entities": [], "relations": []}"#;
+        Arc::new(StubLlm::replying(response).with_temperature(temperature))
+    }
+
+    #[test]
+    fn test_entity_link_signals_follow_extractor() {
+        let entities: Vec<ExtractedEntity> = serde_json::from_value(serde_json::json!([
+            {"id": "rust", "name": "Rust", "type": "concept", "confidence": 95, "sentiment": "Positive"},
+            {"id": "go", "name": "Go", "type": "concept", "confidence": 15, "sentiment": "sarcastic"},
+            {"id": "c", "name": "C", "type": "concept"},
+        ]))
+        .unwrap();
+
+        assert_eq!((entities[0].link_confidence(), entities[0].link_sentiment()), (95, "positive"));
+        assert_eq!((entities[1].link_confidence(), entities[1].link_sentiment()), (15, "neutral"));
+        assert_eq!((entities[2].link_confidence(), entities[2].link_sentiment()), (DEFAULT_ENTITY_CONFIDENCE, "neutral"));
+        assert!(entities[1].link_confidence() < entities[2].link_confidence());
+    }
+
+    #[tokio::test]
+    async fn test_repeated_message_skips_extraction_llm_call() {
+        let provider = counting(0.0);
+        let extractor = LlmExtractor::new(provider.clone() as Arc<dyn LlmProvider>).with_cache(8, 60);
+
+        let first = extractor.extract("I prefer Rust", "alice", true, true).await.unwrap();
+        let second = extractor.extract("I prefer Rust", "alice", true, true).await.unwrap();
+        assert_eq!(first.memories[0].text, second.memories[0].text);
+        assert_eq!(provider.calls(), 1);
+        assert_eq!(extractor.cache_stats(), (1, 1));
+
+        extractor.extract("I prefer Rust", "bob", true, true).await.unwrap();
+        extractor.extract("I prefer Rust", "alice", true, false).await.unwrap();
+        assert_eq!(provider.calls(), 3);
+
+        let sampled = counting(0.7);
+        let extractor = LlmExtractor::new(sampled.clone() as Arc<dyn LlmProvider>).with_cache(8, 60);
+        extractor.extract("I prefer Rust", "alice", true, true).await.unwrap();
+        extractor.extract("I prefer Rust", "alice", true, true).await.unwrap();
+        assert_eq!(sampled.calls(), 2);
+    }
 }
@@ -21,6 +21,7 @@ impl LlmProviderFactory {
         api_key: Option<&str>,
         base_url: Option<&str>,
         temperature: f64,
+        context_window: Option<u32>,
     ) -> Box<dyn LlmProvider> {
         match provider {
             "cerebras" => Box::new(CerebrasProvider::new(
@@ -28,11 +29,17 @@ impl LlmProviderFactory {
                 model.to_string(),
                 temperature,
             )),
-            "ollama" => Box::new(OllamaProvider::new(
-                model.to_string(),
-                base_url.unwrap_or(DEFAULT_OLLAMA_URL).to_string(),
-                temperature,
-            )),
+            "ollama" => {
+                let provider = OllamaProvider::new(
+                    base_url.unwrap_or(DEFAULT_OLLAMA_URL).to_string(),
+                    model.to_string(),
+                    temperature,
+                );
+                Box::new(match context_window {
+                    Some(tokens) => provider.with_context_window(tokens),
+                    None => provider,
+                })
+            }
             _ => panic!("Unknown provider: {provider}. Supported: cerebras, ollama"),
         }
     }
@@ -94,6 +101,7 @@ mod tests {
             None,
             None,
             0.7,
+            None,
         );
         assert_eq!(provider.provider_name(), "ollama");
     }
@@ -106,13 +114,33 @@ mod tests {
             Some("test-key"),
             None,
             0.3,
+            None,
         );
         assert_eq!(provider.provider_name(), "cerebras");
+        assert!(provider.capabilities().supports_json_mode);
+    }
+
+    #[test]
+    fn test_provider_capabilities() {
+        let provider = LlmProviderFactory::create("ollama", "llama3.1:8b", None, None, 0.7, None);
+        let caps = provider.capabilities();
+        assert!(caps.supports_json_mode);
+        assert!(!caps.supports_tools);
+        assert!(!caps.supports_streaming);
+        assert_eq!(caps.max_context_tokens, 2048);
+
+        let sized = LlmProviderFactory::create("ollama", "llama3.1:8b", None, None, 0.7, Some(32768));
+        assert_eq!(sized.capabilities().max_context_tokens, 32768);
+
+        let conservative = crate::llm::providers::ProviderCapabilities::default();
+        assert!(!conservative.supports_json_mode);
+        assert!(!conservative.supports_tools);
+        assert!(!conservative.supports_streaming);
     }
 
     #[test]
     #[should_panic(expected = "Unknown provider")]
     fn test_unknown_provider_panics() {
-        LlmProviderFactory::create("unknown", "model", None, None, 0.5);
+        LlmProviderFactory::create("unknown", "model", None, None, 0.5, None);
     }
 }
@@ -2,6 +2,8 @@
 
 pub mod decision;
 
+pub(crate) mod cache;
+
 pub mod embeddings;
 pub mod extractor;
 pub mod factory;
@@ -9,5 +11,5 @@ pub mod providers;
 
 pub use decision::{LLMDecisionEngine, MemoryDecision, MemoryOperation, SimilarMemory};
 
-pub use embeddings::EmbeddingGenerator;
+pub use embeddings::{EmbeddingGenerator, EmbeddingCacheStats, EmbeddingBackend, EmbeddingBackendStats, EmbeddingHealth, BackendHealth};
 pub use extractor::LlmExtractor;
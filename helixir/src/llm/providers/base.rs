@@ -19,6 +19,9 @@ pub enum LlmProviderError {
 
     #[error("Internal error: {0}")]
     Internal(String),
+
+    #[error("LLM call timed out after {0:?}")]
+    Timeout(std::time::Duration),
 }
 
 
@@ -36,6 +39,9 @@ pub struct LlmMetadata {
     pub tokens_total: Option<u32>,
     #[serde(default)]
     pub fallback_used: bool,
+    /// Served by `CachedLlmProvider` without calling the model, so it cost no tokens
+    #[serde(default)]
+    pub cached: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub original_provider: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -43,6 +49,26 @@ pub struct LlmMetadata {
 }
 
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderCapabilities {
+    pub max_context_tokens: u32,
+    pub supports_json_mode: bool,
+    pub supports_tools: bool,
+    pub supports_streaming: bool,
+}
+
+impl Default for ProviderCapabilities {
+    fn default() -> Self {
+        Self {
+            max_context_tokens: 2048,
+            supports_json_mode: false,
+            supports_tools: false,
+            supports_streaming: false,
+        }
+    }
+}
+
+
 #[async_trait]
 pub trait LlmProvider: Send + Sync {
     
@@ -58,6 +84,21 @@ pub trait LlmProvider: Send + Sync {
 
     
     fn model_name(&self) -> &str;
+
+    
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities::default()
+    }
+
+    /// Sampling temperature, when the provider knows it; `None` means unknown.
+    fn temperature(&self) -> Option<f64> {
+        None
+    }
+
+    /// Cheap reachability check that spends no tokens; providers without one report reachable.
+    async fn health_check(&self) -> Result<(), LlmProviderError> {
+        Ok(())
+    }
 }
 
 
@@ -79,4 +120,16 @@ impl LlmProvider for Arc<dyn LlmProvider> {
     fn model_name(&self) -> &str {
         (**self).model_name()
     }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        (**self).capabilities()
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        (**self).temperature()
+    }
+
+    async fn health_check(&self) -> Result<(), LlmProviderError> {
+        (**self).health_check().await
+    }
 }
@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::base::{LlmMetadata, LlmProvider, LlmProviderError, ProviderCapabilities};
+use crate::llm::cache::{cache_key, TtlCache};
+
+
+/// LRU cache in front of another provider; identical prompts within the TTL skip the LLM.
+pub struct CachedLlmProvider {
+    inner: Arc<dyn LlmProvider>,
+    cache: TtlCache<(String, LlmMetadata)>,
+    deterministic_only: bool,
+}
+
+impl CachedLlmProvider {
+    
+    pub fn new(inner: Arc<dyn LlmProvider>, capacity: usize, ttl_secs: u64) -> Self {
+        Self {
+            inner,
+            cache: TtlCache::new(capacity, ttl_secs),
+            deterministic_only: false,
+        }
+    }
+
+    /// Only cache when the wrapped provider reports a temperature of zero.
+    pub fn with_deterministic_only(mut self, deterministic_only: bool) -> Self {
+        self.deterministic_only = deterministic_only;
+        if !self.is_cacheable() {
+            warn!(
+                "LLM cache is deterministic-only but {} samples at temperature {:?}; nothing will be cached",
+                self.inner.provider_name(),
+                self.inner.temperature()
+            );
+        }
+        self
+    }
+
+    
+    pub fn hits(&self) -> u64 {
+        self.cache.hits()
+    }
+
+    
+    pub fn misses(&self) -> u64 {
+        self.cache.misses()
+    }
+
+    
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
+
+    fn is_cacheable(&self) -> bool {
+        !self.deterministic_only || matches!(self.inner.temperature(), Some(t) if t <= 0.0)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for CachedLlmProvider {
+    async fn generate(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        response_format: Option<&str>,
+    ) -> Result<(String, LlmMetadata), LlmProviderError> {
+        if !self.is_cacheable() {
+            return self.inner.generate(system_prompt, user_prompt, response_format).await;
+        }
+
+        let key = cache_key(&[system_prompt, user_prompt, response_format.unwrap_or_default()]);
+        if let Some((content, mut metadata)) = self.cache.get(&key) {
+            debug!("LLM cache hit ({})", crate::safe_truncate(&key, 12));
+            metadata.cached = true;
+            return Ok((content, metadata));
+        }
+
+        let (content, metadata) = self.inner.generate(system_prompt, user_prompt, response_format).await?;
+        self.cache.put(key, (content.clone(), metadata.clone()));
+        Ok((content, metadata))
+    }
+
+    fn provider_name(&self) -> &str {
+        self.inner.provider_name()
+    }
+
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        self.inner.temperature()
+    }
+
+    async fn health_check(&self) -> Result<(), LlmProviderError> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::StubLlm;
+
+    fn counting(temperature: f64) -> Arc<StubLlm> {
+        let provider = StubLlm::responding(|user_prompt, call| Ok(format!("{} #{}", user_prompt, call)));
+        Arc::new(provider.with_temperature(temperature))
+    }
+
+    #[tokio::test]
+    async fn test_identical_prompt_is_served_from_cache() {
+        let inner = counting(0.0);
+        let cached = CachedLlmProvider::new(inner.clone(), 8, 60);
+
+        let first = cached.generate("sys", "same prompt", None).await.unwrap();
+        let second = cached.generate("sys", "same prompt", None).await.unwrap();
+        assert_eq!(first.0, second.0);
+        assert_eq!(inner.calls(), 1);
+        assert_eq!((cached.hits(), cached.misses()), (1, 1));
+
+        cached.generate("sys", "same prompt", Some("json")).await.unwrap();
+        assert_eq!(inner.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_only_bypasses_sampled_calls() {
+        let inner = counting(0.7);
+        let cached = CachedLlmProvider::new(inner.clone(), 8, 60).with_deterministic_only(true);
+
+        cached.generate("sys", "prompt", None).await.unwrap();
+        cached.generate("sys", "prompt", None).await.unwrap();
+        assert_eq!(inner.calls(), 2);
+        assert_eq!(cached.hits(), 0);
+    }
+}
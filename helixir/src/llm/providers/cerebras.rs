@@ -5,7 +5,7 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use super::base::{LlmMetadata, LlmProvider, LlmProviderError};
+use super::base::{LlmMetadata, LlmProvider, LlmProviderError, ProviderCapabilities};
 
 #[derive(Debug, Serialize)]
 struct CerebrasRequest {
@@ -140,4 +140,28 @@ impl LlmProvider for CerebrasProvider {
     fn model_name(&self) -> &str {
         &self.model
     }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            max_context_tokens: 8192,
+            supports_json_mode: true,
+            supports_tools: false,
+            supports_streaming: false,
+        }
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        Some(self.temperature)
+    }
+
+    /// Lists the account's models rather than generating, which also verifies the API key.
+    async fn health_check(&self) -> Result<(), LlmProviderError> {
+        self.client
+            .get("https://api.cerebras.ai/v1/models")
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
 }
@@ -1,13 +1,15 @@
 
 
 use async_trait::async_trait;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
-use super::base::{LlmMetadata, LlmProvider, LlmProviderError};
+use super::base::{LlmMetadata, LlmProvider, LlmProviderError, ProviderCapabilities};
 use super::ollama::OllamaProvider;
+use super::timeout::TimeoutProvider;
 
 const DEFAULT_FALLBACK_URL: &str = "http://localhost:11434";
 const DEFAULT_FALLBACK_MODEL: &str = "llama3.2";
@@ -190,4 +192,200 @@ impl LlmProvider for LlmProviderWithFallback {
             self.primary.model_name()
         }
     }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        if self.using_fallback.load(Ordering::SeqCst) {
+            OllamaProvider::CAPABILITIES
+        } else {
+            self.primary.capabilities()
+        }
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        if self.using_fallback.load(Ordering::SeqCst) {
+            Some(self.temperature)
+        } else {
+            self.primary.temperature()
+        }
+    }
+    async fn health_check(&self) -> Result<(), LlmProviderError> {
+        match self.primary.health_check().await {
+            Err(e) if self.fallback_enabled => {
+                debug!("Primary LLM unreachable ({}), checking fallback", e);
+                self.get_fallback_provider().await.health_check().await
+            }
+            result => result,
+        }
+    }
+}
+
+
+/// Tries an ordered chain of providers, moving to the next one when a call fails or
+/// outlives that provider's timeout. Name, model and capabilities report the provider
+/// that served the most recent call.
+pub struct FallbackLlmProvider {
+    chain: Vec<Arc<dyn LlmProvider>>,
+    served_counts: Vec<AtomicU64>,
+    last_served: AtomicUsize,
+}
+
+impl FallbackLlmProvider {
+    /// `timeout` applies to every provider in `providers`.
+    pub fn new(providers: Vec<Arc<dyn LlmProvider>>, timeout: Option<Duration>) -> Self {
+        let chain = Self { chain: Vec::new(), served_counts: Vec::new(), last_served: AtomicUsize::new(0) };
+        providers.into_iter().fold(chain, |chain, provider| chain.with_provider(provider, timeout))
+    }
+
+    /// Appends `provider` to the end of the chain with its own timeout.
+    pub fn with_provider(mut self, provider: Arc<dyn LlmProvider>, timeout: Option<Duration>) -> Self {
+        let provider: Arc<dyn LlmProvider> = match timeout {
+            Some(timeout) => Arc::new(TimeoutProvider::new(provider, timeout)),
+            None => provider,
+        };
+        self.chain.push(provider);
+        self.served_counts.push(AtomicU64::new(0));
+        self
+    }
+
+    
+    pub fn len(&self) -> usize {
+        self.chain.len()
+    }
+
+    
+    pub fn is_empty(&self) -> bool {
+        self.chain.is_empty()
+    }
+
+    /// Position in the chain of the provider that served the most recent successful call.
+    pub fn last_served(&self) -> usize {
+        self.last_served.load(Ordering::Relaxed)
+    }
+
+    /// Successful calls per provider, in chain order.
+    pub fn served_counts(&self) -> Vec<u64> {
+        self.served_counts.iter().map(|c| c.load(Ordering::Relaxed)).collect()
+    }
+
+    fn current(&self) -> Option<&Arc<dyn LlmProvider>> {
+        self.chain.get(self.last_served()).or(self.chain.first())
+    }
+}
+
+#[async_trait]
+impl LlmProvider for FallbackLlmProvider {
+    async fn generate(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        response_format: Option<&str>,
+    ) -> Result<(String, LlmMetadata), LlmProviderError> {
+        let mut failures: Vec<String> = Vec::new();
+
+        for (index, provider) in self.chain.iter().enumerate() {
+            match provider.generate(system_prompt, user_prompt, response_format).await {
+                Ok((content, mut metadata)) => {
+                    if index > 0 {
+                        metadata.fallback_used = true;
+                        metadata.original_provider = Some(self.chain[0].provider_name().to_string());
+                        metadata.original_error = failures.last().cloned();
+                    }
+                    self.served_counts[index].fetch_add(1, Ordering::Relaxed);
+                    self.last_served.store(index, Ordering::Relaxed);
+                    debug!("LLM call served by {} (chain position {})", provider.provider_name(), index);
+                    return Ok((content, metadata));
+                }
+                Err(e) => {
+                    warn!("LLM provider {} failed, trying next in chain: {}", provider.provider_name(), e);
+                    failures.push(format!("{}: {}", provider.provider_name(), e));
+                }
+            }
+        }
+
+        if failures.is_empty() {
+            return Err(LlmProviderError::Internal("fallback chain has no providers".to_string()));
+        }
+        Err(LlmProviderError::Provider(format!(
+            "all {} providers in the chain failed: {}",
+            failures.len(),
+            failures.join("; ")
+        )))
+    }
+
+    fn provider_name(&self) -> &str {
+        self.current().map_or("none", |p| p.provider_name())
+    }
+
+    fn model_name(&self) -> &str {
+        self.current().map_or("none", |p| p.model_name())
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.current().map(|p| p.capabilities()).unwrap_or_default()
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        self.current().and_then(|p| p.temperature())
+    }
+    /// Reachable while any provider in the chain is.
+    async fn health_check(&self) -> Result<(), LlmProviderError> {
+        let mut failures: Vec<String> = Vec::new();
+        for provider in &self.chain {
+            match provider.health_check().await {
+                Ok(()) => return Ok(()),
+                Err(e) => failures.push(format!("{}: {}", provider.provider_name(), e)),
+            }
+        }
+        if failures.is_empty() {
+            return Err(LlmProviderError::Internal("fallback chain has no providers".to_string()));
+        }
+        Err(LlmProviderError::Provider(format!("no provider in the chain is reachable: {}", failures.join("; "))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::StubLlm;
+
+    fn stub(name: &'static str, fail: bool) -> Arc<StubLlm> {
+        let provider = if fail {
+            StubLlm::failing(format!("{} is down", name))
+        } else {
+            StubLlm::replying(format!("from {}", name))
+        };
+        Arc::new(provider.named(name))
+    }
+
+    #[tokio::test]
+    async fn test_chain_falls_through_to_next_provider() {
+        let local = stub("local", true);
+        let cloud = stub("cloud", false);
+        let chain = FallbackLlmProvider::new(vec![local.clone(), cloud.clone()], Some(Duration::from_secs(1)));
+
+        let (content, metadata) = chain.generate("sys", "user", None).await.unwrap();
+        assert_eq!(content, "from cloud");
+        assert_eq!(metadata.provider, "cloud");
+        assert!(metadata.fallback_used);
+        assert_eq!(metadata.original_provider.as_deref(), Some("local"));
+        assert!(metadata.original_error.unwrap().contains("local is down"));
+
+        assert_eq!(chain.last_served(), 1);
+        assert_eq!(chain.served_counts(), vec![0, 1]);
+        assert_eq!(chain.provider_name(), "cloud");
+        assert_eq!(local.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_chain_reports_every_failure() {
+        let chain = FallbackLlmProvider::new(
+            vec![stub("a", true), stub("b", true)],
+            None,
+        );
+        let err = chain.generate("sys", "user", None).await.unwrap_err().to_string();
+        assert!(err.contains("a is down") && err.contains("b is down"));
+
+        let empty = FallbackLlmProvider::new(Vec::new(), None);
+        assert!(matches!(empty.generate("sys", "user", None).await, Err(LlmProviderError::Internal(_))));
+    }
 }
@@ -4,8 +4,14 @@ pub mod base;
 pub mod cerebras;
 pub mod ollama;
 pub mod fallback;
+pub mod cached;
+pub mod usage;
+pub mod timeout;
 
-pub use base::{LlmMetadata, LlmProvider, LlmProviderError};
+pub use base::{LlmMetadata, LlmProvider, LlmProviderError, ProviderCapabilities};
 pub use cerebras::CerebrasProvider;
 pub use ollama::OllamaProvider;
-pub use fallback::LlmProviderWithFallback;
+pub use fallback::{FallbackLlmProvider, LlmProviderWithFallback};
+pub use cached::CachedLlmProvider;
+pub use usage::{TokenUsage, UsageTrackingProvider};
+pub use timeout::TimeoutProvider;
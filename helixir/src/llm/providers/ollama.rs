@@ -5,7 +5,7 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use super::base::{LlmMetadata, LlmProvider, LlmProviderError};
+use super::base::{LlmMetadata, LlmProvider, LlmProviderError, ProviderCapabilities};
 
 #[derive(Debug, Serialize)]
 struct OllamaRequest {
@@ -26,6 +26,8 @@ struct OllamaMessage {
 #[derive(Debug, Serialize)]
 struct OllamaOptions {
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ctx: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -44,6 +46,7 @@ pub struct OllamaProvider {
     base_url: String,
     model: String,
     temperature: f64,
+    context_window: Option<u32>,
     client: Client,
 }
 
@@ -61,6 +64,7 @@ impl OllamaProvider {
             base_url,
             model,
             temperature,
+            context_window: None,
             client: Client::builder()
                 .timeout(std::time::Duration::from_secs(600))
                 .build()
@@ -72,6 +76,20 @@ impl OllamaProvider {
     pub fn localhost(model: impl Into<String>, temperature: f64) -> Self {
         Self::new("http://localhost:11434", model, temperature)
     }
+
+    /// Requests a `num_ctx` context window for every call, instead of the server's default.
+    pub fn with_context_window(mut self, tokens: u32) -> Self {
+        self.context_window = Some(tokens);
+        self
+    }
+
+    /// Capabilities while the server picks the context window, assumed to be its 2048-token default.
+    pub const CAPABILITIES: ProviderCapabilities = ProviderCapabilities {
+        max_context_tokens: 2048,
+        supports_json_mode: true,
+        supports_tools: false,
+        supports_streaming: false,
+    };
 }
 
 #[async_trait]
@@ -105,6 +123,7 @@ impl LlmProvider for OllamaProvider {
             stream: false,
             options: OllamaOptions {
                 temperature: self.temperature,
+                num_ctx: self.context_window,
             },
             format,
         };
@@ -142,4 +161,25 @@ impl LlmProvider for OllamaProvider {
     fn model_name(&self) -> &str {
         &self.model
     }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            max_context_tokens: self.context_window.unwrap_or(Self::CAPABILITIES.max_context_tokens),
+            ..Self::CAPABILITIES
+        }
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        Some(self.temperature)
+    }
+
+    /// Lists the server's local models rather than generating.
+    async fn health_check(&self) -> Result<(), LlmProviderError> {
+        self.client
+            .get(format!("{}/api/tags", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
 }
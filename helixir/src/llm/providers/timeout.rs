@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use super::base::{LlmMetadata, LlmProvider, LlmProviderError, ProviderCapabilities};
+
+
+/// Fails calls to the wrapped provider that outlive `timeout` with `LlmProviderError::Timeout`.
+pub struct TimeoutProvider {
+    inner: Arc<dyn LlmProvider>,
+    timeout: Duration,
+}
+
+impl TimeoutProvider {
+    
+    pub fn new(inner: Arc<dyn LlmProvider>, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for TimeoutProvider {
+    async fn generate(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        response_format: Option<&str>,
+    ) -> Result<(String, LlmMetadata), LlmProviderError> {
+        tokio::time::timeout(self.timeout, self.inner.generate(system_prompt, user_prompt, response_format))
+            .await
+            .unwrap_or_else(|_| {
+                warn!("{} call timed out after {:?}", self.inner.provider_name(), self.timeout);
+                Err(LlmProviderError::Timeout(self.timeout))
+            })
+    }
+
+    fn provider_name(&self) -> &str {
+        self.inner.provider_name()
+    }
+
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        self.inner.temperature()
+    }
+
+    async fn health_check(&self) -> Result<(), LlmProviderError> {
+        tokio::time::timeout(self.timeout, self.inner.health_check())
+            .await
+            .unwrap_or(Err(LlmProviderError::Timeout(self.timeout)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::StubLlm;
+
+    fn sleepy(delay: Duration) -> Arc<StubLlm> {
+        Arc::new(StubLlm::replying("done").with_delay(delay))
+    }
+
+    #[tokio::test]
+    async fn test_provider_past_deadline_times_out() {
+        let slow = TimeoutProvider::new(sleepy(Duration::from_secs(5)), Duration::from_millis(20));
+        let err = slow.generate("sys", "user", None).await.unwrap_err();
+        assert!(matches!(err, LlmProviderError::Timeout(d) if d == Duration::from_millis(20)));
+
+        let fast = TimeoutProvider::new(sleepy(Duration::ZERO), Duration::from_millis(200));
+        assert_eq!(fast.generate("sys", "user", None).await.unwrap().0, "done");
+    }
+}
@@ -0,0 +1,185 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use super::base::{LlmMetadata, LlmProvider, LlmProviderError, ProviderCapabilities};
+
+/// Rough characters-per-token ratio for English text on BPE tokenizers.
+const CHARS_PER_TOKEN: u64 = 4;
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total: u64,
+    pub calls: u64,
+    /// Calls whose provider reported no usage and were counted by approximation
+    pub estimated_calls: u64,
+    pub estimated: bool,
+}
+
+impl TokenUsage {
+    /// Usage accrued between `earlier` and this snapshot.
+    pub fn since(&self, earlier: &TokenUsage) -> TokenUsage {
+        let estimated_calls = self.estimated_calls.saturating_sub(earlier.estimated_calls);
+        TokenUsage {
+            prompt_tokens: self.prompt_tokens.saturating_sub(earlier.prompt_tokens),
+            completion_tokens: self.completion_tokens.saturating_sub(earlier.completion_tokens),
+            total: self.total.saturating_sub(earlier.total),
+            calls: self.calls.saturating_sub(earlier.calls),
+            estimated_calls,
+            estimated: estimated_calls > 0,
+        }
+    }
+}
+
+
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(CHARS_PER_TOKEN)
+}
+
+
+/// Counts tokens for every successful call made through the wrapped provider; responses
+/// replayed from `CachedLlmProvider` cost nothing and are not counted.
+pub struct UsageTrackingProvider {
+    inner: Arc<dyn LlmProvider>,
+    prompt_tokens: AtomicU64,
+    completion_tokens: AtomicU64,
+    total: AtomicU64,
+    calls: AtomicU64,
+    estimated_calls: AtomicU64,
+}
+
+impl UsageTrackingProvider {
+    
+    pub fn new(inner: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            inner,
+            prompt_tokens: AtomicU64::new(0),
+            completion_tokens: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+            calls: AtomicU64::new(0),
+            estimated_calls: AtomicU64::new(0),
+        }
+    }
+
+    
+    pub fn usage(&self) -> TokenUsage {
+        let estimated_calls = self.estimated_calls.load(Ordering::Relaxed);
+        TokenUsage {
+            prompt_tokens: self.prompt_tokens.load(Ordering::Relaxed),
+            completion_tokens: self.completion_tokens.load(Ordering::Relaxed),
+            total: self.total.load(Ordering::Relaxed),
+            calls: self.calls.load(Ordering::Relaxed),
+            estimated_calls,
+            estimated: estimated_calls > 0,
+        }
+    }
+
+    // Ollama reports zero counts rather than omitting them when it skips usage.
+    fn record(&self, system_prompt: &str, user_prompt: &str, content: &str, metadata: &LlmMetadata) {
+        let reported = match (metadata.tokens_prompt, metadata.tokens_completion) {
+            (Some(prompt), Some(completion)) if prompt > 0 || completion > 0 => {
+                let (prompt, completion) = (u64::from(prompt), u64::from(completion));
+                Some((prompt, completion, metadata.tokens_total.map_or(prompt + completion, u64::from)))
+            }
+            _ => None,
+        };
+        let (prompt, completion, total) = reported.unwrap_or_else(|| {
+            self.estimated_calls.fetch_add(1, Ordering::Relaxed);
+            let prompt = estimate_tokens(system_prompt) + estimate_tokens(user_prompt);
+            let completion = estimate_tokens(content);
+            (prompt, completion, prompt + completion)
+        });
+
+        self.prompt_tokens.fetch_add(prompt, Ordering::Relaxed);
+        self.completion_tokens.fetch_add(completion, Ordering::Relaxed);
+        self.total.fetch_add(total, Ordering::Relaxed);
+        self.calls.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl LlmProvider for UsageTrackingProvider {
+    async fn generate(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        response_format: Option<&str>,
+    ) -> Result<(String, LlmMetadata), LlmProviderError> {
+        let (content, metadata) = self.inner.generate(system_prompt, user_prompt, response_format).await?;
+        if !metadata.cached {
+            self.record(system_prompt, user_prompt, &content, &metadata);
+        }
+        Ok((content, metadata))
+    }
+
+    fn provider_name(&self) -> &str {
+        self.inner.provider_name()
+    }
+
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        self.inner.temperature()
+    }
+
+    async fn health_check(&self) -> Result<(), LlmProviderError> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::StubLlm;
+
+    fn fixed(reports_usage: bool) -> Arc<StubLlm> {
+        let mut metadata = LlmMetadata::default();
+        if reports_usage {
+            metadata.tokens_prompt = Some(30);
+            metadata.tokens_completion = Some(12);
+            metadata.tokens_total = Some(42);
+        }
+        Arc::new(StubLlm::replying("12345678").with_metadata(metadata))
+    }
+
+    #[tokio::test]
+    async fn test_usage_accumulates_reported_and_estimated_counts() {
+        let reported = UsageTrackingProvider::new(fixed(true));
+        reported.generate("system", "user", None).await.unwrap();
+        reported.generate("system", "user", None).await.unwrap();
+        let usage = reported.usage();
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens, usage.total, usage.calls), (60, 24, 84, 2));
+        assert!(!usage.estimated);
+
+        let estimated = UsageTrackingProvider::new(fixed(false));
+        let before = estimated.usage();
+        estimated.generate("abcd", "abcdefghi", None).await.unwrap();
+        let delta = estimated.usage().since(&before);
+        assert_eq!((delta.prompt_tokens, delta.completion_tokens, delta.total), (4, 2, 6));
+        assert!(delta.estimated);
+        assert_eq!(delta.estimated_calls, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_hits_are_not_counted() {
+        let cached = super::super::CachedLlmProvider::new(fixed(true), 8, 60);
+        let tracked = UsageTrackingProvider::new(Arc::new(cached));
+
+        tracked.generate("system", "user", None).await.unwrap();
+        let (_, replayed) = tracked.generate("system", "user", None).await.unwrap();
+        assert!(replayed.cached);
+
+        let usage = tracked.usage();
+        assert_eq!((usage.total, usage.calls), (42, 1));
+    }
+}
@@ -9,6 +9,10 @@ pub struct AddMemoryParams {
     pub user_id: String,
     #[schemars(description = "Optional agent identifier")]
     pub agent_id: Option<String>,
+    #[schemars(description = "Optional key making retries safe: a repeated key returns the earlier result")]
+    pub idempotency_key: Option<String>,
+    #[schemars(description = "Override chunking threshold in chars for this call (0 always chunks)")]
+    pub chunk_threshold: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
@@ -19,12 +23,14 @@ pub struct SearchMemoryParams {
     pub user_id: String,
     #[schemars(description = "Max results (default: mode-based)")]
     pub limit: Option<i32>,
-    #[schemars(description = "Search mode: 'recent' (4h), 'contextual' (30d), 'deep' (90d), 'full'")]
+    #[schemars(description = "Search mode: 'recent' (4h), 'contextual' (30d), 'deep' (90d), 'full', 'vector' (no graph expansion: faster, lower recall), 'hybrid' (vector + BM25 keyword match), 'auto' (chosen from the query's intent)")]
     pub mode: Option<String>,
     #[schemars(description = "Override time window in days")]
     pub temporal_days: Option<f64>,
     #[schemars(description = "Override graph depth")]
     pub graph_depth: Option<i32>,
+    #[schemars(description = "Also return archived memories (default: false)")]
+    pub include_archived: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
@@ -45,6 +51,8 @@ pub struct GetMemoryGraphParams {
     pub memory_id: Option<String>,
     #[schemars(description = "Traversal depth (default: 2)")]
     pub depth: Option<i32>,
+    #[schemars(description = "Seed memories when no memory_id is given (default: 10)")]
+    pub max_seeds: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
@@ -61,6 +69,8 @@ pub struct SearchByConceptParams {
     pub mode: Option<String>,
     #[schemars(description = "Max results (default: 10)")]
     pub limit: Option<i32>,
+    #[schemars(description = "Also match subtypes of the concept type (default: false)")]
+    pub include_subconcepts: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, rmcp::schemars::JsonSchema)]
@@ -75,6 +85,8 @@ pub struct SearchReasoningChainParams {
     pub max_depth: Option<i32>,
     #[schemars(description = "Number of seed memories")]
     pub limit: Option<i32>,
+    #[schemars(description = "Comma-separated edge types to follow, overriding the chain mode (e.g. 'BECAUSE' or 'IMPLIES,BECAUSE')")]
+    pub edge_types: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -19,6 +19,7 @@ use tracing::{info, warn};
 use crate::core::config::HelixirConfig;
 use crate::core::helixir_client::{HelixirClient, HelixirClientError};
 use crate::toolkit::fast_think::{FastThinkManager, FastThinkLimits, FastThinkError, ThoughtType};
+use crate::toolkit::mind_toolbox::reasoning::ReasoningType;
 
 use super::params::*;
 use super::prompts;
@@ -61,6 +62,25 @@ impl HelixirMcpServer {
         }
     }
 
+    /// Comma-separated edge names; an unknown name is an invalid-params error naming it.
+    fn parse_edge_types(types: &str) -> Result<Vec<ReasoningType>, McpError> {
+        types
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| {
+                ReasoningType::from_edge_name(name).ok_or_else(|| {
+                    McpError::invalid_params(
+                        format!(
+                            "unknown edge type '{}' (expected IMPLIES, BECAUSE, CONTRADICTS or SUPPORTS)",
+                            name.trim()
+                        ),
+                        None,
+                    )
+                })
+            })
+            .collect()
+    }
+
     fn result_to_json<T: Serialize>(result: T) -> Result<String, McpError> {
         serde_json::to_string_pretty(&result)
             .map_err(|e| McpError::internal_error(e.to_string(), None))
@@ -69,7 +89,7 @@ impl HelixirMcpServer {
 
 #[tool_router]
 impl HelixirMcpServer {
-    #[tool(description = "Add memory with LLM-powered extraction. Extracts atomic facts, generates embeddings, creates graph relations. Returns: {memories_added, entities, relations, memory_ids, chunks_created}")]
+    #[tool(description = "Add memory with LLM-powered extraction. Extracts atomic facts, generates embeddings, creates graph relations. Returns: {memories_added, memory_ids, memories_updated, updated_ids, memories_deleted, deleted_ids, skipped, entities_extracted, relations_created, chunks_created}")]
     async fn add_memory(
         &self,
         Parameters(params): Parameters<AddMemoryParams>,
@@ -77,13 +97,21 @@ impl HelixirMcpServer {
         info!("🧠 Adding memory for user={}", params.user_id);
 
         let result = self.client
-            .add(&params.message, &params.user_id, params.agent_id.as_deref(), None)
+            .add(
+                &params.message,
+                &params.user_id,
+                params.agent_id.as_deref(),
+                None,
+                params.idempotency_key.as_deref(),
+                params.chunk_threshold,
+            )
             .await
             .map_err(Self::convert_error)?;
 
         info!(
-            "✅ Added {} memories ({} chunks)",
+            "✅ Added {} memories, updated {} ({} chunks)",
             result.memories_added,
+            result.memories_updated,
             result.chunks_created
         );
 
@@ -91,7 +119,7 @@ impl HelixirMcpServer {
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
-    #[tool(description = "Smart memory search with automatic strategy selection. Modes: 'recent' (4h, fast), 'contextual' (30d, balanced), 'deep' (90d), 'full' (all). Returns: [{memory_id, content, score, metadata}]")]
+    #[tool(description = "Smart memory search with automatic strategy selection. Modes: 'recent' (4h, fast), 'contextual' (30d, balanced), 'deep' (90d), 'full' (all), 'vector' (pure vector search, no graph expansion: lowest latency, lower recall), 'hybrid' (vector + BM25 keyword match over the user's memories), 'auto' (picks recent, causal or contextual from the query). Returns: [{memory_id, content, score, metadata}]")]
     async fn search_memory(
         &self,
         Parameters(params): Parameters<SearchMemoryParams>,
@@ -113,6 +141,7 @@ impl HelixirMcpServer {
                 Some(&mode),
                 params.temporal_days,
                 params.graph_depth.map(|d| d as usize),
+                params.include_archived.unwrap_or(false),
             )
             .await
             .map_err(Self::convert_error)?;
@@ -154,7 +183,12 @@ impl HelixirMcpServer {
         info!("📊 Getting memory graph for user={}", params.user_id);
 
         let result = self.client
-            .get_graph(&params.user_id, params.memory_id.as_deref(), params.depth.map(|d| d as usize))
+            .get_graph(
+                &params.user_id,
+                params.memory_id.as_deref(),
+                params.depth.map(|d| d as usize),
+                params.max_seeds.map(|s| s as usize),
+            )
             .await
             .map_err(Self::convert_error)?;
 
@@ -181,6 +215,7 @@ impl HelixirMcpServer {
                 params.tags.as_deref(),
                 params.mode.as_deref(),
                 params.limit.map(|l| l as usize),
+                params.include_subconcepts.unwrap_or(false),
             )
             .await
             .map_err(Self::convert_error)?;
@@ -204,6 +239,8 @@ impl HelixirMcpServer {
             query_preview, chain_mode
         );
 
+        let edge_types = params.edge_types.as_deref().map(Self::parse_edge_types).transpose()?;
+
         let result = self.client
             .search_reasoning_chain(
                 &params.query,
@@ -211,6 +248,7 @@ impl HelixirMcpServer {
                 Some(&chain_mode),
                 params.max_depth.map(|d| d as usize),
                 params.limit.map(|l| l as usize),
+                edge_types.as_deref(),
             )
             .await
             .map_err(Self::convert_error)?;
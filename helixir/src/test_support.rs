@@ -0,0 +1,227 @@
+//! Fixtures shared by unit tests: a scripted HTTP server that stands in for HelixDB and
+//! Ollama, the ollama-backed `EmbeddingGenerator` pointed at it, and an in-process `StubLlm`.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::db::HelixClient;
+use crate::llm::embeddings::EmbeddingGenerator;
+use crate::llm::providers::base::{LlmMetadata, LlmProvider, LlmProviderError};
+
+/// A running stub server.
+pub(crate) struct StubServer {
+    pub port: u16,
+    /// Request paths without the leading '/' (HelixDB query names), in arrival order
+    pub requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl StubServer {
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    pub fn client(&self) -> Arc<HelixClient> {
+        Arc::new(HelixClient::new("127.0.0.1", self.port).unwrap())
+    }
+
+    /// How many requests hit `path`.
+    pub fn count(&self, path: &str) -> usize {
+        self.requests.lock().iter().filter(|p| p.as_str() == path).count()
+    }
+}
+
+/// Answers each request with `respond(path, body)` as JSON; `None` answers HTTP 500.
+pub(crate) async fn spawn_stub<F>(respond: F) -> StubServer
+where
+    F: Fn(&str, &serde_json::Value) -> Option<serde_json::Value> + Send + Sync + 'static,
+{
+    let respond = Arc::new(respond);
+    spawn_async_stub(move |path, body| {
+        let respond = Arc::clone(&respond);
+        async move { respond(&path, &body) }
+    })
+    .await
+}
+
+/// Answers every request with `reply`.
+pub(crate) async fn spawn_reply_stub(reply: serde_json::Value) -> StubServer {
+    spawn_stub(move |_, _| Some(reply.clone())).await
+}
+
+/// Like `spawn_stub`, for responders that need to await, e.g. to simulate latency.
+pub(crate) async fn spawn_async_stub<F, Fut>(respond: F) -> StubServer
+where
+    F: Fn(String, serde_json::Value) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Option<serde_json::Value>> + Send + 'static,
+{
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests: Arc<Mutex<Vec<String>>> = Arc::default();
+    let log = Arc::clone(&requests);
+    let respond = Arc::new(respond);
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let log = Arc::clone(&log);
+            let respond = Arc::clone(&respond);
+            tokio::spawn(async move {
+                let (path, body) = read_request(&mut socket).await;
+                log.lock().push(path.clone());
+                let reply = respond(path, body).await;
+                write_reply(&mut socket, reply).await;
+            });
+        }
+    });
+
+    StubServer { port, requests }
+}
+
+/// An ollama `EmbeddingGenerator` talking to `url`, without fallback.
+pub(crate) fn ollama_embedder(url: impl Into<String>) -> EmbeddingGenerator {
+    EmbeddingGenerator::new("ollama", url, "stub-embed", None, None, 5, 10, 60, false, None, None)
+}
+
+type Responder = dyn Fn(&str, usize) -> Result<String, LlmProviderError> + Send + Sync;
+
+/// An `LlmProvider` answering from a closure, counting calls.
+pub(crate) struct StubLlm {
+    name: &'static str,
+    respond: Box<Responder>,
+    metadata: LlmMetadata,
+    temperature: Option<f64>,
+    delay: Duration,
+    calls: AtomicUsize,
+}
+
+impl StubLlm {
+    /// Answers with `respond(user_prompt, call_index)`.
+    pub fn responding<F>(respond: F) -> Self
+    where
+        F: Fn(&str, usize) -> Result<String, LlmProviderError> + Send + Sync + 'static,
+    {
+        Self {
+            name: "stub",
+            respond: Box::new(respond),
+            metadata: LlmMetadata::default(),
+            temperature: None,
+            delay: Duration::ZERO,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Answers every prompt with `reply`.
+    pub fn replying(reply: impl Into<String>) -> Self {
+        let reply = reply.into();
+        Self::responding(move |_, _| Ok(reply.clone()))
+    }
+
+    /// Fails every call with `LlmProviderError::Provider(message)`.
+    pub fn failing(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self::responding(move |_, _| Err(LlmProviderError::Provider(message.clone())))
+    }
+
+    /// Reported as both `provider_name` and `metadata.provider`.
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: LlmMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Sleeps this long before answering.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for StubLlm {
+    async fn generate(
+        &self,
+        _system_prompt: &str,
+        user_prompt: &str,
+        _response_format: Option<&str>,
+    ) -> Result<(String, LlmMetadata), LlmProviderError> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        let content = (self.respond)(user_prompt, call)?;
+        let metadata = LlmMetadata { provider: self.name.to_string(), ..self.metadata.clone() };
+        Ok((content, metadata))
+    }
+
+    fn provider_name(&self) -> &str {
+        self.name
+    }
+
+    fn model_name(&self) -> &str {
+        "test"
+    }
+
+    fn temperature(&self) -> Option<f64> {
+        self.temperature
+    }
+}
+
+async fn read_request(socket: &mut TcpStream) -> (String, serde_json::Value) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let (head_end, body_len) = loop {
+        let n = socket.read(&mut chunk).await.unwrap();
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&buf[..pos]).to_lowercase();
+            let len = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length:"))
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            break (pos + 4, len);
+        }
+    };
+    while buf.len() < head_end + body_len {
+        let n = socket.read(&mut chunk).await.unwrap();
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let request_line = String::from_utf8_lossy(&buf[..head_end]).lines().next().unwrap_or("").to_string();
+    let path = request_line.split_whitespace().nth(1).unwrap_or("").trim_start_matches('/').to_string();
+    let body = serde_json::from_slice(&buf[head_end..head_end + body_len]).unwrap_or_default();
+    (path, body)
+}
+
+async fn write_reply(socket: &mut TcpStream, reply: Option<serde_json::Value>) {
+    let (status, payload) = match reply {
+        Some(reply) => ("200 OK", reply.to_string()),
+        None => ("500 Internal Server Error", String::new()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        status,
+        payload.len(),
+        payload
+    );
+    let _ = socket.write_all(response.as_bytes()).await;
+}
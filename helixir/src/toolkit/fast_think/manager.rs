@@ -98,6 +98,7 @@ impl FastThinkManager {
                 None,
                 None,
                 None,
+                false,
             )
             .await
             .map_err(|e| FastThinkError::RecallFailed(e.to_string()))?;
@@ -186,7 +187,7 @@ impl FastThinkManager {
 
         let result = self
             .main_memory
-            .add(&full_content, user_id, None, None)
+            .add(&full_content, user_id, None, None, None, None)
             .await
             .map_err(|e| FastThinkError::CommitFailed(e.to_string()))?;
 
@@ -251,7 +252,7 @@ impl FastThinkManager {
         // Use add_with_tags to mark as incomplete_thought - tag is inherited by all extracted facts
         let result = self
             .main_memory
-            .add_with_tags(&partial_content, user_id, None, None, Some("incomplete_thought"))
+            .add_with_tags(&partial_content, user_id, None, None, Some("incomplete_thought"), None, None)
             .await
             .map_err(|e| FastThinkError::CommitFailed(e.to_string()))?;
 
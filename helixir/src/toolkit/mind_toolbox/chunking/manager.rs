@@ -113,7 +113,13 @@ impl ChunkingManager {
     
     #[inline]
     pub fn should_chunk(&self, text: &str) -> bool {
-        text.chars().count() > self.threshold
+        self.should_chunk_with(text, None)
+    }
+
+    /// Like `should_chunk`, but `threshold` overrides the configured one: 0 always chunks, `usize::MAX` never does.
+    #[inline]
+    pub fn should_chunk_with(&self, text: &str, threshold: Option<usize>) -> bool {
+        text.chars().count() > threshold.unwrap_or(self.threshold)
     }
 
     
@@ -150,10 +156,11 @@ impl ChunkingManager {
         source: &str,
         context_tags: &str,
         metadata: &str,
+        threshold: Option<usize>,
     ) -> Result<ChunkingResult, ChunkingError> {
         let char_count = content.chars().count();
 
-        if !self.should_chunk(content) {
+        if !self.should_chunk_with(content, threshold) {
             debug!(
                 "Content below threshold ({} chars), no chunking needed",
                 char_count
@@ -247,7 +254,7 @@ impl ChunkingManager {
 
             let chunk_result: AddChunkOutput = self
                 .client
-                .execute_query("addChunk", &input)
+                .execute_mutation("addChunk", &input)
                 .await
                 .map_err(|e| ChunkingError::Database(e.to_string()))?;
 
@@ -279,7 +286,7 @@ impl ChunkingManager {
 
                             if let Err(e) = self
                                 .client
-                                .execute_query::<serde_json::Value, _>(
+                                .execute_mutation::<serde_json::Value, _>(
                                     "addChunkEmbedding",
                                     &embed_input,
                                 )
@@ -335,6 +342,17 @@ mod tests {
         assert!(long_text.chars().count() > DEFAULT_THRESHOLD);
     }
 
+    #[test]
+    fn test_threshold_override() {
+        let manager = ChunkingManager::new(Arc::new(HelixClient::new("localhost", 6969).unwrap()), None);
+        let text = "A".repeat(120);
+
+        assert!(!manager.should_chunk(&text));
+        assert!(manager.should_chunk_with(&text, Some(100)));
+        assert!(manager.should_chunk_with("tiny", Some(0)));
+        assert!(!manager.should_chunk_with(&"A".repeat(5_000), Some(usize::MAX)));
+    }
+
     #[test]
     fn test_split_text_semantic() {
         let splitter = TextSplitter::new(100);
@@ -1,6 +1,6 @@
 
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
@@ -73,7 +73,7 @@ impl From<&str> for EntityType {
 }
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EntityEdgeType {
     
     ExtractedEntity,
@@ -174,6 +174,16 @@ pub struct ExtractedEntity {
 }
 
 
+// One memory-to-entity edge, as carried over by `EntityManager::merge_entities`.
+struct EntityLink {
+    memory_id: String,
+    edge_type: EntityEdgeType,
+    confidence: i32,
+    salience: i32,
+    sentiment: String,
+}
+
+
 #[derive(Error, Debug)]
 pub enum EntityError {
     #[error("Entity not found: {0}")]
@@ -216,17 +226,19 @@ impl EntityManager {
         let mut name_map = self.name_to_id.write();
 
         
-        if cache.len() >= self.cache_size {
+        if cache.len() >= self.cache_size && !cache.contains_key(&entity.entity_id) {
             if let Some(oldest_id) = cache.keys().next().cloned() {
-                if let Some(evicted) = cache.remove(&oldest_id) {
-                    name_map.remove(&evicted.name.to_lowercase());
+                if cache.remove(&oldest_id).is_some() {
+                    name_map.retain(|_, id| *id != oldest_id);
                     debug!("Cache eviction: {} (size: {})", oldest_id, self.cache_size);
                 }
             }
         }
 
         cache.insert(entity.entity_id.clone(), entity.clone());
-        name_map.insert(entity.name.to_lowercase(), entity.entity_id.clone());
+        for name in std::iter::once(&entity.name).chain(&entity.aliases) {
+            name_map.insert(name.trim().to_lowercase(), entity.entity_id.clone());
+        }
     }
 
     
@@ -257,7 +269,7 @@ impl EntityManager {
         
         match self
             .client
-            .execute_query::<CreateEntityResponse, _>(
+            .execute_mutation::<CreateEntityResponse, _>(
                 "createEntity",
                 &serde_json::json!({
                     "entity_id": entity.entity_id,
@@ -374,11 +386,211 @@ impl EntityManager {
             }
         }
 
+        if let Some(entity) = self.resolve_alias(&normalized_name).await {
+            debug!("Entity '{}' resolved through alias to {}", name, entity.entity_id);
+            return Ok(entity);
+        }
+
         
         debug!("Creating new entity: {}", name);
         self.create_entity(name, entity_type, properties).await
     }
 
+    // Canonical entity recorded for a merged-away name, if any.
+    async fn resolve_alias(&self, normalized_name: &str) -> Option<Entity> {
+        #[derive(Deserialize)]
+        struct AliasResult {
+            #[serde(default)]
+            alias_node: Option<AliasNode>,
+        }
+
+        #[derive(Deserialize)]
+        struct AliasNode {
+            #[serde(default)]
+            entity_id: String,
+        }
+
+        let alias = self
+            .client
+            .execute_query::<AliasResult, _>("getEntityAlias", &serde_json::json!({"alias": normalized_name}))
+            .await
+            .ok()?
+            .alias_node?;
+        if alias.entity_id.is_empty() {
+            return None;
+        }
+        let entity = self.get_entity(&alias.entity_id).await.ok()??;
+        self.name_to_id.write().insert(normalized_name.to_string(), entity.entity_id.clone());
+        Some(entity)
+    }
+
+    // Memories linked to `entity_id`, one entry per edge.
+    async fn entity_links(&self, entity_id: &str) -> Result<Vec<EntityLink>, EntityError> {
+        #[derive(Deserialize)]
+        struct EntityLinksResult {
+            #[serde(default)]
+            memories: Vec<LinkedMemory>,
+            #[serde(default)]
+            memory_edges: Vec<LinkEdge>,
+            #[serde(default)]
+            mentioned: Vec<LinkedMemory>,
+            #[serde(default)]
+            mention_edges: Vec<LinkEdge>,
+        }
+
+        #[derive(Deserialize)]
+        struct LinkedMemory {
+            #[serde(default)]
+            id: String,
+            memory_id: String,
+        }
+
+        #[derive(Deserialize)]
+        struct LinkEdge {
+            #[serde(default)]
+            from_node: String,
+            #[serde(default)]
+            confidence: Option<i64>,
+            #[serde(default)]
+            salience: Option<i64>,
+            #[serde(default)]
+            sentiment: Option<String>,
+        }
+
+        let result = self
+            .client
+            .execute_query::<EntityLinksResult, _>("getEntityMemories", &serde_json::json!({"entity_id": entity_id}))
+            .await
+            .map_err(|e| EntityError::Database(e.to_string()))?;
+
+        let link = |memory: LinkedMemory, edges: &[LinkEdge], edge_type: EntityEdgeType| {
+            let edge = edges.iter().find(|e| !memory.id.is_empty() && e.from_node == memory.id);
+            EntityLink {
+                memory_id: memory.memory_id,
+                edge_type,
+                confidence: edge.and_then(|e| e.confidence).unwrap_or(80) as i32,
+                salience: edge.and_then(|e| e.salience).unwrap_or(50) as i32,
+                sentiment: edge.and_then(|e| e.sentiment.clone()).unwrap_or_else(|| "neutral".to_string()),
+            }
+        };
+        let mut links: Vec<EntityLink> = result
+            .memories
+            .into_iter()
+            .map(|m| link(m, &result.memory_edges, EntityEdgeType::ExtractedEntity))
+            .collect();
+        links.extend(result.mentioned.into_iter().map(|m| link(m, &result.mention_edges, EntityEdgeType::Mentions)));
+        Ok(links)
+    }
+
+    /// Folds `alias_id` into `canonical_id`: its memory links move to the canonical entity
+    /// (skipping memories already linked there), its name and aliases become aliases of the
+    /// canonical entity, and the alias node is deleted. Returns how many links were moved.
+    pub async fn merge_entities(&self, canonical_id: &str, alias_id: &str) -> Result<usize, EntityError> {
+        if canonical_id == alias_id {
+            return Err(EntityError::Invalid(format!("cannot merge entity {} into itself", alias_id)));
+        }
+        let mut canonical = self
+            .get_entity(canonical_id)
+            .await?
+            .ok_or_else(|| EntityError::NotFound(canonical_id.to_string()))?;
+        let alias = self
+            .get_entity(alias_id)
+            .await?
+            .ok_or_else(|| EntityError::NotFound(alias_id.to_string()))?;
+
+        let (existing, moving) = futures::future::try_join(self.entity_links(canonical_id), self.entity_links(alias_id)).await?;
+        let mut linked: HashSet<(String, EntityEdgeType)> =
+            existing.into_iter().map(|l| (l.memory_id, l.edge_type)).collect();
+
+        let mut moved = 0;
+        for link in moving {
+            if !linked.insert((link.memory_id.clone(), link.edge_type)) {
+                continue;
+            }
+            self.link_to_memory(canonical_id, &link.memory_id, link.edge_type, link.confidence, link.salience, &link.sentiment)
+                .await?;
+            moved += 1;
+        }
+
+        let mut known: HashSet<String> = std::iter::once(&canonical.name)
+            .chain(&canonical.aliases)
+            .map(|n| n.trim().to_lowercase())
+            .collect();
+        let new_aliases: Vec<String> = std::iter::once(&alias.name)
+            .chain(&alias.aliases)
+            .filter(|n| known.insert(n.trim().to_lowercase()))
+            .cloned()
+            .collect();
+        canonical.aliases.extend(new_aliases.iter().cloned());
+
+        self.client
+            .execute_query::<serde_json::Value, _>(
+                "updateEntityAliases",
+                &serde_json::json!({
+                    "entity_id": canonical_id,
+                    "aliases": serde_json::to_string(&canonical.aliases).unwrap_or_default(),
+                }),
+            )
+            .await
+            .map_err(|e| EntityError::Database(e.to_string()))?;
+        for name in &new_aliases {
+            self.client
+                .execute_mutation::<serde_json::Value, _>(
+                    "addEntityAlias",
+                    &serde_json::json!({"alias": name.trim().to_lowercase(), "entity_id": canonical_id}),
+                )
+                .await
+                .map_err(|e| EntityError::Database(e.to_string()))?;
+        }
+        self.client
+            .execute_query::<serde_json::Value, _>(
+                "repointEntityAliases",
+                &serde_json::json!({"from_entity_id": alias_id, "to_entity_id": canonical_id}),
+            )
+            .await
+            .map_err(|e| EntityError::Database(e.to_string()))?;
+        self.client
+            .execute_query::<serde_json::Value, _>("deleteEntity", &serde_json::json!({"entity_id": alias_id}))
+            .await
+            .map_err(|e| EntityError::Database(e.to_string()))?;
+
+        self.entity_cache.write().remove(alias_id);
+        self.add_to_cache(&canonical);
+        info!(
+            "Merged entity '{}' into '{}' ({} links moved, {} new aliases)",
+            alias.name, canonical.name, moved, new_aliases.len()
+        );
+        Ok(moved)
+    }
+
+    /// Every entity called `name`, optionally narrowed to one type. Unknown names yield an empty list.
+    pub async fn find_by_name(&self, name: &str, entity_type: Option<&str>) -> Result<Vec<Entity>, EntityError> {
+        #[derive(Deserialize)]
+        struct EntitiesByNameResult {
+            #[serde(default)]
+            entities: Vec<EntityDbResponse>,
+        }
+
+        let result = self
+            .client
+            .execute_query::<EntitiesByNameResult, _>("getEntitiesByName", &serde_json::json!({"name": name.trim()}))
+            .await
+            .map_err(|e| EntityError::Database(e.to_string()))?;
+
+        let wanted = entity_type.map(EntityType::from);
+        let entities: Vec<Entity> = result
+            .entities
+            .into_iter()
+            .map(Entity::from)
+            .filter(|e| wanted.as_ref().map_or(true, |t| *t == e.entity_type))
+            .collect();
+        for entity in &entities {
+            self.add_to_cache(entity);
+        }
+        debug!("Resolved '{}' ({:?}) to {} entities", name, entity_type, entities.len());
+        Ok(entities)
+    }
+
     
     pub async fn link_to_memory(
         &self,
@@ -399,7 +611,7 @@ impl EntityManager {
         match edge_type {
             EntityEdgeType::ExtractedEntity => {
                 self.client
-                    .execute_query::<EdgeResponse, _>(
+                    .execute_mutation::<EdgeResponse, _>(
                         "linkExtractedEntity",
                         &serde_json::json!({
                             "memory_id": memory_id,
@@ -413,7 +625,7 @@ impl EntityManager {
             }
             EntityEdgeType::Mentions => {
                 self.client
-                    .execute_query::<EdgeResponse, _>(
+                    .execute_mutation::<EdgeResponse, _>(
                         "linkMentionsEntity",
                         &serde_json::json!({
                             "memory_id": memory_id,
@@ -520,3 +732,122 @@ impl std::fmt::Debug for EntityManager {
 
 
 pub use EntityEdgeType as EdgeType;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct StubGraph {
+        entities: HashMap<String, serde_json::Value>,
+        // (memory_id, entity_id, confidence)
+        links: Vec<(String, String, i64)>,
+        aliases: HashMap<String, String>,
+        created: usize,
+    }
+
+    impl StubGraph {
+        fn respond(&mut self, query: &str, body: &serde_json::Value) -> serde_json::Value {
+            let field = |key: &str| body[key].as_str().unwrap_or_default().to_string();
+            match query {
+                "getEntity" => serde_json::json!({"entity": self.entities.get(&field("entity_id"))}),
+                "getEntityByName" => serde_json::json!({
+                    "entity": self.entities.values().find(|e| e["name"] == body["name"]),
+                }),
+                "getEntityAlias" => serde_json::json!({
+                    "alias_node": self.aliases.get(&field("alias")).map(|id| serde_json::json!({"entity_id": id})),
+                }),
+                "getEntityMemories" => {
+                    let entity_id = field("entity_id");
+                    let linked: Vec<&(String, String, i64)> = self.links.iter().filter(|l| l.1 == entity_id).collect();
+                    serde_json::json!({
+                        "memories": linked.iter().map(|l| serde_json::json!({"id": format!("n_{}", l.0), "memory_id": l.0})).collect::<Vec<_>>(),
+                        "memory_edges": linked.iter().map(|l| serde_json::json!({"from_node": format!("n_{}", l.0), "confidence": l.2})).collect::<Vec<_>>(),
+                    })
+                }
+                "linkExtractedEntity" => {
+                    self.links.push((field("memory_id"), field("entity_id"), body["confidence"].as_i64().unwrap_or_default()));
+                    serde_json::json!({})
+                }
+                "updateEntityAliases" => {
+                    if let Some(entity) = self.entities.get_mut(&field("entity_id")) {
+                        entity["aliases"] = body["aliases"].clone();
+                    }
+                    serde_json::json!({})
+                }
+                "addEntityAlias" => {
+                    self.aliases.insert(field("alias"), field("entity_id"));
+                    serde_json::json!({})
+                }
+                "repointEntityAliases" => {
+                    let (from, to) = (field("from_entity_id"), field("to_entity_id"));
+                    self.aliases.values_mut().filter(|id| **id == from).for_each(|id| *id = to.clone());
+                    serde_json::json!({})
+                }
+                "deleteEntity" => {
+                    let entity_id = field("entity_id");
+                    self.entities.remove(&entity_id);
+                    self.links.retain(|l| l.1 != entity_id);
+                    serde_json::json!({})
+                }
+                "createEntity" => {
+                    self.created += 1;
+                    serde_json::json!({"entity": body})
+                }
+                _ => serde_json::json!({}),
+            }
+        }
+    }
+
+    async fn spawn_graph_stub(graph: Arc<parking_lot::Mutex<StubGraph>>) -> Arc<HelixClient> {
+        crate::test_support::spawn_stub(move |query, body| Some(graph.lock().respond(query, body)))
+            .await
+            .client()
+    }
+
+    fn stub_entity(entity_id: &str, name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "entity_id": entity_id,
+            "name": name,
+            "entity_type": "location",
+            "properties": "{}",
+            "aliases": "[]",
+        })
+    }
+
+    #[tokio::test]
+    async fn test_merge_repoints_links_and_resolves_alias() {
+        let graph = Arc::new(parking_lot::Mutex::new(StubGraph::default()));
+        {
+            let mut g = graph.lock();
+            g.entities.insert("e_nyc".to_string(), stub_entity("e_nyc", "New York City"));
+            g.entities.insert("e_alias".to_string(), stub_entity("e_alias", "NYC"));
+            for (memory_id, entity_id, confidence) in [("m1", "e_nyc", 90), ("m1", "e_alias", 70), ("m2", "e_alias", 40)] {
+                g.links.push((memory_id.to_string(), entity_id.to_string(), confidence));
+            }
+        }
+        let client = spawn_graph_stub(Arc::clone(&graph)).await;
+        let manager = EntityManager::new(Arc::clone(&client), 100);
+
+        let moved = manager.merge_entities("e_nyc", "e_alias").await.unwrap();
+        assert_eq!(moved, 1);
+        {
+            let g = graph.lock();
+            assert!(!g.entities.contains_key("e_alias"));
+            let mut links: Vec<(&str, &str, i64)> = g.links.iter().map(|l| (l.0.as_str(), l.1.as_str(), l.2)).collect();
+            links.sort();
+            assert_eq!(links, [("m1", "e_nyc", 90), ("m2", "e_nyc", 40)]);
+            assert_eq!(g.entities["e_nyc"]["aliases"], serde_json::json!(r#"["NYC"]"#));
+        }
+
+        let cached = manager.get_or_create_entity("nyc", "location", None).await.unwrap();
+        assert_eq!(cached.entity_id, "e_nyc");
+
+        let fresh = EntityManager::new(client, 100);
+        let resolved = fresh.get_or_create_entity("NYC", "location", None).await.unwrap();
+        assert_eq!(resolved.entity_id, "e_nyc");
+        assert_eq!(graph.lock().created, 0);
+
+        assert!(matches!(manager.merge_entities("e_nyc", "e_nyc").await, Err(EntityError::Invalid(_))));
+    }
+}
@@ -46,7 +46,7 @@ impl EdgeCreator {
                     }
 
                     self.client
-                        .execute_query(
+                        .execute_mutation(
                             "addMemoryImplication",
                             &Params {
                                 from_id: source_id.to_string(),
@@ -67,7 +67,7 @@ impl EdgeCreator {
                     }
 
                     self.client
-                        .execute_query(
+                        .execute_mutation(
                             "addMemoryCausation",
                             &Params {
                                 from_id: source_id.to_string(),
@@ -89,7 +89,7 @@ impl EdgeCreator {
                     }
 
                     self.client
-                        .execute_query(
+                        .execute_mutation(
                             "addMemoryContradiction",
                             &Params {
                                 from_id: source_id.to_string(),
@@ -113,7 +113,7 @@ impl EdgeCreator {
                     }
 
                     self.client
-                        .execute_query(
+                        .execute_mutation(
                             "addMemoryRelation",
                             &Params {
                                 source_id: source_id.to_string(),
@@ -7,6 +7,7 @@ use tracing::{debug, info, warn};
 
 use super::models::SimilarMemory;
 use super::similarity::cosine_similarity;
+use crate::toolkit::mind_toolbox::search::cmp_results;
 
 #[derive(Error, Debug)]
 pub enum FinderError {
@@ -97,7 +98,7 @@ impl SimilarMemoryFinder {
             }
         }
 
-        candidates.sort_by(|a, b| b.similarity_score.partial_cmp(&a.similarity_score).unwrap());
+        candidates.sort_by(cmp_results);
 
         candidates.truncate(self.max_similar);
 
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use strum::{EnumString, IntoStaticStr};
 
+use crate::toolkit::mind_toolbox::search::Ranked;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimilarMemory {
     pub memory_id: String,
@@ -11,6 +13,16 @@ pub struct SimilarMemory {
     pub created_at: DateTime<Utc>,
 }
 
+impl Ranked for SimilarMemory {
+    fn rank_score(&self) -> f64 {
+        self.similarity_score
+    }
+
+    fn rank_id(&self) -> &str {
+        &self.memory_id
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, EnumString, IntoStaticStr)]
 pub enum RelationType {
     Supersedes,
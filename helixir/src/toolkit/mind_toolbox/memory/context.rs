@@ -148,7 +148,7 @@ impl ContextManager {
             created_at: context.created_at.to_rfc3339(),
         };
 
-        match self.client.execute_query::<(), _>("addContext", &params).await {
+        match self.client.execute_mutation::<(), _>("addContext", &params).await {
             Ok(_) => {
                 self.add_to_cache(context.clone());
                 info!("Created context: {} ({})", context.name, crate::safe_truncate(&context.context_id, 8));
@@ -123,7 +123,7 @@ impl MemoryCrud {
             metadata: metadata.unwrap_or_else(|| "{}".to_string()),
         };
 
-        let result: AddMemoryOutput = self.client.execute_query("addMemory", &input).await?;
+        let result: AddMemoryOutput = self.client.execute_mutation("addMemory", &input).await?;
         let internal_id = result.memory.id;
         
         if internal_id.is_empty() {
@@ -141,7 +141,7 @@ impl MemoryCrud {
                         embedding_model: embedder.model(),
                         created_at: now.clone(),
                     };
-                    if let Err(e) = self.client.execute_query::<(), _>("addMemoryEmbedding", &embed_input).await {
+                    if let Err(e) = self.client.execute_mutation::<(), _>("addMemoryEmbedding", &embed_input).await {
                         warn!("Failed to create embedding for {}: {}", memory_id, e);
                     } else {
                         debug!("Embedding created for {}", memory_id);
@@ -153,7 +153,7 @@ impl MemoryCrud {
 
         if let Err(_) = self.client.execute_query::<serde_json::Value, _>("getUser", &serde_json::json!({"user_id": user_id.clone()})).await {
             let user_input = AddUserInput { user_id: user_id.clone(), name: user_id.clone() };
-            if let Err(e) = self.client.execute_query::<(), _>("addUser", &user_input).await {
+            if let Err(e) = self.client.execute_mutation::<(), _>("addUser", &user_input).await {
                 warn!("Failed to create user {}: {}", user_id, e);
             } else {
                 debug!("Created user {}", user_id);
@@ -165,7 +165,7 @@ impl MemoryCrud {
             memory_id: memory_id.clone(),
             context: "created".to_string(),
         };
-        if let Err(e) = self.client.execute_query::<(), _>("linkUserToMemory", &link_input).await {
+        if let Err(e) = self.client.execute_mutation::<(), _>("linkUserToMemory", &link_input).await {
             warn!("Failed to link memory to user: {}", e);
         } else {
             debug!("Linked memory {} to user", memory_id);
@@ -42,7 +42,7 @@ impl RelationCopier {
         
         for edge in outgoing.get("implies_out").unwrap_or(&vec![]) {
             if let Some(target) = edge.to.get("memory_id").and_then(|v| v.as_str()) {
-                if let Err(e) = self.client.execute_query("addMemoryImplication", HashMap::from([
+                if let Err(e) = self.client.execute_mutation("addMemoryImplication", HashMap::from([
                     ("from_id", new_memory_id),
                     ("to_id", target),
                     ("probability", &edge.probability.unwrap_or(80).to_string()),
@@ -58,7 +58,7 @@ impl RelationCopier {
         
         for edge in outgoing.get("because_out").unwrap_or(&vec![]) {
             if let Some(target) = edge.to.get("memory_id").and_then(|v| v.as_str()) {
-                if let Err(e) = self.client.execute_query("addMemoryCausation", HashMap::from([
+                if let Err(e) = self.client.execute_mutation("addMemoryCausation", HashMap::from([
                     ("from_id", new_memory_id),
                     ("to_id", target),
                     ("strength", &edge.strength.unwrap_or(80).to_string()),
@@ -75,7 +75,7 @@ impl RelationCopier {
         for edge in outgoing.get("relations_out").unwrap_or(&vec![]) {
             if let Some(target) = edge.to.get("memory_id").and_then(|v| v.as_str()) {
                 let metadata = format!(r#"{{"copied_from": "{}"}}"#, old_memory_id);
-                if let Err(e) = self.client.execute_query("addMemoryRelation", HashMap::from([
+                if let Err(e) = self.client.execute_mutation("addMemoryRelation", HashMap::from([
                     ("source_id", new_memory_id),
                     ("target_id", target),
                     ("relation_type", edge.relation_type.as_deref().unwrap_or("related")),
@@ -113,7 +113,7 @@ impl MemoryRelationManager {
         metadata: Option<&str>,
     ) -> Result<(), RelationError> {
         let metadata = metadata.unwrap_or("{}");
-        self.client.execute_query("addMemoryRelation", HashMap::from([
+        self.client.execute_mutation("addMemoryRelation", HashMap::from([
             ("source_id", source_id),
             ("target_id", target_id),
             ("relation_type", relation_type),
@@ -135,14 +135,14 @@ impl MemoryRelationManager {
     ) -> Result<(), RelationError> {
         match link_type {
             "INSTANCE_OF" => {
-                self.client.execute_query("linkMemoryToInstanceOf", HashMap::from([
+                self.client.execute_mutation("linkMemoryToInstanceOf", HashMap::from([
                     ("memory_id", memory_id),
                     ("concept_id", concept_id),
                     ("confidence", &confidence.to_string()),
                 ])).await?;
             }
             "BELONGS_TO_CATEGORY" => {
-                self.client.execute_query("linkMemoryToCategory", HashMap::from([
+                self.client.execute_mutation("linkMemoryToCategory", HashMap::from([
                     ("memory_id", memory_id),
                     ("concept_id", concept_id),
                     ("relevance", &confidence.to_string()),
@@ -79,7 +79,7 @@ pub async fn remark_single_memory(
                 "confidence": confidence,
             });
             
-            match db_client.execute_query::<serde_json::Value, _>(query_name, &params).await {
+            match db_client.execute_mutation::<serde_json::Value, _>(query_name, &params).await {
                 Ok(_) => {
                     concepts_added += 1;
                     debug!("Linked concept '{}' to memory {}", concept_id, crate::safe_truncate(memory_id, 8));
@@ -98,7 +98,7 @@ pub async fn remark_single_memory(
                 "confidence": 90,
             });
             
-            match db_client.execute_query::<serde_json::Value, _>("linkMemoryToInstanceOf", &params).await {
+            match db_client.execute_mutation::<serde_json::Value, _>("linkMemoryToInstanceOf", &params).await {
                 Ok(_) => {
                     concepts_added += 1;
                     debug!("Linked LLM concept '{}' to memory {}", concept_name, crate::safe_truncate(memory_id, 8));
@@ -88,7 +88,7 @@ impl SupersessionManager {
         params.insert("is_contradiction".to_string(), if is_contradiction { 1 } else { 0 });
 
         self.client
-            .execute_query("addMemorySupersession", params)
+            .execute_mutation("addMemorySupersession", params)
             .await
             .map_err(|e| SupersessionError::EdgeCreationFailed(e.to_string()))?;
 
@@ -100,7 +100,7 @@ impl SupersessionManager {
             params.insert("resolved".to_string(), 1);
             params.insert("resolution_strategy".to_string(), "newer_wins".to_string());
 
-            if let Err(e) = self.client.execute_query("addMemoryContradiction", params).await {
+            if let Err(e) = self.client.execute_mutation("addMemoryContradiction", params).await {
                 warn!("Failed to create contradiction edge: {}", e);
             }
         }
@@ -86,7 +86,7 @@ impl MemoryChainStrategy {
 
     
     async fn vector_search(&self, query: &str, limit: usize) -> Result<Vec<serde_json::Value>, String> {
-        let embedding = self.embedder.generate(query, true).await
+        let embedding = self.embedder.generate_query(query, true).await
             .map_err(|e| format!("Embedding failed: {}", e))?;
 
         #[derive(serde::Deserialize)]
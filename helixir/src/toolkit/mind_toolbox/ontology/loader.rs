@@ -48,7 +48,7 @@ impl OntologyLoader {
 
     pub async fn initialize_base(&self) -> Result<(), LoaderError> {
         let _: () = self.client
-            .execute_query("initializeBaseOntology", &serde_json::json!({}))
+            .execute_mutation("initializeBaseOntology", &serde_json::json!({}))
             .await
             .map_err(|e| LoaderError::Database(e.to_string()))?;
         
@@ -119,7 +119,7 @@ impl ConceptMapper {
         }
 
         
-        matches.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+        matches.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
 
         
         matches.into_iter().take(top_k).collect()
@@ -132,7 +132,7 @@ mod tests {
 
     #[test]
     fn test_map_preference() {
-        let mapper = ConceptMapper::new(HashMap::new());
+        let mapper = ConceptMapper::new();
         let matches = mapper.map_to_concepts("I love programming", 3);
 
         assert!(!matches.is_empty());
@@ -141,7 +141,7 @@ mod tests {
 
     #[test]
     fn test_map_skill() {
-        let mapper = ConceptMapper::new(HashMap::new());
+        let mapper = ConceptMapper::new();
         let matches = mapper.map_to_concepts("I can write Rust code", 3);
 
         assert!(!matches.is_empty());
@@ -153,7 +153,7 @@ mod tests {
 
     #[test]
     fn test_no_match() {
-        let mapper = ConceptMapper::new(HashMap::new());
+        let mapper = ConceptMapper::new();
         let matches = mapper.map_to_concepts("xyz123", 3);
 
         assert!(matches.is_empty());
@@ -161,7 +161,7 @@ mod tests {
 
     #[test]
     fn test_case_insensitive() {
-        let mapper = ConceptMapper::new(HashMap::new());
+        let mapper = ConceptMapper::new();
         let matches1 = mapper.map_to_concepts("I LOVE RUST", 3);
         let matches2 = mapper.map_to_concepts("i love rust", 3);
 
@@ -15,7 +15,7 @@ pub use classifier::ConceptClassifier;
 
 use crate::db::HelixClient;
 use std::sync::{Arc, RwLock};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 use tracing::info;
 
@@ -60,16 +60,132 @@ impl OntologyManager {
     pub async fn load(&mut self) -> Result<(), OntologyError> {
         info!("Loading ontology");
         let (concepts, relations) = self.loader.load_base_ontology().await?;
+        self.load_concepts(concepts, relations);
+        Ok(())
+    }
+
+    pub fn load_concepts(&mut self, concepts: HashMap<String, Concept>, relations: Vec<ConceptRelation>) {
         *self.concepts_cache.write().unwrap() = concepts;
         self.relations_cache = relations;
         self.is_loaded = true;
-        Ok(())
+    }
+
+    /// Parent and sibling concept names for concepts mentioned in `text`, for query expansion.
+    pub fn related_terms(&self, text: &str, max_terms: usize) -> Vec<String> {
+        if !self.is_loaded {
+            return Vec::new();
+        }
+
+        let tokens: HashSet<String> = text
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .flat_map(|t| [t.to_string(), t.strip_suffix('s').unwrap_or(t).to_string()])
+            .collect();
+
+        let cache = self.concepts_cache.read().unwrap();
+        let mut mentioned: Vec<&Concept> = cache
+            .values()
+            .filter(|c| tokens.contains(&c.name.to_lowercase()))
+            .collect();
+        mentioned.sort_by(|a, b| a.concept_id.cmp(&b.concept_id));
+        let mentioned_ids: HashSet<&str> = mentioned.iter().map(|c| c.concept_id.as_str()).collect();
+
+        let mut terms = Vec::new();
+        let mut seen: HashSet<String> = tokens;
+        for concept in mentioned {
+            let Some(parent_id) = &concept.parent_concept else {
+                continue;
+            };
+            let mut related: Vec<&Concept> = cache
+                .values()
+                .filter(|c| c.parent_concept.as_ref() == Some(parent_id) && !mentioned_ids.contains(c.concept_id.as_str()))
+                .collect();
+            related.sort_by(|a, b| a.concept_id.cmp(&b.concept_id));
+            if let Some(parent) = cache.get(parent_id) {
+                related.insert(0, parent);
+            }
+            for term in related.into_iter().map(|c| c.name.to_lowercase()) {
+                if terms.len() >= max_terms {
+                    return terms;
+                }
+                if seen.insert(term.clone()) {
+                    terms.push(term);
+                }
+            }
+        }
+        terms
     }
 
     pub fn get_concept(&self, id: &str) -> Option<Concept> {
+        if !self.is_loaded {
+            return None;
+        }
         self.concepts_cache.read().unwrap().get(id).cloned()
     }
 
+    /// Looks a concept up by id or name, ignoring case.
+    pub fn resolve_concept(&self, name_or_id: &str) -> Option<Concept> {
+        if !self.is_loaded {
+            return None;
+        }
+        let cache = self.concepts_cache.read().unwrap();
+        cache
+            .get(name_or_id)
+            .or_else(|| cache.values().find(|c| c.concept_id.eq_ignore_ascii_case(name_or_id)))
+            .or_else(|| cache.values().find(|c| c.name.eq_ignore_ascii_case(name_or_id)))
+            .cloned()
+    }
+
+    /// Direct subtypes of `id`, ordered by concept id.
+    pub fn children(&self, id: &str) -> Vec<Concept> {
+        if !self.is_loaded {
+            return Vec::new();
+        }
+        let mut children = self.hierarchy.get_subtypes(id).unwrap_or_default();
+        children.sort_by(|a, b| a.concept_id.cmp(&b.concept_id));
+        children
+    }
+
+    /// Concepts sharing `id`'s parent, excluding `id` itself.
+    pub fn siblings(&self, id: &str) -> Vec<Concept> {
+        let Some(parent_id) = self.get_concept(id).and_then(|c| c.parent_concept) else {
+            return Vec::new();
+        };
+        self.children(&parent_id)
+            .into_iter()
+            .filter(|c| c.concept_id != id)
+            .collect()
+    }
+
+    /// Every concept below `id`, breadth-first.
+    pub fn descendants(&self, id: &str) -> Vec<Concept> {
+        let mut descendants = Vec::new();
+        let mut seen: HashSet<String> = HashSet::from([id.to_string()]);
+        let mut frontier = vec![id.to_string()];
+        while !frontier.is_empty() {
+            let mut next = Vec::new();
+            for concept_id in frontier {
+                for child in self.children(&concept_id) {
+                    if seen.insert(child.concept_id.clone()) {
+                        next.push(child.concept_id.clone());
+                        descendants.push(child);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        descendants
+    }
+
+    /// Parent chain of `id`, nearest first.
+    pub fn ancestors(&self, id: &str) -> Vec<Concept> {
+        if !self.is_loaded {
+            return Vec::new();
+        }
+        self.hierarchy.get_ancestors(id)
+    }
+
     pub fn add_concept(&mut self, concept: Concept) -> Result<(), OntologyError> {
         if self.concepts_cache.read().unwrap().contains_key(&concept.concept_id) {
             return Err(OntologyError::AlreadyExists(concept.concept_id));
@@ -86,10 +202,7 @@ impl OntologyManager {
     }
 
     pub fn get_ancestors(&self, id: &str) -> Vec<Concept> {
-        if !self.is_loaded {
-            return Vec::new();
-        }
-        self.hierarchy.get_ancestors(id)
+        self.ancestors(id)
     }
 
     pub fn classify_text(&self, text: &str, min_confidence: f64) -> Vec<(String, f64)> {
@@ -119,4 +232,65 @@ impl OntologyManager {
     pub fn is_loaded(&self) -> bool {
         self.is_loaded
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn concept(id: &str, parent: Option<&str>, level: u8) -> Concept {
+        Concept::new(
+            id.to_string(),
+            id.to_string(),
+            ConceptType::Abstract,
+            String::new(),
+            parent.map(str::to_string),
+            level,
+        )
+    }
+
+    fn fixture() -> OntologyManager {
+        let mut manager = OntologyManager::new(Arc::new(HelixClient::new("localhost", 6969).unwrap()));
+        let concepts = [
+            concept("Thing", None, 1),
+            concept("Attribute", Some("Thing"), 2),
+            concept("Event", Some("Thing"), 2),
+            concept("Preference", Some("Attribute"), 3),
+            concept("Skill", Some("Attribute"), 3),
+            concept("Taste", Some("Preference"), 4),
+        ];
+        manager.load_concepts(
+            concepts.into_iter().map(|c| (c.concept_id.clone(), c)).collect(),
+            Vec::new(),
+        );
+        manager
+    }
+
+    fn ids(concepts: Vec<Concept>) -> Vec<String> {
+        concepts.into_iter().map(|c| c.concept_id).collect()
+    }
+
+    #[test]
+    fn test_hierarchy_navigation() {
+        let ontology = fixture();
+
+        assert_eq!(ontology.get_concept("Skill").unwrap().parent_concept.as_deref(), Some("Attribute"));
+        assert!(ontology.get_concept("Missing").is_none());
+        assert_eq!(ids(ontology.children("Attribute")), ["Preference", "Skill"]);
+        assert_eq!(ids(ontology.siblings("Preference")), ["Skill"]);
+        assert_eq!(ids(ontology.ancestors("Taste")), ["Preference", "Attribute", "Thing"]);
+        assert_eq!(ids(ontology.descendants("Attribute")), ["Preference", "Skill", "Taste"]);
+        assert!(ontology.children("Taste").is_empty());
+        assert_eq!(ontology.resolve_concept("preference").unwrap().concept_id, "Preference");
+    }
+
+    #[test]
+    fn test_hierarchy_is_empty_before_load() {
+        let ontology = OntologyManager::new(Arc::new(HelixClient::new("localhost", 6969).unwrap()));
+
+        assert!(ontology.get_concept("Thing").is_none());
+        assert!(ontology.children("Thing").is_empty());
+        assert!(ontology.ancestors("Thing").is_empty());
+        assert!(ontology.descendants("Thing").is_empty());
+        assert!(ontology.resolve_concept("Thing").is_none());
+    }
+}
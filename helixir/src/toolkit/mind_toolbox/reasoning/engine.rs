@@ -9,6 +9,7 @@ use tracing::{debug, error, info, warn};
 
 use crate::db::HelixClient;
 use crate::llm::providers::base::LlmProvider;
+use crate::toolkit::mind_toolbox::search::edge_weights;
 
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -34,6 +35,293 @@ impl ReasoningType {
             Self::Supports => "SUPPORTS",
         }
     }
+
+    
+    #[must_use]
+    pub fn from_edge_name(name: &str) -> Option<Self> {
+        match name.trim().to_uppercase().as_str() {
+            "IMPLIES" => Some(Self::Implies),
+            "BECAUSE" => Some(Self::Because),
+            "CONTRADICTS" => Some(Self::Contradicts),
+            "SUPPORTS" => Some(Self::Supports),
+            _ => None,
+        }
+    }
+
+    
+    #[must_use]
+    pub fn for_chain_mode(chain_type: &str) -> &'static [ReasoningType] {
+        match chain_type {
+            "causal" => &[Self::Because],
+            "forward" => &[Self::Implies],
+            _ => &[Self::Implies, Self::Because, Self::Contradicts],
+        }
+    }
+}
+
+
+#[derive(Debug, Default, Deserialize)]
+struct ChainConnections {
+    #[serde(default)]
+    implies_out: Vec<ChainNode>,
+    #[serde(default)]
+    implies_in: Vec<ChainNode>,
+    #[serde(default)]
+    because_out: Vec<ChainNode>,
+    #[serde(default)]
+    because_in: Vec<ChainNode>,
+    #[serde(default)]
+    contradicts_out: Vec<ChainNode>,
+    #[serde(default)]
+    contradicts_in: Vec<ChainNode>,
+    #[serde(default)]
+    relation_out: Vec<ChainNode>,
+    #[serde(default)]
+    relation_in: Vec<ChainNode>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct ChainNode {
+    #[serde(default)]
+    id: String,
+    memory_id: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    created_at: String,
+}
+
+impl ChainConnections {
+    
+    /// A neighbour listed twice under one edge type is offered once.
+    fn candidates(&self, types: &[ReasoningType]) -> Vec<ChainCandidate> {
+        let mut all = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for relation_type in types {
+            let (nodes, is_incoming) = match relation_type {
+                ReasoningType::Implies => (&self.implies_out, false),
+                ReasoningType::Because => (&self.because_in, true),
+                ReasoningType::Contradicts => (&self.contradicts_out, false),
+                ReasoningType::Supports => (&self.relation_out, false),
+            };
+            for n in nodes.iter().filter(|n| seen.insert((n.memory_id.as_str(), *relation_type))) {
+                all.push(ChainCandidate {
+                    node: n.clone(),
+                    relation_type: *relation_type,
+                    is_incoming,
+                    strength: DEFAULT_CHAIN_STRENGTH,
+                });
+            }
+        }
+        all
+    }
+}
+
+
+#[derive(Debug, Default, Deserialize)]
+struct ChainEdges {
+    #[serde(default)]
+    implies_out: Vec<ChainEdge>,
+    #[serde(default)]
+    because_in: Vec<ChainEdge>,
+    #[serde(default)]
+    relation_out: Vec<ChainEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChainEdge {
+    #[serde(default, alias = "from_node")]
+    from: String,
+    #[serde(default, alias = "to_node")]
+    to: String,
+    #[serde(default)]
+    strength: Option<i64>,
+    #[serde(default)]
+    probability: Option<i64>,
+    #[serde(default)]
+    created_at: Option<String>,
+}
+
+impl ChainEdges {
+    
+    fn apply_to(&self, candidates: &mut [ChainCandidate], decay: StrengthDecay, now: chrono::DateTime<chrono::Utc>) {
+        for candidate in candidates.iter_mut() {
+            let (edges, incoming) = match candidate.relation_type {
+                ReasoningType::Implies => (&self.implies_out, false),
+                ReasoningType::Because => (&self.because_in, true),
+                ReasoningType::Supports => (&self.relation_out, false),
+                ReasoningType::Contradicts => continue,
+            };
+            let neighbor = |e: &&ChainEdge| if incoming { e.from == candidate.node.id } else { e.to == candidate.node.id };
+            if let Some(edge) = edges.iter().find(neighbor) {
+                if let Some(strength) = edge.strength.or(edge.probability) {
+                    candidate.strength = decay.effective(strength as f64, edge.created_at.as_deref(), now).round() as i64;
+                }
+            }
+        }
+    }
+}
+
+
+#[derive(Debug, Clone)]
+struct ChainCandidate {
+    node: ChainNode,
+    relation_type: ReasoningType,
+    is_incoming: bool,
+    strength: i64,
+}
+
+impl ChainCandidate {
+    /// Edge-type traversal weight times strength, so a strong BECAUSE beats a weak CONTRADICTS.
+    fn score(&self) -> f64 {
+        let edge_type = match self.relation_type {
+            ReasoningType::Supports => "MEMORY_RELATION",
+            other => other.edge_name(),
+        };
+        edge_weights::get_weight(edge_type) * self.strength as f64
+    }
+}
+
+const DEFAULT_CHAIN_STRENGTH: i64 = 80;
+
+
+/// How `add_relation` combines a new strength with the one already stored on the edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StrengthMerge {
+    /// Keep the stronger of the two
+    #[default]
+    Max,
+    /// The new strength wins
+    Replace,
+    /// Average of stored and new strength
+    Mean,
+}
+
+impl StrengthMerge {
+    
+    #[must_use]
+    pub fn merge(&self, stored: i32, incoming: i32) -> i32 {
+        match self {
+            Self::Max => stored.max(incoming),
+            Self::Replace => incoming,
+            Self::Mean => (stored + incoming) / 2,
+        }
+    }
+}
+
+
+// One cache slot per (type, from, to) on full ids; ids sharing a prefix must not collide.
+fn relation_key(from_id: &str, to_id: &str, relation_type: ReasoningType) -> String {
+    format!("{}:{}:{}", relation_type.edge_name(), from_id, to_id)
+}
+
+
+// Query and params that address the `relation_type` edge from `from_id` to `to_id`.
+fn relation_edge_query(from_id: &str, to_id: &str, relation_type: ReasoningType, action: &str) -> (String, serde_json::Value) {
+    let (suffix, params) = match relation_type {
+        ReasoningType::Implies => ("MemoryImplication", serde_json::json!({"from_id": from_id, "to_id": to_id})),
+        ReasoningType::Because => ("MemoryCausation", serde_json::json!({"from_id": from_id, "to_id": to_id})),
+        ReasoningType::Contradicts => ("MemoryContradiction", serde_json::json!({"from_id": from_id, "to_id": to_id})),
+        ReasoningType::Supports => (
+            "ReasoningRelation",
+            serde_json::json!({"from_id": from_id, "to_id": to_id, "relation_type": "SUPPORTS"}),
+        ),
+    };
+    (format!("{}{}", action, suffix), params)
+}
+
+
+/// Fades reasoning-edge strength with edge age when read; stored strengths are never rewritten.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StrengthDecay {
+    /// `None` keeps strengths as stored
+    pub half_life_days: Option<f64>,
+}
+
+impl StrengthDecay {
+    
+    #[must_use]
+    pub fn factor(&self, age_days: f64) -> f64 {
+        match self.half_life_days {
+            Some(half_life) if half_life > 0.0 && age_days > 0.0 => 0.5_f64.powf(age_days / half_life),
+            _ => 1.0,
+        }
+    }
+
+    /// Edges without a parseable `created_at` keep their stored strength.
+    #[must_use]
+    pub fn effective(&self, stored: f64, created_at: Option<&str>, now: chrono::DateTime<chrono::Utc>) -> f64 {
+        use crate::toolkit::mind_toolbox::search::parse_datetime_utc;
+
+        let age_days = created_at
+            .and_then(parse_datetime_utc)
+            .map_or(0.0, |created| (now - created).num_seconds() as f64 / 86_400.0);
+        stored * self.factor(age_days)
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ContradictionStrategy {
+    /// Drop the older of the two memories
+    KeepNewer,
+    /// Drop the newer of the two memories
+    KeepOlder,
+    /// Both memories stay; the resolution text carries the merged statement
+    Merge,
+    /// Both memories stay as they are
+    KeepBoth,
+}
+
+impl ContradictionStrategy {
+    
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::KeepNewer => "keep_newer",
+            Self::KeepOlder => "keep_older",
+            Self::Merge => "merge",
+            Self::KeepBoth => "keep_both",
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChainSelection {
+    /// Ask the LLM to pick among several candidates (first candidate without a provider)
+    #[default]
+    LlmGuided,
+    /// Follow the edge with the highest type weight × strength; ties go to the lowest memory id
+    HighestStrength,
+    /// Follow the most recently created memory; ties go to the lowest memory id
+    MostRecent,
+}
+
+
+fn pick_deterministic(candidates: Vec<ChainCandidate>, selection: ChainSelection) -> Option<ChainCandidate> {
+    use crate::toolkit::mind_toolbox::search::parse_datetime_utc;
+
+    candidates.into_iter().min_by(|a, b| {
+        let preference = match selection {
+            ChainSelection::MostRecent => parse_datetime_utc(&b.node.created_at)
+                .cmp(&parse_datetime_utc(&a.node.created_at)),
+            _ => b.score().total_cmp(&a.score()),
+        };
+        preference
+            .then_with(|| a.node.memory_id.cmp(&b.node.memory_id))
+            .then_with(|| a.relation_type.edge_name().cmp(b.relation_type.edge_name()))
+    })
+}
+
+// Best-scored first, with the same tie-breaks as `pick_deterministic`.
+fn rank_candidates(candidates: &mut [ChainCandidate]) {
+    candidates.sort_by(|a, b| {
+        b.score()
+            .total_cmp(&a.score())
+            .then_with(|| a.node.memory_id.cmp(&b.node.memory_id))
+            .then_with(|| a.relation_type.edge_name().cmp(b.relation_type.edge_name()))
+    });
 }
 
 
@@ -71,12 +359,149 @@ pub struct ReasoningChain {
 }
 
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningChainNode {
+    
+    pub memory_id: String,
+    
+    pub content: String,
+    /// Edge that led here from the parent; `None` for the seed
+    pub relation_type: Option<ReasoningType>,
+    
+    pub depth: usize,
+    
+    pub children: Vec<ReasoningChainNode>,
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningChainTree {
+    
+    pub seed_memory_id: String,
+    
+    pub root: ReasoningChainNode,
+    
+    pub chain_type: String,
+    
+    pub depth: usize,
+    
+    pub node_count: usize,
+    
+    pub reasoning_trail: String,
+}
+
+
+struct ChainTreeArena {
+    nodes: Vec<ReasoningChainNode>,
+    children: Vec<Vec<usize>>,
+}
+
+impl ChainTreeArena {
+    fn new(seed_id: &str) -> Self {
+        Self {
+            nodes: vec![ReasoningChainNode {
+                memory_id: seed_id.to_string(),
+                content: String::new(),
+                relation_type: None,
+                depth: 0,
+                children: Vec::new(),
+            }],
+            children: vec![Vec::new()],
+        }
+    }
+
+    // A node reachable through several parents is attached to whichever reaches it first.
+    fn attach(
+        &mut self,
+        parent: usize,
+        candidates: Vec<ChainCandidate>,
+        visited: &mut std::collections::HashSet<String>,
+        max_branching: usize,
+    ) -> Vec<usize> {
+        let depth = self.nodes[parent].depth + 1;
+        let mut attached = Vec::new();
+
+        for candidate in candidates {
+            if attached.len() >= max_branching.max(1) {
+                break;
+            }
+            if !visited.insert(candidate.node.memory_id.clone()) {
+                continue;
+            }
+            let idx = self.nodes.len();
+            self.nodes.push(ReasoningChainNode {
+                memory_id: candidate.node.memory_id,
+                content: candidate.node.content,
+                relation_type: Some(candidate.relation_type),
+                depth,
+                children: Vec::new(),
+            });
+            self.children.push(Vec::new());
+            self.children[parent].push(idx);
+            attached.push(idx);
+        }
+
+        attached
+    }
+
+    fn into_tree(self) -> ReasoningChainNode {
+        fn assemble(idx: usize, nodes: &mut [Option<ReasoningChainNode>], children: &[Vec<usize>]) -> ReasoningChainNode {
+            let mut node = nodes[idx].take().expect("each arena node is assembled once");
+            node.children = children[idx].iter().map(|&c| assemble(c, nodes, children)).collect();
+            node
+        }
+
+        let mut nodes: Vec<Option<ReasoningChainNode>> = self.nodes.into_iter().map(Some).collect();
+        assemble(0, &mut nodes, &self.children)
+    }
+}
+
+
+fn relation_arrow(relation_type: ReasoningType) -> &'static str {
+    match relation_type {
+        ReasoningType::Implies => "→",
+        ReasoningType::Because => "←",
+        ReasoningType::Contradicts => "⊗",
+        ReasoningType::Supports => "↔",
+    }
+}
+
+
+fn render_chain_tree(root: &ReasoningChainNode) -> String {
+    fn render(node: &ReasoningChainNode, out: &mut Vec<String>) {
+        let label = match node.relation_type {
+            Some(relation_type) => format!(
+                "{}{} [{}] {}",
+                "  ".repeat(node.depth),
+                relation_arrow(relation_type),
+                crate::safe_truncate(&node.memory_id, 8),
+                crate::safe_truncate(&node.content, 60)
+            ),
+            None => format!("[{}]", crate::safe_truncate(&node.memory_id, 8)),
+        };
+        out.push(label.trim_end().to_string());
+        for child in &node.children {
+            render(child, out);
+        }
+    }
+
+    if root.children.is_empty() {
+        return "No reasoning chain found.".to_string();
+    }
+    let mut lines = Vec::new();
+    render(root, &mut lines);
+    lines.join("\n")
+}
+
+
 pub struct ReasoningEngine {
     client: Arc<HelixClient>,
     llm_provider: Option<Arc<dyn LlmProvider>>,
     relation_cache: parking_lot::Mutex<LruCache<String, ReasoningRelation>>,
     cache_size: usize,
     is_warmed_up: std::sync::atomic::AtomicBool,
+    strength_decay: StrengthDecay,
+    strength_merge: StrengthMerge,
 }
 
 impl ReasoningEngine {
@@ -107,10 +532,32 @@ impl ReasoningEngine {
             relation_cache: parking_lot::Mutex::new(cache),
             cache_size,
             is_warmed_up: std::sync::atomic::AtomicBool::new(false),
+            strength_decay: StrengthDecay::default(),
+            strength_merge: StrengthMerge::default(),
         }
     }
 
     
+    #[must_use]
+    pub fn with_strength_merge(mut self, merge: StrengthMerge) -> Self {
+        self.strength_merge = merge;
+        self
+    }
+
+    
+    #[must_use]
+    pub fn with_strength_decay(mut self, decay: StrengthDecay) -> Self {
+        self.strength_decay = decay;
+        self
+    }
+
+    
+    #[must_use]
+    pub fn strength_decay(&self) -> StrengthDecay {
+        self.strength_decay
+    }
+
+    
     pub async fn warm_up_cache(
         &self,
         memory_id: Option<&str>,
@@ -169,8 +616,23 @@ impl ReasoningEngine {
         
         let strength = strength.clamp(0, 100);
 
+        // CONTRADICTS edges carry no strength, so any existing one is already up to date.
+        if let Some(existing) = self.get_relation(from_id, to_id, relation_type).await? {
+            let merged = self.strength_merge.merge(existing.strength, strength);
+            if merged == existing.strength || relation_type == ReasoningType::Contradicts {
+                debug!(
+                    "{} relation {} -> {} already exists, skipping insert",
+                    relation_type.edge_name(),
+                    from_id,
+                    to_id
+                );
+                return Ok(existing);
+            }
+            return self.update_relation_strength(existing, merged).await;
+        }
+
         let relation = ReasoningRelation {
-            relation_id: format!("rel_{}_{}", crate::safe_truncate(from_id, 8), crate::safe_truncate(to_id, 8)),
+            relation_id: relation_key(from_id, to_id, relation_type),
             from_memory_id: from_id.to_string(),
             to_memory_id: to_id.to_string(),
             to_memory_content: String::new(),
@@ -189,7 +651,7 @@ impl ReasoningEngine {
         let persist_result = match relation_type {
             ReasoningType::Implies => {
                 self.client
-                    .execute_query::<EdgeResponse, _>(
+                    .execute_mutation::<EdgeResponse, _>(
                         "addMemoryImplication",
                         &serde_json::json!({
                             "from_id": from_id,
@@ -202,7 +664,7 @@ impl ReasoningEngine {
             }
             ReasoningType::Because => {
                 self.client
-                    .execute_query::<EdgeResponse, _>(
+                    .execute_mutation::<EdgeResponse, _>(
                         "addMemoryCausation",
                         &serde_json::json!({
                             "from_id": from_id,
@@ -215,7 +677,7 @@ impl ReasoningEngine {
             }
             ReasoningType::Contradicts => {
                 self.client
-                    .execute_query::<EdgeResponse, _>(
+                    .execute_mutation::<EdgeResponse, _>(
                         "addMemoryContradiction",
                         &serde_json::json!({
                             "from_id": from_id,
@@ -231,10 +693,10 @@ impl ReasoningEngine {
                 
                 let now = chrono::Utc::now().to_rfc3339();
                 self.client
-                    .execute_query::<EdgeResponse, _>(
+                    .execute_mutation::<EdgeResponse, _>(
                         "addReasoningRelation",
                         &serde_json::json!({
-                            "relation_id": format!("rel_{}_{}", crate::safe_truncate(from_id, 8), crate::safe_truncate(to_id, 8)),
+                            "relation_id": relation_key(from_id, to_id, relation_type),
                             "from_memory_id": from_id,
                             "to_memory_id": to_id,
                             "relation_type": "SUPPORTS",
@@ -268,38 +730,265 @@ impl ReasoningEngine {
     }
 
     
-    pub async fn get_chain(
+    async fn update_relation_strength(
         &self,
-        memory_id: &str,
-        chain_type: &str,
-        max_depth: usize,
-    ) -> Result<ReasoningChain, ReasoningError> {
+        existing: ReasoningRelation,
+        strength: i32,
+    ) -> Result<ReasoningRelation, ReasoningError> {
+        let (query, mut params) = relation_edge_query(
+            &existing.from_memory_id,
+            &existing.to_memory_id,
+            existing.relation_type,
+            "update",
+        );
+        params["strength"] = serde_json::json!(strength as i64);
+        self.client
+            .execute_query::<serde_json::Value, _>(&query, &params)
+            .await
+            .map_err(|e| ReasoningError::Database(e.to_string()))?;
+
+        debug!(
+            "Updated {} relation: {} -> {} (strength {} -> {})",
+            existing.relation_type.edge_name(),
+            existing.from_memory_id,
+            existing.to_memory_id,
+            existing.strength,
+            strength
+        );
+        let relation = ReasoningRelation { strength, ..existing };
+        self.relation_cache
+            .lock()
+            .put(relation.relation_id.clone(), relation.clone());
+        Ok(relation)
+    }
+
+    /// Cached relation if present, otherwise the stored edge with its real strength.
+    pub async fn get_relation(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        relation_type: ReasoningType,
+    ) -> Result<Option<ReasoningRelation>, ReasoningError> {
+        if let Some(cached) = self.cached_relation(from_id, to_id, relation_type) {
+            return Ok(Some(cached));
+        }
+
         #[derive(Deserialize)]
-        struct ConnectionsResult {
-            #[serde(default)]
-            implies_out: Vec<MemoryNode>,
+        struct RelationResponse {
             #[serde(default)]
-            implies_in: Vec<MemoryNode>,
+            edges: Vec<StoredEdge>,
             #[serde(default)]
-            because_out: Vec<MemoryNode>,
+            target: serde_json::Value,
+        }
+        #[derive(Deserialize)]
+        struct StoredEdge {
             #[serde(default)]
-            because_in: Vec<MemoryNode>,
+            strength: Option<i64>,
             #[serde(default)]
-            contradicts_out: Vec<MemoryNode>,
+            probability: Option<i64>,
             #[serde(default)]
-            contradicts_in: Vec<MemoryNode>,
+            reasoning_id: Option<String>,
+        }
+
+        let (query, params) = relation_edge_query(from_id, to_id, relation_type, "get");
+        let response = self
+            .client
+            .execute_query::<RelationResponse, _>(&query, &params)
+            .await
+            .map_err(|e| ReasoningError::Database(e.to_string()))?;
+
+        let Some(edge) = response.edges.into_iter().next() else {
+            return Ok(None);
+        };
+        let relation = ReasoningRelation {
+            relation_id: relation_key(from_id, to_id, relation_type),
+            from_memory_id: from_id.to_string(),
+            to_memory_id: to_id.to_string(),
+            to_memory_content: response.target.get("content").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            relation_type,
+            strength: edge.strength.or(edge.probability).unwrap_or(DEFAULT_CHAIN_STRENGTH).clamp(0, 100) as i32,
+            reasoning_id: edge.reasoning_id.filter(|id| !id.is_empty()),
+        };
+
+        self.relation_cache
+            .lock()
+            .put(relation.relation_id.clone(), relation.clone());
+        Ok(Some(relation))
+    }
+
+    
+    pub async fn delete_relation(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        relation_type: ReasoningType,
+    ) -> Result<bool, ReasoningError> {
+        #[derive(Deserialize)]
+        struct DeleteResponse {
             #[serde(default)]
-            relation_out: Vec<MemoryNode>,
+            deleted: serde_json::Value,
+        }
+
+        let (query, params) = relation_edge_query(from_id, to_id, relation_type, "delete");
+
+        let response = self
+            .client
+            .execute_query::<DeleteResponse, _>(&query, &params)
+            .await
+            .map_err(|e| ReasoningError::Database(e.to_string()))?;
+
+        let deleted = response.deleted.as_u64().unwrap_or(0) > 0;
+        let evicted = self.evict_cached_relation(from_id, to_id, relation_type);
+
+        debug!(
+            "Deleted {} relation: {} -> {} (deleted={}, evicted={})",
+            relation_type.edge_name(),
+            from_id,
+            to_id,
+            deleted,
+            evicted
+        );
+
+        Ok(deleted)
+    }
+
+    
+    /// Marks a CONTRADICTS edge resolved and applies the strategy; returns the ids of archived memories.
+    /// `KeepNewer`/`KeepOlder` need both memories to carry a parseable `created_at`.
+    pub async fn resolve_contradiction(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        resolution: &str,
+        strategy: ContradictionStrategy,
+    ) -> Result<Vec<String>, ReasoningError> {
+        #[derive(Deserialize)]
+        struct EdgeResponse {
             #[serde(default)]
-            relation_in: Vec<MemoryNode>,
+            updated: serde_json::Value,
         }
-        
-        #[derive(Deserialize, Clone)]
-        struct MemoryNode {
-            memory_id: String,
+
+        // Settle which side loses before touching the edge, so a missing timestamp changes nothing.
+        let superseded = match strategy {
+            ContradictionStrategy::KeepNewer | ContradictionStrategy::KeepOlder => {
+                let (Some(from_created), Some(to_created)) =
+                    (self.memory_created_at(from_id).await?, self.memory_created_at(to_id).await?)
+                else {
+                    return Err(ReasoningError::Invalid(format!(
+                        "cannot tell which of {} and {} is newer: created_at missing or unparseable",
+                        from_id, to_id
+                    )));
+                };
+                let from_is_newer = from_created >= to_created;
+                let keep_from = from_is_newer == (strategy == ContradictionStrategy::KeepNewer);
+                vec![if keep_from { to_id } else { from_id }.to_string()]
+            }
+            ContradictionStrategy::Merge | ContradictionStrategy::KeepBoth => Vec::new(),
+        };
+
+        let response = self
+            .client
+            .execute_mutation::<EdgeResponse, _>(
+                "resolveMemoryContradiction",
+                &serde_json::json!({
+                    "from_id": from_id,
+                    "to_id": to_id,
+                    "resolution": resolution,
+                    "resolution_strategy": strategy.as_str(),
+                }),
+            )
+            .await
+            .map_err(|e| ReasoningError::Database(e.to_string()))?;
+
+        if response.updated.as_array().is_some_and(|edges| edges.is_empty()) {
+            return Err(ReasoningError::Invalid(format!(
+                "no contradiction between {} and {}",
+                from_id, to_id
+            )));
+        }
+
+        // Archived rather than deleted, so a wrong resolution can be undone with a restore.
+        let archived_at = chrono::Utc::now().to_rfc3339();
+        for memory_id in &superseded {
+            self.client
+                .execute_mutation::<serde_json::Value, _>(
+                    "setMemoryArchivedAt",
+                    &serde_json::json!({"memory_id": memory_id, "archived_at": archived_at}),
+                )
+                .await
+                .map_err(|e| ReasoningError::Database(e.to_string()))?;
+        }
+
+        info!(
+            "Resolved contradiction {} -> {} ({}), archived {:?}",
+            from_id,
+            to_id,
+            strategy.as_str(),
+            superseded
+        );
+
+        Ok(superseded)
+    }
+
+    
+    async fn memory_created_at(&self, memory_id: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>, ReasoningError> {
+        use crate::toolkit::mind_toolbox::search::parse_datetime_utc;
+
+        #[derive(Deserialize)]
+        struct MemoryResponse {
             #[serde(default)]
-            content: String,
+            memory: serde_json::Value,
+        }
+
+        let response = self
+            .client
+            .execute_query::<MemoryResponse, _>("getMemory", &serde_json::json!({"memory_id": memory_id}))
+            .await
+            .map_err(|e| ReasoningError::Database(e.to_string()))?;
+
+        let created_at = response.memory.get("created_at").and_then(|v| v.as_str()).unwrap_or_default();
+        Ok(parse_datetime_utc(created_at))
+    }
+
+    
+    fn cached_relation(&self, from_id: &str, to_id: &str, relation_type: ReasoningType) -> Option<ReasoningRelation> {
+        self.relation_cache
+            .lock()
+            .get(&relation_key(from_id, to_id, relation_type))
+            .filter(|cached| {
+                cached.from_memory_id == from_id
+                    && cached.to_memory_id == to_id
+                    && cached.relation_type == relation_type
+            })
+            .cloned()
+    }
+
+    
+    fn evict_cached_relation(&self, from_id: &str, to_id: &str, relation_type: ReasoningType) -> bool {
+        let key = relation_key(from_id, to_id, relation_type);
+        let mut cache = self.relation_cache.lock();
+
+        let matches = cache.peek(&key).is_some_and(|cached| {
+            cached.from_memory_id == from_id
+                && cached.to_memory_id == to_id
+                && cached.relation_type == relation_type
+        });
+        if matches {
+            cache.pop(&key);
         }
+        matches
+    }
+
+    
+    pub async fn get_chain(
+        &self,
+        memory_id: &str,
+        chain_type: &str,
+        max_depth: usize,
+        edge_types: Option<&[ReasoningType]>,
+        selection: ChainSelection,
+    ) -> Result<ReasoningChain, ReasoningError> {
+        let follow = edge_types.unwrap_or_else(|| ReasoningType::for_chain_mode(chain_type));
 
         let mut relations = Vec::new();
         let mut visited = std::collections::HashSet::new();
@@ -314,7 +1003,7 @@ impl ReasoningEngine {
 
             let result = match self
                 .client
-                .execute_query::<ConnectionsResult, _>(
+                .execute_query::<ChainConnections, _>(
                     "getMemoryLogicalConnections",
                     &serde_json::json!({"memory_id": &current_id}),
                 )
@@ -324,50 +1013,44 @@ impl ReasoningEngine {
                 Err(_) => break,
             };
 
-            let candidates: Vec<(MemoryNode, ReasoningType, bool)> = match chain_type {
-                "causal" => {
-                    result.because_in.iter()
-                        .map(|n| (n.clone(), ReasoningType::Because, true))
-                        .collect()
-                }
-                "forward" => {
-                    result.implies_out.iter()
-                        .map(|n| (n.clone(), ReasoningType::Implies, false))
-                        .collect()
-                }
-                "both" | "deep" | _ => {
-                    let mut all = Vec::new();
-                    for n in &result.implies_out {
-                        all.push((n.clone(), ReasoningType::Implies, false));
-                    }
-                    for n in &result.because_in {
-                        all.push((n.clone(), ReasoningType::Because, true));
-                    }
-                    for n in &result.contradicts_out {
-                        all.push((n.clone(), ReasoningType::Contradicts, false));
-                    }
-                    all
-                }
-            };
+            let candidates = result.candidates(follow);
 
-            let unvisited: Vec<_> = candidates
+            let mut unvisited: Vec<_> = candidates
                 .into_iter()
-                .filter(|(n, _, _)| !visited.contains(&n.memory_id))
+                .filter(|c| !visited.contains(&c.node.memory_id))
                 .collect();
 
             if unvisited.is_empty() {
                 break;
             }
 
+            if selection != ChainSelection::MostRecent {
+                match self
+                    .client
+                    .execute_query::<ChainEdges, _>(
+                        "getMemoryLogicalEdges",
+                        &serde_json::json!({"memory_id": &current_id}),
+                    )
+                    .await
+                {
+                    Ok(edges) => edges.apply_to(&mut unvisited, self.strength_decay, chrono::Utc::now()),
+                    Err(e) => debug!("Edge strengths unavailable for {}: {}", current_id, e),
+                }
+                // The LLM sees options best-first, and without one the best is followed.
+                rank_candidates(&mut unvisited);
+            }
+
             let best = if unvisited.len() == 1 {
                 unvisited.into_iter().next()
+            } else if selection != ChainSelection::LlmGuided {
+                pick_deterministic(unvisited, selection)
             } else if let Some(llm) = &self.llm_provider {
                 let prompt = format!(
                     "Given current memory and {} connected memories, which ONE is most logically relevant?\n\nCurrent: {}\n\nOptions:\n{}\n\nRespond with just the number (1-{}).",
                     unvisited.len(),
                     &current_id[..current_id.len().min(50)],
                     unvisited.iter().enumerate()
-                        .map(|(i, (n, t, _))| format!("{}. [{}] {}", i + 1, t.edge_name(), n.content.chars().take(100).collect::<String>()))
+                        .map(|(i, c)| format!("{}. [{}] {}", i + 1, c.relation_type.edge_name(), c.node.content.chars().take(100).collect::<String>()))
                         .collect::<Vec<_>>()
                         .join("\n"),
                     unvisited.len()
@@ -384,7 +1067,7 @@ impl ReasoningEngine {
                 unvisited.into_iter().next()
             };
 
-            if let Some((node, relation_type, is_incoming)) = best {
+            if let Some(ChainCandidate { node, relation_type, is_incoming, strength }) = best {
                 let (from_id, to_id) = if is_incoming {
                     (node.memory_id.clone(), current_id.clone())
                 } else {
@@ -397,7 +1080,7 @@ impl ReasoningEngine {
                     to_memory_id: to_id,
                     to_memory_content: node.content.clone(),
                     relation_type,
-                    strength: 80,
+                    strength: strength.clamp(0, 100) as i32,
                     reasoning_id: None,
                 });
 
@@ -420,6 +1103,63 @@ impl ReasoningEngine {
     }
 
     
+    pub async fn get_chain_tree(
+        &self,
+        memory_id: &str,
+        chain_type: &str,
+        max_depth: usize,
+        max_branching: usize,
+    ) -> Result<ReasoningChainTree, ReasoningError> {
+        let follow = ReasoningType::for_chain_mode(chain_type);
+
+        let mut visited = std::collections::HashSet::from([memory_id.to_string()]);
+        let mut arena = ChainTreeArena::new(memory_id);
+        let mut frontier = vec![0];
+        let mut depth = 0;
+
+        while depth < max_depth && !frontier.is_empty() {
+            let mut next = Vec::new();
+            for idx in frontier {
+                let current_id = arena.nodes[idx].memory_id.clone();
+                let connections = match self
+                    .client
+                    .execute_query::<ChainConnections, _>(
+                        "getMemoryLogicalConnections",
+                        &serde_json::json!({"memory_id": &current_id}),
+                    )
+                    .await
+                {
+                    Ok(r) => r,
+                    Err(e) => {
+                        debug!("Skipping expansion of {}: {}", current_id, e);
+                        continue;
+                    }
+                };
+                next.extend(arena.attach(idx, connections.candidates(follow), &mut visited, max_branching));
+            }
+
+            if next.is_empty() {
+                break;
+            }
+            depth += 1;
+            frontier = next;
+        }
+
+        let node_count = arena.nodes.len();
+        let root = arena.into_tree();
+        let reasoning_trail = render_chain_tree(&root);
+
+        Ok(ReasoningChainTree {
+            seed_memory_id: memory_id.to_string(),
+            root,
+            chain_type: chain_type.to_string(),
+            depth,
+            node_count,
+            reasoning_trail,
+        })
+    }
+
+    
     pub async fn infer_relations(
         &self,
         memory_id: &str,
@@ -491,12 +1231,7 @@ Output JSON array with relations: [{"from_id": "...", "to_id": "...", "type": "I
 
         let mut trail = String::new();
         for (i, rel) in relations.iter().enumerate() {
-            let arrow = match rel.relation_type {
-                ReasoningType::Implies => "→",
-                ReasoningType::Because => "←",
-                ReasoningType::Contradicts => "⊗",
-                ReasoningType::Supports => "↔",
-            };
+            let arrow = relation_arrow(rel.relation_type);
 
             if i > 0 {
                 trail.push_str(" ");
@@ -563,12 +1298,169 @@ mod tests {
         assert_eq!(ReasoningType::Supports.edge_name(), "SUPPORTS");
     }
 
+    // (getMemoryLogicalConnections, getMemoryLogicalEdges) responses per memory id.
+    fn stub_graph(memory_id: &str) -> (serde_json::Value, serde_json::Value) {
+        match memory_id {
+            "seed" => (
+                serde_json::json!({
+                    "implies_out": [
+                        {"id": "n1", "memory_id": "weak", "created_at": "2024-05-01T00:00:00Z"},
+                        {"id": "n2", "memory_id": "strong", "created_at": "2024-01-01T00:00:00Z"},
+                    ],
+                    "because_in": [{"id": "n3", "memory_id": "cause", "created_at": "2024-03-01T00:00:00Z"}],
+                }),
+                serde_json::json!({
+                    "implies_out": [
+                        {"from_node": "n0", "to_node": "n1", "probability": 40},
+                        {"from_node": "n0", "to_node": "n2", "probability": 95},
+                    ],
+                    "because_in": [{"from_node": "n3", "to_node": "n0", "strength": 70}],
+                }),
+            ),
+            "strong" => (
+                serde_json::json!({
+                    "implies_out": [
+                        {"id": "n4", "memory_id": "tie_b"},
+                        {"id": "n5", "memory_id": "tie_a"},
+                    ],
+                }),
+                serde_json::json!({}),
+            ),
+            _ => (serde_json::json!({}), serde_json::json!({})),
+        }
+    }
+
+    // Memory ids visited by `get_chain` from `seed`; `shuffle` reverses every list the stub returns.
+    async fn walk(seed: &str, selection: ChainSelection, shuffle: bool) -> Vec<String> {
+        let (client, _) = spawn_stub(move |query, body| {
+            let (connections, edges) = stub_graph(body["memory_id"].as_str().unwrap_or_default());
+            let mut response = match query {
+                "getMemoryLogicalConnections" => connections,
+                "getMemoryLogicalEdges" => edges,
+                _ => serde_json::json!({}),
+            };
+            if shuffle {
+                for list in response.as_object_mut().into_iter().flat_map(|o| o.values_mut()) {
+                    if let Some(items) = list.as_array_mut() {
+                        items.reverse();
+                    }
+                }
+            }
+            response
+        })
+        .await;
+        let engine = ReasoningEngine::new(client, None, 10);
+
+        let chain = engine
+            .get_chain(seed, "forward", 5, Some(&[ReasoningType::Implies, ReasoningType::Because]), selection)
+            .await
+            .unwrap();
+        let mut path = vec![seed.to_string()];
+        for relation in chain.relations {
+            let next = if relation.from_memory_id == *path.last().unwrap() {
+                relation.to_memory_id
+            } else {
+                relation.from_memory_id
+            };
+            path.push(next);
+        }
+        path
+    }
+
+    #[tokio::test]
+    async fn test_highest_strength_chain_is_deterministic() {
+        let expected = vec!["seed", "strong", "tie_a"];
+        for _ in 0..3 {
+            assert_eq!(walk("seed", ChainSelection::HighestStrength, false).await, expected);
+            assert_eq!(walk("seed", ChainSelection::HighestStrength, true).await, expected);
+        }
+        assert_eq!(walk("seed", ChainSelection::MostRecent, true).await, vec!["seed", "weak"]);
+    }
+
+    #[test]
+    fn test_candidates_rank_by_edge_weight_times_strength() {
+        let conns: ChainConnections = serde_json::from_value(serde_json::json!({
+            "implies_out": [{"id": "n1", "memory_id": "implied"}],
+            "because_in": [{"id": "n2", "memory_id": "strong_cause"}, {"id": "n3", "memory_id": "weak_cause"}],
+            "contradicts_out": [{"id": "n4", "memory_id": "conflict"}],
+            "relation_out": [{"id": "n5", "memory_id": "support"}],
+        }))
+        .unwrap();
+        let edges: ChainEdges = serde_json::from_value(serde_json::json!({
+            "implies_out": [{"from_node": "n0", "to_node": "n1", "strength": 80}],
+            "because_in": [
+                {"from_node": "n2", "to_node": "n0", "strength": 90},
+                {"from_node": "n3", "to_node": "n0", "strength": 20},
+            ],
+            "relation_out": [{"from_node": "n0", "to_node": "n5", "strength": 100}],
+        }))
+        .unwrap();
+
+        let mut candidates = conns.candidates(&[
+            ReasoningType::Contradicts,
+            ReasoningType::Because,
+            ReasoningType::Implies,
+            ReasoningType::Supports,
+        ]);
+        edges.apply_to(&mut candidates, StrengthDecay::default(), chrono::Utc::now());
+        rank_candidates(&mut candidates);
+
+        let order: Vec<&str> = candidates.iter().map(|c| c.node.memory_id.as_str()).collect();
+        // 90×1.0, 80×0.9, 100×0.7, default 80×0.4, 20×1.0
+        assert_eq!(order, ["strong_cause", "implied", "support", "conflict", "weak_cause"]);
+    }
+
+    #[test]
+    fn test_chain_tree_branches_and_visits_once() {
+        let candidates = |ids: &[&str]| -> Vec<ChainCandidate> {
+            let value = serde_json::json!({
+                "implies_out": ids.iter().map(|id| serde_json::json!({"memory_id": id, "content": format!("fact {}", id)})).collect::<Vec<_>>(),
+            });
+            serde_json::from_value::<ChainConnections>(value).unwrap().candidates(&[ReasoningType::Implies])
+        };
+
+        let mut visited = std::collections::HashSet::from(["seed".to_string()]);
+        let mut arena = ChainTreeArena::new("seed");
+        let level1 = arena.attach(0, candidates(&["a", "b", "c"]), &mut visited, 2);
+        assert_eq!(level1.len(), 2);
+
+        // "shared" is reachable from both a and b; only a keeps it. The back-edge to seed is dropped.
+        arena.attach(level1[0], candidates(&["shared", "seed"]), &mut visited, 2);
+        arena.attach(level1[1], candidates(&["shared", "d"]), &mut visited, 2);
+
+        let root = arena.into_tree();
+        let child_ids = |node: &ReasoningChainNode| node.children.iter().map(|c| c.memory_id.clone()).collect::<Vec<_>>();
+        assert_eq!(child_ids(&root), vec!["a", "b"]);
+        assert_eq!(child_ids(&root.children[0]), vec!["shared"]);
+        assert_eq!(child_ids(&root.children[1]), vec!["d"]);
+        assert_eq!(root.children[1].children[0].depth, 2);
+
+        let trail = render_chain_tree(&root);
+        assert_eq!(trail.lines().next(), Some("[seed]"));
+        assert!(trail.contains("\n    → [shared] fact shared"));
+    }
+
+    #[test]
+    fn test_strength_decay_halves_per_half_life() {
+        let now = chrono::Utc::now();
+        let month_ago = (now - chrono::Duration::days(30)).to_rfc3339();
+        let decay = StrengthDecay { half_life_days: Some(30.0) };
+
+        assert!((decay.factor(0.0) - 1.0).abs() < 1e-12);
+        assert!((decay.factor(30.0) - 0.5).abs() < 1e-12);
+        assert!((decay.factor(60.0) - 0.25).abs() < 1e-12);
+        assert!((decay.effective(80.0, Some(&month_ago), now) - 40.0).abs() < 1e-6);
+        assert_eq!(decay.effective(80.0, None, now), 80.0);
+        assert_eq!(StrengthDecay::default().effective(80.0, Some(&month_ago), now), 80.0);
+    }
+
     #[test]
     fn test_relation_creation() {
         let relation = ReasoningRelation {
             relation_id: "test".to_string(),
             from_memory_id: "mem_1".to_string(),
             to_memory_id: "mem_2".to_string(),
+            to_memory_content: String::new(),
             relation_type: ReasoningType::Implies,
             strength: 80,
             reasoning_id: None,
@@ -578,6 +1470,238 @@ mod tests {
         assert_eq!(relation.relation_type, ReasoningType::Implies);
     }
 
+    #[test]
+    fn test_evict_cached_relation_checks_full_ids() {
+        let engine = ReasoningEngine::new(
+            Arc::new(HelixClient::new("localhost", 6969).unwrap()),
+            None,
+            10,
+        );
+        let relation = |from: &str, to: &str, relation_type| ReasoningRelation {
+            relation_id: relation_key(from, to, relation_type),
+            from_memory_id: from.to_string(),
+            to_memory_id: to.to_string(),
+            to_memory_content: String::new(),
+            relation_type,
+            strength: 80,
+            reasoning_id: None,
+        };
+
+        let cached = relation("mem_abcd1111", "mem_efgh2222", ReasoningType::Implies);
+        engine.relation_cache.lock().put(cached.relation_id.clone(), cached.clone());
+
+        assert!(!engine.evict_cached_relation("mem_abcd9999", "mem_efgh2222", ReasoningType::Implies));
+        assert!(!engine.evict_cached_relation("mem_abcd1111", "mem_efgh2222", ReasoningType::Because));
+        assert!(engine.relation_cache.lock().contains(&cached.relation_id));
+
+        assert!(engine.evict_cached_relation("mem_abcd1111", "mem_efgh2222", ReasoningType::Implies));
+        assert!(!engine.relation_cache.lock().contains(&cached.relation_id));
+    }
+
+    // Answers every query with an empty object and records the query names it was asked for.
+    async fn spawn_recording_stub() -> (Arc<HelixClient>, Arc<parking_lot::Mutex<Vec<String>>>) {
+        spawn_stub(|_, _| serde_json::json!({})).await
+    }
+
+    // Answers each query with `respond(query_name, body)` and records the query names.
+    async fn spawn_stub<F>(respond: F) -> (Arc<HelixClient>, Arc<parking_lot::Mutex<Vec<String>>>)
+    where
+        F: Fn(&str, &serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    {
+        let stub = crate::test_support::spawn_stub(move |query, body| Some(respond(query, body))).await;
+        (stub.client(), stub.requests)
+    }
+
+    #[tokio::test]
+    async fn test_get_relation_prefers_cache_then_queries_db() {
+        let (client, queries) = spawn_recording_stub().await;
+        let engine = ReasoningEngine::new(client, None, 10);
+        let cached = ReasoningRelation {
+            relation_id: relation_key("mem_cause", "mem_effect", ReasoningType::Implies),
+            from_memory_id: "mem_cause".to_string(),
+            to_memory_id: "mem_effect".to_string(),
+            to_memory_content: String::new(),
+            relation_type: ReasoningType::Implies,
+            strength: 65,
+            reasoning_id: None,
+        };
+        engine.relation_cache.lock().put(cached.relation_id.clone(), cached);
+
+        let hit = engine.get_relation("mem_cause", "mem_effect", ReasoningType::Implies).await.unwrap();
+        assert_eq!(hit.map(|r| r.strength), Some(65));
+        assert!(queries.lock().is_empty());
+
+        let miss = engine.get_relation("mem_cause", "mem_effect", ReasoningType::Because).await.unwrap();
+        assert!(miss.is_none());
+        assert_eq!(*queries.lock(), vec!["getMemoryCausation".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_add_relation_twice_merges_into_one_edge() {
+        let probabilities = Arc::new(parking_lot::Mutex::new(Vec::<i64>::new()));
+        let store = Arc::clone(&probabilities);
+        let (client, queries) = spawn_stub(move |query, body| match query {
+            "addMemoryImplication" => {
+                store.lock().push(body["probability"].as_i64().unwrap());
+                serde_json::json!({"edge": {}})
+            }
+            "getMemoryImplication" => {
+                let edges: Vec<_> = store.lock().iter().map(|p| serde_json::json!({"probability": p})).collect();
+                serde_json::json!({"edges": edges, "target": {}})
+            }
+            "updateMemoryImplication" => {
+                let strength = body["strength"].as_i64().unwrap();
+                store.lock().iter_mut().for_each(|p| *p = strength);
+                serde_json::json!({})
+            }
+            _ => serde_json::json!({}),
+        })
+        .await;
+        let engine = ReasoningEngine::new(client, None, 10);
+
+        engine.add_relation("mem_a", "mem_b", ReasoningType::Implies, 60, None).await.unwrap();
+        let merged = engine.add_relation("mem_a", "mem_b", ReasoningType::Implies, 85, None).await.unwrap();
+        let kept = engine.add_relation("mem_a", "mem_b", ReasoningType::Implies, 70, None).await.unwrap();
+
+        assert_eq!(*probabilities.lock(), vec![85]);
+        assert_eq!((merged.strength, kept.strength), (85, 85));
+        assert_eq!(queries.lock().iter().filter(|q| q.as_str() == "updateMemoryImplication").count(), 1);
+        assert!(engine.cached_relation("mem_a", "mem_b", ReasoningType::Because).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_relation_types_between_same_pair_cache_separately() {
+        let (client, queries) = spawn_recording_stub().await;
+        let engine = ReasoningEngine::new(client, None, 10);
+
+        engine.add_relation("mem_abcd1111", "mem_efgh2222", ReasoningType::Implies, 70, None).await.unwrap();
+        engine.add_relation("mem_abcd1111", "mem_efgh2222", ReasoningType::Contradicts, 50, None).await.unwrap();
+        engine.add_relation("mem_abcd9999", "mem_efgh2222", ReasoningType::Implies, 40, None).await.unwrap();
+        assert_eq!(engine.get_cache_stats().size, 3);
+
+        queries.lock().clear();
+        let implies = engine.get_relation("mem_abcd1111", "mem_efgh2222", ReasoningType::Implies).await.unwrap().unwrap();
+        let contradicts = engine.get_relation("mem_abcd1111", "mem_efgh2222", ReasoningType::Contradicts).await.unwrap().unwrap();
+        assert_eq!((implies.relation_type, implies.strength), (ReasoningType::Implies, 70));
+        assert_eq!(contradicts.relation_type, ReasoningType::Contradicts);
+        assert!(queries.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_contradiction_keep_both_deletes_nothing() {
+        let (client, queries) = spawn_recording_stub().await;
+        let engine = ReasoningEngine::new(client, None, 10);
+
+        let removed = engine
+            .resolve_contradiction("mem_a", "mem_b", "both hold in different contexts", ContradictionStrategy::KeepBoth)
+            .await
+            .unwrap();
+
+        assert!(removed.is_empty());
+        assert_eq!(*queries.lock(), vec!["resolveMemoryContradiction".to_string()]);
+        assert_eq!(ContradictionStrategy::KeepBoth.as_str(), "keep_both");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_contradiction_keep_newer_archives_older_memory() {
+        let (client, queries) = spawn_stub(|query, body| match (query, body["memory_id"].as_str()) {
+            ("getMemory", Some("mem_a")) => serde_json::json!({"memory": {"created_at": "2026-01-02T00:00:00Z"}}),
+            ("getMemory", Some("mem_b")) => serde_json::json!({"memory": {"created_at": "2026-01-01T00:00:00Z"}}),
+            _ => serde_json::json!({}),
+        })
+        .await;
+        let engine = ReasoningEngine::new(client, None, 10);
+
+        let archived = engine
+            .resolve_contradiction("mem_a", "mem_b", "a is current", ContradictionStrategy::KeepNewer)
+            .await
+            .unwrap();
+
+        assert_eq!(archived, ["mem_b"]);
+        assert_eq!(
+            *queries.lock(),
+            ["getMemory", "getMemory", "resolveMemoryContradiction", "setMemoryArchivedAt"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_contradiction_rejects_missing_timestamps() {
+        let (client, queries) = spawn_stub(|query, body| match (query, body["memory_id"].as_str()) {
+            ("getMemory", Some("mem_a")) => serde_json::json!({"memory": {"created_at": "2026-01-02T00:00:00Z"}}),
+            ("getMemory", _) => serde_json::json!({"memory": {"created_at": ""}}),
+            _ => serde_json::json!({}),
+        })
+        .await;
+        let engine = ReasoningEngine::new(client, None, 10);
+
+        let err = engine
+            .resolve_contradiction("mem_a", "mem_b", "unclear", ContradictionStrategy::KeepOlder)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ReasoningError::Invalid(_)));
+        assert_eq!(*queries.lock(), ["getMemory", "getMemory"]);
+    }
+
+    #[test]
+    fn test_from_edge_name() {
+        assert_eq!(ReasoningType::from_edge_name("because"), Some(ReasoningType::Because));
+        assert_eq!(ReasoningType::from_edge_name(" IMPLIES "), Some(ReasoningType::Implies));
+        assert_eq!(ReasoningType::from_edge_name("SIMILAR_TO"), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_chain_follows_only_because_through_mixed_graph() {
+        let (client, _) = spawn_stub(|query, body| match (query, body["memory_id"].as_str()) {
+            ("getMemoryLogicalConnections", Some("a")) => serde_json::json!({
+                "implies_out": [{"memory_id": "x", "content": "implied"}],
+                "contradicts_out": [{"memory_id": "y", "content": "contradiction"}],
+                "because_in": [{"memory_id": "b", "content": "cause of a"}],
+            }),
+            ("getMemoryLogicalConnections", Some("b")) => serde_json::json!({
+                "implies_out": [{"memory_id": "z", "content": "implied"}],
+                "because_in": [{"memory_id": "c", "content": "cause of b"}],
+            }),
+            _ => serde_json::json!({}),
+        })
+        .await;
+        let engine = ReasoningEngine::new(client, None, 10);
+
+        let chain = engine
+            .get_chain("a", "forward", 5, Some(&[ReasoningType::Because]), ChainSelection::default())
+            .await
+            .unwrap();
+
+        let hops: Vec<(&str, &str)> =
+            chain.relations.iter().map(|r| (r.from_memory_id.as_str(), r.to_memory_id.as_str())).collect();
+        assert_eq!(hops, [("b", "a"), ("c", "b")]);
+        assert!(chain.relations.iter().all(|r| r.relation_type == ReasoningType::Because));
+    }
+
+    #[test]
+    fn test_candidates_skip_repeated_neighbours() {
+        let conns: ChainConnections = serde_json::from_value(serde_json::json!({
+            "implies_out": [{"memory_id": "b"}, {"memory_id": "b"}, {"memory_id": "c"}],
+            "relation_out": [{"memory_id": "b"}],
+        }))
+        .unwrap();
+
+        let candidates = conns.candidates(&[ReasoningType::Implies, ReasoningType::Supports]);
+        let offered: Vec<(&str, ReasoningType)> =
+            candidates.iter().map(|c| (c.node.memory_id.as_str(), c.relation_type)).collect();
+        assert_eq!(
+            offered,
+            [("b", ReasoningType::Implies), ("c", ReasoningType::Implies), ("b", ReasoningType::Supports)]
+        );
+    }
+
+    #[test]
+    fn test_chain_mode_bundles() {
+        assert_eq!(ReasoningType::for_chain_mode("causal"), &[ReasoningType::Because]);
+        assert_eq!(ReasoningType::for_chain_mode("forward"), &[ReasoningType::Implies]);
+        assert_eq!(ReasoningType::for_chain_mode("both").len(), 3);
+    }
+
     #[test]
     fn test_build_reasoning_trail() {
         let relations = vec![
@@ -585,6 +1709,7 @@ mod tests {
                 relation_id: "r1".to_string(),
                 from_memory_id: "mem_aaaa".to_string(),
                 to_memory_id: "mem_bbbb".to_string(),
+                to_memory_content: String::new(),
                 relation_type: ReasoningType::Implies,
                 strength: 90,
                 reasoning_id: None,
@@ -593,13 +1718,14 @@ mod tests {
                 relation_id: "r2".to_string(),
                 from_memory_id: "mem_bbbb".to_string(),
                 to_memory_id: "mem_cccc".to_string(),
+                to_memory_content: String::new(),
                 relation_type: ReasoningType::Because,
                 strength: 85,
                 reasoning_id: None,
             },
         ];
 
-        let client = HelixDB::new(None, None, None);
+        let client = Arc::new(HelixClient::new("localhost", 6969).unwrap());
         let engine = ReasoningEngine::new(client, None, 100);
         let trail = engine.build_reasoning_trail(&relations);
 
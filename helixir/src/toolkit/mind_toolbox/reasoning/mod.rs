@@ -3,5 +3,8 @@
 pub mod engine;
 
 
-pub use engine::{ReasoningEngine, ReasoningType, ReasoningRelation, ReasoningChain, ReasoningError};
+pub use engine::{
+    ReasoningEngine, ReasoningType, ReasoningRelation, ReasoningChain, ReasoningChainNode, ReasoningChainTree,
+    ReasoningError, ChainSelection, ContradictionStrategy, StrengthDecay, StrengthMerge,
+};
 
@@ -1,68 +1,324 @@
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use lazy_static::lazy_static;
-use super::models::{SearchResult, SearchMethod};
+use rust_stemmers::Stemmer;
+use super::models::{SearchResult, SearchMethod, Snippet, cmp_results};
+
+pub use rust_stemmers::Algorithm as StemmerLanguage;
 
 lazy_static! {
-    static ref STOPWORDS: HashSet<&'static str> = {
-        let mut set = HashSet::new();
-        set.extend([
+    static ref STOPWORDS: Arc<HashSet<String>> = Arc::new(
+        [
             "the", "a", "an", "and", "or", "but", "in", "on", "at", "to", "for", "of", 
             "with", "by", "from", "as", "is", "was", "are", "were", "be", "been", "being"
-        ]);
-        set
-    };
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    );
     static ref WORD_REGEX: Regex = Regex::new(r"\b\w+\b").unwrap();
+    static ref QUERY_PART_REGEX: Regex = Regex::new(r#""([^"]*)"|(\S+)"#).unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bm25Config {
+    pub k1: f64,
+    pub b: f64,
+    /// Off by default: stemming conflates identifiers when searching code-like text
+    pub stemming: bool,
+    pub language: StemmerLanguage,
+}
+
+impl Default for Bm25Config {
+    fn default() -> Self {
+        Self {
+            k1: 1.5,
+            b: 0.75,
+            stemming: false,
+            language: StemmerLanguage::English,
+        }
+    }
+}
+
+impl Bm25Config {
+    pub fn with_stemming(mut self, language: StemmerLanguage) -> Self {
+        self.stemming = true;
+        self.language = language;
+        self
+    }
+
+    pub fn stemmer(&self) -> Option<Stemmer> {
+        self.stemming.then(|| Stemmer::create(self.language))
+    }
+}
+
+/// Default minimum token length in bytes; shorter tokens are dropped.
+pub const DEFAULT_MIN_TOKEN_LEN: usize = 3;
+
+/// Parsed BM25 query: `"exact phrase"`, `+required` and `-excluded` operators on top of plain terms.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bm25Query {
+    pub terms: Vec<String>,
+    pub phrases: Vec<Vec<String>>,
+    pub required: Vec<String>,
+    pub excluded: Vec<String>,
 }
 
-pub struct Bm25Search;
+impl Bm25Query {
+    pub fn parse(query: &str) -> Self {
+        Bm25Search::default().parse_query(query)
+    }
+
+    fn parse_with(query: &str, tokenize: impl Fn(&str) -> Vec<String>) -> Self {
+        let mut parsed = Self::default();
+        for part in QUERY_PART_REGEX.captures_iter(query) {
+            if let Some(phrase) = part.get(1) {
+                let tokens = tokenize(phrase.as_str());
+                if !tokens.is_empty() {
+                    parsed.phrases.push(tokens);
+                }
+                continue;
+            }
+            let word = &part[2];
+            if let Some(required) = word.strip_prefix('+') {
+                parsed.required.extend(tokenize(required));
+            } else if let Some(excluded) = word.strip_prefix('-') {
+                parsed.excluded.extend(tokenize(excluded));
+            } else {
+                parsed.terms.extend(tokenize(word));
+            }
+        }
+        parsed
+    }
+
+    pub fn has_operators(&self) -> bool {
+        !self.phrases.is_empty() || !self.required.is_empty() || !self.excluded.is_empty()
+    }
+
+    /// Terms that contribute to the score; excluded terms only filter.
+    pub fn scoring_terms(&self) -> Vec<String> {
+        self.terms
+            .iter()
+            .chain(&self.required)
+            .chain(self.phrases.iter().flatten())
+            .cloned()
+            .collect()
+    }
+
+    fn admits(&self, positions: &HashMap<&str, Vec<usize>>) -> bool {
+        self.excluded.iter().all(|term| !positions.contains_key(term.as_str()))
+            && self.required.iter().all(|term| positions.contains_key(term.as_str()))
+            && self.phrases.iter().all(|phrase| contains_phrase(phrase, positions))
+    }
+}
+
+fn contains_phrase(phrase: &[String], positions: &HashMap<&str, Vec<usize>>) -> bool {
+    let Some(starts) = positions.get(phrase[0].as_str()) else {
+        return false;
+    };
+    starts.iter().any(|&start| {
+        phrase[1..].iter().enumerate().all(|(offset, token)| {
+            positions
+                .get(token.as_str())
+                .is_some_and(|at| at.contains(&(start + offset + 1)))
+        })
+    })
+}
+
+/// BM25 scorer with its own stopword set and tokenizer options.
+#[derive(Debug, Clone)]
+pub struct Bm25Search {
+    config: Bm25Config,
+    /// `None` disables stopword filtering
+    stopwords: Option<Arc<HashSet<String>>>,
+    min_token_len: usize,
+}
+
+impl Default for Bm25Search {
+    fn default() -> Self {
+        Self::new(Bm25Config::default())
+    }
+}
 
 impl Bm25Search {
-    pub fn tokenize(text: &str) -> Vec<String> {
+    pub fn new(config: Bm25Config) -> Self {
+        Self {
+            config,
+            stopwords: Some(Arc::clone(&STOPWORDS)),
+            min_token_len: DEFAULT_MIN_TOKEN_LEN,
+        }
+    }
+
+    /// Replaces the built-in English stopwords; matching is case-insensitive.
+    pub fn with_stopwords<I, S>(mut self, stopwords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.stopwords = Some(Arc::new(
+            stopwords.into_iter().map(|w| w.as_ref().to_lowercase()).collect(),
+        ));
+        self
+    }
+
+    pub fn without_stopwords(mut self) -> Self {
+        self.stopwords = None;
+        self
+    }
+
+    pub fn with_min_token_len(mut self, min_token_len: usize) -> Self {
+        self.min_token_len = min_token_len;
+        self
+    }
+
+    pub fn config(&self) -> &Bm25Config {
+        &self.config
+    }
+
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        self.tokenize_with(text, self.config.stemmer().as_ref())
+    }
+
+    pub fn parse_query(&self, query: &str) -> Bm25Query {
+        let stemmer = self.config.stemmer();
+        Bm25Query::parse_with(query, |text| self.tokenize_with(text, stemmer.as_ref()))
+    }
+
+    /// Stopwords and short tokens are dropped before stemming, so their stems never leak in.
+    fn tokenize_with(&self, text: &str, stemmer: Option<&Stemmer>) -> Vec<String> {
         let lower = text.to_lowercase();
         WORD_REGEX
             .find_iter(&lower)
-            .map(|m| m.as_str().to_string())
-            .filter(|token| !STOPWORDS.contains(token.as_str()) && token.len() > 2)
+            .map(|m| m.as_str())
+            .filter(|token| {
+                token.len() >= self.min_token_len
+                    && !self.stopwords.as_ref().is_some_and(|stopwords| stopwords.contains(*token))
+            })
+            .map(|token| match stemmer {
+                Some(stemmer) => stemmer.stem(token).into_owned(),
+                None => token.to_string(),
+            })
+            .collect()
+    }
+
+    pub fn inverse_document_frequency(&self, query_tokens: &[String], doc_tokens: &[Vec<String>]) -> HashMap<String, f64> {
+        let total_docs = doc_tokens.len() as f64;
+        let unique_query: HashSet<&str> = query_tokens.iter().map(String::as_str).collect();
+
+        unique_query
+            .into_iter()
+            .map(|term| {
+                let df = doc_tokens
+                    .iter()
+                    .filter(|tokens| tokens.iter().any(|t| t == term))
+                    .count() as f64;
+                let idf = ((total_docs - df + 0.5) / (df + 0.5) + 1.0).ln();
+                (term.to_string(), idf)
+            })
             .collect()
     }
 
+    /// The window of at most `max_len` bytes holding the most query-token matches, cut at
+    /// word boundaries; content with no lexical overlap falls back to its prefix.
+    pub fn make_snippet(&self, content: &str, query_tokens: &[String], max_len: usize) -> Snippet {
+        let wanted: HashSet<&str> = query_tokens.iter().map(String::as_str).collect();
+        let words: Vec<(usize, usize)> = WORD_REGEX.find_iter(content).map(|m| (m.start(), m.end())).collect();
+        let matches: Vec<(usize, usize)> = words
+            .iter()
+            .copied()
+            .filter(|&(start, end)| {
+                self.tokenize(&content[start..end]).first().is_some_and(|t| wanted.contains(t.as_str()))
+            })
+            .collect();
+
+        // Densest run of matches whose span fits; ties keep the earliest.
+        let (mut first, mut last) = (0, 0);
+        let mut tail = 0;
+        for head in 0..matches.len() {
+            while matches[head].1 - matches[tail].0 > max_len && tail < head {
+                tail += 1;
+            }
+            if head - tail > last - first {
+                (first, last) = (tail, head);
+            }
+        }
+
+        let (window_start, window_end) = match (matches.get(first), matches.get(last)) {
+            (Some(&(span_start, _)), Some(&(_, span_end))) => {
+                let slack = max_len.saturating_sub(span_end - span_start);
+                let start = span_start - (slack / 2).min(span_start);
+                let end = (span_end + slack - (span_start - start)).min(content.len());
+                (start.min(end.saturating_sub(max_len)), end)
+            }
+            _ => (0, max_len.min(content.len())),
+        };
+
+        // Snap to whole words; a match never straddles the window, so none is lost.
+        let start = words.iter().map(|&(s, _)| s).find(|&s| s >= window_start).unwrap_or(window_start);
+        let end = words.iter().map(|&(_, e)| e).filter(|&e| e <= window_end).max().unwrap_or(0);
+        let (start, end) = if end > start {
+            (start, end)
+        } else {
+            (0, (0..=window_end).rev().find(|&i| content.is_char_boundary(i)).unwrap_or(0))
+        };
+
+        Snippet {
+            text: content[start..end].to_string(),
+            start,
+            highlights: matches
+                .iter()
+                .filter(|&&(s, e)| s >= start && e <= end)
+                .map(|&(s, e)| (s - start, e - start))
+                .collect(),
+        }
+    }
+
+    /// Token positions per distinct term, in document order.
+    pub fn token_positions(doc_tokens: &[String]) -> HashMap<&str, Vec<usize>> {
+        let mut positions: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (position, token) in doc_tokens.iter().enumerate() {
+            positions.entry(token.as_str()).or_default().push(position);
+        }
+        positions
+    }
+
     pub fn calculate_score(
-        query_tokens: &[String],
+        &self,
+        query: &Bm25Query,
         doc_tokens: &[String],
+        idf: &HashMap<String, f64>,
         avg_doc_length: f64,
-        k1: f64,
-        b: f64,
     ) -> f64 {
+        let Bm25Config { k1, b, .. } = self.config;
+        let query_tokens = query.scoring_terms();
         if query_tokens.is_empty() || doc_tokens.is_empty() {
             return 0.0;
         }
 
+        let positions = Self::token_positions(doc_tokens);
+        if query.has_operators() && !query.admits(&positions) {
+            return 0.0;
+        }
+
         let doc_length = doc_tokens.len() as f64;
         let mut score = 0.0;
 
-        let mut doc_tf: HashMap<&str, i32> = HashMap::new();
-        for token in doc_tokens {
-            *doc_tf.entry(token).or_insert(0) += 1;
-        }
-
-        for query_term in query_tokens {
-            if let Some(&tf) = doc_tf.get(query_term.as_str()) {
-                let tf = tf as f64;
+        for query_term in &query_tokens {
+            if let Some(at) = positions.get(query_term.as_str()) {
+                let tf = at.len() as f64;
                 let numerator = tf * (k1 + 1.0);
                 let denominator = tf + k1 * (1.0 - b + b * (doc_length / avg_doc_length));
-                score += numerator / denominator;
+                let term_idf = idf.get(query_term).copied().unwrap_or(0.0);
+                score += term_idf * numerator / denominator;
             }
         }
 
-        if !query_tokens.is_empty() {
-            score /= query_tokens.len() as f64;
-        }
-
-        score.min(1.0)
+        score
     }
 
     pub fn search(
+        &self,
         query: &str,
         documents: &[(String, String)],
         limit: usize,
@@ -72,31 +328,49 @@ impl Bm25Search {
             return Vec::new();
         }
 
-        let query_tokens = Self::tokenize(query);
+        let stemmer = self.config.stemmer();
+        let query = Bm25Query::parse_with(query, |text| self.tokenize_with(text, stemmer.as_ref()));
+        let query_tokens = query.scoring_terms();
         if query_tokens.is_empty() {
             return Vec::new();
         }
 
         let doc_tokens: Vec<Vec<String>> = documents
             .iter()
-            .map(|(_, content)| Self::tokenize(content))
+            .map(|(_, content)| self.tokenize_with(content, stemmer.as_ref()))
             .collect();
 
         let total_length: f64 = doc_tokens.iter().map(|tokens| tokens.len() as f64).sum();
         let avg_doc_length = total_length / documents.len() as f64;
+        let idf = self.inverse_document_frequency(&query_tokens, &doc_tokens);
+
+        let raw_scores: Vec<f64> = doc_tokens
+            .iter()
+            .map(|tokens| self.calculate_score(&query, tokens, &idf, avg_doc_length))
+            .collect();
+
+        // Raw BM25 is unbounded once IDF is applied, so scores are divided by the best
+        // raw score in this corpus: the top match is 1.0 and hybrid fusion stays in [0, 1].
+        // `min_score` applies to the normalized value; the raw score is kept in metadata.
+        let max_raw = raw_scores.iter().copied().fold(0.0, f64::max);
 
         let mut results: Vec<SearchResult> = documents
             .iter()
-            .zip(doc_tokens.iter())
-            .filter_map(|((memory_id, content), tokens)| {
-                let score = Self::calculate_score(&query_tokens, tokens, avg_doc_length, 1.5, 0.75);
+            .zip(raw_scores)
+            .filter_map(|((memory_id, content), raw_score)| {
+                if raw_score <= 0.0 {
+                    return None;
+                }
+                let score = raw_score / max_raw;
                 if score >= min_score {
+                    let mut metadata = HashMap::new();
+                    metadata.insert("bm25_raw".to_string(), serde_json::json!(raw_score));
                     Some(SearchResult {
                         memory_id: memory_id.clone(),
                         content: content.clone(),
                         score,
                         method: SearchMethod::Bm25,
-                        metadata: HashMap::new(),
+                        metadata,
                         created_at: String::new(),
                     })
                 } else {
@@ -105,8 +379,150 @@ impl Bm25Search {
             })
             .collect();
 
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        results.sort_by(cmp_results);
         results.truncate(limit);
         results
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn docs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items.iter().map(|(id, content)| (id.to_string(), content.to_string())).collect()
+    }
+
+    #[test]
+    fn test_rare_term_outranks_common_term() {
+        let documents = docs(&[
+            ("common", "coffee morning routine"),
+            ("rare", "espresso morning routine"),
+            ("filler_1", "coffee evening walk"),
+            ("filler_2", "coffee weekend hike"),
+        ]);
+
+        let results = Bm25Search::default().search("coffee espresso", &documents, 10, 0.0);
+        assert_eq!(results[0].memory_id, "rare");
+        let common = results.iter().find(|r| r.memory_id == "common").unwrap();
+        assert!(results[0].score > common.score);
+    }
+
+    #[test]
+    fn test_idf_orders_terms_by_rarity() {
+        let bm25 = Bm25Search::default();
+        let doc_tokens = vec![
+            bm25.tokenize("coffee espresso"),
+            bm25.tokenize("coffee tea"),
+            bm25.tokenize("coffee milk"),
+        ];
+        let query = bm25.tokenize("coffee espresso");
+        let idf = bm25.inverse_document_frequency(&query, &doc_tokens);
+
+        assert!(idf["espresso"] > idf["coffee"]);
+        assert!(idf["coffee"] > 0.0);
+    }
+
+    #[test]
+    fn test_scores_are_normalized_to_best_match() {
+        let documents = docs(&[("a", "rust memory graph"), ("b", "python scripts"), ("c", "rust tooling")]);
+        let bm25 = Bm25Search::new(Bm25Config { k1: 1.2, b: 0.5, ..Default::default() });
+        let results = bm25.search("rust memory", &documents, 10, 0.0);
+
+        assert_eq!(results.len(), 2);
+        assert!((results[0].score - 1.0).abs() < 1e-9);
+        assert!(results.iter().all(|r| r.score <= 1.0 && r.metadata.contains_key("bm25_raw")));
+    }
+
+    #[test]
+    fn test_snippet_centres_on_densest_matches() {
+        let bm25 = Bm25Search::default();
+        let content = "Coffee came up once early on. Much later the user said they brew espresso \
+                       every morning and prefer espresso over drip. Then nothing relevant at all.";
+        let query = bm25.tokenize("espresso morning");
+
+        let snippet = bm25.make_snippet(content, &query, 60);
+        assert!(snippet.text.len() <= 60);
+        assert!(snippet.text.contains("espresso every morning and prefer espresso"));
+        let marked: Vec<&str> = snippet.highlights.iter().map(|&(s, e)| &snippet.text[s..e]).collect();
+        assert_eq!(marked, ["espresso", "morning", "espresso"]);
+        assert_eq!(&content[snippet.start..snippet.start + snippet.text.len()], snippet.text);
+    }
+
+    #[test]
+    fn test_snippet_falls_back_to_prefix_without_overlap() {
+        let bm25 = Bm25Search::default();
+        let snippet = bm25.make_snippet("Walks the dog before work every day", &bm25.tokenize("espresso"), 20);
+        assert_eq!(snippet.text, "Walks the dog before");
+        assert_eq!(snippet.start, 0);
+        assert!(snippet.highlights.is_empty());
+    }
+
+    #[test]
+    fn test_plain_query_has_no_operators() {
+        let query = Bm25Query::parse("coffee espresso");
+        assert!(!query.has_operators());
+        assert_eq!(query.terms, Bm25Search::default().tokenize("coffee espresso"));
+    }
+
+    #[test]
+    fn test_phrase_requires_adjacent_tokens() {
+        let documents = docs(&[
+            ("adjacent", "prefers dark roast coffee"),
+            ("scattered", "dark chocolate with roast coffee"),
+        ]);
+
+        let results = Bm25Search::default().search("\"dark roast\"", &documents, 10, 0.0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory_id, "adjacent");
+    }
+
+    #[test]
+    fn test_required_term_must_be_present() {
+        let documents = docs(&[
+            ("both", "coffee with oat milk"),
+            ("coffee_only", "coffee coffee black"),
+        ]);
+
+        let results = Bm25Search::default().search("coffee +oat", &documents, 10, 0.0);
+        assert_eq!(results.iter().map(|r| r.memory_id.as_str()).collect::<Vec<_>>(), vec!["both"]);
+    }
+
+    #[test]
+    fn test_excluded_term_drops_document() {
+        let documents = docs(&[
+            ("decaf", "decaf coffee evening"),
+            ("regular", "regular coffee morning"),
+        ]);
+
+        let results = Bm25Search::default().search("coffee -decaf", &documents, 10, 0.0);
+        assert_eq!(results.iter().map(|r| r.memory_id.as_str()).collect::<Vec<_>>(), vec!["regular"]);
+    }
+
+    #[test]
+    fn test_stemming_matches_inflected_forms() {
+        let documents = docs(&[("cats", "two cats sleeping"), ("dogs", "dog barking")]);
+
+        assert!(Bm25Search::default().search("cat", &documents, 10, 0.0).is_empty());
+
+        let stemmed = Bm25Search::new(Bm25Config::default().with_stemming(StemmerLanguage::English));
+        let results = stemmed.search("cat", &documents, 10, 0.0);
+        assert_eq!(results.iter().map(|r| r.memory_id.as_str()).collect::<Vec<_>>(), vec!["cats"]);
+        assert_eq!(stemmed.tokenize("running the"), vec!["run"]);
+    }
+
+    #[test]
+    fn test_custom_and_disabled_stopwords() {
+        let default = Bm25Search::default();
+        assert_eq!(default.tokenize("this is the marker"), vec!["this", "marker"]);
+
+        let custom = Bm25Search::default().with_stopwords(["THIS"]);
+        assert_eq!(custom.tokenize("this is the marker"), vec!["the", "marker"]);
+
+        let unfiltered = Bm25Search::default().without_stopwords().with_min_token_len(1);
+        assert_eq!(unfiltered.tokenize("this is the marker"), vec!["this", "is", "the", "marker"]);
+        let documents = docs(&[("marked", "flag is set"), ("other", "flag cleared")]);
+        let results = unfiltered.search("+is flag", &documents, 10, 0.0);
+        assert_eq!(results.iter().map(|r| r.memory_id.as_str()).collect::<Vec<_>>(), vec!["marked"]);
+    }
+}
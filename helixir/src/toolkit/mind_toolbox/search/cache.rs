@@ -1,12 +1,25 @@
 use lru::LruCache;
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use std::sync::atomic::{AtomicU64, Ordering};
 use sha2::{Sha256, Digest};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+
+/// How an entry's lifetime is measured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExpirationPolicy {
+    /// Entries expire `ttl` after they were stored.
+    #[default]
+    Absolute,
+    /// Every hit pushes the expiry `ttl` past the access, so hot queries stay cached.
+    Sliding,
+}
 
 pub struct SearchCache<T> {
+    /// Values with their expiry deadline.
     cache: Mutex<LruCache<String, (T, Instant)>>,
     ttl: Duration,
+    policy: ExpirationPolicy,
     hits: AtomicU64,
     misses: AtomicU64,
 }
@@ -20,22 +33,33 @@ pub struct CacheStats {
 }
 
 impl<T> SearchCache<T> {
-    pub fn new(capacity: usize, ttl_secs: u64) -> Self {
+    pub fn new(capacity: usize, ttl_secs: u64, policy: ExpirationPolicy) -> Self {
         Self {
             cache: Mutex::new(LruCache::new(capacity.try_into().unwrap())),
             ttl: Duration::from_secs(ttl_secs),
+            policy,
             hits: AtomicU64::new(0),
             misses: AtomicU64::new(0),
         }
     }
 
     pub fn get(&self, key: &str) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get_at(key, Instant::now())
+    }
+
+    fn get_at(&self, key: &str, now: Instant) -> Option<T>
     where
         T: Clone,
     {
         let mut cache = self.cache.lock();
-        if let Some((value, timestamp)) = cache.get(key) {
-            if timestamp.elapsed() < self.ttl {
+        if let Some((value, expires_at)) = cache.get_mut(key) {
+            if now < *expires_at {
+                if self.policy == ExpirationPolicy::Sliding {
+                    *expires_at = now + self.ttl;
+                }
                 self.hits.fetch_add(1, Ordering::Relaxed);
                 Some(value.clone())
             } else {
@@ -49,8 +73,12 @@ impl<T> SearchCache<T> {
     }
 
     pub fn set(&self, key: &str, value: T) {
+        self.set_at(key, value, Instant::now());
+    }
+
+    fn set_at(&self, key: &str, value: T, now: Instant) {
         let mut cache = self.cache.lock();
-        cache.put(key.to_string(), (value, Instant::now()));
+        cache.put(key.to_string(), (value, now + self.ttl));
     }
 
     pub fn make_key(query: &str, user_id: Option<&str>, limit: usize, min_score: f64) -> String {
@@ -85,4 +113,51 @@ impl<T> SearchCache<T> {
         self.hits.store(0, Ordering::Relaxed);
         self.misses.store(0, Ordering::Relaxed);
     }
-}
\ No newline at end of file
+}
+
+/// Per-user write counter folded into cache keys, so a write strands that user's entries;
+/// unscoped searches are keyed under "" and go stale on every write.
+#[derive(Debug, Default)]
+pub struct GenerationCounter {
+    generations: RwLock<HashMap<String, AtomicU64>>,
+}
+
+impl GenerationCounter {
+    pub fn get(&self, user_id: &str) -> u64 {
+        self.generations.read().get(user_id).map_or(0, |g| g.load(Ordering::Acquire))
+    }
+
+    /// Advances `user_id` and the unscoped "" generation.
+    pub fn bump(&self, user_id: &str) {
+        for user in [user_id, ""] {
+            if let Some(generation) = self.generations.read().get(user) {
+                generation.fetch_add(1, Ordering::AcqRel);
+                continue;
+            }
+            self.generations.write().entry(user.to_string()).or_default().fetch_add(1, Ordering::AcqRel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sliding_expiration_keeps_hot_entries() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let absolute = SearchCache::new(4, 10, ExpirationPolicy::Absolute);
+        let sliding = SearchCache::new(4, 10, ExpirationPolicy::Sliding);
+        absolute.set_at("q", 1, start);
+        sliding.set_at("q", 1, start);
+
+        for secs in [6, 12, 18] {
+            assert_eq!(sliding.get_at("q", at(secs)), Some(1));
+        }
+        assert_eq!(absolute.get_at("q", at(6)), Some(1));
+        assert_eq!(absolute.get_at("q", at(12)), None);
+
+        assert_eq!(sliding.get_at("q", at(29)), None);
+    }
+}
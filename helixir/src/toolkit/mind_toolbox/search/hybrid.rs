@@ -5,8 +5,8 @@ use tokio;
 use tracing::info;
 
 use super::bm25::Bm25Search;
-use super::models::{SearchResult, SearchMethod};
-use super::vector::{VectorSearch, VectorSearchError};
+use super::models::{SearchResult, SearchMethod, cmp_results};
+use super::vector::{is_archived, VectorSearch, VectorSearchError};
 
 #[derive(Error, Debug)]
 pub enum HybridSearchError {
@@ -16,10 +16,23 @@ pub enum HybridSearchError {
     InvalidWeights,
 }
 
+pub const DEFAULT_RRF_K: f64 = 60.0;
+
+/// How vector and BM25 result lists are merged.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FusionStrategy {
+    #[default]
+    WeightedSum,
+    /// Reciprocal rank fusion: each list contributes `1 / (k + rank)`
+    Rrf { k: f64 },
+}
+
 pub struct HybridSearch {
     vector_search: Arc<VectorSearch>,
     vector_weight: f64,
     bm25_weight: f64,
+    fusion: FusionStrategy,
+    bm25: Bm25Search,
 }
 
 impl HybridSearch {
@@ -36,9 +49,21 @@ impl HybridSearch {
             vector_search,
             vector_weight: normalized_vector_weight,
             bm25_weight: normalized_bm25_weight,
+            fusion: FusionStrategy::default(),
+            bm25: Bm25Search::default(),
         }
     }
 
+    pub fn with_fusion(mut self, fusion: FusionStrategy) -> Self {
+        self.fusion = fusion;
+        self
+    }
+
+    pub fn with_bm25(mut self, bm25: Bm25Search) -> Self {
+        self.bm25 = bm25;
+        self
+    }
+
     pub async fn search(
         &self,
         query: &str,
@@ -46,19 +71,52 @@ impl HybridSearch {
         documents: Option<&[(String, String)]>,
         limit: usize,
     ) -> Result<Vec<SearchResult>, HybridSearchError> {
-        let vector_future = self.vector_search.search(query, user_id, limit * 2, 0.0, true);
+        self.search_expanded(query, user_id, documents, limit, &[], 0.0, false).await
+    }
+
+    /// Like `search`, but `expansion_terms` also feed the BM25 arm at `expansion_weight`;
+    /// the vector arm always sees the original query. Archived memories are dropped from
+    /// the vector arm unless `include_archived`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_expanded(
+        &self,
+        query: &str,
+        user_id: Option<&str>,
+        documents: Option<&[(String, String)]>,
+        limit: usize,
+        expansion_terms: &[String],
+        expansion_weight: f64,
+        include_archived: bool,
+    ) -> Result<Vec<SearchResult>, HybridSearchError> {
+        let vector_future = async {
+            let mut results = self.vector_search.search(query, user_id, limit * 2, 0.0, true).await?;
+            if !include_archived {
+                results.retain(|r| !is_archived(r));
+            }
+            Ok::<_, VectorSearchError>(results)
+        };
         let bm25_future = async {
-            if let Some(docs) = documents {
-                Bm25Search::search(query, docs, limit * 2, 0.0)
-            } else {
-                Vec::new()
+            let Some(docs) = documents else {
+                return Vec::new();
+            };
+            let original = self.bm25.search(query, docs, limit * 2, 0.0);
+            if expansion_terms.is_empty() || expansion_weight <= 0.0 {
+                return original;
             }
+            let expanded = self.bm25.search(&expansion_terms.join(" "), docs, limit * 2, 0.0);
+            merge_expanded_bm25(original, expanded, expansion_weight, limit * 2)
         };
 
         let (vector_results, bm25_results) = tokio::join!(vector_future, bm25_future);
         let vector_results = vector_results?;
         let bm25_results = bm25_results;
 
+        if let FusionStrategy::Rrf { k } = self.fusion {
+            let results = fuse_rrf(&vector_results, &bm25_results, k, limit);
+            info!("Hybrid RRF search returned {} results", results.len());
+            return Ok(results);
+        }
+
         let mut combined_scores: HashMap<String, (String, String, f64, HashMap<String, f64>)> = HashMap::new();
 
         for result in vector_results {
@@ -100,9 +158,139 @@ impl HybridSearch {
             })
             .collect();
 
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        results.sort_by(cmp_results);
 
         info!("Hybrid search returned {} results", results.len().min(limit));
         Ok(results.into_iter().take(limit).collect())
     }
-}
\ No newline at end of file
+}
+/// Adds down-weighted expansion-term BM25 scores onto the original query's scores.
+pub fn merge_expanded_bm25(
+    original: Vec<SearchResult>,
+    expanded: Vec<SearchResult>,
+    expansion_weight: f64,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let mut merged: HashMap<String, SearchResult> = original
+        .into_iter()
+        .map(|r| (r.memory_id.clone(), r))
+        .collect();
+
+    for result in expanded {
+        let boost = result.score * expansion_weight;
+        merged
+            .entry(result.memory_id.clone())
+            .and_modify(|existing| existing.score += boost)
+            .or_insert_with(|| {
+                let mut added = result;
+                added.score = boost;
+                added.metadata.insert("via_expansion".to_string(), serde_json::json!(true));
+                added
+            });
+    }
+
+    let mut results: Vec<SearchResult> = merged.into_values().collect();
+    results.sort_by(cmp_results);
+    results.truncate(limit);
+    results
+}
+
+/// Fuses ranked lists with RRF, recording each list's rank and contribution in metadata.
+/// Scores are scaled so a result ranked first in both lists scores 1.0.
+pub fn fuse_rrf(
+    vector_results: &[SearchResult],
+    bm25_results: &[SearchResult],
+    k: f64,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let mut fused: HashMap<String, SearchResult> = HashMap::new();
+
+    for (source, results) in [("vector", vector_results), ("bm25", bm25_results)] {
+        for (index, result) in results.iter().enumerate() {
+            let rank = index + 1;
+            let contribution = 1.0 / (k + rank as f64);
+            let entry = fused.entry(result.memory_id.clone()).or_insert_with(|| SearchResult {
+                memory_id: result.memory_id.clone(),
+                content: result.content.clone(),
+                score: 0.0,
+                method: SearchMethod::Rrf,
+                metadata: HashMap::new(),
+                created_at: result.created_at.clone(),
+            });
+            if entry.created_at.is_empty() {
+                entry.created_at = result.created_at.clone();
+            }
+            entry.score += contribution;
+            entry.metadata.insert(source.to_string(), serde_json::json!(result.score));
+            entry.metadata.insert(format!("{}_rank", source), serde_json::json!(rank));
+            entry.metadata.insert(format!("{}_rrf", source), serde_json::json!(contribution));
+        }
+    }
+
+    let scale = (k + 1.0) / 2.0;
+    let mut results: Vec<SearchResult> = fused
+        .into_values()
+        .map(|mut result| {
+            result.score *= scale;
+            result
+        })
+        .collect();
+    results.sort_by(cmp_results);
+    results.truncate(limit);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked(memory_id: &str, score: f64, method: SearchMethod) -> SearchResult {
+        SearchResult {
+            memory_id: memory_id.to_string(),
+            content: format!("content of {}", memory_id),
+            score,
+            method,
+            metadata: HashMap::new(),
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_rrf_records_rank_provenance() {
+        let vector = vec![ranked("a", 0.9, SearchMethod::Vector), ranked("b", 0.8, SearchMethod::Vector)];
+        let bm25 = vec![ranked("b", 7.5, SearchMethod::Bm25), ranked("c", 3.0, SearchMethod::Bm25)];
+
+        let results = fuse_rrf(&vector, &bm25, 60.0, 10);
+        let ids: Vec<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a", "c"]);
+
+        let b = &results[0];
+        assert!(matches!(b.method, SearchMethod::Rrf));
+        assert_eq!(b.metadata["vector_rank"], serde_json::json!(2));
+        assert_eq!(b.metadata["bm25_rank"], serde_json::json!(1));
+        assert!((b.metadata["vector_rrf"].as_f64().unwrap() - 1.0 / 62.0).abs() < 1e-12);
+        assert!((b.metadata["bm25_rrf"].as_f64().unwrap() - 1.0 / 61.0).abs() < 1e-12);
+        assert!((b.score - (1.0 / 62.0 + 1.0 / 61.0) * 30.5).abs() < 1e-12);
+
+        let a = &results[1];
+        assert!(a.metadata.get("bm25_rank").is_none());
+        assert!((a.score - 0.5).abs() < 1e-12);
+
+        assert_eq!(fuse_rrf(&vector, &bm25, 60.0, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_expansion_scores_are_down_weighted() {
+        let original = vec![ranked("car_note", 2.0, SearchMethod::Bm25)];
+        let expanded = vec![
+            ranked("vehicle_note", 3.0, SearchMethod::Bm25),
+            ranked("car_note", 1.0, SearchMethod::Bm25),
+        ];
+
+        let merged = merge_expanded_bm25(original, expanded, 0.5, 10);
+        let scores: Vec<(&str, f64)> = merged.iter().map(|r| (r.memory_id.as_str(), r.score)).collect();
+        assert_eq!(scores, vec![("car_note", 2.5), ("vehicle_note", 1.5)]);
+        assert_eq!(merged[1].metadata["via_expansion"], serde_json::json!(true));
+        assert!(merged[0].metadata.get("via_expansion").is_none());
+    }
+}
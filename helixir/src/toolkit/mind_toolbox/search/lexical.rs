@@ -0,0 +1,384 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use super::bm25::Bm25Search;
+
+/// How long a `LexicalStore` gathers changes before writing them out in one save.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Error, Debug)]
+pub enum LexicalIndexError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct UserLexicon {
+    documents: HashMap<String, String>,
+    postings: HashMap<String, HashSet<String>>,
+    #[serde(default)]
+    created_at: HashMap<String, String>,
+}
+
+impl UserLexicon {
+    fn insert(&mut self, memory_id: &str, content: &str) {
+        for token in Bm25Search::default().tokenize(content) {
+            self.postings.entry(token).or_default().insert(memory_id.to_string());
+        }
+        self.documents.insert(memory_id.to_string(), content.to_string());
+    }
+
+    fn remove(&mut self, memory_id: &str) -> bool {
+        self.created_at.remove(memory_id);
+        let Some(content) = self.documents.remove(memory_id) else {
+            return false;
+        };
+        for token in Bm25Search::default().tokenize(&content) {
+            if let Some(ids) = self.postings.get_mut(&token) {
+                ids.remove(memory_id);
+                if ids.is_empty() {
+                    self.postings.remove(&token);
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Per-user token -> memory postings used to feed BM25 without a full corpus scan.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LexicalIndex {
+    users: HashMap<String, UserLexicon>,
+    #[serde(skip)]
+    owners: HashMap<String, String>,
+}
+
+impl LexicalIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: &Path) -> Result<Self, LexicalIndexError> {
+        let raw = std::fs::read_to_string(path)?;
+        let mut index: Self = serde_json::from_str(&raw)?;
+        index.owners = index
+            .users
+            .iter()
+            .flat_map(|(user_id, lexicon)| {
+                lexicon.documents.keys().map(move |memory_id| (memory_id.clone(), user_id.clone()))
+            })
+            .collect();
+        Ok(index)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), LexicalIndexError> {
+        write_atomically(path, &serde_json::to_vec(self)?)
+    }
+
+    pub fn has_user(&self, user_id: &str) -> bool {
+        self.users.contains_key(user_id)
+    }
+
+    pub fn document_count(&self, user_id: &str) -> usize {
+        self.users.get(user_id).map_or(0, |lexicon| lexicon.documents.len())
+    }
+
+    pub fn upsert(&mut self, user_id: &str, memory_id: &str, content: &str) {
+        self.remove(memory_id);
+        self.users.entry(user_id.to_string()).or_default().insert(memory_id, content);
+        self.owners.insert(memory_id.to_string(), user_id.to_string());
+    }
+
+    /// Records when `memory_id` was created so searches can apply a recency window.
+    pub fn set_created_at(&mut self, memory_id: &str, created_at: &str) {
+        let Some(user_id) = self.owners.get(memory_id) else {
+            return;
+        };
+        if let Some(lexicon) = self.users.get_mut(user_id) {
+            lexicon.created_at.insert(memory_id.to_string(), created_at.to_string());
+        }
+    }
+
+    pub fn created_at(&self, memory_id: &str) -> Option<&str> {
+        let user_id = self.owners.get(memory_id)?;
+        self.users.get(user_id)?.created_at.get(memory_id).map(String::as_str)
+    }
+
+    pub fn update_content(&mut self, memory_id: &str, content: &str) -> bool {
+        match self.owners.get(memory_id).cloned() {
+            Some(user_id) => {
+                let created_at = self.created_at(memory_id).map(str::to_string);
+                self.upsert(&user_id, memory_id, content);
+                if let Some(created_at) = created_at {
+                    self.set_created_at(memory_id, &created_at);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, memory_id: &str) -> bool {
+        let Some(user_id) = self.owners.remove(memory_id) else {
+            return false;
+        };
+        self.users.get_mut(&user_id).is_some_and(|lexicon| lexicon.remove(memory_id))
+    }
+
+    pub fn replace_user<I>(&mut self, user_id: &str, documents: I)
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        if let Some(old) = self.users.remove(user_id) {
+            for memory_id in old.documents.keys() {
+                self.owners.remove(memory_id);
+            }
+        }
+        let mut lexicon = UserLexicon::default();
+        for (memory_id, content) in documents {
+            lexicon.insert(&memory_id, &content);
+            self.owners.insert(memory_id, user_id.to_string());
+        }
+        self.users.insert(user_id.to_string(), lexicon);
+    }
+
+    /// Documents sharing at least one query token, most distinct matches first.
+    pub fn candidates(&self, user_id: &str, query: &str, limit: usize) -> Vec<(String, String)> {
+        let Some(lexicon) = self.users.get(user_id) else {
+            return Vec::new();
+        };
+
+        let query_tokens: HashSet<String> = Bm25Search::default().tokenize(query).into_iter().collect();
+        let mut matches: HashMap<&str, usize> = HashMap::new();
+        for token in &query_tokens {
+            for memory_id in lexicon.postings.get(token).into_iter().flatten() {
+                *matches.entry(memory_id.as_str()).or_default() += 1;
+            }
+        }
+
+        let mut ranked: Vec<(&str, usize)> = matches.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        ranked
+            .into_iter()
+            .take(limit)
+            .filter_map(|(memory_id, _)| {
+                lexicon
+                    .documents
+                    .get(memory_id)
+                    .map(|content| (memory_id.to_string(), content.clone()))
+            })
+            .collect()
+    }
+}
+
+/// The one `LexicalIndex` every BM25 path reads, optionally mirrored to a file.
+#[derive(Debug, Default)]
+pub struct LexicalStore {
+    index: Arc<RwLock<LexicalIndex>>,
+    writer: Option<Arc<IndexWriter>>,
+}
+
+impl LexicalStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the index at `path` if present; later changes are saved back to it.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let mut index = LexicalIndex::new();
+        if path.exists() {
+            match LexicalIndex::load(&path) {
+                Ok(loaded) => {
+                    info!("Loaded lexical index from {}", path.display());
+                    index = loaded;
+                }
+                Err(e) => warn!("Failed to load lexical index from {}: {}", path.display(), e),
+            }
+        }
+        Self {
+            index: Arc::new(RwLock::new(index)),
+            writer: Some(Arc::new(IndexWriter::new(path))),
+        }
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, LexicalIndex> {
+        self.index.read()
+    }
+
+    /// Applies `change` and schedules a save when it reports a modification.
+    pub fn update<F>(&self, change: F) -> bool
+    where
+        F: FnOnce(&mut LexicalIndex) -> bool,
+    {
+        let changed = change(&mut self.index.write());
+        if changed {
+            self.schedule_save();
+        }
+        changed
+    }
+
+    /// Writes the current index out immediately instead of waiting for the debounce.
+    pub async fn flush(&self) -> Result<(), LexicalIndexError> {
+        let Some(writer) = &self.writer else {
+            return Ok(());
+        };
+        let bytes = serde_json::to_vec(&*self.index.read())?;
+        let writer = Arc::clone(writer);
+        tokio::task::spawn_blocking(move || writer.write(&bytes))
+            .await
+            .map_err(|e| LexicalIndexError::Io(std::io::Error::other(e)))?
+    }
+
+    /// Coalesces changes made within `SAVE_DEBOUNCE` into one save, written off the
+    /// async runtime.
+    fn schedule_save(&self) {
+        let Some(writer) = &self.writer else {
+            return;
+        };
+        if writer.pending.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let index = Arc::clone(&self.index);
+        let writer = Arc::clone(writer);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    tokio::time::sleep(SAVE_DEBOUNCE).await;
+                    writer.pending.store(false, Ordering::Release);
+                    let bytes = match serde_json::to_vec(&*index.read()) {
+                        Ok(bytes) => bytes,
+                        Err(e) => return warn!("Failed to serialize lexical index: {}", e),
+                    };
+                    let path = writer.path.clone();
+                    let result = tokio::task::spawn_blocking(move || writer.write(&bytes)).await;
+                    if let Ok(Err(e)) = result {
+                        warn!("Failed to persist lexical index to {}: {}", path.display(), e);
+                    }
+                });
+            }
+            Err(_) => {
+                writer.pending.store(false, Ordering::Release);
+                let result = serde_json::to_vec(&*index.read())
+                    .map_err(LexicalIndexError::from)
+                    .and_then(|bytes| writer.write(&bytes));
+                if let Err(e) = result {
+                    warn!("Failed to persist lexical index to {}: {}", writer.path.display(), e);
+                }
+            }
+        }
+    }
+}
+
+/// Serializes saves of one index file; `pending` marks a save that is already scheduled.
+#[derive(Debug)]
+struct IndexWriter {
+    path: PathBuf,
+    pending: AtomicBool,
+    lock: Mutex<()>,
+}
+
+impl IndexWriter {
+    fn new(path: PathBuf) -> Self {
+        Self { path, pending: AtomicBool::new(false), lock: Mutex::new(()) }
+    }
+
+    fn write(&self, bytes: &[u8]) -> Result<(), LexicalIndexError> {
+        let _guard = self.lock.lock();
+        write_atomically(&self.path, bytes)
+    }
+}
+
+/// Writes through a uniquely named temp file so concurrent savers never share one.
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), LexicalIndexError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+    std::fs::write(&tmp, bytes)?;
+    if let Err(e) = std::fs::rename(&tmp, path) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incremental_updates_and_candidates() {
+        let mut index = LexicalIndex::new();
+        index.upsert("alice", "m1", "Alice drinks green tea every morning");
+        index.upsert("alice", "m2", "Alice prefers coffee after lunch");
+        index.upsert("bob", "m3", "Bob drinks green tea too");
+
+        let hits = index.candidates("alice", "green tea", 10);
+        assert_eq!(hits, vec![("m1".to_string(), "Alice drinks green tea every morning".to_string())]);
+
+        assert!(index.update_content("m2", "Alice switched to green tea"));
+        let ids: Vec<String> = index.candidates("alice", "green tea", 10).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["m1", "m2"]);
+
+        assert!(index.remove("m1"));
+        assert!(!index.remove("m1"));
+        let ids: Vec<String> = index.candidates("alice", "morning", 10).into_iter().map(|(id, _)| id).collect();
+        assert!(ids.is_empty());
+        assert_eq!(index.document_count("bob"), 1);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = std::env::temp_dir().join(format!("helixir_lexical_{}.json", uuid::Uuid::new_v4()));
+        let mut index = LexicalIndex::new();
+        index.replace_user("alice", vec![("m1".to_string(), "Rust borrow checker notes".to_string())]);
+        index.save(&path).unwrap();
+
+        let mut loaded = LexicalIndex::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.candidates("alice", "borrow checker", 5).len(), 1);
+        assert!(loaded.update_content("m1", "Notes about lifetimes"));
+        assert!(loaded.candidates("alice", "borrow", 5).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_store_debounces_saves_into_one_file() {
+        let dir = std::env::temp_dir().join(format!("helixir_lexical_{}", uuid::Uuid::new_v4()));
+        let path = dir.join("index.json");
+        let store = LexicalStore::open(&path);
+
+        for i in 0..20 {
+            store.update(|index| {
+                index.upsert("alice", &format!("m{i}"), "tea notes");
+                true
+            });
+        }
+        assert!(!path.exists(), "saves wait for the debounce window");
+
+        tokio::time::sleep(SAVE_DEBOUNCE * 3).await;
+        let loaded = LexicalIndex::load(&path).unwrap();
+        assert_eq!(loaded.document_count("alice"), 20);
+
+        store.update(|index| index.remove("m0"));
+        store.flush().await.unwrap();
+        assert_eq!(LexicalIndex::load(&path).unwrap().document_count("alice"), 19);
+
+        let leftovers: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(leftovers.len(), 1, "no temp files remain");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Mode labels tracked individually; anything else is counted as "other".
+const TRACKED_MODES: [&str; 8] = ["recent", "contextual", "deep", "causal", "full", "hybrid", "vector", "other"];
+
+/// Upper bounds (ms) of the latency buckets; the last bucket is unbounded.
+const LATENCY_BUCKETS_MS: [f64; 10] = [5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, f64::INFINITY];
+
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencyBucket {
+    pub le_ms: f64,
+    pub count: u64,
+}
+
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    pub buckets: Vec<LatencyBucket>,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchMetrics {
+    pub total_searches: u64,
+    pub failed_searches: u64,
+    pub searches_by_mode: HashMap<String, u64>,
+    pub avg_results: f64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub cache_hit_rate: f64,
+    pub avg_latency_ms: f64,
+    pub latency: LatencyHistogram,
+}
+
+/// Lock-free counters behind `SearchEngine::metrics`.
+#[derive(Debug, Default)]
+pub(crate) struct SearchMetricsRecorder {
+    searches_by_mode: [AtomicU64; TRACKED_MODES.len()],
+    failed_searches: AtomicU64,
+    total_results: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS_MS.len()],
+    latency_total_us: AtomicU64,
+}
+
+impl SearchMetricsRecorder {
+    pub(crate) fn record_search(&self, mode: &str, result_count: Option<usize>, elapsed: Duration) {
+        let mode = mode.to_lowercase();
+        let slot = TRACKED_MODES
+            .iter()
+            .position(|m| *m == mode)
+            .unwrap_or(TRACKED_MODES.len() - 1);
+        self.searches_by_mode[slot].fetch_add(1, Ordering::Relaxed);
+
+        match result_count {
+            Some(count) => {
+                self.total_results.fetch_add(count as u64, Ordering::Relaxed);
+            }
+            None => {
+                self.failed_searches.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|le| elapsed_ms <= *le)
+            .unwrap_or(LATENCY_BUCKETS_MS.len() - 1);
+        self.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.latency_total_us.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_cache(&self, hit: bool) {
+        let counter = if hit { &self.cache_hits } else { &self.cache_misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// `extra_cache` adds lookups tracked by caches the recorder does not see directly.
+    pub(crate) fn snapshot(&self, extra_cache: (u64, u64)) -> SearchMetrics {
+        let searches_by_mode: HashMap<String, u64> = TRACKED_MODES
+            .iter()
+            .zip(&self.searches_by_mode)
+            .map(|(mode, count)| (mode.to_string(), count.load(Ordering::Relaxed)))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        let total_searches: u64 = searches_by_mode.values().sum();
+        let failed_searches = self.failed_searches.load(Ordering::Relaxed);
+        let succeeded = total_searches.saturating_sub(failed_searches);
+
+        let cache_hits = self.cache_hits.load(Ordering::Relaxed) + extra_cache.0;
+        let cache_misses = self.cache_misses.load(Ordering::Relaxed) + extra_cache.1;
+        let lookups = cache_hits + cache_misses;
+
+        let buckets: Vec<LatencyBucket> = LATENCY_BUCKETS_MS
+            .iter()
+            .zip(&self.latency_buckets)
+            .map(|(le, count)| LatencyBucket { le_ms: *le, count: count.load(Ordering::Relaxed) })
+            .collect();
+        let timed: u64 = buckets.iter().map(|b| b.count).sum();
+
+        SearchMetrics {
+            total_searches,
+            failed_searches,
+            searches_by_mode,
+            avg_results: ratio(self.total_results.load(Ordering::Relaxed), succeeded),
+            cache_hits,
+            cache_misses,
+            cache_hit_rate: ratio(cache_hits, lookups),
+            avg_latency_ms: ratio(self.latency_total_us.load(Ordering::Relaxed), timed) / 1000.0,
+            latency: LatencyHistogram {
+                p50_ms: percentile(&buckets, 0.50),
+                p95_ms: percentile(&buckets, 0.95),
+                p99_ms: percentile(&buckets, 0.99),
+                buckets,
+            },
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        let counters = self
+            .searches_by_mode
+            .iter()
+            .chain(&self.latency_buckets)
+            .chain([
+                &self.failed_searches,
+                &self.total_results,
+                &self.cache_hits,
+                &self.cache_misses,
+                &self.latency_total_us,
+            ]);
+        for counter in counters {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 { 0.0 } else { numerator as f64 / denominator as f64 }
+}
+
+/// Upper bound of the bucket holding the requested rank; the open bucket reports the last finite bound.
+fn percentile(buckets: &[LatencyBucket], quantile: f64) -> f64 {
+    let total: u64 = buckets.iter().map(|b| b.count).sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let rank = ((total as f64) * quantile).ceil().max(1.0) as u64;
+    let mut seen = 0;
+    let mut last_finite = 0.0;
+    for bucket in buckets {
+        if bucket.le_ms.is_finite() {
+            last_finite = bucket.le_ms;
+        }
+        seen += bucket.count;
+        if seen >= rank {
+            return last_finite;
+        }
+    }
+    last_finite
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorder_snapshot_and_reset() {
+        let recorder = SearchMetricsRecorder::default();
+        recorder.record_search("recent", Some(4), Duration::from_millis(3));
+        recorder.record_search("Recent", Some(2), Duration::from_millis(40));
+        recorder.record_search("hybrid", None, Duration::from_millis(700));
+        recorder.record_search("mystery", Some(0), Duration::from_millis(8));
+        recorder.record_cache(true);
+        recorder.record_cache(false);
+
+        let metrics = recorder.snapshot((1, 0));
+        assert_eq!(metrics.total_searches, 4);
+        assert_eq!(metrics.failed_searches, 1);
+        assert_eq!(metrics.searches_by_mode.get("recent"), Some(&2));
+        assert_eq!(metrics.searches_by_mode.get("other"), Some(&1));
+        assert!((metrics.avg_results - 2.0).abs() < 1e-9);
+        assert!((metrics.cache_hit_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(metrics.latency.p50_ms, 10.0);
+        assert_eq!(metrics.latency.p99_ms, 1000.0);
+
+        recorder.reset();
+        let metrics = recorder.snapshot((0, 0));
+        assert_eq!(metrics.total_searches, 0);
+        assert!(metrics.searches_by_mode.is_empty());
+        assert_eq!(metrics.latency.p95_ms, 0.0);
+        assert_eq!(metrics.cache_hit_rate, 0.0);
+    }
+}
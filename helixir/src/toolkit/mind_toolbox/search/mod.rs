@@ -4,21 +4,31 @@ pub mod models;
 pub mod cache;
 pub mod vector;
 pub mod bm25;
+pub mod lexical;
+pub mod metrics;
 pub mod hybrid;
 pub mod smart_traversal_v2;
 pub mod onto_search;
 pub mod query_processor;
+pub mod rerank;
+pub mod modes;
 
-pub use models::{SearchResult, SearchMethod};
-pub use cache::{SearchCache, CacheStats};
-pub use vector::{VectorSearch, VectorSearchError};
-pub use bm25::Bm25Search;
-pub use hybrid::{HybridSearch, HybridSearchError};
+pub use models::{SearchResult, SearchMethod, MetadataFilter, Audience, Snippet, Ranked, cmp_results, cmp_scored};
+pub use cache::{SearchCache, CacheStats, ExpirationPolicy, GenerationCounter};
+pub use vector::{VectorSearch, VectorSearchError, DEFAULT_MMR_LAMBDA};
+pub use bm25::{Bm25Config, Bm25Query, Bm25Search, StemmerLanguage};
+pub use lexical::{LexicalIndex, LexicalIndexError, LexicalStore};
+pub use metrics::{SearchMetrics, LatencyHistogram, LatencyBucket};
+pub use hybrid::{HybridSearch, HybridSearchError, FusionStrategy, DEFAULT_RRF_K};
+pub use rerank::{Reranker, LlmReranker};
+pub use modes::{ModeDefinition, ModeRegistry};
 
 
 pub use smart_traversal_v2::{
     SmartTraversalV2,
     SearchConfig as SmartSearchConfig,
+    ScoreWeights,
+    CombineWeights,
     cosine_similarity,
     calculate_temporal_freshness,
     edge_weights,
@@ -34,16 +44,65 @@ pub use onto_search::{
 };
 
 
-pub use query_processor::{QueryProcessor, QueryIntent, EnhancedQuery};
+pub use query_processor::{QueryProcessor, QueryIntent, EnhancedQuery, LEXICAL_INTENT};
 
 use crate::db::HelixClient;
 use crate::llm::EmbeddingGenerator;
 use crate::core::search_modes::SearchMode;
-use smart_traversal_v2::models::SearchConfig;
-use std::collections::HashMap;
+use smart_traversal_v2::models::{SearchConfig, SearchResult as TraversalResult};
+use metrics::SearchMetricsRecorder;
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::Entry;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 use chrono::{DateTime, Utc, Duration};
-use tracing::{debug, info};
+use futures::{future, stream, Stream, StreamExt};
+use tracing::{debug, info, warn};
+
+
+
+/// Upper bound on memories loaded when building a user's lexical index.
+const LEXICAL_SCAN_LIMIT: i64 = 10_000;
+
+/// BM25 candidates gathered per requested result.
+const LEXICAL_CANDIDATE_FACTOR: usize = 10;
+
+/// Users whose lexical index an unscoped search builds at once.
+const LEXICAL_BUILD_CONCURRENCY: usize = 4;
+
+/// Mode name that asks `search` to pick a mode from the query's detected intent.
+pub const AUTO_MODE: &str = "auto";
+
+/// What "auto" runs when the query suggests no known mode.
+const AUTO_FALLBACK_MODE: &str = "contextual";
+
+/// Lexical hits without the exact phrase rank below every exact match.
+const LEXICAL_PARTIAL_WEIGHT: f64 = 0.5;
+
+/// Pages' worth of results `search_page` ranks up front; each later miss doubles the window.
+const PAGE_PREFETCH: usize = 4;
+
+/// Largest `limit` a single search honours; larger requests are clamped with a warning.
+pub const MAX_SEARCH_LIMIT: usize = 1_000;
+
+
+/// The window a fractional `temporal_days` count stands for.
+pub fn days_to_window(days: f64) -> Duration {
+    Duration::milliseconds((days * 24.0 * 60.0 * 60.0 * 1000.0) as i64)
+}
+
+fn temporal_cutoff(window: Option<Duration>) -> Option<DateTime<Utc>> {
+    window.map(|window| Utc::now() - window)
+}
+
+/// Memories without a parseable `created_at` are kept, matching the BM25 document filter.
+fn created_within(created_at: &str, cutoff: Option<DateTime<Utc>>) -> bool {
+    match (cutoff, parse_datetime_utc(created_at)) {
+        (Some(cutoff), Some(created)) => created >= cutoff,
+        _ => true,
+    }
+}
 
 
 #[derive(Debug, thiserror::Error)]
@@ -54,15 +113,42 @@ pub enum SearchError {
     Hybrid(#[from] HybridSearchError),
     #[error("Invalid mode: {0}")]
     InvalidMode(String),
+    #[error("Graph traversal failed: {0}")]
+    Traversal(#[from] smart_traversal_v2::TraversalError),
+    #[error("Database error: {0}")]
+    Database(String),
 }
 
 #[derive(Debug, Clone)]
 pub struct SearchEngineConfig {
     pub cache_size: usize,
     pub cache_ttl: u64,
+    /// Whether vector-cache hits extend an entry's TTL
+    pub cache_expiration: ExpirationPolicy,
     pub enable_smart_traversal: bool,
     pub vector_weight: f64,
     pub bm25_weight: f64,
+    pub temporal_half_life_days: f64,
+    pub hybrid_fusion: FusionStrategy,
+    /// Opt-in: lets ontology expansion terms reach the BM25 arm of hybrid search
+    pub ontology_expansion: bool,
+    pub expansion_weight: f64,
+    /// Similar memories scoring below this never reach the add-time LLM decision
+    pub decision_similarity_floor: f64,
+    /// Upper bound on over-fetched candidates (e.g. `limit * 2` for deep/full traversal)
+    pub max_candidate_fetch: usize,
+    /// Serve vector results when graph traversal errors; when false the error propagates
+    pub fallback_on_traversal_error: bool,
+    /// Byte budget for result snippets; 0 leaves `snippet` unset
+    pub snippet_max_len: usize,
+    pub bm25: Bm25Search,
+    /// Graph-traversal modes `search` dispatches to by name; unknown names use vector search
+    pub modes: ModeRegistry,
+    /// Reject unknown modes with `SearchError::InvalidMode` instead of falling back to vector search
+    pub strict_modes: bool,
+    /// Serve identifier-like queries (ids, phone numbers, quoted phrases) from BM25 alone in
+    /// "auto" and "hybrid" modes, falling back to vector search when nothing matches
+    pub lexical_short_circuit: bool,
 }
 
 impl Default for SearchEngineConfig {
@@ -70,14 +156,37 @@ impl Default for SearchEngineConfig {
         Self {
             cache_size: 500,
             cache_ttl: 300,
+            cache_expiration: ExpirationPolicy::default(),
             enable_smart_traversal: true,
             vector_weight: 0.6,
             bm25_weight: 0.4,
+            temporal_half_life_days: smart_traversal_v2::DEFAULT_TEMPORAL_HALF_LIFE_DAYS,
+            hybrid_fusion: FusionStrategy::default(),
+            ontology_expansion: false,
+            expansion_weight: 0.5,
+            decision_similarity_floor: 0.6,
+            max_candidate_fetch: 2_000,
+            fallback_on_traversal_error: true,
+            snippet_max_len: 200,
+            bm25: Bm25Search::default(),
+            modes: ModeRegistry::default(),
+            strict_modes: false,
+            lexical_short_circuit: true,
         }
     }
 }
 
 
+/// Per-call settings for `SearchEngine::search_with`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchOptions {
+    /// Only memories created within this window; `None` keeps the mode's own window
+    pub temporal_window: Option<Duration>,
+    /// Keep archived memories, which every search path drops by default
+    pub include_archived: bool,
+}
+
+
 #[derive(Debug, Clone)]
 pub struct UnifiedSearchResult {
     pub memory_id: String,
@@ -86,6 +195,134 @@ pub struct UnifiedSearchResult {
     pub method: String,
     pub metadata: HashMap<String, serde_json::Value>,
     pub created_at: String,
+    /// Component scores behind `score`; only graph-traversal modes fill it in.
+    pub explanation: Option<ScoreBreakdown>,
+    pub snippet: Option<Snippet>,
+    pub provenance: Provenance,
+}
+
+impl UnifiedSearchResult {
+    fn from_traversal(result: TraversalResult, method: String) -> Self {
+        let explanation = ScoreBreakdown {
+            vector_score: result.vector_score,
+            graph_score: result.graph_score,
+            temporal_score: result.temporal_score,
+            depth: result.depth,
+            source: result.source,
+            edge_path: result.edge_path.clone().unwrap_or_default(),
+        };
+        let metadata = result.metadata.unwrap_or_default();
+        let provenance = Provenance {
+            source: explanation.source.clone(),
+            edge_path: explanation.edge_path.clone(),
+            depth: explanation.depth,
+            matched_chunk_id: matched_chunk_id(&metadata),
+            vector_score: Some(explanation.vector_score),
+            bm25_score: None,
+        };
+        Self {
+            memory_id: result.memory_id,
+            content: result.content,
+            score: result.combined_score as f32,
+            method,
+            metadata,
+            created_at: result.created_at.unwrap_or_default(),
+            explanation: Some(explanation),
+            snippet: None,
+            provenance,
+        }
+    }
+}
+
+impl Ranked for UnifiedSearchResult {
+    fn rank_score(&self) -> f64 {
+        f64::from(self.score)
+    }
+
+    fn rank_id(&self) -> &str {
+        &self.memory_id
+    }
+}
+
+/// Why a traversal result ranked where it did.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ScoreBreakdown {
+    pub vector_score: f64,
+    pub graph_score: f64,
+    pub temporal_score: f64,
+    pub depth: u32,
+    /// "vector" for seed hits, "graph" for memories reached through edges
+    pub source: String,
+    /// Edge types walked from the seed, empty for vector hits
+    pub edge_path: Vec<String>,
+}
+
+/// What produced a result: the edges walked from its seed, the chunk that matched,
+/// or the per-arm scores a hybrid search fused.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Provenance {
+    /// "vector", "graph", "hybrid" or "rrf"
+    pub source: String,
+    /// Edge types walked from the seed, empty unless `source` is "graph"
+    pub edge_path: Vec<String>,
+    pub depth: u32,
+    pub matched_chunk_id: Option<String>,
+    pub vector_score: Option<f64>,
+    pub bm25_score: Option<f64>,
+}
+
+impl Provenance {
+    pub fn from_vector(result: &SearchResult) -> Self {
+        Self {
+            source: "vector".to_string(),
+            matched_chunk_id: matched_chunk_id(&result.metadata),
+            vector_score: Some(result.score),
+            ..Self::default()
+        }
+    }
+
+    /// Hybrid and RRF fusion record each arm's raw score under "vector" / "bm25".
+    pub fn from_hybrid(result: &SearchResult) -> Self {
+        Self {
+            source: result.method.to_string().to_lowercase(),
+            matched_chunk_id: matched_chunk_id(&result.metadata),
+            vector_score: result.metadata.get("vector").and_then(|v| v.as_f64()),
+            bm25_score: result.metadata.get("bm25").and_then(|v| v.as_f64()),
+            ..Self::default()
+        }
+    }
+}
+
+fn matched_chunk_id(metadata: &HashMap<String, serde_json::Value>) -> Option<String> {
+    metadata.get("matched_chunk_id").and_then(|v| v.as_str()).map(str::to_string)
+}
+
+/// Position after the last result of a page; the next page resumes strictly after it.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SearchCursor {
+    pub score: f32,
+    pub memory_id: String,
+    pub consumed: usize,
+    /// Names the ranked window cached for this scroll; an unknown or expired token re-ranks.
+    #[serde(default)]
+    pub token: String,
+}
+
+/// One page of `SearchEngine::search_page`.
+#[derive(Debug, Clone)]
+pub struct SearchPage {
+    pub results: Vec<UnifiedSearchResult>,
+    /// `None` on the last page
+    pub next: Option<SearchCursor>,
+    /// Set on the last page when ranking stopped at `MAX_SEARCH_LIMIT`, so more matches may
+    /// exist than the scroll could reach.
+    pub window_exhausted: bool,
+}
+
+/// The ranked results of one scroll, `window` being the limit they were searched with.
+struct RankedWindow {
+    results: Vec<UnifiedSearchResult>,
+    window: usize,
 }
 
 pub struct SearchEngine {
@@ -93,23 +330,61 @@ pub struct SearchEngine {
     vector: Arc<VectorSearch>,
     hybrid: HybridSearch,
     smart_traversal: Option<SmartTraversalV2>,
+    reranker: Option<Arc<dyn Reranker>>,
+    lexical: Arc<LexicalStore>,
     config: SearchEngineConfig,
+    metrics: SearchMetricsRecorder,
+    vector_cache_baseline: [AtomicU64; 2],
+    pages: SearchCache<Arc<RankedWindow>>,
 }
 
 impl SearchEngine {
     pub fn new(
         client: Arc<HelixClient>, 
-        _embedder: Arc<EmbeddingGenerator>,
+        embedder: Arc<EmbeddingGenerator>,
         config: SearchEngineConfig,
     ) -> Self {
-        let vector = Arc::new(VectorSearch::new(Arc::clone(&client), config.cache_size, config.cache_ttl));
-        let hybrid = HybridSearch::new(vector.clone(), config.vector_weight, config.bm25_weight);
+        let vector = Arc::new(VectorSearch::new(Arc::clone(&client), config.cache_size, config.cache_ttl, config.cache_expiration));
+        let hybrid = HybridSearch::new(vector.clone(), config.vector_weight, config.bm25_weight)
+            .with_fusion(config.hybrid_fusion)
+            .with_bm25(config.bm25.clone());
         let smart_traversal = if config.enable_smart_traversal {
-            Some(SmartTraversalV2::new(Arc::clone(&client), config.cache_size, config.cache_ttl))
+            Some(
+                SmartTraversalV2::new(Arc::clone(&client), config.cache_size, config.cache_ttl)
+                    .with_query_normalization(!embedder.is_normalized()),
+            )
         } else {
             None
         };
-        Self { client, vector, hybrid, smart_traversal, config }
+        Self {
+            client,
+            vector,
+            hybrid,
+            smart_traversal,
+            reranker: None,
+            lexical: Arc::new(LexicalStore::new()),
+            pages: SearchCache::new(config.cache_size, config.cache_ttl, config.cache_expiration),
+            config,
+            metrics: SearchMetricsRecorder::default(),
+            vector_cache_baseline: Default::default(),
+        }
+    }
+
+    /// Shares `lexical` as the BM25 corpus for hybrid search, so every caller ranks over
+    /// the same documents.
+    pub fn with_lexical_store(mut self, lexical: Arc<LexicalStore>) -> Self {
+        self.lexical = lexical;
+        self
+    }
+
+    pub fn lexical_store(&self) -> &Arc<LexicalStore> {
+        &self.lexical
+    }
+
+    /// Opt-in second pass over each search's final top-k results.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
     }
 
     
@@ -122,143 +397,664 @@ impl SearchEngine {
         mode: &str,
         temporal_days: Option<f64>,
     ) -> Result<Vec<UnifiedSearchResult>, SearchError> {
+        self.search_within(query, query_embedding, user_id, limit, mode, temporal_days.map(days_to_window))
+            .await
+    }
+
+    /// Like `search`, but the recency window is a `Duration`, so sub-day windows such as
+    /// "the last 3 hours" need no day fractions. A zero or negative window matches nothing.
+    pub async fn search_within(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        user_id: &str,
+        limit: usize,
+        mode: &str,
+        temporal_window: Option<Duration>,
+    ) -> Result<Vec<UnifiedSearchResult>, SearchError> {
+        let options = SearchOptions { temporal_window, ..Default::default() };
+        self.search_with(query, query_embedding, user_id, limit, mode, options).await
+    }
+
+    /// `search` with every per-call setting spelled out in `options`.
+    pub async fn search_with(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        user_id: &str,
+        limit: usize,
+        mode: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<UnifiedSearchResult>, SearchError> {
+        self.search_scoped(query, query_embedding, Some(user_id), limit, mode, options).await
+    }
+
+    /// `search_with` for one user's memories, or for every user's when `user_id` is `None`.
+    async fn search_scoped(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        user_id: Option<&str>,
+        limit: usize,
+        mode: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<UnifiedSearchResult>, SearchError> {
+        let start = Instant::now();
+        let limit = self.bounded_limit(limit);
+        // The lexical index only holds live memories, so archive-inclusive searches skip it.
+        let lexical = !options.include_archived && self.routes_lexically(query, mode);
+        let mode = self.resolve_mode(query, mode);
+        let mode = mode.as_str();
+        let mut results = self
+            .run_search(query, query_embedding, user_id, limit, mode, options, lexical)
+            .await;
+        if let (Some(reranker), Ok(candidates)) = (&self.reranker, &mut results) {
+            *candidates = reranker.rerank(query, std::mem::take(candidates)).await;
+        }
+        if let Ok(results) = &mut results {
+            self.attach_snippets(query, results);
+        }
+        let result_count = results.as_ref().ok().map(Vec::len);
+        self.metrics.record_search(mode, result_count, start.elapsed());
+        results
+    }
+
+    /// Runs `search` once per user in `audience` (once, unscoped, for `Audience::All`) and
+    /// merges the results, tagging each with `owner_user_id`; a memory reached through several
+    /// users keeps its best score, and one owned outside the audience is dropped.
+    pub async fn search_audience(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        audience: &Audience,
+        limit: usize,
+        mode: &str,
+        temporal_days: Option<f64>,
+    ) -> Result<Vec<UnifiedSearchResult>, SearchError> {
+        let limit = self.bounded_limit(limit);
+        let members: Option<HashSet<&str>> = audience.user_ids().map(|ids| ids.into_iter().collect());
+        let scopes: Vec<Option<&str>> = match audience.user_ids() {
+            Some(ids) => ids.into_iter().map(Some).collect(),
+            None => vec![None],
+        };
+        info!("SearchEngine.search_audience: {} user scope(s), mode={}, limit={}", scopes.len(), mode, limit);
+
+        let options = SearchOptions { temporal_window: temporal_days.map(days_to_window), ..Default::default() };
+        let searches = scopes
+            .iter()
+            .map(|scope| self.search_scoped(query, query_embedding, *scope, limit, mode, options));
+        let per_owner = future::try_join_all(searches).await?;
+
+        let mut best: HashMap<String, UnifiedSearchResult> = HashMap::new();
+        for (scope, results) in scopes.iter().zip(per_owner) {
+            for mut result in results {
+                // Prefer the owner recorded on the memory itself; graph neighbours only carry the scope.
+                let owner_user_id = result
+                    .metadata
+                    .get("user_id")
+                    .and_then(|v| v.as_str())
+                    .filter(|u| !u.is_empty())
+                    .or(*scope)
+                    .unwrap_or("")
+                    .to_string();
+                if members.as_ref().is_some_and(|members| !members.contains(owner_user_id.as_str())) {
+                    continue;
+                }
+                if !owner_user_id.is_empty() {
+                    result.metadata.insert("owner_user_id".to_string(), serde_json::json!(owner_user_id));
+                }
+                match best.entry(result.memory_id.clone()) {
+                    Entry::Occupied(mut existing) => {
+                        if result.score > existing.get().score {
+                            existing.insert(result);
+                        }
+                    }
+                    Entry::Vacant(slot) => {
+                        slot.insert(result);
+                    }
+                }
+            }
+        }
+
+        let mut merged: Vec<UnifiedSearchResult> = best.into_values().collect();
+        merged.sort_by(cmp_results);
+        merged.truncate(limit);
+        Ok(merged)
+    }
+
+    
+    pub fn metrics(&self) -> SearchMetrics {
+        let vector = self.vector.stats();
+        let vector_lookups = (
+            vector.hits.saturating_sub(self.vector_cache_baseline[0].load(Ordering::Relaxed)),
+            vector.misses.saturating_sub(self.vector_cache_baseline[1].load(Ordering::Relaxed)),
+        );
+        self.metrics.snapshot(vector_lookups)
+    }
+
+    
+    pub fn reset_metrics(&self) {
+        let vector = self.vector.stats();
+        self.vector_cache_baseline[0].store(vector.hits, Ordering::Relaxed);
+        self.vector_cache_baseline[1].store(vector.misses, Ordering::Relaxed);
+        self.metrics.reset();
+    }
+
+    /// `limit * factor`, clamped to `max_candidate_fetch`.
+    pub fn candidate_fetch(&self, limit: usize, factor: usize) -> usize {
+        let requested = limit.saturating_mul(factor);
+        if requested > self.config.max_candidate_fetch {
+            warn!(
+                "Candidate fetch of {} exceeds max_candidate_fetch, clamping to {}",
+                requested, self.config.max_candidate_fetch
+            );
+            return self.config.max_candidate_fetch;
+        }
+        requested
+    }
+
+    fn attach_snippets(&self, query: &str, results: &mut [UnifiedSearchResult]) {
+        if self.config.snippet_max_len == 0 {
+            return;
+        }
+        let query_tokens = self.config.bm25.tokenize(query);
+        for result in results {
+            result.snippet = Some(self.config.bm25.make_snippet(&result.content, &query_tokens, self.config.snippet_max_len));
+        }
+    }
+
+    /// `limit`, clamped to `MAX_SEARCH_LIMIT`.
+    pub fn bounded_limit(&self, limit: usize) -> usize {
+        if limit > MAX_SEARCH_LIMIT {
+            warn!("Search limit {} exceeds maximum, clamping to {}", limit, MAX_SEARCH_LIMIT);
+            return MAX_SEARCH_LIMIT;
+        }
+        limit
+    }
+
+    /// `Ok(None)` when traversal failed and the engine is configured to fall back to vector search.
+    async fn run_traversal(
+        &self,
+        traversal: &SmartTraversalV2,
+        query: &str,
+        query_embedding: &[f32],
+        user_id: Option<&str>,
+        config: SearchConfig,
+        temporal_cutoff: Option<DateTime<Utc>>,
+    ) -> Result<Option<Vec<smart_traversal_v2::SearchResult>>, SearchError> {
+        match traversal
+            .search_with_stats(query, query_embedding, user_id, config, temporal_cutoff)
+            .await
+        {
+            Ok((results, stats)) => {
+                self.metrics.record_cache(stats.served_from_cache);
+                Ok(Some(results))
+            }
+            Err(e) if self.config.fallback_on_traversal_error => {
+                warn!("Smart traversal failed, falling back to vector search: {}", e);
+                Ok(None)
+            }
+            Err(e) => Err(SearchError::Traversal(e)),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run_search(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        user_id: Option<&str>,
+        limit: usize,
+        mode: &str,
+        options: SearchOptions,
+        lexical: bool,
+    ) -> Result<Vec<UnifiedSearchResult>, SearchError> {
         
         let query_preview: String = query.chars().take(30).collect();
         
         
         let search_mode = SearchMode::from_str(mode);
         let mode_defaults = search_mode.get_defaults();
-        let effective_temporal_days = temporal_days.or(mode_defaults.temporal_days);
-        
-        let temporal_cutoff: Option<DateTime<Utc>> = effective_temporal_days.map(|days| {
-            let millis = (days * 24.0 * 60.0 * 60.0 * 1000.0) as i64;
-            Utc::now() - Duration::milliseconds(millis)
+        let mode_key = mode.to_lowercase();
+        if self.config.strict_modes && !self.is_known_mode(&mode_key) {
+            return Err(SearchError::InvalidMode(mode.to_string()));
+        }
+        let mode_temporal_days = match self.config.modes.get(&mode_key) {
+            Some(definition) => definition.temporal_days,
+            None if mode_key == "hybrid" => mode_defaults.temporal_days,
+            // Vector and unknown modes only honour an explicit window.
+            None => None,
+        };
+        let effective_window = options.temporal_window.or(mode_temporal_days.map(days_to_window));
+        if effective_window.is_some_and(|window| window <= Duration::zero()) {
+            debug!("Empty temporal window {:?}, nothing can match", effective_window);
+            return Ok(Vec::new());
+        }
+        let temporal_cutoff = temporal_cutoff(effective_window);
+        if lexical {
+            debug!("Lexical lookup '{}', serving from BM25 without vector search", query_preview);
+            let documents = self.scoped_lexical_candidates(user_id, query, limit, temporal_cutoff).await;
+            let results = lexical_results(&self.config.bm25, query, &documents, limit);
+            if !results.is_empty() {
+                return Ok(results);
+            }
+        }
+        let traversal_config = self.traversal_config(&mode_key, limit).map(|config| SearchConfig {
+            include_archived: options.include_archived,
+            ..config
         });
         
         info!(
-            "SearchEngine.search: query='{}...', user={}, mode={}, limit={}, temporal_days={:?}", 
-            query_preview, user_id, mode, limit, effective_temporal_days
+            "SearchEngine.search: query='{}...', user={}, mode={}, limit={}, temporal_window={:?}", 
+            query_preview, user_id.unwrap_or("*"), mode, limit, effective_window
         );
 
-        let results = match mode.to_lowercase().as_str() {
-            "recent" | "contextual" => {
-                
-                if let Some(ref traversal) = self.smart_traversal {
-                    debug!(
-                        "Using SmartTraversalV2 for mode={}, temporal_cutoff={:?}", 
-                        mode, temporal_cutoff
-                    );
-                    let config = SearchConfig {
-                        vector_top_k: limit,
-                        graph_depth: if mode == "recent" { 1 } else { 2 },
-                        min_vector_score: mode_defaults.min_vector_score,
-                        min_combined_score: mode_defaults.min_combined_score,
-                        ..Default::default()
-                    };
-                    let traversal_results = traversal
-                        .search(query, query_embedding, Some(user_id), config, temporal_cutoff)
-                        .await
-                        .unwrap_or_default();
-                    
-                    traversal_results
-                        .into_iter()
-                        .map(|r| UnifiedSearchResult {
-                            memory_id: r.memory_id,
-                            content: r.content,
-                            score: r.combined_score as f32,
-                            method: format!("smart_v2_{}", mode),
-                            metadata: r.metadata.unwrap_or_default(),
-                            created_at: r.created_at.unwrap_or_default(),
-                        })
-                        .collect()
-                } else {
-                    
-                    self.vector_search_unified(query, Some(user_id), limit).await?
-                }
+        let results = match mode_key.as_str() {
+            // Vector search embeds the query itself, so a lexical miss needs no caller embedding.
+            _ if lexical => {
+                debug!("No lexical match for '{}', falling back to vector search", query_preview);
+                self.vector_search_unified(query, user_id, limit, options.include_archived).await?
             }
-            "deep" => {
-                
-                if let Some(ref traversal) = self.smart_traversal {
-                    debug!(
-                        "Using SmartTraversalV2 for deep search, temporal_cutoff={:?}", 
-                        temporal_cutoff
-                    );
-                    let config = SearchConfig {
-                        vector_top_k: limit * 2,
-                        graph_depth: 3,
-                        min_combined_score: mode_defaults.min_combined_score,
-                        ..Default::default()
-                    };
-                    let traversal_results = traversal
-                        .search(query, query_embedding, Some(user_id), config, temporal_cutoff)
-                        .await
-                        .unwrap_or_default();
-                    
-                    traversal_results
-                        .into_iter()
-                        .take(limit)
-                        .map(|r| UnifiedSearchResult {
-                            memory_id: r.memory_id,
-                            content: r.content,
-                            score: r.combined_score as f32,
-                            method: "smart_v2_deep".to_string(),
-                            metadata: r.metadata.unwrap_or_default(),
-                            created_at: r.created_at.unwrap_or_default(),
-                        })
-                        .collect()
-                } else {
-                    self.vector_search_unified(query, Some(user_id), limit).await?
-                }
+            "hybrid" => {
+                let documents = self.scoped_lexical_candidates(user_id, query, limit, temporal_cutoff).await;
+                debug!("Hybrid search over {} BM25 documents", documents.len());
+                self.hybrid
+                    .search_expanded(query, user_id, Some(&documents), limit, &[], 0.0, options.include_archived)
+                    .await?
+                    .into_iter()
+                    .filter(|r| r.score >= mode_defaults.min_combined_score)
+                    .map(|r| UnifiedSearchResult {
+                        provenance: Provenance::from_hybrid(&r),
+                        memory_id: r.memory_id,
+                        content: r.content,
+                        score: r.score as f32,
+                        method: r.method.to_string().to_lowercase(),
+                        metadata: r.metadata,
+                        created_at: r.created_at,
+                        explanation: None,
+                        snippet: None,
+                    })
+                    .collect()
             }
-            "full" => {
+            "vector" => {
+                // Per-request fast path: skips graph expansion, trading recall for latency.
+                debug!("Vector-only fast path requested, skipping smart traversal");
+                self.vector_search_unified(query, user_id, limit, options.include_archived).await?
+            }
+            _ => {
                 
-                if let Some(ref traversal) = self.smart_traversal {
-                    debug!("Using SmartTraversalV2 for full mode (no temporal filter)");
-                    let config = SearchConfig {
-                        vector_top_k: limit * 2,
-                        graph_depth: 4,
-                        min_combined_score: 0.3,
-                        ..Default::default()
-                    };
-                    let traversal_results = traversal
-                        .search(query, query_embedding, Some(user_id), config, None)
-                        .await
-                        .unwrap_or_default();
-                    
-                    traversal_results
+                let traversal_results = match (&self.smart_traversal, traversal_config) {
+                    (Some(traversal), Some(config)) => {
+                        debug!(
+                            "Using SmartTraversalV2 for mode={}, temporal_cutoff={:?}", 
+                            mode_key, temporal_cutoff
+                        );
+                        self.run_traversal(traversal, query, query_embedding, user_id, config, temporal_cutoff)
+                            .await?
+                    }
+                    (None, Some(_)) => {
+                        debug!("SmartTraversal disabled, falling back to vector search for mode={}", mode_key);
+                        None
+                    }
+                    (_, None) => {
+                        debug!("Unknown mode '{}', falling back to vector search", mode);
+                        None
+                    }
+                };
+                match traversal_results {
+                    Some(results) => results
                         .into_iter()
                         .take(limit)
-                        .map(|r| UnifiedSearchResult {
-                            memory_id: r.memory_id,
-                            content: r.content,
-                            score: r.combined_score as f32,
-                            method: "smart_v2_full".to_string(),
-                            metadata: r.metadata.unwrap_or_default(),
-                            created_at: r.created_at.unwrap_or_default(),
-                        })
-                        .collect()
-                } else {
-                    debug!("SmartTraversal not available, returning empty for full mode");
-                    Vec::new()
+                        .map(|r| UnifiedSearchResult::from_traversal(r, format!("smart_v2_{}", mode_key)))
+                        .collect(),
+                    None => self.vector_search_unified(query, user_id, limit, options.include_archived).await?,
                 }
             }
-            _ => {
-                
-                debug!("Unknown mode '{}', falling back to vector search", mode);
-                self.vector_search_unified(query, Some(user_id), limit).await?
-            }
         };
 
+        // The vector arms do not filter by time themselves.
+        let results: Vec<UnifiedSearchResult> = results
+            .into_iter()
+            .filter(|r| created_within(&r.created_at, temporal_cutoff))
+            .collect();
+
         info!("SearchEngine.search complete: {} results", results.len());
         Ok(results)
     }
 
+    /// The mode `search` runs for `mode`. Explicit modes pass through unchanged; "auto" takes
+    /// `QueryProcessor`'s suggestion (recent for temporal queries, causal for "why" questions)
+    /// when it names a known mode, and contextual otherwise.
+    pub fn resolve_mode(&self, query: &str, mode: &str) -> String {
+        if !mode.eq_ignore_ascii_case(AUTO_MODE) {
+            return mode.to_string();
+        }
+        let resolved = QueryProcessor::new(None, false, 0)
+            .process(query)
+            .suggested_mode
+            .filter(|suggested| self.is_known_mode(suggested))
+            .unwrap_or_else(|| AUTO_FALLBACK_MODE.to_string());
+        debug!("Auto mode resolved to '{}'", resolved);
+        resolved
+    }
+
+    /// Whether `search` serves `query` in `mode` lexically; callers can then skip computing
+    /// its embedding. Only "auto" and "hybrid" short-circuit, explicit modes run as asked.
+    pub fn routes_lexically(&self, query: &str, mode: &str) -> bool {
+        self.config.lexical_short_circuit
+            && (mode.eq_ignore_ascii_case(AUTO_MODE) || mode.eq_ignore_ascii_case("hybrid"))
+            && query_processor::is_lexical_lookup(query)
+    }
+
+    fn is_known_mode(&self, mode_key: &str) -> bool {
+        matches!(mode_key, "hybrid" | "vector") || self.config.modes.get(mode_key).is_some()
+    }
+
+    /// Traversal settings for the registered graph-backed modes; `None` for modes that skip the graph.
+    fn traversal_config(&self, mode_key: &str, limit: usize) -> Option<SearchConfig> {
+        let definition = self.config.modes.get(mode_key)?;
+        let mut config = SearchConfig {
+            temporal_half_life_days: self.config.temporal_half_life_days,
+            ..definition.config.clone()
+        };
+        if definition.candidate_factor > 0 {
+            config.vector_top_k = self.candidate_fetch(limit, definition.candidate_factor);
+        }
+        Some(config)
+    }
+
+    /// Incremental variant of `search`: graph modes yield vector hits first, then each
+    /// expansion batch as it completes. Results arrive in completion order, only
+    /// approximately ranked, and without the traversal cache or content dedup; lexical
+    /// lookups and other modes (or a disabled traversal) yield the regular `search` results.
+    /// A failed traversal falls back to vector search under `fallback_on_traversal_error`,
+    /// and the search is counted in the metrics once the stream is dropped.
+    pub fn search_stream<'a>(
+        &'a self,
+        query: &'a str,
+        query_embedding: &'a [f32],
+        user_id: &'a str,
+        limit: usize,
+        mode: &'a str,
+        temporal_days: Option<f64>,
+    ) -> impl Stream<Item = Result<UnifiedSearchResult, SearchError>> + 'a {
+        let start = Instant::now();
+        let limit = self.bounded_limit(limit);
+        let mode_key = self.resolve_mode(query, mode).to_lowercase();
+        let planned = self
+            .smart_traversal
+            .as_ref()
+            .zip(self.traversal_config(&mode_key, limit))
+            .filter(|_| !self.routes_lexically(query, mode));
+
+        let Some((traversal, config)) = planned else {
+            return stream::once(self.search(query, query_embedding, user_id, limit, mode, temporal_days))
+                .flat_map(|results| match results {
+                    Ok(results) => stream::iter(results.into_iter().map(Ok)).left_stream(),
+                    Err(e) => stream::once(future::ready(Err(e))).right_stream(),
+                })
+                .right_stream();
+        };
+
+        let window = temporal_days
+            .or(self.config.modes.get(&mode_key).and_then(|definition| definition.temporal_days))
+            .map(days_to_window);
+        let limit = if window.is_some_and(|window| window <= Duration::zero()) { 0 } else { limit };
+        let temporal_cutoff = temporal_cutoff(window);
+        let min_score = config.min_combined_score;
+        let fallback = self.config.fallback_on_traversal_error;
+        let method = format!("smart_v2_{}", mode_key);
+        let mut seen = HashSet::new();
+        let mut recorded = StreamedSearch { metrics: &self.metrics, mode: mode_key, start, results: Some(0) };
+
+        traversal
+            .search_stream(query_embedding, Some(user_id), config, temporal_cutoff)
+            .then(move |batch| {
+                let method = method.clone();
+                async move {
+                    match batch {
+                        Ok(mut batch) => {
+                            batch.retain(|r| r.combined_score >= min_score);
+                            batch.sort_by(cmp_results);
+                            Ok(batch
+                                .into_iter()
+                                .map(|r| UnifiedSearchResult::from_traversal(r, method.clone()))
+                                .collect())
+                        }
+                        Err(e) if fallback => {
+                            warn!("Smart traversal failed, falling back to vector search: {}", e);
+                            let results = self.vector_search_unified(query, Some(user_id), limit, false).await?;
+                            Ok(results.into_iter().filter(|r| created_within(&r.created_at, temporal_cutoff)).collect())
+                        }
+                        Err(e) => Err(SearchError::Traversal(e)),
+                    }
+                }
+            })
+            .flat_map(move |batch: Result<Vec<UnifiedSearchResult>, SearchError>| {
+                let batch = match batch {
+                    Ok(batch) => batch.into_iter().filter(|r| seen.insert(r.memory_id.clone())).map(Ok).collect(),
+                    Err(e) => vec![Err(e)],
+                };
+                stream::iter(batch)
+            })
+            .take(limit)
+            .inspect(move |item| recorded.observe(item))
+            .left_stream()
+    }
+
+    /// Pages through `search` results. The ranked list is cached per scroll, so later pages
+    /// slice it instead of searching again until they run past it; then the window doubles,
+    /// up to `MAX_SEARCH_LIMIT`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_page(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        user_id: &str,
+        page_size: usize,
+        mode: &str,
+        temporal_days: Option<f64>,
+        cursor: Option<SearchCursor>,
+    ) -> Result<SearchPage, SearchError> {
+        let token = cursor
+            .as_ref()
+            .map(|c| c.token.clone())
+            .filter(|token| !token.is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let key = format!("{}|{}|{}|{:?}|{}", token, user_id, mode, temporal_days, query);
+        let consumed = cursor.as_ref().map_or(0, |c| c.consumed);
+        // One extra result tells us whether another page exists.
+        let needed = consumed + page_size + 1;
+
+        let cached = cursor.as_ref().and_then(|_| self.pages.get(&key));
+        let ranked = match cached {
+            // Enough for this page, or the search already returned everything it had.
+            Some(ranked) if ranked.results.len() >= needed || ranked.results.len() < ranked.window => ranked,
+            previous => {
+                let window = previous
+                    .map_or(needed.saturating_mul(PAGE_PREFETCH), |r| r.window.saturating_mul(2))
+                    .max(needed)
+                    .min(MAX_SEARCH_LIMIT);
+                let results = self
+                    .search(query, query_embedding, user_id, window, mode, temporal_days)
+                    .await?;
+                let ranked = Arc::new(RankedWindow { results, window });
+                self.pages.set(&key, Arc::clone(&ranked));
+                ranked
+            }
+        };
+
+        let (results, next) = page_after(ranked.results.clone(), cursor.as_ref(), page_size, &token);
+        let window_exhausted = next.is_none() && ranked.window == MAX_SEARCH_LIMIT && ranked.results.len() >= MAX_SEARCH_LIMIT;
+        Ok(SearchPage { results, next, window_exhausted })
+    }
+
+    
+    pub async fn search_multimode(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        user_id: &str,
+        modes: &[&str],
+        weights: &[f64],
+        limit: usize,
+    ) -> Result<Vec<UnifiedSearchResult>, SearchError> {
+        if modes.is_empty() || modes.len() != weights.len() {
+            return Err(SearchError::InvalidMode(format!(
+                "expected one weight per mode, got {} modes and {} weights",
+                modes.len(),
+                weights.len()
+            )));
+        }
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) || weights.iter().sum::<f64>() <= 0.0 {
+            return Err(SearchError::InvalidMode("weights must be non-negative and not all zero".to_string()));
+        }
+
+        info!("SearchEngine.search_multimode: modes={:?}, weights={:?}, limit={}", modes, weights, limit);
+
+        let searches = modes
+            .iter()
+            .map(|mode| self.search(query, query_embedding, user_id, limit, mode, None));
+        let per_mode = futures::future::join_all(searches).await;
+
+        let mut weighted = Vec::with_capacity(modes.len());
+        for ((mode, weight), results) in modes.iter().zip(weights).zip(per_mode) {
+            weighted.push((mode.to_string(), *weight, results?));
+        }
+
+        Ok(blend_mode_results(weighted, limit))
+    }
+
+    /// BM25 candidates for `query` from the shared lexical index, building the user's
+    /// entry from the database on first use.
+    pub async fn lexical_candidates(
+        &self,
+        user_id: &str,
+        query: &str,
+        limit: usize,
+        temporal_cutoff: Option<DateTime<Utc>>,
+    ) -> Vec<(String, String)> {
+        self.index_missing_users(&[user_id]).await;
+        self.indexed_candidates(&[user_id], query, limit, temporal_cutoff)
+    }
+
+    /// `lexical_candidates` for `user_id`, or pooled across every user when it is `None`.
+    async fn scoped_lexical_candidates(
+        &self,
+        user_id: Option<&str>,
+        query: &str,
+        limit: usize,
+        temporal_cutoff: Option<DateTime<Utc>>,
+    ) -> Vec<(String, String)> {
+        if let Some(user_id) = user_id {
+            return self.lexical_candidates(user_id, query, limit, temporal_cutoff).await;
+        }
+        let user_ids = self.all_user_ids().await;
+        let user_ids: Vec<&str> = user_ids.iter().map(String::as_str).collect();
+        self.index_missing_users(&user_ids).await;
+        self.indexed_candidates(&user_ids, query, limit, temporal_cutoff)
+    }
+
+    /// Builds lexical entries for the users not indexed yet, up to
+    /// `LEXICAL_BUILD_CONCURRENCY` at a time; indexed users are left as they are.
+    async fn index_missing_users(&self, user_ids: &[&str]) {
+        let missing: Vec<&str> = {
+            let index = self.lexical.read();
+            user_ids.iter().copied().filter(|user_id| !index.has_user(user_id)).collect()
+        };
+        stream::iter(missing)
+            .for_each_concurrent(LEXICAL_BUILD_CONCURRENCY, |user_id| async move {
+                if let Err(e) = self.rebuild_lexical_index(user_id).await {
+                    warn!("Lexical index unavailable for user={}: {}", user_id, e);
+                }
+            })
+            .await;
+    }
+
+    fn indexed_candidates(
+        &self,
+        user_ids: &[&str],
+        query: &str,
+        limit: usize,
+        temporal_cutoff: Option<DateTime<Utc>>,
+    ) -> Vec<(String, String)> {
+        let fetch = self.candidate_fetch(limit, LEXICAL_CANDIDATE_FACTOR);
+        let index = self.lexical.read();
+        user_ids
+            .iter()
+            .flat_map(|user_id| index.candidates(user_id, query, fetch))
+            .filter(|(memory_id, _)| created_within(index.created_at(memory_id).unwrap_or_default(), temporal_cutoff))
+            .collect()
+    }
+
+    async fn all_user_ids(&self) -> Vec<String> {
+        #[derive(serde::Deserialize)]
+        struct User {
+            user_id: String,
+        }
+        #[derive(serde::Deserialize)]
+        struct Users {
+            #[serde(default)]
+            users: Vec<User>,
+        }
+        match self.client.execute_query::<Users, _>("getAllUsers", &serde_json::json!({})).await {
+            Ok(response) => response.users.into_iter().map(|u| u.user_id).collect(),
+            Err(e) => {
+                warn!("Could not list users for an unscoped lexical search: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Reloads the user's live (not deleted, not archived) memories into the lexical index.
+    pub async fn rebuild_lexical_index(&self, user_id: &str) -> Result<usize, SearchError> {
+        #[derive(serde::Deserialize)]
+        struct UserMemory {
+            memory_id: String,
+            #[serde(default)]
+            content: String,
+            #[serde(default)]
+            created_at: String,
+            #[serde(default)]
+            is_deleted: i64,
+            #[serde(default)]
+            archived_at: String,
+        }
+        #[derive(serde::Deserialize)]
+        struct UserMemories {
+            #[serde(default)]
+            memories: Vec<UserMemory>,
+        }
+
+        let result: UserMemories = self.client
+            .execute_query("getUserMemories", &serde_json::json!({"user_id": user_id, "limit": LEXICAL_SCAN_LIMIT}))
+            .await
+            .map_err(|e| SearchError::Database(e.to_string()))?;
+        let live: Vec<UserMemory> = result.memories
+            .into_iter()
+            .filter(|m| m.is_deleted == 0 && m.archived_at.is_empty() && !m.content.is_empty())
+            .collect();
+        let count = live.len();
+
+        self.lexical.update(|index| {
+            index.replace_user(user_id, live.iter().map(|m| (m.memory_id.clone(), m.content.clone())));
+            for memory in &live {
+                index.set_created_at(&memory.memory_id, &memory.created_at);
+            }
+            true
+        });
+
+        info!("Rebuilt lexical index for user={}: {} documents", user_id, count);
+        Ok(count)
+    }
+
     
     async fn vector_search_unified(
         &self,
         query: &str,
         user_id: Option<&str>,
         limit: usize,
+        include_archived: bool,
     ) -> Result<Vec<UnifiedSearchResult>, SearchError> {
         let vector_results = self.vector
             .search(query, user_id, limit, 0.0, true)
@@ -266,13 +1062,17 @@ impl SearchEngine {
         
         Ok(vector_results
             .into_iter()
+            .filter(|r| include_archived || !vector::is_archived(r))
             .map(|r| UnifiedSearchResult {
+                provenance: Provenance::from_vector(&r),
                 memory_id: r.memory_id,
                 content: r.content,
                 score: r.score as f32,
                 method: "vector".to_string(),
                 metadata: r.metadata,
                 created_at: r.created_at,
+                explanation: None,
+                snippet: None,
             })
             .collect())
     }
@@ -289,8 +1089,19 @@ impl SearchEngine {
     }
 
     
+    pub async fn vector_search_diverse(
+        &self,
+        query: &str,
+        user_id: Option<&str>,
+        limit: usize,
+        lambda: f64,
+    ) -> Result<Vec<SearchResult>, VectorSearchError> {
+        self.vector.search_diverse(query, user_id, limit, lambda).await
+    }
+
+    
     pub fn bm25_search(&self, query: &str, documents: &[(String, String)], limit: usize) -> Vec<SearchResult> {
-        Bm25Search::search(query, documents, limit, 0.0)
+        self.config.bm25.search(query, documents, limit, 0.0)
     }
 
     
@@ -305,12 +1116,825 @@ impl SearchEngine {
     }
 
     
+    pub fn ontology_expansion_enabled(&self) -> bool {
+        self.config.ontology_expansion
+    }
+
+    
+    pub fn decision_similarity_floor(&self) -> f64 {
+        self.config.decision_similarity_floor
+    }
+
+    /// Hybrid search whose BM25 arm also scores `expansion_terms`, if expansion is enabled.
+    pub async fn hybrid_search_expanded(
+        &self,
+        query: &str,
+        user_id: Option<&str>,
+        documents: Option<&[(String, String)]>,
+        limit: usize,
+        expansion_terms: &[String],
+        include_archived: bool,
+    ) -> Result<Vec<SearchResult>, HybridSearchError> {
+        let terms = if self.config.ontology_expansion { expansion_terms } else { &[] };
+        self.hybrid
+            .search_expanded(query, user_id, documents, limit, terms, self.config.expansion_weight, include_archived)
+            .await
+    }
+
+    
     pub fn cache_stats(&self) -> CacheStats {
-        CacheStats::default()
+        self.vector.stats()
     }
 
     
     pub fn clear_cache(&self) {
-        
+        self.vector.clear();
+    }
+
+    /// Strands cached vector and traversal results for `user_id` after their memories changed.
+    pub fn bump_generation(&self, user_id: &str) {
+        self.vector.bump_generation(user_id);
+        if let Some(traversal) = &self.smart_traversal {
+            traversal.bump_generation(user_id);
+        }
+    }
+}
+
+
+/// Counts a `search_stream` call in the metrics when its stream is dropped, with however
+/// many results were read by then.
+struct StreamedSearch<'a> {
+    metrics: &'a SearchMetricsRecorder,
+    mode: String,
+    start: Instant,
+    /// `None` once the stream yielded an error.
+    results: Option<usize>,
+}
+
+impl StreamedSearch<'_> {
+    fn observe(&mut self, item: &Result<UnifiedSearchResult, SearchError>) {
+        self.results = match item {
+            Ok(_) => self.results.map(|count| count + 1),
+            Err(_) => None,
+        };
+    }
+}
+
+impl Drop for StreamedSearch<'_> {
+    fn drop(&mut self) {
+        self.metrics.record_search(&self.mode, self.results, self.start.elapsed());
+    }
+}
+
+fn page_after(
+    mut results: Vec<UnifiedSearchResult>,
+    cursor: Option<&SearchCursor>,
+    page_size: usize,
+    token: &str,
+) -> (Vec<UnifiedSearchResult>, Option<SearchCursor>) {
+    results.sort_by(cmp_results);
+    let mut seen = std::collections::HashSet::new();
+    results.retain(|r| seen.insert(r.memory_id.clone()));
+
+    let mut remaining: Vec<UnifiedSearchResult> = match cursor {
+        Some(cursor) => results
+            .into_iter()
+            .filter(|r| {
+                r.score < cursor.score || (r.score == cursor.score && r.memory_id > cursor.memory_id)
+            })
+            .collect(),
+        None => results,
+    };
+
+    let has_more = remaining.len() > page_size;
+    remaining.truncate(page_size);
+
+    let next = match remaining.last() {
+        Some(last) if has_more => Some(SearchCursor {
+            score: last.score,
+            memory_id: last.memory_id.clone(),
+            consumed: cursor.map_or(0, |c| c.consumed) + remaining.len(),
+            token: token.to_string(),
+        }),
+        _ => None,
+    };
+    (remaining, next)
+}
+
+
+/// Documents containing the query phrase verbatim (case-insensitively) score 1.0;
+/// other BM25 hits follow at `LEXICAL_PARTIAL_WEIGHT` of their normalized score.
+fn lexical_results(
+    bm25: &Bm25Search,
+    query: &str,
+    documents: &[(String, String)],
+    limit: usize,
+) -> Vec<UnifiedSearchResult> {
+    let phrase = query.trim().trim_matches('"').to_lowercase();
+    let mut results: Vec<UnifiedSearchResult> = bm25
+        .search(query, documents, documents.len(), 0.0)
+        .into_iter()
+        .map(|r| {
+            let exact = r.content.to_lowercase().contains(&phrase);
+            let score = if exact { 1.0 } else { r.score * LEXICAL_PARTIAL_WEIGHT };
+            let mut metadata = r.metadata;
+            metadata.insert("exact_match".to_string(), serde_json::json!(exact));
+            UnifiedSearchResult {
+                provenance: Provenance {
+                    source: "bm25".to_string(),
+                    bm25_score: Some(r.score),
+                    ..Provenance::default()
+                },
+                memory_id: r.memory_id,
+                content: r.content,
+                score: score as f32,
+                method: LEXICAL_INTENT.to_string(),
+                metadata,
+                created_at: r.created_at,
+                explanation: None,
+                snippet: None,
+            }
+        })
+        .collect();
+    results.sort_by(cmp_results);
+    results.truncate(limit);
+    results
+}
+
+fn blend_mode_results(
+    per_mode: Vec<(String, f64, Vec<UnifiedSearchResult>)>,
+    limit: usize,
+) -> Vec<UnifiedSearchResult> {
+    let total_weight: f64 = per_mode.iter().map(|(_, weight, _)| weight).sum();
+    let mut blended: HashMap<String, (UnifiedSearchResult, f64, serde_json::Map<String, serde_json::Value>)> =
+        HashMap::new();
+
+    for (mode, weight, results) in per_mode {
+        let max_score = results.iter().map(|r| r.score as f64).fold(0.0, f64::max);
+        let mut seen_in_mode = std::collections::HashSet::new();
+
+        for result in results {
+            if !seen_in_mode.insert(result.memory_id.clone()) {
+                continue;
+            }
+            let normalized = if max_score > 0.0 { result.score as f64 / max_score } else { 0.0 };
+            let contribution = weight * normalized / total_weight;
+
+            let entry = blended
+                .entry(result.memory_id.clone())
+                .or_insert_with(|| (result, 0.0, serde_json::Map::new()));
+            entry.1 += contribution;
+            entry.2.insert(mode.clone(), serde_json::json!(contribution));
+        }
+    }
+
+    let mut results: Vec<UnifiedSearchResult> = blended
+        .into_values()
+        .map(|(mut result, score, contributions)| {
+            result.score = score as f32;
+            result.method = "multimode".to_string();
+            result.metadata.insert("mode_contributions".to_string(), serde_json::Value::Object(contributions));
+            result
+        })
+        .collect();
+
+    results.sort_by(cmp_results);
+    results.truncate(limit);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked(memory_id: &str, score: f32) -> UnifiedSearchResult {
+        UnifiedSearchResult {
+            memory_id: memory_id.to_string(),
+            content: String::new(),
+            score,
+            method: "vector".to_string(),
+            metadata: HashMap::new(),
+            created_at: String::new(),
+            explanation: None,
+            snippet: None,
+            provenance: Provenance::default(),
+        }
+    }
+
+    #[test]
+    fn test_page_after_breaks_score_ties_on_memory_id() {
+        let corpus = vec![
+            ranked("m4", 0.5), ranked("m2", 0.8), ranked("m3", 0.8),
+            ranked("m1", 0.9), ranked("m5", 0.5), ranked("m6", 0.1),
+        ];
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            // Each page re-runs the query, possibly returning results in a different order.
+            let mut shuffled = corpus.clone();
+            shuffled.rotate_left(seen.len() % corpus.len());
+            let (page, next) = page_after(shuffled, cursor.as_ref(), 2, "scroll");
+            seen.extend(page.into_iter().map(|r| r.memory_id));
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(seen, vec!["m1", "m2", "m3", "m4", "m5", "m6"]);
+    }
+
+    fn offline_engine() -> SearchEngine {
+        offline_engine_with(SearchEngineConfig::default())
+    }
+
+    fn offline_engine_with(config: SearchEngineConfig) -> SearchEngine {
+        // Nothing listens on this port: any graph query would fail and yield no results.
+        let client = Arc::new(HelixClient::new("127.0.0.1", 1).unwrap());
+        let embedder = Arc::new(crate::test_support::ollama_embedder("http://127.0.0.1:1"));
+        SearchEngine::new(client, embedder, config)
+    }
+
+    fn seed_tea(engine: &SearchEngine) {
+        engine.vector.seed_cache("tea", Some("user_1"), 5, 0.0, vec![SearchResult {
+            memory_id: "mem_1".to_string(),
+            content: "User likes tea".to_string(),
+            score: 0.91,
+            method: SearchMethod::Vector,
+            metadata: HashMap::new(),
+            created_at: String::new(),
+        }]);
+    }
+
+    #[tokio::test]
+    async fn test_vector_mode_skips_graph_expansion() {
+        let stub = crate::test_support::spawn_reply_stub(serde_json::json!({})).await;
+        let config = SearchEngineConfig { fallback_on_traversal_error: false, ..Default::default() };
+        let engine = SearchEngine::new(stub.client(), Arc::new(crate::test_support::ollama_embedder(stub.url())), config);
+        seed_tea(&engine);
+
+        let results = engine
+            .search("tea", &[0.1, 0.2], "user_1", 5, "vector", None)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory_id, "mem_1");
+        assert_eq!(results[0].method, "vector");
+        let snippet = results[0].snippet.as_ref().unwrap();
+        assert_eq!(snippet.text, "User likes tea");
+        assert_eq!(snippet.highlights, [(11, 14)]);
+        assert!(stub.requests.lock().is_empty(), "vector mode queried {:?}", stub.requests.lock());
+
+        // The same engine does reach the graph once a traversal mode is asked for.
+        engine.search("tea", &[0.1, 0.2], "user_1", 5, "contextual", None).await.unwrap();
+        assert_eq!(stub.count("smartVectorSearchWithChunks"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_full_mode_falls_back_to_vector_without_traversal() {
+        let engine = offline_engine_with(SearchEngineConfig {
+            enable_smart_traversal: false,
+            ..Default::default()
+        });
+        seed_tea(&engine);
+
+        let results = engine
+            .search("tea", &[0.1, 0.2], "user_1", 5, "full", None)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory_id, "mem_1");
+        assert_eq!(results[0].method, "vector");
+    }
+
+    #[tokio::test]
+    async fn test_traversal_error_falls_back_or_propagates() {
+        // The offline client makes every traversal query fail.
+        let engine = offline_engine();
+        seed_tea(&engine);
+        let results = engine.search("tea", &[0.1, 0.2], "user_1", 5, "recent", None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].method, "vector");
+
+        let strict = offline_engine_with(SearchEngineConfig {
+            fallback_on_traversal_error: false,
+            ..Default::default()
+        });
+        seed_tea(&strict);
+        let err = strict.search("tea", &[0.1, 0.2], "user_1", 5, "recent", None).await.unwrap_err();
+        assert!(matches!(err, SearchError::Traversal(_)));
+    }
+
+    struct ReversingReranker;
+
+    #[async_trait::async_trait]
+    impl Reranker for ReversingReranker {
+        async fn rerank(&self, _query: &str, mut candidates: Vec<UnifiedSearchResult>) -> Vec<UnifiedSearchResult> {
+            candidates.reverse();
+            candidates
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reranker_reorders_final_results() {
+        let engine = offline_engine().with_reranker(Arc::new(ReversingReranker));
+        engine.vector.seed_cache("tea", Some("user_1"), 5, 0.0, vec![
+            SearchResult {
+                memory_id: "mem_1".to_string(),
+                content: "User likes tea".to_string(),
+                score: 0.91,
+                method: SearchMethod::Vector,
+                metadata: HashMap::new(),
+                created_at: String::new(),
+            },
+            SearchResult {
+                memory_id: "mem_2".to_string(),
+                content: "User drinks green tea".to_string(),
+                score: 0.72,
+                method: SearchMethod::Vector,
+                metadata: HashMap::new(),
+                created_at: String::new(),
+            },
+        ]);
+
+        let results = engine.search("tea", &[0.1], "user_1", 5, "vector", None).await.unwrap();
+
+        let order: Vec<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(order, ["mem_2", "mem_1"]);
+    }
+
+    fn vector_hit(memory_id: &str, score: f64) -> SearchResult {
+        SearchResult {
+            memory_id: memory_id.to_string(),
+            content: format!("memory {}", memory_id),
+            score,
+            method: SearchMethod::Vector,
+            metadata: HashMap::new(),
+            created_at: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_page_ranks_once_per_scroll() {
+        let engine = offline_engine();
+        // A page of 2 needs 3 results; the first search ranks PAGE_PREFETCH times that.
+        let hits = (0..5).map(|i| vector_hit(&format!("mem_{}", i), 0.9 - i as f64 * 0.1)).collect();
+        engine.vector.seed_cache("tea", Some("alice"), 12, 0.0, hits);
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = engine.search_page("tea", &[0.1], "alice", 2, "vector", None, cursor).await.unwrap();
+            seen.extend(page.results.into_iter().map(|r| r.memory_id));
+            assert!(!page.window_exhausted);
+            match page.next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(seen, ["mem_0", "mem_1", "mem_2", "mem_3", "mem_4"]);
+        assert_eq!(engine.metrics().total_searches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_page_flags_scroll_cut_short_by_max_limit() {
+        let engine = offline_engine();
+        let hits = (0..MAX_SEARCH_LIMIT).map(|i| vector_hit(&format!("mem_{:04}", i), 0.9)).collect();
+        engine.vector.seed_cache("tea", Some("alice"), MAX_SEARCH_LIMIT, 0.0, hits);
+
+        let mut pages = 0;
+        let mut cursor = None;
+        let last = loop {
+            let page = engine.search_page("tea", &[0.1], "alice", 300, "vector", None, cursor).await.unwrap();
+            pages += 1;
+            match page.next.clone() {
+                Some(next) => cursor = Some(next),
+                None => break page,
+            }
+        };
+
+        assert_eq!((pages, last.results.len()), (4, 100));
+        assert!(last.window_exhausted);
+    }
+
+    #[tokio::test]
+    async fn test_audience_search_merges_and_dedupes_across_users() {
+        let engine = offline_engine();
+        let mut outsider = vector_hit("mem_c", 0.95);
+        outsider.metadata.insert("user_id".to_string(), serde_json::json!("carol"));
+        engine.vector.seed_cache("tea", Some("alice"), 5, 0.0, vec![vector_hit("mem_a", 0.91), vector_hit("shared", 0.5), outsider]);
+        engine.vector.seed_cache("tea", Some("bob"), 5, 0.0, vec![vector_hit("shared", 0.8), vector_hit("mem_b", 0.6)]);
+
+        let audience = Audience::Any(vec!["alice".to_string(), "bob".to_string(), "alice".to_string()]);
+        let results = engine.search_audience("tea", &[0.1], &audience, 5, "vector", None).await.unwrap();
+
+        let merged: Vec<(&str, &str)> = results
+            .iter()
+            .map(|r| (r.memory_id.as_str(), r.metadata["owner_user_id"].as_str().unwrap()))
+            .collect();
+        assert_eq!(merged, [("mem_a", "alice"), ("shared", "bob"), ("mem_b", "bob")]);
+        assert_eq!(engine.metrics().total_searches, 2);
+    }
+
+    #[tokio::test]
+    async fn test_audience_all_searches_every_user_in_graph_modes() {
+        let now = Utc::now().to_rfc3339();
+        let stub = crate::test_support::spawn_stub(move |path, _| match path {
+            "smartVectorSearchWithChunks" => Some(serde_json::json!({"memories": [
+                {"memory_id": "mem_a", "content": "tea", "score": 0.9, "user_id": "alice", "created_at": now},
+                {"memory_id": "mem_b", "content": "tea", "score": 0.8, "user_id": "bob", "created_at": now},
+            ]})),
+            _ => Some(serde_json::json!({})),
+        })
+        .await;
+        let config = SearchEngineConfig { fallback_on_traversal_error: false, ..Default::default() };
+        let engine = SearchEngine::new(stub.client(), Arc::new(crate::test_support::ollama_embedder(stub.url())), config);
+
+        let results = engine.search_audience("tea", &[0.1], &Audience::All, 5, "contextual", None).await.unwrap();
+
+        let owners: Vec<(&str, &str)> = results
+            .iter()
+            .map(|r| (r.memory_id.as_str(), r.metadata["owner_user_id"].as_str().unwrap()))
+            .collect();
+        assert_eq!(owners, [("mem_a", "alice"), ("mem_b", "bob")]);
+    }
+
+    #[tokio::test]
+    async fn test_bump_generation_invalidates_graph_mode_cache() {
+        let now = Utc::now().to_rfc3339();
+        let stub = crate::test_support::spawn_stub(move |path, _| match path {
+            "smartVectorSearchWithChunks" => Some(serde_json::json!({"memories": [
+                {"memory_id": "mem_a", "content": "tea", "score": 0.9, "user_id": "alice", "created_at": now},
+            ]})),
+            _ => Some(serde_json::json!({})),
+        })
+        .await;
+        let config = SearchEngineConfig { fallback_on_traversal_error: false, ..Default::default() };
+        let engine = SearchEngine::new(stub.client(), Arc::new(crate::test_support::ollama_embedder(stub.url())), config);
+
+        for _ in 0..2 {
+            engine.search("tea", &[0.1], "alice", 5, "contextual", None).await.unwrap();
+        }
+        assert_eq!(stub.count("smartVectorSearchWithChunks"), 1);
+
+        engine.bump_generation("alice");
+        engine.search("tea", &[0.1], "alice", 5, "contextual", None).await.unwrap();
+        assert_eq!(stub.count("smartVectorSearchWithChunks"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_stream_follows_traversal_fallback_policy() {
+        let engine = offline_engine();
+        seed_tea(&engine);
+
+        // The offline client fails the traversal; the default policy serves vector hits instead.
+        let results: Vec<_> = engine.search_stream("tea", &[0.1], "user_1", 5, "contextual", None).collect().await;
+        let ids: Vec<String> = results.into_iter().map(|r| r.unwrap().memory_id).collect();
+        assert_eq!(ids, ["mem_1"]);
+
+        let strict = offline_engine_with(SearchEngineConfig { fallback_on_traversal_error: false, ..Default::default() });
+        seed_tea(&strict);
+        let results: Vec<_> = strict.search_stream("tea", &[0.1], "user_1", 5, "contextual", None).collect().await;
+        assert!(matches!(results.as_slice(), [Err(SearchError::Traversal(_))]));
+
+        let (metrics, strict_metrics) = (engine.metrics(), strict.metrics());
+        assert_eq!(metrics.searches_by_mode.get("contextual"), Some(&1));
+        assert_eq!((metrics.failed_searches, strict_metrics.failed_searches), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_registered_mode_dispatches_to_traversal() {
+        let mut config = SearchEngineConfig { fallback_on_traversal_error: false, ..Default::default() };
+        config.modes.register(
+            "Precise",
+            ModeDefinition::new(SearchConfig { graph_depth: 0, min_vector_score: 0.85, ..Default::default() })
+                .with_candidate_factor(3),
+        );
+        let engine = offline_engine_with(config);
+        seed_tea(&engine);
+
+        let precise = engine.traversal_config("precise", 10).unwrap();
+        assert_eq!((precise.graph_depth, precise.vector_top_k), (0, 30));
+        assert_eq!(precise.min_vector_score, 0.85);
+
+        // The offline client fails any traversal, which proves "precise" reached it.
+        let err = engine.search("tea", &[0.1], "user_1", 5, "precise", None).await.unwrap_err();
+        assert!(matches!(err, SearchError::Traversal(_)));
+
+        let unknown = engine.search("tea", &[0.1], "user_1", 5, "bogus", None).await.unwrap();
+        assert_eq!(unknown[0].method, "vector");
+    }
+
+    #[tokio::test]
+    async fn test_temporal_window_filters_older_memories() {
+        let engine = offline_engine();
+        let mut day_old = vector_hit("day_old", 0.9);
+        day_old.created_at = (Utc::now() - Duration::days(1)).to_rfc3339();
+        let mut hour_old = vector_hit("hour_old", 0.7);
+        hour_old.created_at = (Utc::now() - Duration::hours(1)).to_rfc3339();
+        engine.vector.seed_cache("tea", Some("user_1"), 5, 0.0, vec![day_old, hour_old]);
+
+        let results = engine
+            .search_within("tea", &[0.1], "user_1", 5, "vector", Some(Duration::hours(3)))
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, ["hour_old"]);
+
+        let by_days = engine.search("tea", &[0.1], "user_1", 5, "vector", Some(0.125)).await.unwrap();
+        assert_eq!(by_days.len(), 1);
+
+        for empty in [Duration::zero(), Duration::hours(-3)] {
+            let results = engine.search_within("tea", &[0.1], "user_1", 5, "vector", Some(empty)).await.unwrap();
+            assert!(results.is_empty());
+        }
+        assert_eq!(engine.search("tea", &[0.1], "user_1", 5, "vector", None).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_digit_query_routes_to_lexical_path() {
+        let store = Arc::new(LexicalStore::new());
+        store.update(|index| {
+            index.replace_user("user_1", [("m_phone".to_string(), "Office phone is 5550100".to_string())]);
+            true
+        });
+        let engine = offline_engine().with_lexical_store(store);
+        assert!(engine.routes_lexically("5550100", "auto"));
+        assert!(engine.routes_lexically("5550100", "hybrid"));
+        assert!(!engine.routes_lexically("5550100", "vector"));
+        assert!(!engine.routes_lexically("5550100", "deep"));
+        assert!(!engine.routes_lexically("what is my phone number", "auto"));
+        let opted_out = offline_engine_with(SearchEngineConfig { lexical_short_circuit: false, ..Default::default() });
+        assert!(!opted_out.routes_lexically("5550100", "auto"));
+
+        // No vector cache is seeded for this query, so only the lexical index can answer it.
+        let results = engine.search("5550100", &[], "user_1", 5, "auto", None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory_id, "m_phone");
+        assert_eq!(results[0].method, "lexical");
+
+        // A lexical miss falls back to vector search.
+        engine.vector.seed_cache("5550199", Some("user_1"), 5, 0.0, vec![SearchResult {
+            memory_id: "m_vector".to_string(),
+            content: "Front desk extension".to_string(),
+            score: 0.7,
+            method: SearchMethod::Vector,
+            metadata: HashMap::new(),
+            created_at: String::new(),
+        }]);
+        let results = engine.search("5550199", &[], "user_1", 5, "auto", None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory_id, "m_vector");
+        assert_eq!(results[0].method, "vector");
+
+        let documents = vec![
+            ("partial".to_string(), "Call the office at 5550100 or 5550100".to_string()),
+            ("exact".to_string(), "Office lines are 5550100 5550101".to_string()),
+            ("none".to_string(), "Unrelated memory".to_string()),
+        ];
+        let results = lexical_results(&Bm25Search::default(), "5550100 5550101", &documents, 5);
+        let ids: Vec<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, ["exact", "partial"]);
+        assert_eq!(results[0].score, 1.0);
+        assert!(results[1].score <= LEXICAL_PARTIAL_WEIGHT as f32);
+        assert_eq!(results[0].method, "lexical");
+        assert_eq!(results[0].provenance.source, "bm25");
+    }
+
+    #[test]
+    fn test_auto_mode_follows_query_intent() {
+        let engine = offline_engine();
+        let cases = [
+            ("what did I do yesterday", "recent"),
+            ("why did the deploy fail", "causal"),
+            ("what tea do I like", "contextual"),
+            ("the weather", "contextual"),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(engine.resolve_mode(query, "auto"), expected, "{query}");
+        }
+        assert_eq!(engine.resolve_mode("why did the deploy fail", "deep"), "deep");
+        assert_eq!(engine.resolve_mode("what did I do yesterday", "Vector"), "Vector");
+
+        let causal = engine.traversal_config("causal", 10).unwrap();
+        assert_eq!(causal.edge_types, Some(vec!["BECAUSE".to_string(), "IMPLIES".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_auto_mode_searches_with_resolved_mode() {
+        let engine = offline_engine();
+        engine.vector.seed_cache("why do I drink tea", Some("user_1"), 5, 0.0, vec![vector_hit("mem_1", 0.9)]);
+
+        let results = engine.search("why do I drink tea", &[0.1], "user_1", 5, "auto", None).await.unwrap();
+        assert_eq!(results[0].memory_id, "mem_1");
+        assert_eq!(engine.metrics().searches_by_mode.get("causal"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_mode_strict_and_lenient() {
+        let lenient = offline_engine();
+        seed_tea(&lenient);
+        let results = lenient.search("tea", &[0.1], "user_1", 5, "contexual", None).await.unwrap();
+        assert_eq!(results[0].method, "vector");
+
+        let strict = offline_engine_with(SearchEngineConfig { strict_modes: true, ..Default::default() });
+        seed_tea(&strict);
+        let err = strict.search("tea", &[0.1], "user_1", 5, "contexual", None).await.unwrap_err();
+        assert!(matches!(err, SearchError::InvalidMode(mode) if mode == "contexual"));
+        assert!(strict.search("tea", &[0.1], "user_1", 5, "Vector", None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cache_stats_and_clear() {
+        let engine = offline_engine();
+        seed_tea(&engine);
+
+        for _ in 0..2 {
+            engine.search("tea", &[0.1], "user_1", 5, "vector", None).await.unwrap();
+        }
+
+        let stats = engine.cache_stats();
+        assert_eq!(stats.size, 1);
+        assert_eq!(stats.hits, 2);
+        assert!((stats.hit_rate - 1.0).abs() < 1e-9);
+
+        engine.clear_cache();
+        let stats = engine.cache_stats();
+        assert_eq!(stats.size, 0);
+        assert_eq!(stats.hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_track_modes_and_cache_hits() {
+        let engine = offline_engine();
+        seed_tea(&engine);
+
+        for _ in 0..2 {
+            engine.search("tea", &[0.1], "user_1", 5, "vector", None).await.unwrap();
+        }
+
+        let metrics = engine.metrics();
+        assert_eq!(metrics.total_searches, 2);
+        assert_eq!(metrics.searches_by_mode.get("vector"), Some(&2));
+        assert!((metrics.avg_results - 1.0).abs() < 1e-9);
+        assert_eq!(metrics.cache_hits, 2);
+        assert!((metrics.cache_hit_rate - 1.0).abs() < 1e-9);
+        assert_eq!(metrics.latency.buckets.iter().map(|b| b.count).sum::<u64>(), 2);
+
+        engine.reset_metrics();
+        assert_eq!(engine.metrics().total_searches, 0);
+        assert_eq!(engine.metrics().cache_hits, 0);
+
+        engine.search("tea", &[0.1], "user_1", 5, "vector", None).await.unwrap();
+        assert_eq!(engine.metrics().cache_hits, 1);
+    }
+
+    fn unified(memory_id: &str, score: f32) -> UnifiedSearchResult {
+        UnifiedSearchResult {
+            memory_id: memory_id.to_string(),
+            content: format!("content of {}", memory_id),
+            score,
+            method: "test".to_string(),
+            metadata: HashMap::new(),
+            created_at: String::new(),
+            explanation: None,
+            snippet: None,
+            provenance: Provenance::default(),
+        }
+    }
+
+    #[test]
+    fn test_blend_mode_results_normalizes_and_dedupes() {
+        let per_mode = vec![
+            ("recent".to_string(), 1.0, vec![unified("a", 0.8), unified("b", 0.4)]),
+            ("deep".to_string(), 3.0, vec![unified("b", 0.3), unified("c", 0.15)]),
+        ];
+
+        let results = blend_mode_results(per_mode, 10);
+        let ids: Vec<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c", "a"]);
+
+        let b = &results[0];
+        assert!((b.score - 0.875).abs() < 1e-6);
+        assert_eq!(b.method, "multimode");
+        let contributions = b.metadata["mode_contributions"].as_object().unwrap();
+        assert!((contributions["recent"].as_f64().unwrap() - 0.125).abs() < 1e-6);
+        assert!((contributions["deep"].as_f64().unwrap() - 0.75).abs() < 1e-6);
+
+        assert_eq!(blend_mode_results(vec![
+            ("recent".to_string(), 1.0, vec![unified("a", 0.8), unified("b", 0.4)]),
+        ], 1).len(), 1);
+    }
+
+    #[test]
+    fn test_traversal_results_carry_score_breakdown() {
+        let graph_hit = TraversalResult::from_graph("m2", "reached via edge", 0.6, 0.8, 0.5, 2, vec!["IMPLIES".to_string(), "BECAUSE".to_string()]);
+        let result = UnifiedSearchResult::from_traversal(graph_hit, "smart_v2_deep".to_string());
+
+        let explanation = result.explanation.expect("traversal results are explained");
+        assert_eq!(explanation.source, "graph");
+        assert_eq!(explanation.depth, 2);
+        assert_eq!(explanation.edge_path, vec!["IMPLIES", "BECAUSE"]);
+        assert_eq!((explanation.vector_score, explanation.graph_score, explanation.temporal_score), (0.6, 0.8, 0.5));
+        assert!((result.score - 0.68).abs() < 1e-6);
+        assert_eq!(result.provenance.edge_path, vec!["IMPLIES", "BECAUSE"]);
+        assert_eq!(result.provenance.source, "graph");
+    }
+
+    #[test]
+    fn test_provenance_records_chunk_and_hybrid_arm_scores() {
+        let mut seed = TraversalResult::from_vector("m1", "chunked seed", 0.9, 0.5);
+        seed.metadata = Some(HashMap::from([("matched_chunk_id".to_string(), serde_json::json!("m1_chunk_2"))]));
+        let result = UnifiedSearchResult::from_traversal(seed, "smart_v2_deep".to_string());
+        assert_eq!(result.provenance.source, "vector");
+        assert_eq!(result.provenance.matched_chunk_id.as_deref(), Some("m1_chunk_2"));
+        assert!(result.provenance.edge_path.is_empty());
+
+        let hybrid = SearchResult {
+            memory_id: "m2".to_string(),
+            content: String::new(),
+            score: 0.7,
+            method: SearchMethod::Hybrid,
+            metadata: HashMap::from([
+                ("vector".to_string(), serde_json::json!(0.8)),
+                ("bm25".to_string(), serde_json::json!(4.5)),
+            ]),
+            created_at: String::new(),
+        };
+        let provenance = Provenance::from_hybrid(&hybrid);
+        assert_eq!(provenance.source, "hybrid");
+        assert_eq!((provenance.vector_score, provenance.bm25_score), (Some(0.8), Some(4.5)));
+    }
+
+    #[test]
+    fn test_absurd_limit_keeps_candidate_fetch_bounded() {
+        let engine = offline_engine();
+        let cap = SearchEngineConfig::default().max_candidate_fetch;
+
+        let deep = engine.traversal_config("deep", usize::MAX).unwrap();
+        assert_eq!(deep.vector_top_k, cap);
+        assert_eq!(engine.traversal_config("full", 10).unwrap().vector_top_k, 20);
+        assert_eq!(engine.candidate_fetch(usize::MAX / 2, 3), cap);
+        assert_eq!(engine.bounded_limit(usize::MAX), MAX_SEARCH_LIMIT);
+        assert_eq!(engine.bounded_limit(25), 25);
+    }
+
+    #[tokio::test]
+    async fn test_lexical_candidates_read_the_shared_index() {
+        let store = Arc::new(LexicalStore::new());
+        store.update(|index| {
+            index.replace_user("user_1", [
+                ("m_old".to_string(), "green tea notes".to_string()),
+                ("m_new".to_string(), "black tea notes".to_string()),
+            ]);
+            index.set_created_at("m_old", "2000-01-01T00:00:00Z");
+            index.set_created_at("m_new", &Utc::now().to_rfc3339());
+            true
+        });
+        let engine = offline_engine().with_lexical_store(Arc::clone(&store));
+
+        let all = engine.lexical_candidates("user_1", "tea", 10, None).await;
+        assert_eq!(all.len(), 2);
+
+        let recent = engine
+            .lexical_candidates("user_1", "tea", 10, temporal_cutoff(Some(Duration::days(1))))
+            .await;
+        assert_eq!(recent, [("m_new".to_string(), "black tea notes".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_unscoped_lexical_search_builds_only_missing_users() {
+        let stub = crate::test_support::spawn_stub(|path, body| match path {
+            "getAllUsers" => Some(serde_json::json!({"users": [
+                {"user_id": "user_1"}, {"user_id": "user_2"}, {"user_id": "user_3"},
+            ]})),
+            "getUserMemories" => {
+                let user_id = body["user_id"].as_str().unwrap();
+                Some(serde_json::json!({"memories": [
+                    {"memory_id": format!("m_{}", user_id), "content": format!("tea for {}", user_id)},
+                ]}))
+            }
+            _ => None,
+        })
+        .await;
+        let store = Arc::new(LexicalStore::new());
+        store.update(|index| {
+            index.replace_user("user_1", [("m_cached".to_string(), "cached tea".to_string())]);
+            true
+        });
+        let engine = SearchEngine::new(stub.client(), Arc::new(crate::test_support::ollama_embedder(stub.url())), SearchEngineConfig::default())
+            .with_lexical_store(store);
+
+        let mut ids: Vec<String> = engine.scoped_lexical_candidates(None, "tea", 10, None).await.into_iter().map(|(id, _)| id).collect();
+        ids.sort();
+        assert_eq!(ids, ["m_cached", "m_user_2", "m_user_3"]);
+        assert_eq!(stub.count("getUserMemories"), 2);
+
+        engine.scoped_lexical_candidates(None, "tea", 10, None).await;
+        assert_eq!(stub.count("getUserMemories"), 2);
     }
 }
@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
 
@@ -7,6 +8,7 @@ pub enum SearchMethod {
     Vector,
     Bm25,
     Hybrid,
+    Rrf,
     SmartGraphV2,
     OntoSearch,
 }
@@ -17,6 +19,7 @@ impl fmt::Display for SearchMethod {
             SearchMethod::Vector => write!(f, "Vector"),
             SearchMethod::Bm25 => write!(f, "BM25"),
             SearchMethod::Hybrid => write!(f, "Hybrid"),
+            SearchMethod::Rrf => write!(f, "RRF"),
             SearchMethod::SmartGraphV2 => write!(f, "SmartGraphV2"),
             SearchMethod::OntoSearch => write!(f, "OntoSearch"),
         }
@@ -38,4 +41,166 @@ impl fmt::Display for SearchResult {
         let short_id = crate::safe_truncate(&self.memory_id, 8);
         write!(f, "{} [{:.3}] {}", short_id, self.score, self.method)
     }
-}
\ No newline at end of file
+}
+
+/// The part of a result's content around its query matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub text: String,
+    /// Byte offset of `text` within the full content
+    pub start: usize,
+    /// Byte ranges of query-token matches within `text`
+    pub highlights: Vec<(usize, usize)>,
+}
+
+/// A search hit that `cmp_results` can order.
+pub trait Ranked {
+    fn rank_score(&self) -> f64;
+    fn rank_id(&self) -> &str;
+}
+
+impl Ranked for SearchResult {
+    fn rank_score(&self) -> f64 {
+        self.score
+    }
+
+    fn rank_id(&self) -> &str {
+        &self.memory_id
+    }
+}
+
+impl Ranked for crate::llm::decision::SimilarMemory {
+    fn rank_score(&self) -> f64 {
+        self.score
+    }
+
+    fn rank_id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// Score descending, ties broken by `memory_id`; NaN scores sort last instead of panicking.
+pub fn cmp_results<T: Ranked>(a: &T, b: &T) -> Ordering {
+    cmp_scored((a.rank_score(), a.rank_id()), (b.rank_score(), b.rank_id()))
+}
+
+/// `cmp_results` over bare `(score, memory_id)` pairs, for candidates that are not `Ranked`.
+pub fn cmp_scored((a_score, a_id): (f64, &str), (b_score, b_id): (f64, &str)) -> Ordering {
+    let by_score = match (a_score.is_nan(), b_score.is_nan()) {
+        (false, false) => b_score.partial_cmp(&a_score).unwrap_or(Ordering::Equal),
+        (a_nan, b_nan) => a_nan.cmp(&b_nan),
+    };
+    by_score.then_with(|| a_id.cmp(b_id))
+}
+
+/// Whose memories a search covers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Audience {
+    Single(String),
+    Any(Vec<String>),
+    All,
+}
+
+impl Audience {
+    /// Distinct user ids to search on behalf of, in order; `None` for an unscoped search.
+    pub fn user_ids(&self) -> Option<Vec<&str>> {
+        match self {
+            Audience::Single(user_id) => Some(vec![user_id.as_str()]),
+            Audience::Any(user_ids) => {
+                let mut seen = std::collections::HashSet::new();
+                Some(user_ids.iter().map(String::as_str).filter(|u| seen.insert(*u)).collect())
+            }
+            Audience::All => None,
+        }
+    }
+}
+
+const DEFAULT_IMPORTANCE: i64 = 50;
+
+/// Post-fetch constraints on memory attributes; unset fields match everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataFilter {
+    pub memory_type: Option<Vec<String>>,
+    pub min_importance: Option<i64>,
+    pub tag_contains: Option<String>,
+}
+
+impl MetadataFilter {
+    pub fn is_empty(&self) -> bool {
+        self.memory_type.is_none() && self.min_importance.is_none() && self.tag_contains.is_none()
+    }
+
+    // Memories stored without an importance carry the schema default.
+    pub fn matches(&self, memory_type: &str, importance: Option<i64>, context_tags: &str) -> bool {
+        if let Some(types) = &self.memory_type {
+            if !types.iter().any(|t| t.eq_ignore_ascii_case(memory_type)) {
+                return false;
+            }
+        }
+        if let Some(min) = self.min_importance {
+            if importance.unwrap_or(DEFAULT_IMPORTANCE) < min {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag_contains {
+            if !context_tags.to_lowercase().contains(&tag.to_lowercase()) {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn matches_metadata(&self, metadata: &HashMap<String, serde_json::Value>) -> bool {
+        let text = |key: &str| metadata.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+        self.matches(
+            text("memory_type"),
+            metadata.get("importance").and_then(|v| v.as_i64()),
+            text("context_tags"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(memory_id: &str, score: f64) -> SearchResult {
+        SearchResult {
+            memory_id: memory_id.to_string(),
+            content: String::new(),
+            score,
+            method: SearchMethod::Vector,
+            metadata: HashMap::new(),
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_cmp_results_orders_ties_and_nan() {
+        let mut results = [hit("c", 0.5), hit("nan", f64::NAN), hit("b", 0.9), hit("a", 0.5), hit("d", f64::NAN)];
+        results.sort_by(cmp_results);
+
+        let order: Vec<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(order, ["b", "a", "c", "d", "nan"]);
+    }
+
+    #[test]
+    fn test_metadata_filter_combination() {
+        let filter = MetadataFilter {
+            memory_type: Some(vec!["preference".to_string()]),
+            min_importance: Some(70),
+            tag_contains: None,
+        };
+
+        assert!(filter.matches("Preference", Some(80), ""));
+        assert!(!filter.matches("preference", Some(60), ""));
+        assert!(!filter.matches("fact", Some(90), ""));
+        assert!(!filter.matches("preference", None, ""));
+        assert!(MetadataFilter::default().matches("fact", None, ""));
+        assert!(MetadataFilter::default().is_empty());
+
+        let tagged = MetadataFilter { tag_contains: Some("work".to_string()), ..Default::default() };
+        assert!(tagged.matches("fact", None, "home,Work-Project"));
+        assert!(!tagged.matches("fact", None, ""));
+    }
+}
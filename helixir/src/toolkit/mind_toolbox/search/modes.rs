@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use crate::core::search_modes::SearchMode;
+use super::smart_traversal_v2::models::SearchConfig;
+
+
+/// A graph-traversal search mode that `SearchEngine::search` can dispatch to by name.
+#[derive(Debug, Clone)]
+pub struct ModeDefinition {
+    /// Traversal settings; the engine substitutes its own temporal half-life
+    pub config: SearchConfig,
+    /// When non-zero, `vector_top_k` becomes `limit * candidate_factor` (bounded by `max_candidate_fetch`)
+    pub candidate_factor: usize,
+    /// Recency window used when the caller passes none; `None` searches the full history
+    pub temporal_days: Option<f64>,
+}
+
+impl ModeDefinition {
+    
+    pub fn new(config: SearchConfig) -> Self {
+        Self { config, candidate_factor: 0, temporal_days: None }
+    }
+
+    
+    pub fn with_candidate_factor(mut self, factor: usize) -> Self {
+        self.candidate_factor = factor;
+        self
+    }
+
+    
+    pub fn with_temporal_days(mut self, days: Option<f64>) -> Self {
+        self.temporal_days = days;
+        self
+    }
+}
+
+
+/// Named traversal modes, keyed case-insensitively. `hybrid` and `vector` are dispatched
+/// by the engine itself and cannot be overridden here.
+#[derive(Debug, Clone)]
+pub struct ModeRegistry {
+    modes: HashMap<String, ModeDefinition>,
+}
+
+impl Default for ModeRegistry {
+    fn default() -> Self {
+        let recent = SearchMode::Recent.get_defaults();
+        let contextual = SearchMode::Contextual.get_defaults();
+        let deep = SearchMode::Deep.get_defaults();
+
+        let mut registry = Self::empty();
+        registry.register(
+            "recent",
+            ModeDefinition::new(SearchConfig {
+                graph_depth: 1,
+                min_vector_score: recent.min_vector_score,
+                min_combined_score: recent.min_combined_score,
+                ..Default::default()
+            })
+            .with_candidate_factor(1)
+            .with_temporal_days(recent.temporal_days),
+        );
+        registry.register(
+            "contextual",
+            ModeDefinition::new(SearchConfig {
+                graph_depth: 2,
+                min_vector_score: contextual.min_vector_score,
+                min_combined_score: contextual.min_combined_score,
+                ..Default::default()
+            })
+            .with_candidate_factor(1)
+            .with_temporal_days(contextual.temporal_days),
+        );
+        registry.register(
+            "deep",
+            ModeDefinition::new(SearchConfig {
+                graph_depth: 3,
+                min_combined_score: deep.min_combined_score,
+                ..Default::default()
+            })
+            .with_candidate_factor(2)
+            .with_temporal_days(deep.temporal_days),
+        );
+        // Follows only reasoning edges, deep enough to walk a cause back through its effects.
+        registry.register(
+            "causal",
+            ModeDefinition::new(SearchConfig {
+                graph_depth: 3,
+                edge_types: Some(vec!["BECAUSE".to_string(), "IMPLIES".to_string()]),
+                min_combined_score: deep.min_combined_score,
+                ..Default::default()
+            })
+            .with_candidate_factor(2),
+        );
+        registry.register(
+            "full",
+            ModeDefinition::new(SearchConfig { graph_depth: 4, min_combined_score: 0.3, ..Default::default() })
+                .with_candidate_factor(2),
+        );
+        registry
+    }
+}
+
+impl ModeRegistry {
+    /// A registry without the built-in modes.
+    pub fn empty() -> Self {
+        Self { modes: HashMap::new() }
+    }
+
+    /// Adds or replaces the mode called `name`.
+    pub fn register(&mut self, name: &str, definition: ModeDefinition) {
+        self.modes.insert(name.to_lowercase(), definition);
+    }
+
+    
+    pub fn get(&self, name: &str) -> Option<&ModeDefinition> {
+        self.modes.get(&name.to_lowercase())
+    }
+
+    
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.modes.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
@@ -1,6 +1,7 @@
 
 
 use serde::{Deserialize, Serialize};
+use crate::toolkit::mind_toolbox::search::models::Ranked;
 
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +47,16 @@ pub struct OntoSearchResult {
     pub source: String,
 }
 
+impl Ranked for OntoSearchResult {
+    fn rank_score(&self) -> f64 {
+        self.final_score
+    }
+
+    fn rank_id(&self) -> &str {
+        &self.memory_id
+    }
+}
+
 impl Default for OntoSearchResult {
     fn default() -> Self {
         Self {
@@ -3,6 +3,7 @@
 use std::collections::HashMap;
 use super::super::config::OntoSearchConfig;
 use super::super::models::OntoSearchResult;
+use crate::toolkit::mind_toolbox::search::models::cmp_results;
 
 
 pub fn calculate_combined_score(result: &OntoSearchResult, config: &OntoSearchConfig) -> f64 {
@@ -38,6 +39,6 @@ pub fn rank_results(results: Vec<OntoSearchResult>, config: &OntoSearchConfig) -
         .filter(|r| r.final_score >= config.min_final_score)
         .collect();
 
-    ranked.sort_by(|a, b| b.final_score.partial_cmp(&a.final_score).unwrap_or(std::cmp::Ordering::Equal));
+    ranked.sort_by(cmp_results);
     ranked
 }
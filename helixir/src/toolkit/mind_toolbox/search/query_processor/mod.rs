@@ -9,5 +9,5 @@ pub use models::ProcessedQuery;
 
 pub type QueryIntent = String;
 pub type EnhancedQuery = ProcessedQuery;
-pub use patterns::{INTENT_PATTERNS, EXPANSION_MAPPINGS, detect_intent, intent_to_concept};
+pub use patterns::{INTENT_PATTERNS, EXPANSION_MAPPINGS, LEXICAL_INTENT, detect_intent, intent_to_concept, is_lexical_lookup};
 pub use processor::QueryProcessor;
@@ -6,6 +6,9 @@ use std::collections::HashMap;
 pub struct ProcessedQuery {
     
     pub original_query: String,
+    /// Lowercased, whitespace-collapsed and typo-corrected form of `original_query`
+    #[serde(default)]
+    pub normalized_query: String,
     
     pub enhanced_query: String,
     
@@ -14,6 +17,9 @@ pub struct ProcessedQuery {
     pub concept_hints: Vec<String>,
     
     pub expanded_terms: Vec<String>,
+    /// Related ontology concepts, meant for the lexical arm only
+    #[serde(default)]
+    pub expansion_terms: Vec<String>,
     
     pub suggested_mode: Option<String>,
     
@@ -21,14 +27,20 @@ pub struct ProcessedQuery {
 }
 
 impl ProcessedQuery {
+    /// Whether the query is an exact-phrase lookup that lexical search should serve alone.
+    pub fn is_lexical(&self) -> bool {
+        self.detected_intents.iter().any(|intent| intent == super::patterns::LEXICAL_INTENT)
+    }
     
     pub fn empty(query: &str) -> Self {
         Self {
             original_query: query.to_string(),
+            normalized_query: query.to_string(),
             enhanced_query: query.to_string(),
             detected_intents: Vec::new(),
             concept_hints: Vec::new(),
             expanded_terms: Vec::new(),
+            expansion_terms: Vec::new(),
             suggested_mode: None,
             confidence: 0.0,
         }
@@ -38,6 +50,7 @@ impl ProcessedQuery {
     pub fn to_dict(&self) -> HashMap<String, serde_json::Value> {
         let mut map = HashMap::new();
         map.insert("original_query".to_string(), serde_json::Value::String(self.original_query.clone()));
+        map.insert("normalized_query".to_string(), serde_json::Value::String(self.normalized_query.clone()));
         map.insert("enhanced_query".to_string(), serde_json::Value::String(self.enhanced_query.clone()));
         map.insert("detected_intents".to_string(), serde_json::Value::Array(
             self.detected_intents.iter().map(|s| serde_json::Value::String(s.clone())).collect()
@@ -48,6 +61,9 @@ impl ProcessedQuery {
         map.insert("expanded_terms".to_string(), serde_json::Value::Array(
             self.expanded_terms.iter().map(|s| serde_json::Value::String(s.clone())).collect()
         ));
+        map.insert("expansion_terms".to_string(), serde_json::Value::Array(
+            self.expansion_terms.iter().map(|s| serde_json::Value::String(s.clone())).collect()
+        ));
         map.insert("suggested_mode".to_string(), match &self.suggested_mode {
             Some(mode) => serde_json::Value::String(mode.clone()),
             None => serde_json::Value::Null,
@@ -61,10 +77,12 @@ impl Default for ProcessedQuery {
     fn default() -> Self {
         Self {
             original_query: String::new(),
+            normalized_query: String::new(),
             enhanced_query: String::new(),
             detected_intents: Vec::new(),
             concept_hints: Vec::new(),
             expanded_terms: Vec::new(),
+            expansion_terms: Vec::new(),
             suggested_mode: None,
             confidence: 0.0,
         }
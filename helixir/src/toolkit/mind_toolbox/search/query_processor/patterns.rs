@@ -29,6 +29,10 @@ lazy_static! {
             r"\b(did|have i|was i|when did|remember when)\b",
             r"\b(my (experience|history) with)\b",
         ]);
+        m.insert("causal", vec![
+            r"\b(why|because|reason|cause[sd]?|led to|result of)\b",
+            r"\b(how come|what made)\b",
+        ]);
         m.insert("recent", vec![
             r"\b(today|yesterday|recently|lately|just now|this week)\b",
             r"\b(what (did|have) i (do|done)|current|latest)\b",
@@ -68,8 +72,32 @@ pub fn intent_to_concept(intent: &str) -> Option<&'static str> {
     }
 }
 
+/// Exact-phrase lookups: ids, phone numbers, quoted strings. Embeddings add nothing here.
+pub const LEXICAL_INTENT: &str = "lexical";
+
+/// Longer queries read as natural language even when they contain an id.
+const MAX_LEXICAL_TOKENS: usize = 3;
+
+pub fn is_lexical_lookup(query: &str) -> bool {
+    let query = query.trim();
+    if query.len() > 2 && query.starts_with('"') && query.ends_with('"') {
+        return true;
+    }
+    let tokens: Vec<&str> = query.split_whitespace().collect();
+    !tokens.is_empty()
+        && tokens.len() <= MAX_LEXICAL_TOKENS
+        && tokens.iter().all(|token| looks_like_identifier(token))
+}
+
+fn looks_like_identifier(token: &str) -> bool {
+    token.chars().any(|c| c.is_ascii_digit()) || token.contains(['_', '@'])
+}
+
 pub fn detect_intent(query: &str) -> Vec<&'static str> {
     let mut detected_intents = Vec::new();
+    if is_lexical_lookup(query) {
+        detected_intents.push(LEXICAL_INTENT);
+    }
     
     for (intent, patterns) in INTENT_PATTERNS.iter() {
         for pattern in patterns {
@@ -1,14 +1,25 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 use crate::llm::providers::base::LlmProvider;
+use crate::toolkit::mind_toolbox::ontology::OntologyManager;
+use super::super::bm25::Bm25Search;
 use super::models::ProcessedQuery;
-use super::patterns::{detect_intent, intent_to_concept, EXPANSION_MAPPINGS};
+use super::patterns::{detect_intent, intent_to_concept, EXPANSION_MAPPINGS, LEXICAL_INTENT};
+
+/// Shorter tokens are left alone: one edit away from too many real words (and names).
+const MIN_CORRECTION_LEN: usize = 5;
+
+const MAX_ONTOLOGY_EXPANSIONS: usize = 6;
 
 
 pub struct QueryProcessor {
     llm_provider: Option<Arc<dyn LlmProvider>>,
     enable_expansion: bool,
     max_expansions: usize,
+    enable_normalization: bool,
+    vocabulary: HashSet<String>,
+    enable_ontology_expansion: bool,
 }
 
 impl QueryProcessor {
@@ -21,8 +32,88 @@ impl QueryProcessor {
             llm_provider,
             enable_expansion,
             max_expansions,
+            enable_normalization: false,
+            vocabulary: HashSet::new(),
+            enable_ontology_expansion: false,
         }
     }
+
+    pub fn with_ontology_expansion(mut self, enabled: bool) -> Self {
+        self.enable_ontology_expansion = enabled;
+        self
+    }
+
+    
+    pub fn process_with_ontology(&self, query: &str, ontology: &OntologyManager) -> ProcessedQuery {
+        let mut result = self.process(query);
+        if self.enable_ontology_expansion {
+            result.expansion_terms = ontology.related_terms(&result.normalized_query, MAX_ONTOLOGY_EXPANSIONS);
+            debug!("Ontology expansion added {} terms", result.expansion_terms.len());
+        }
+        result
+    }
+
+    pub fn with_normalization(mut self, enabled: bool) -> Self {
+        self.enable_normalization = enabled;
+        self
+    }
+
+    /// Corpus vocabulary used for typo correction, e.g. the BM25 token set.
+    pub fn with_vocabulary<I>(mut self, vocabulary: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        self.vocabulary = vocabulary.into_iter().map(|t| t.to_lowercase()).collect();
+        self
+    }
+
+    pub fn with_vocabulary_from_documents(self, documents: &[(String, String)]) -> Self {
+        let vocabulary: Vec<String> = documents
+            .iter()
+            .flat_map(|(_, content)| Bm25Search::default().tokenize(content))
+            .collect();
+        self.with_vocabulary(vocabulary)
+    }
+
+    
+    pub fn normalize(&self, query: &str) -> String {
+        query
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                let is_punct = |c: char| !c.is_alphanumeric();
+                let start = word.len() - word.trim_start_matches(is_punct).len();
+                let end = start + word.trim_matches(is_punct).len();
+                match self.correct_token(&word[start..end]) {
+                    Some(fixed) => format!("{}{}{}", &word[..start], fixed, &word[end..]),
+                    None => word,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn correct_token(&self, token: &str) -> Option<String> {
+        if token.chars().count() < MIN_CORRECTION_LEN
+            || !token.chars().all(char::is_alphabetic)
+            || self.vocabulary.contains(token)
+        {
+            return None;
+        }
+
+        let mut candidates = self
+            .vocabulary
+            .iter()
+            .filter(|word| word.chars().count().abs_diff(token.chars().count()) <= 1)
+            .filter(|word| edit_distance(token, word) == 1);
+        let candidate = candidates.next()?;
+        // Ambiguous corrections are skipped rather than guessed.
+        if candidates.next().is_some() {
+            return None;
+        }
+        debug!("Corrected query token '{}' -> '{}'", token, candidate);
+        Some(candidate.clone())
+    }
     
     
     pub fn process(&self, query: &str) -> ProcessedQuery {
@@ -32,6 +123,14 @@ impl QueryProcessor {
             return ProcessedQuery::empty(query);
         }
         
+        let original_query = query;
+        let normalized_query = if self.enable_normalization {
+            self.normalize(query)
+        } else {
+            query.to_string()
+        };
+        let query = normalized_query.as_str();
+        
         
         let detected_intents_raw = detect_intent(query);
         let detected_intents: Vec<String> = detected_intents_raw.iter().map(|&s| s.to_string()).collect();
@@ -58,11 +157,13 @@ impl QueryProcessor {
         info!("Query processed with {} intents, confidence: {}", detected_intents.len(), confidence);
         
         ProcessedQuery {
-            original_query: query.to_string(),
+            original_query: original_query.to_string(),
+            normalized_query: normalized_query.clone(),
             enhanced_query,
             detected_intents,
             concept_hints,
             expanded_terms,
+            expansion_terms: Vec::new(),
             suggested_mode,
             confidence,
         }
@@ -83,10 +184,10 @@ impl QueryProcessor {
 Query: "{}"
 
 Return a JSON object with:
-- intents: array of detected intents (preference, skill, goal, fact, opinion, experience, recent)
+- intents: array of detected intents (preference, skill, goal, fact, opinion, experience, recent, causal)
 - concepts: array of relevant ontology concepts
 - expansions: array of terms to expand the query
-- mode: suggested search mode (recent, contextual, deep, or null)"#,
+- mode: suggested search mode (recent, contextual, deep, causal, or null)"#,
                 query
             );
             
@@ -195,6 +296,9 @@ Return a JSON object with:
     fn suggest_mode(&self, intents: &[String], query: &str) -> Option<String> {
         let query_lower = query.to_lowercase();
         
+        if intents.iter().any(|intent| intent == LEXICAL_INTENT) {
+            return Some(LEXICAL_INTENT.to_string());
+        }
         
         if intents.contains(&"recent".to_string()) || 
            query_lower.contains("today") || 
@@ -206,6 +310,10 @@ Return a JSON object with:
             return Some("recent".to_string());
         }
         
+        if intents.contains(&"causal".to_string()) {
+            return Some("causal".to_string());
+        }
+        
         
         if query_lower.contains("all") || 
            query_lower.contains("everything") || 
@@ -236,4 +344,102 @@ Return a JSON object with:
         
         confidence.min(1.0)
     }
-}
\ No newline at end of file
+}
+
+/// Optimal string alignment distance: Levenshtein plus adjacent transpositions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::toolkit::mind_toolbox::ontology::{Concept, ConceptType};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_normalization_corrects_conservatively() {
+        let documents = vec![
+            ("m1".to_string(), "Remember the address of the Berlin office".to_string()),
+            ("m2".to_string(), "Grace prefers green tea".to_string()),
+        ];
+        let processor = QueryProcessor::new(None, false, 0)
+            .with_normalization(true)
+            .with_vocabulary_from_documents(&documents);
+
+        let processed = processor.process("  Remebmer my   ADRESS? ");
+        assert_eq!(processed.original_query, "  Remebmer my   ADRESS? ");
+        assert_eq!(processed.normalized_query, "remember my address?");
+        assert_eq!(processed.enhanced_query, "remember my address?");
+
+        // Short tokens and names far from the vocabulary are left untouched.
+        assert_eq!(processor.normalize("Teh Nikita grean"), "teh nikita green");
+        assert_eq!(edit_distance("remebmer", "remember"), 1);
+
+        let disabled = QueryProcessor::new(None, false, 0).process("Remebmer  me");
+        assert_eq!(disabled.normalized_query, "Remebmer  me");
+    }
+
+    #[test]
+    fn test_identifier_queries_have_lexical_intent() {
+        let processor = QueryProcessor::new(None, false, 0);
+        for query in ["5550100", "+1 (555) 010-0199", "ticket_4821", "\"blue door\""] {
+            let processed = processor.process(query);
+            assert!(processed.is_lexical(), "{query} should be lexical");
+            assert_eq!(processed.suggested_mode.as_deref(), Some("lexical"));
+        }
+        for query in ["what tea do I like", "tea", "what happened on 12 may at the office"] {
+            assert!(!processor.process(query).is_lexical(), "{query} should not be lexical");
+        }
+    }
+
+    fn concept(id: &str, name: &str, parent: Option<&str>) -> Concept {
+        Concept::new(id.to_string(), name.to_string(), ConceptType::Concrete, String::new(), parent.map(str::to_string), 1)
+    }
+
+    #[test]
+    fn test_ontology_expansion() {
+        let client = Arc::new(crate::db::HelixClient::new("127.0.0.1", 1).unwrap());
+        let mut ontology = OntologyManager::new(client);
+        let processor = QueryProcessor::new(None, false, 0).with_ontology_expansion(true);
+
+        let unloaded = processor.process_with_ontology("what car do I drive", &ontology);
+        assert!(unloaded.expansion_terms.is_empty());
+
+        ontology.load_concepts(
+            HashMap::from([
+                ("vehicle".to_string(), concept("vehicle", "Vehicle", None)),
+                ("car".to_string(), concept("car", "Car", Some("vehicle"))),
+                ("automobile".to_string(), concept("automobile", "Automobile", Some("vehicle"))),
+                ("bicycle".to_string(), concept("bicycle", "Bicycle", Some("vehicle"))),
+            ]),
+            Vec::new(),
+        );
+
+        let expanded = processor.process_with_ontology("What cars do I drive", &ontology);
+        assert_eq!(expanded.expansion_terms, vec!["vehicle", "automobile", "bicycle"]);
+        assert_eq!(expanded.enhanced_query, "What cars do I drive");
+
+        let opted_out = QueryProcessor::new(None, false, 0).process_with_ontology("what car", &ontology);
+        assert!(opted_out.expansion_terms.is_empty());
+    }
+}
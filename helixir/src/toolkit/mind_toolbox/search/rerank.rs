@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::models::cmp_results;
+use super::UnifiedSearchResult;
+use crate::llm::decision::extract_json_object;
+use crate::llm::providers::base::LlmProvider;
+
+/// Characters of each candidate shown to the LLM reranker.
+const RERANK_PREVIEW_CHARS: usize = 400;
+
+
+/// Second-pass scorer applied to the final top-k of a search.
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    async fn rerank(&self, query: &str, candidates: Vec<UnifiedSearchResult>) -> Vec<UnifiedSearchResult>;
+}
+
+
+#[derive(Debug, Deserialize)]
+struct RerankResponse {
+    scores: Vec<f64>,
+}
+
+
+/// Asks the LLM for a 0-10 relevance score per candidate in one batched call.
+/// Any failure leaves the candidates in their original order.
+pub struct LlmReranker {
+    llm: Arc<dyn LlmProvider>,
+}
+
+impl LlmReranker {
+    
+    pub fn new(llm: Arc<dyn LlmProvider>) -> Self {
+        Self { llm }
+    }
+
+    fn build_prompt(query: &str, candidates: &[UnifiedSearchResult]) -> String {
+        let listing: Vec<String> = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("[{}] {}", i, crate::safe_truncate(&c.content, RERANK_PREVIEW_CHARS)))
+            .collect();
+        format!(
+            r#"Rate how relevant each memory is to the query on a scale of 0 (unrelated) to 10 (answers it directly).
+Query: "{}"
+
+Memories:
+{}
+
+Return a JSON object {{"scores": [...]}} with exactly {} numbers, one per memory, in the order listed."#,
+            query,
+            listing.join("\n"),
+            candidates.len()
+        )
+    }
+
+    async fn score(&self, query: &str, candidates: &[UnifiedSearchResult]) -> Option<Vec<f64>> {
+        let system_prompt = "You are a search relevance judge. Respond only with valid JSON.";
+        let user_prompt = Self::build_prompt(query, candidates);
+
+        let response_format = self.llm.capabilities().supports_json_mode.then_some("json_object");
+
+        let response = match self.llm.generate(system_prompt, &user_prompt, response_format).await {
+            Ok((response, _metadata)) => response,
+            Err(e) => {
+                warn!("LLM rerank failed: {}", e);
+                return None;
+            }
+        };
+        let Some(json_text) = extract_json_object(&response) else {
+            warn!("LLM rerank response contained no JSON object");
+            return None;
+        };
+        match serde_json::from_str::<RerankResponse>(json_text) {
+            Ok(parsed) if parsed.scores.len() == candidates.len() => Some(parsed.scores),
+            Ok(parsed) => {
+                warn!("LLM rerank returned {} scores for {} candidates", parsed.scores.len(), candidates.len());
+                None
+            }
+            Err(e) => {
+                warn!("Failed to parse LLM rerank response: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Reranker for LlmReranker {
+    async fn rerank(&self, query: &str, mut candidates: Vec<UnifiedSearchResult>) -> Vec<UnifiedSearchResult> {
+        if candidates.len() < 2 {
+            return candidates;
+        }
+        let Some(scores) = self.score(query, &candidates).await else {
+            return candidates;
+        };
+
+        for (candidate, score) in candidates.iter_mut().zip(scores) {
+            candidate.metadata.insert("pre_rerank_score".to_string(), serde_json::json!(candidate.score));
+            candidate.score = if score.is_finite() { (score / 10.0).clamp(0.0, 1.0) as f32 } else { 0.0 };
+        }
+        candidates.sort_by(cmp_results);
+        debug!("Reranked {} candidates with {}", candidates.len(), self.llm.model_name());
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::test_support::StubLlm;
+    use crate::toolkit::mind_toolbox::search::Provenance;
+
+    fn candidate(memory_id: &str, score: f32) -> UnifiedSearchResult {
+        UnifiedSearchResult {
+            memory_id: memory_id.to_string(),
+            content: format!("memory {}", memory_id),
+            score,
+            method: "vector".to_string(),
+            metadata: HashMap::new(),
+            created_at: String::new(),
+            explanation: None,
+            snippet: None,
+            provenance: Provenance::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_llm_reranker_orders_by_llm_scores() {
+        let reranker = LlmReranker::new(Arc::new(StubLlm::replying(r#"{"scores": [2, 9, 5]}"#)));
+        let reranked = reranker
+            .rerank("query", vec![candidate("a", 0.9), candidate("b", 0.8), candidate("c", 0.7)])
+            .await;
+
+        let order: Vec<&str> = reranked.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(order, ["b", "c", "a"]);
+        assert!((reranked[0].score - 0.9).abs() < 1e-6);
+        assert_eq!(reranked[0].metadata["pre_rerank_score"], serde_json::json!(0.8f32));
+
+        let fenced = LlmReranker::new(Arc::new(StubLlm::replying("```json\n{\"scores\": [1, 8]}\n```")));
+        let reranked = fenced.rerank("query", vec![candidate("a", 0.9), candidate("b", 0.8)]).await;
+        assert_eq!(reranked[0].memory_id, "b");
+
+        let malformed = LlmReranker::new(Arc::new(StubLlm::replying(r#"{"scores": [1]}"#)));
+        let kept = malformed.rerank("query", vec![candidate("a", 0.9), candidate("b", 0.8)]).await;
+        assert_eq!(kept[0].memory_id, "a");
+    }
+}
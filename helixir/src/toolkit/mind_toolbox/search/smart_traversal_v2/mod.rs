@@ -6,7 +6,7 @@ pub mod phases;
 pub mod traversal;
 
 
-pub use models::{SearchResult, SearchConfig, TraversalStats};
+pub use models::{SearchResult, SearchConfig, ScoreWeights, CombineWeights, TraversalStats, DEFAULT_TEMPORAL_HALF_LIFE_DAYS, DEFAULT_MAX_CONCURRENT_EXPANSIONS};
 pub use models::edge_weights;
 
 
@@ -16,6 +16,7 @@ pub use scoring::{
     calculate_graph_score,
     calculate_vector_combined_score,
     calculate_graph_combined_score,
+    sanitize_score,
 };
 
 
@@ -23,7 +24,11 @@ pub use phases::{
     TraversalError,
     vector_search_phase,
     graph_expansion_phase,
+    graph_expansion_stream,
     rank_and_filter,
+    content_dedup_phase,
+    dedup_by_content,
+    fetch_memory_embeddings,
 };
 
 
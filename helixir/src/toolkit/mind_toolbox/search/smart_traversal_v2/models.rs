@@ -3,8 +3,14 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub use crate::toolkit::mind_toolbox::search::models::MetadataFilter;
+use crate::toolkit::mind_toolbox::search::models::Ranked;
+use super::scoring::sanitize_score;
+
 
 pub mod edge_weights {
+    use std::collections::HashMap;
+
     pub const BECAUSE: f64 = 1.0;
     pub const IMPLIES: f64 = 0.9;
     pub const SIMILAR_TO: f64 = 0.75;
@@ -25,6 +31,94 @@ pub mod edge_weights {
             _ => DEFAULT,
         }
     }
+
+    // Walking an edge against its direction is weaker evidence than following it.
+    pub const REVERSE_IMPLIES: f64 = 0.9;
+    pub const REVERSE_BECAUSE: f64 = 0.85;
+    pub const REVERSE_CONTRADICTS: f64 = 0.8;
+    pub const REVERSE_MEMORY_RELATION: f64 = 0.6;
+    pub const REVERSE_DEFAULT: f64 = 1.0;
+
+    
+    pub fn reverse_multiplier(edge_type: &str) -> f64 {
+        match edge_type.to_uppercase().as_str() {
+            "IMPLIES" => REVERSE_IMPLIES,
+            "BECAUSE" => REVERSE_BECAUSE,
+            "CONTRADICTS" => REVERSE_CONTRADICTS,
+            "MEMORY_RELATION" => REVERSE_MEMORY_RELATION,
+            _ => REVERSE_DEFAULT,
+        }
+    }
+
+    
+    pub fn reverse_weight(edge_type: &str) -> f64 {
+        get_weight(edge_type) * reverse_multiplier(edge_type)
+    }
+
+    /// Weight for a traversal label such as `IMPLIES` or `IMPLIES_IN`, using
+    /// `reverse_overrides` (keyed by base edge type) for the multiplier when present.
+    pub fn directional_weight(edge_label: &str, reverse_overrides: &HashMap<String, f64>) -> f64 {
+        let Some(base) = edge_label.strip_suffix("_IN") else {
+            return get_weight(edge_label);
+        };
+        let multiplier = reverse_overrides
+            .iter()
+            .find(|(edge_type, _)| edge_type.eq_ignore_ascii_case(base))
+            .map_or_else(|| reverse_multiplier(base), |(_, multiplier)| *multiplier);
+        get_weight(base) * multiplier
+    }
+}
+
+
+impl Ranked for SearchResult {
+    fn rank_score(&self) -> f64 {
+        self.combined_score
+    }
+
+    fn rank_id(&self) -> &str {
+        &self.memory_id
+    }
+}
+
+
+/// How `combined_score` blends the vector, graph and temporal components.
+/// Weights are relative: `combine` divides by their sum, so they need not add up to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoreWeights {
+    pub vector: f64,
+    pub graph: f64,
+    pub temporal: f64,
+}
+
+impl ScoreWeights {
+    pub const VECTOR_DEFAULT: Self = Self { vector: 0.7, graph: 0.0, temporal: 0.3 };
+    pub const GRAPH_DEFAULT: Self = Self { vector: 0.3, graph: 0.5, temporal: 0.2 };
+
+    
+    pub fn combine(&self, vector_score: f64, graph_score: f64, temporal_score: f64) -> f64 {
+        let total = self.vector + self.graph + self.temporal;
+        if total <= 0.0 {
+            return 0.0;
+        }
+        (vector_score * self.vector + graph_score * self.graph + temporal_score * self.temporal) / total
+    }
+}
+
+
+/// Weight sets for seed (vector) hits and graph-reached hits.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CombineWeights {
+    pub vector: ScoreWeights,
+    pub graph: ScoreWeights,
+}
+
+impl Default for CombineWeights {
+    fn default() -> Self {
+        Self {
+            vector: ScoreWeights::VECTOR_DEFAULT,
+            graph: ScoreWeights::GRAPH_DEFAULT,
+        }
+    }
 }
 
 
@@ -55,6 +149,12 @@ pub struct SearchResult {
     
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub importance: Option<i64>,
+    
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certainty: Option<i64>,
 }
 
 impl SearchResult {
@@ -65,7 +165,18 @@ impl SearchResult {
         vector_score: f64,
         temporal_score: f64,
     ) -> Self {
-        let combined = vector_score * 0.7 + temporal_score * 0.3;
+        Self::from_vector_weighted(memory_id, content, vector_score, temporal_score, &ScoreWeights::VECTOR_DEFAULT)
+    }
+
+    
+    pub fn from_vector_weighted(
+        memory_id: impl Into<String>,
+        content: impl Into<String>,
+        vector_score: f64,
+        temporal_score: f64,
+        weights: &ScoreWeights,
+    ) -> Self {
+        let combined = sanitize_score(weights.combine(vector_score, 0.0, temporal_score));
         Self {
             memory_id: memory_id.into(),
             content: content.into(),
@@ -78,6 +189,8 @@ impl SearchResult {
             edge_path: None,
             metadata: None,
             created_at: None,
+            importance: None,
+            certainty: None,
         }
     }
 
@@ -91,8 +204,31 @@ impl SearchResult {
         depth: u32,
         edge_path: Vec<String>,
     ) -> Self {
-        
-        let combined = semantic_sim * 0.3 + graph_score * 0.5 + temporal_score * 0.2;
+        Self::from_graph_weighted(
+            memory_id,
+            content,
+            semantic_sim,
+            graph_score,
+            temporal_score,
+            depth,
+            edge_path,
+            &ScoreWeights::GRAPH_DEFAULT,
+        )
+    }
+
+    
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_graph_weighted(
+        memory_id: impl Into<String>,
+        content: impl Into<String>,
+        semantic_sim: f64,
+        graph_score: f64,
+        temporal_score: f64,
+        depth: u32,
+        edge_path: Vec<String>,
+        weights: &ScoreWeights,
+    ) -> Self {
+        let combined = sanitize_score(weights.combine(semantic_sim, graph_score, temporal_score));
         Self {
             memory_id: memory_id.into(),
             content: content.into(),
@@ -105,6 +241,8 @@ impl SearchResult {
             edge_path: Some(edge_path),
             metadata: None,
             created_at: None,
+            importance: None,
+            certainty: None,
         }
     }
 
@@ -113,9 +251,43 @@ impl SearchResult {
         self.metadata = Some(metadata);
         self
     }
+
+    
+    pub fn with_salience(mut self, importance: Option<i64>, certainty: Option<i64>) -> Self {
+        self.importance = importance;
+        self.certainty = certainty;
+        self
+    }
+
+    /// Mean of importance and certainty scaled to [0, 1]; unset values take the schema defaults.
+    pub fn salience(&self) -> f64 {
+        let importance = self.importance.unwrap_or(DEFAULT_IMPORTANCE).clamp(0, 100);
+        let certainty = self.certainty.unwrap_or(DEFAULT_CERTAINTY).clamp(0, 100);
+        (importance + certainty) as f64 / 200.0
+    }
+
+    /// Scale `combined_score` by `(1 - boost) + boost * salience`; a boost of 0 leaves it unchanged.
+    pub fn apply_salience_boost(&mut self, boost: f64) {
+        let boost = boost.clamp(0.0, 1.0);
+        self.combined_score = sanitize_score(self.combined_score * ((1.0 - boost) + boost * self.salience()));
+    }
+
+    /// Re-rank an already-fetched result with a different component blend, e.g. to favour recency.
+    pub fn recompute_combined(&mut self, weights: ScoreWeights) {
+        self.combined_score = sanitize_score(weights.combine(self.vector_score, self.graph_score, self.temporal_score));
+    }
 }
 
 
+pub const DEFAULT_TEMPORAL_HALF_LIFE_DAYS: f64 = 30.0;
+
+pub const DEFAULT_MAX_CONCURRENT_EXPANSIONS: usize = 8;
+
+/// Schema defaults for memories stored without importance or certainty.
+pub const DEFAULT_IMPORTANCE: i64 = 50;
+pub const DEFAULT_CERTAINTY: i64 = 100;
+
+
 #[derive(Debug, Clone)]
 pub struct SearchConfig {
     
@@ -128,6 +300,25 @@ pub struct SearchConfig {
     pub min_combined_score: f64,
     
     pub edge_types: Option<Vec<String>>,
+    
+    pub expansion_min_similarity: f64,
+    
+    pub temporal_half_life_days: f64,
+    /// Neighbors descended into per node; 0 stops expansion after the first hop
+    pub beam_width: usize,
+    /// Drops non-matching memories from the results; traversal still passes through them
+    pub metadata_filter: Option<MetadataFilter>,
+    /// Collapses results whose embeddings are at least this similar (or whose content matches)
+    pub dedup_content_threshold: Option<f64>,
+    /// Per-edge-type overrides of `edge_weights::reverse_multiplier`
+    pub reverse_edge_multipliers: HashMap<String, f64>,
+    pub combine_weights: CombineWeights,
+    /// Weight in [0, 1] of importance and certainty in `combined_score`; 0 ignores them
+    pub importance_boost: f64,
+    /// Seed expansions allowed to query the database at once
+    pub max_concurrent_expansions: usize,
+    /// Keep archived memories in the results; traversal passes through them either way
+    pub include_archived: bool,
 }
 
 impl Default for SearchConfig {
@@ -142,6 +333,16 @@ impl Default for SearchConfig {
                 "IMPLIES".to_string(),
                 "MEMORY_RELATION".to_string(),
             ]),
+            expansion_min_similarity: 0.0,
+            temporal_half_life_days: DEFAULT_TEMPORAL_HALF_LIFE_DAYS,
+            beam_width: 3,
+            metadata_filter: None,
+            dedup_content_threshold: None,
+            reverse_edge_multipliers: HashMap::new(),
+            combine_weights: CombineWeights::default(),
+            importance_boost: 0.0,
+            max_concurrent_expansions: DEFAULT_MAX_CONCURRENT_EXPANSIONS,
+            include_archived: false,
         }
     }
 }
@@ -157,5 +358,94 @@ pub struct TraversalStats {
     pub phase2_duration_ms: f64,
     pub phase3_duration_ms: f64,
     pub total_duration_ms: f64,
+    /// Whether this call was answered from the result cache
+    #[serde(default)]
+    pub served_from_cache: bool,
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::edge_weights::*;
+    use super::{CombineWeights, ScoreWeights, SearchResult};
+    use crate::toolkit::mind_toolbox::search::models::cmp_results;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_recompute_combined_reorders_by_recency() {
+        let mut relevant = SearchResult::from_vector("relevant", "old but on topic", 0.9, 0.1);
+        let mut recent = SearchResult::from_vector("recent", "fresh but looser match", 0.5, 1.0);
+        assert!((relevant.combined_score - 0.66).abs() < 1e-9);
+        assert!(relevant.combined_score > recent.combined_score);
+
+        let recency_first = ScoreWeights { vector: 0.3, graph: 0.0, temporal: 0.7 };
+        relevant.recompute_combined(recency_first);
+        recent.recompute_combined(recency_first);
+        assert!(recent.combined_score > relevant.combined_score);
+
+        let mut graph = SearchResult::from_graph("g", "linked", 0.5, 0.8, 0.9, 1, Vec::new());
+        let original = graph.combined_score;
+        graph.recompute_combined(ScoreWeights::GRAPH_DEFAULT);
+        assert!((graph.combined_score - original).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_custom_combine_weights_change_ordering() {
+        let defaults = CombineWeights::default();
+        let on_topic = SearchResult::from_vector_weighted("on_topic", "", 0.9, 0.2, &defaults.vector);
+        let fresh = SearchResult::from_vector_weighted("fresh", "", 0.5, 1.0, &defaults.vector);
+        assert!(on_topic.combined_score > fresh.combined_score);
+
+        // Unnormalized weights behave like their proportions: 1:3 is 0.25/0.75.
+        let recency = CombineWeights { vector: ScoreWeights { vector: 1.0, graph: 0.0, temporal: 3.0 }, ..defaults };
+        let on_topic = SearchResult::from_vector_weighted("on_topic", "", 0.9, 0.2, &recency.vector);
+        let fresh = SearchResult::from_vector_weighted("fresh", "", 0.5, 1.0, &recency.vector);
+        assert!(fresh.combined_score > on_topic.combined_score);
+        assert!((fresh.combined_score - 0.875).abs() < 1e-12);
+
+        let graph_led = ScoreWeights { vector: 0.0, graph: 1.0, temporal: 0.0 };
+        let linked = SearchResult::from_graph_weighted("linked", "", 0.2, 0.9, 0.1, 1, Vec::new(), &graph_led);
+        assert!((linked.combined_score - 0.9).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_importance_boost_reorders_equal_similarity() {
+        let hunch = SearchResult::from_vector("hunch", "", 0.8, 0.5).with_salience(Some(20), Some(40));
+        let fact = SearchResult::from_vector("fact", "", 0.8, 0.5).with_salience(Some(90), None);
+        assert_eq!(hunch.combined_score, fact.combined_score);
+
+        let mut unboosted = hunch.clone();
+        unboosted.apply_salience_boost(0.0);
+        assert_eq!(unboosted.combined_score, hunch.combined_score);
+
+        let mut ranked: Vec<SearchResult> = [hunch, fact]
+            .into_iter()
+            .map(|mut r| {
+                r.apply_salience_boost(0.5);
+                r
+            })
+            .collect();
+        ranked.sort_by(cmp_results);
+        assert_eq!(ranked[0].memory_id, "fact");
+        assert!((ranked[0].salience() - 0.95).abs() < 1e-12);
+        assert!((ranked[1].salience() - 0.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_reverse_weight_defaults() {
+        assert_eq!(reverse_multiplier("IMPLIES"), 0.9);
+        assert_eq!(reverse_multiplier("because"), 0.85);
+        assert_eq!(reverse_multiplier("CONTRADICTS"), 0.8);
+        assert_eq!(reverse_multiplier("MEMORY_RELATION"), 0.6);
+        assert_eq!(reverse_multiplier("SIMILAR_TO"), 1.0);
+        assert!((reverse_weight("IMPLIES") - IMPLIES * 0.9).abs() < 1e-12);
+
+        let none = HashMap::new();
+        assert_eq!(directional_weight("IMPLIES", &none), IMPLIES);
+        assert_eq!(directional_weight("BECAUSE_IN", &none), reverse_weight("BECAUSE"));
+
+        let overrides = HashMap::from([("implies".to_string(), 0.5)]);
+        assert!((directional_weight("IMPLIES_IN", &overrides) - IMPLIES * 0.5).abs() < 1e-12);
+        assert_eq!(directional_weight("CONTRADICTS_IN", &overrides), reverse_weight("CONTRADICTS"));
+    }
+}
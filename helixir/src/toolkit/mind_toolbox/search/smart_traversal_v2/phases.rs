@@ -1,13 +1,22 @@
 
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
 use tracing::{debug, info, warn};
-use super::models::{SearchResult, edge_weights};
-use super::scoring::{calculate_temporal_freshness, calculate_graph_score};
+use super::models::{SearchResult, SearchConfig, MetadataFilter, ScoreWeights, edge_weights};
+use super::scoring::{calculate_temporal_freshness, calculate_graph_score, cosine_similarity, sanitize_score};
 use crate::db::HelixClient;
+use crate::toolkit::mind_toolbox::search::models::{cmp_results, cmp_scored};
+
+/// Expansion tasks that may be running or waiting to be consumed in streaming mode.
+const EXPANSION_STREAM_CONCURRENCY: usize = 4;
+
+/// Vector score assumed for unscored hits whose stored embedding cannot be read either.
+const UNSCORED_VECTOR_SCORE: f64 = 0.8;
 
 
 #[derive(Debug, thiserror::Error)]
@@ -24,19 +33,50 @@ struct VectorSearchResponse {
     #[serde(default)]
     memories: Vec<VectorMemory>,
     #[serde(default)]
-    chunks: Vec<serde_json::Value>,
+    chunks: Vec<VectorChunk>,
+    #[serde(default)]
+    chunk_parents: Vec<VectorMemory>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 struct VectorMemory {
     memory_id: String,
     content: String,
+    #[serde(default, alias = "similarity_score")]
+    score: Option<f64>,
     #[serde(default)]
     created_at: String,
     #[serde(default)]
     memory_type: String,
     #[serde(default)]
     user_id: String,
+    #[serde(default)]
+    importance: Option<i64>,
+    #[serde(default)]
+    certainty: Option<i64>,
+    #[serde(default)]
+    context_tags: String,
+    #[serde(default)]
+    archived_at: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct VectorChunk {
+    chunk_id: String,
+    parent_memory_id: String,
+    #[serde(default)]
+    position: i64,
+    #[serde(default, alias = "similarity_score")]
+    score: Option<f64>,
+}
+
+
+#[derive(Debug)]
+struct VectorHit {
+    memory: VectorMemory,
+    /// `None` when neither the memory nor any of its chunks came back with a similarity
+    vector_score: Option<f64>,
+    matched_chunk: Option<VectorChunk>,
 }
 
 
@@ -61,6 +101,36 @@ struct GraphConnectionsResponse {
 }
 
 
+#[derive(Debug, Deserialize, Default)]
+struct MemoryEmbeddingResponse {
+    #[serde(default)]
+    embedding: Option<StoredEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredEmbedding {
+    #[serde(default, alias = "vector")]
+    data: Vec<f64>,
+}
+
+
+#[derive(Debug, Clone)]
+struct ExpansionSettings {
+    max_depth: u32,
+    min_similarity: f64,
+    edge_types: Option<Vec<String>>,
+    half_life_days: f64,
+    beam_width: usize,
+    metadata_filter: Option<MetadataFilter>,
+    include_archived: bool,
+    reverse_multipliers: HashMap<String, f64>,
+    graph_weights: ScoreWeights,
+    importance_boost: f64,
+    /// Shared by every seed expansion of one search
+    expansion_permits: Arc<Semaphore>,
+}
+
+
 #[derive(Debug, Deserialize)]
 struct ConnectedMemory {
     memory_id: String,
@@ -69,6 +139,14 @@ struct ConnectedMemory {
     created_at: String,
     #[serde(default)]
     memory_type: String,
+    #[serde(default)]
+    importance: Option<i64>,
+    #[serde(default)]
+    certainty: Option<i64>,
+    #[serde(default)]
+    context_tags: String,
+    #[serde(default)]
+    archived_at: String,
 }
 
 
@@ -76,10 +154,12 @@ pub async fn vector_search_phase(
     client: Arc<HelixClient>,
     query_embedding: &[f32],
     user_id: Option<&str>,
-    top_k: usize,
-    min_score: f64,
     temporal_cutoff: Option<DateTime<Utc>>,
+    config: &SearchConfig,
 ) -> Result<Vec<SearchResult>, TraversalError> {
+    let top_k = config.vector_top_k;
+    let min_score = config.min_vector_score;
+    let filter = config.metadata_filter.as_ref();
     info!("Starting Phase 1: Vector search with top_k={}", top_k);
 
     
@@ -88,111 +168,254 @@ pub async fn vector_search_phase(
         "query_vector": query_vector,
         "limit": top_k as i64
     });
-    
-    
-    let _ = (user_id, min_score); 
 
     let response: VectorSearchResponse = client
         .execute_query("smartVectorSearchWithChunks", &params)
         .await
         .map_err(|e| TraversalError::Database(e.to_string()))?;
 
-    let mut results = Vec::new();
-    let mut seen_ids = HashSet::new();
+    let hits: Vec<VectorHit> = rollup_vector_hits(response)
+        .into_iter()
+        .filter(|hit| {
+            let memory = &hit.memory;
+            // The vector index is shared by all users, so ownership is checked on every hit.
+            if user_id.is_some_and(|u| memory.user_id != u) {
+                return false;
+            }
+            if filter.is_some_and(|f| !f.matches(&memory.memory_type, memory.importance, &memory.context_tags)) {
+                return false;
+            }
+            if !config.include_archived && !memory.archived_at.is_empty() {
+                return false;
+            }
+            match (&temporal_cutoff, DateTime::parse_from_rfc3339(&memory.created_at)) {
+                (Some(cutoff), Ok(created_at)) => created_at.with_timezone(&Utc) >= *cutoff,
+                _ => true,
+            }
+        })
+        .collect();
+    let similarities = score_unscored_hits(&client, query_embedding, &hits).await;
 
-    for memory in response.memories {
-        if seen_ids.contains(&memory.memory_id) {
+    let mut results = Vec::new();
+    for VectorHit { memory, vector_score, matched_chunk } in hits {
+        let vector_score = vector_score
+            .or_else(|| similarities.get(&memory.memory_id).copied())
+            .unwrap_or(UNSCORED_VECTOR_SCORE);
+        if sanitize_score(vector_score) < min_score {
             continue;
         }
-        seen_ids.insert(memory.memory_id.clone());
 
-        if let Some(cutoff) = &temporal_cutoff {
-            if let Ok(created_at) = DateTime::parse_from_rfc3339(&memory.created_at) {
-                if created_at.with_timezone(&Utc) < *cutoff {
-                    continue;
-                }
-            }
-        }
-
-        let temporal_score = calculate_temporal_freshness(&memory.created_at, 30.0);
+        let temporal_score = calculate_temporal_freshness(&memory.created_at, config.temporal_half_life_days);
         
-        let mut result = SearchResult::from_vector(
+        let mut result = SearchResult::from_vector_weighted(
             &memory.memory_id,
             &memory.content,
-            0.8,
+            vector_score,
             temporal_score,
-        );
+            &config.combine_weights.vector,
+        )
+        .with_salience(memory.importance, memory.certainty);
+        result.apply_salience_boost(config.importance_boost);
         result.created_at = Some(memory.created_at.clone());
-
-        if result.combined_score >= min_score {
-            results.push(result);
+        let mut metadata = HashMap::new();
+        // Unscoped searches span users, so seeds record whose memory they are.
+        if !memory.user_id.is_empty() {
+            metadata.insert("user_id".to_string(), serde_json::json!(memory.user_id));
+        }
+        if let Some(chunk) = matched_chunk {
+            metadata.insert("matched_chunk_id".to_string(), serde_json::json!(chunk.chunk_id));
+            metadata.insert("matched_chunk_position".to_string(), serde_json::json!(chunk.position));
         }
+        if !metadata.is_empty() {
+            result.metadata = Some(metadata);
+        }
+
+        results.push(result);
     }
 
     
-    results.sort_by(|a, b| b.combined_score.partial_cmp(&a.combined_score).unwrap());
+    results.sort_by(cmp_results);
 
     info!("Phase 1 completed: {} results", results.len());
     Ok(results)
 }
 
+/// Cosine similarity against the stored embedding for hits the query returned without a score.
+async fn score_unscored_hits(client: &HelixClient, query_embedding: &[f32], hits: &[VectorHit]) -> HashMap<String, f64> {
+    let unscored: HashSet<&str> = hits
+        .iter()
+        .filter(|hit| hit.vector_score.is_none())
+        .map(|hit| hit.memory.memory_id.as_str())
+        .collect();
+    if unscored.is_empty() {
+        return HashMap::new();
+    }
+
+    debug!("Scoring {} vector hits against their stored embeddings", unscored.len());
+    fetch_memory_embeddings(client, unscored)
+        .await
+        .into_iter()
+        .map(|(memory_id, vector)| (memory_id, cosine_similarity(query_embedding, &vector)))
+        .collect()
+}
+
+/// One hit per memory: chunk hits count towards their parent, which keeps its best score.
+fn rollup_vector_hits(response: VectorSearchResponse) -> Vec<VectorHit> {
+    let mut hits: Vec<VectorHit> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for memory in response.memories {
+        if index.contains_key(&memory.memory_id) {
+            continue;
+        }
+        index.insert(memory.memory_id.clone(), hits.len());
+        hits.push(VectorHit {
+            vector_score: memory.score,
+            memory,
+            matched_chunk: None,
+        });
+    }
+
+    let parents: HashMap<String, VectorMemory> = response
+        .chunk_parents
+        .into_iter()
+        .map(|parent| (parent.memory_id.clone(), parent))
+        .collect();
+
+    for chunk in response.chunks {
+        let score = chunk.score;
+        if let Some(&i) = index.get(&chunk.parent_memory_id) {
+            let hit = &mut hits[i];
+            let best_chunk = hit.matched_chunk.as_ref().map(|best| best.score);
+            if best_chunk.is_none() || best_chunk < Some(score) {
+                hit.vector_score = match (hit.vector_score, score) {
+                    (Some(current), Some(chunk_score)) => Some(current.max(chunk_score)),
+                    (current, chunk_score) => current.or(chunk_score),
+                };
+                hit.matched_chunk = Some(chunk);
+            }
+        } else if let Some(parent) = parents.get(&chunk.parent_memory_id) {
+            index.insert(parent.memory_id.clone(), hits.len());
+            hits.push(VectorHit {
+                memory: parent.clone(),
+                vector_score: score,
+                matched_chunk: Some(chunk),
+            });
+        } else {
+            debug!("Chunk {} has no parent memory in the response, skipping", chunk.chunk_id);
+        }
+    }
+
+    hits
+}
+
 
 pub async fn graph_expansion_phase(
     client: Arc<HelixClient>,
     vector_hits: &[SearchResult],
     query_embedding: &[f32],
-    max_depth: u32,
-    edge_types: &[String],
+    config: &SearchConfig,
 ) -> Result<Vec<SearchResult>, TraversalError> {
     info!("Starting Phase 2: Graph expansion from {} vector hits", vector_hits.len());
 
-    let mut all_results = Vec::new();
-    let mut expansion_tasks = Vec::new();
-
-    for hit in vector_hits {
-        let client = Arc::clone(&client);
-        let query_embedding = query_embedding.to_vec();
-        let hit = hit.clone();
-        let edge_types = edge_types.to_vec();
-
-        let task = tokio::spawn(async move {
-            let mut visited = HashSet::new();
-            visited.insert(hit.memory_id.clone());
-            
-            expand_from_node(
-                client,
-                &hit.memory_id,
-                &query_embedding,
-                1,
-                max_depth,
-                &mut visited,
-                hit.combined_score,
-            ).await
-        });
-
-        expansion_tasks.push(task);
-    }
+    let settings = expansion_settings(config);
+    let query_embedding: Arc<[f32]> = query_embedding.into();
+    let expansion_tasks: Vec<_> = vector_hits
+        .iter()
+        .map(|hit| spawn_expansion(Arc::clone(&client), hit.clone(), Arc::clone(&query_embedding), Arc::clone(&settings)))
+        .collect();
 
-    
+    let mut all_results = Vec::new();
     for task in expansion_tasks {
-        match task.await {
-            Ok(Ok(results)) => all_results.extend(results),
-            Ok(Err(e)) => warn!("Graph expansion failed: {}", e),
-            Err(e) => warn!("Graph expansion task panicked: {}", e),
-        }
+        all_results.extend(joined_expansion(task.await));
     }
 
     info!("Phase 2 completed: {} expanded results", all_results.len());
     Ok(all_results)
 }
 
+/// Yields each vector hit's expansion as soon as it finishes. Tasks are spawned only
+/// while fewer than `EXPANSION_STREAM_CONCURRENCY` are pending, so a slow consumer
+/// pauses expansion instead of letting finished batches pile up.
+pub fn graph_expansion_stream(
+    client: Arc<HelixClient>,
+    vector_hits: Vec<SearchResult>,
+    query_embedding: Arc<[f32]>,
+    config: &SearchConfig,
+) -> impl Stream<Item = Vec<SearchResult>> + Send + use<> {
+    let settings = expansion_settings(config);
+    futures::stream::iter(vector_hits)
+        .map(move |hit| spawn_expansion(Arc::clone(&client), hit, Arc::clone(&query_embedding), Arc::clone(&settings)))
+        .buffer_unordered(EXPANSION_STREAM_CONCURRENCY)
+        .map(joined_expansion)
+        .filter(|results| futures::future::ready(!results.is_empty()))
+}
+
+
+fn expansion_settings(config: &SearchConfig) -> Arc<ExpansionSettings> {
+    Arc::new(ExpansionSettings {
+        max_depth: config.graph_depth,
+        min_similarity: config.expansion_min_similarity,
+        edge_types: config.edge_types.clone(),
+        half_life_days: config.temporal_half_life_days,
+        beam_width: config.beam_width,
+        metadata_filter: config.metadata_filter.clone(),
+        include_archived: config.include_archived,
+        reverse_multipliers: config.reverse_edge_multipliers.clone(),
+        graph_weights: config.combine_weights.graph,
+        importance_boost: config.importance_boost,
+        expansion_permits: Arc::new(Semaphore::new(config.max_concurrent_expansions.max(1))),
+    })
+}
+
+
+fn spawn_expansion(
+    client: Arc<HelixClient>,
+    hit: SearchResult,
+    query_embedding: Arc<[f32]>,
+    settings: Arc<ExpansionSettings>,
+) -> tokio::task::JoinHandle<Result<Vec<SearchResult>, TraversalError>> {
+    tokio::spawn(async move {
+        let _permit = settings.expansion_permits.acquire().await.unwrap();
+        let mut visited = HashSet::new();
+        visited.insert(hit.memory_id.clone());
+
+        expand_from_node(
+            client,
+            &hit.memory_id,
+            &query_embedding,
+            1,
+            &settings,
+            &mut visited,
+            hit.combined_score,
+        ).await
+    })
+}
+
+
+fn joined_expansion(
+    joined: Result<Result<Vec<SearchResult>, TraversalError>, tokio::task::JoinError>,
+) -> Vec<SearchResult> {
+    match joined {
+        Ok(Ok(results)) => results,
+        Ok(Err(e)) => {
+            warn!("Graph expansion failed: {}", e);
+            Vec::new()
+        }
+        Err(e) => {
+            warn!("Graph expansion task panicked: {}", e);
+            Vec::new()
+        }
+    }
+}
+
 
 async fn expand_from_node(
     client: Arc<HelixClient>,
     node_id: &str,
     query_embedding: &[f32],
     current_depth: u32,
-    max_depth: u32,
+    settings: &ExpansionSettings,
     visited: &mut HashSet<String>,
     parent_score: f64,
 ) -> Result<Vec<SearchResult>, TraversalError> {
@@ -207,113 +430,170 @@ async fn expand_from_node(
         .await
         .map_err(|e| TraversalError::Database(e.to_string()))?;
 
-    let mut results = Vec::new();
-    let mut neighbors = Vec::new();
+    let allowed = settings.edge_types.as_deref();
+    let candidate_ids: HashSet<&str> = edge_families(&response)
+        .into_iter()
+        .filter(|(_, edge_type)| edge_type_allowed(edge_type, allowed))
+        .flat_map(|(memories, _)| memories.iter())
+        .map(|mem| mem.memory_id.as_str())
+        .filter(|id| !visited.contains(*id))
+        .collect();
+    let similarities = fetch_semantic_similarities(&client, query_embedding, candidate_ids).await;
 
-    
-    process_edge_collection(
-        &response.implies_out,
-        "IMPLIES",
-        edge_weights::IMPLIES,
+    let (mut results, neighbors) = collect_neighbors(
+        &response,
+        allowed,
+        &similarities,
         parent_score,
         visited,
-        &mut results,
-        &mut neighbors,
+        settings.half_life_days,
+        &settings.reverse_multipliers,
     );
+    for result in &mut results {
+        result.recompute_combined(settings.graph_weights);
+        result.apply_salience_boost(settings.importance_boost);
+    }
 
-    process_edge_collection(
-        &response.because_out,
-        "BECAUSE",
-        edge_weights::BECAUSE,
-        parent_score,
-        visited,
-        &mut results,
-        &mut neighbors,
-    );
+    let filter = settings.metadata_filter.as_ref();
+    if filter.is_some() || !settings.include_archived {
+        let rejected: HashSet<&str> = edge_families(&response)
+            .into_iter()
+            .flat_map(|(memories, _)| memories.iter())
+            .filter(|mem| {
+                filter.is_some_and(|f| !f.matches(&mem.memory_type, mem.importance, &mem.context_tags))
+                    || (!settings.include_archived && !mem.archived_at.is_empty())
+            })
+            .map(|mem| mem.memory_id.as_str())
+            .collect();
+        results.retain(|r| !rejected.contains(r.memory_id.as_str()));
+    }
 
-    process_edge_collection(
-        &response.contradicts_out,
-        "CONTRADICTS",
-        edge_weights::CONTRADICTS,
-        parent_score,
-        visited,
-        &mut results,
-        &mut neighbors,
-    );
+    
+    if current_depth < settings.max_depth {
+        for (neighbor_id, neighbor_score) in select_expansion_candidates(neighbors, visited, settings.min_similarity, settings.beam_width) {
+            visited.insert(neighbor_id.clone());
+            let expanded = Box::pin(expand_from_node(
+                Arc::clone(&client),
+                &neighbor_id,
+                query_embedding,
+                current_depth + 1,
+                settings,
+                visited,
+                neighbor_score,
+            )).await?;
+            results.extend(expanded);
+        }
+    }
 
-    process_edge_collection(
-        &response.relation_out,
-        "MEMORY_RELATION",
-        edge_weights::MEMORY_RELATION,
-        parent_score,
-        visited,
-        &mut results,
-        &mut neighbors,
-    );
+    Ok(results)
+}
 
-    
-    process_edge_collection(
-        &response.implies_in,
-        "IMPLIES_IN",
-        edge_weights::IMPLIES * 0.9,
-        parent_score,
-        visited,
-        &mut results,
-        &mut neighbors,
-    );
 
-    process_edge_collection(
-        &response.because_in,
-        "BECAUSE_IN",
-        edge_weights::BECAUSE * 0.85,
-        parent_score,
-        visited,
-        &mut results,
-        &mut neighbors,
-    );
+fn edge_type_allowed(edge_type: &str, allowed: Option<&[String]>) -> bool {
+    let Some(allowed) = allowed else { return true; };
+    let base_type = edge_type.strip_suffix("_IN").unwrap_or(edge_type);
+    allowed.iter().any(|a| {
+        let a = a.to_uppercase();
+        a == edge_type || a == base_type
+    })
+}
 
-    process_edge_collection(
-        &response.contradicts_in,
-        "CONTRADICTS_IN",
-        edge_weights::CONTRADICTS * 0.8,
-        parent_score,
-        visited,
-        &mut results,
-        &mut neighbors,
-    );
 
-    process_edge_collection(
-        &response.relation_in,
-        "MEMORY_RELATION_IN",
-        edge_weights::MEMORY_RELATION * 0.6,
-        parent_score,
-        visited,
-        &mut results,
-        &mut neighbors,
-    );
+async fn fetch_semantic_similarities(
+    client: &HelixClient,
+    query_embedding: &[f32],
+    memory_ids: HashSet<&str>,
+) -> HashMap<String, f64> {
+    if query_embedding.is_empty() || memory_ids.is_empty() {
+        return HashMap::new();
+    }
 
-    
-    if current_depth < max_depth {
-        
-        neighbors.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        for (neighbor_id, neighbor_score) in neighbors.into_iter().take(3) {
-            if !visited.contains(&neighbor_id) {
-                visited.insert(neighbor_id.clone());
-                let expanded = Box::pin(expand_from_node(
-                    Arc::clone(&client),
-                    &neighbor_id,
-                    query_embedding,
-                    current_depth + 1,
-                    max_depth,
-                    visited,
-                    neighbor_score,
-                )).await?;
-                results.extend(expanded);
+    fetch_memory_embeddings(client, memory_ids)
+        .await
+        .into_iter()
+        .map(|(memory_id, vector)| {
+            let similarity = cosine_similarity(query_embedding, &vector);
+            (memory_id, similarity)
+        })
+        .collect()
+}
+
+
+/// Stored embeddings for `memory_ids`, fetched concurrently; ids without one are left out.
+pub async fn fetch_memory_embeddings(
+    client: &HelixClient,
+    memory_ids: HashSet<&str>,
+) -> HashMap<String, Vec<f32>> {
+    let lookups = memory_ids.into_iter().map(|memory_id| async move {
+        let response = client
+            .execute_query::<MemoryEmbeddingResponse, _>(
+                "getMemoryEmbeddingByMemoryId",
+                &serde_json::json!({ "memory_id": memory_id }),
+            )
+            .await;
+        match response {
+            Ok(MemoryEmbeddingResponse { embedding: Some(embedding) }) if !embedding.data.is_empty() => {
+                let vector: Vec<f32> = embedding.data.iter().map(|&x| x as f32).collect();
+                Some((memory_id.to_string(), vector))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                debug!("Embedding lookup failed for {}: {}", memory_id, e);
+                None
             }
         }
+    });
+
+    futures::future::join_all(lookups).await.into_iter().flatten().collect()
+}
+
+
+fn edge_families(response: &GraphConnectionsResponse) -> [(&[ConnectedMemory], &'static str); 8] {
+    [
+        (&response.implies_out, "IMPLIES"),
+        (&response.because_out, "BECAUSE"),
+        (&response.contradicts_out, "CONTRADICTS"),
+        (&response.relation_out, "MEMORY_RELATION"),
+        (&response.implies_in, "IMPLIES_IN"),
+        (&response.because_in, "BECAUSE_IN"),
+        (&response.contradicts_in, "CONTRADICTS_IN"),
+        (&response.relation_in, "MEMORY_RELATION_IN"),
+    ]
+}
+
+
+fn collect_neighbors(
+    response: &GraphConnectionsResponse,
+    allowed: Option<&[String]>,
+    similarities: &HashMap<String, f64>,
+    parent_score: f64,
+    visited: &HashSet<String>,
+    half_life_days: f64,
+    reverse_multipliers: &HashMap<String, f64>,
+) -> (Vec<SearchResult>, Vec<(String, f64, f64)>) {
+    let mut results = Vec::new();
+
+    for (memories, edge_type) in edge_families(response) {
+        if !edge_type_allowed(edge_type, allowed) {
+            continue;
+        }
+        results.extend(process_edge_collection(
+            memories,
+            edge_type,
+            edge_weights::directional_weight(edge_type, reverse_multipliers),
+            parent_score,
+            visited,
+            similarities,
+            half_life_days,
+        ));
     }
 
-    Ok(results)
+    let neighbors = results
+        .iter()
+        .map(|r| (r.memory_id.clone(), r.graph_score, r.vector_score))
+        .collect();
+
+    (results, neighbors)
 }
 
 
@@ -323,33 +603,62 @@ fn process_edge_collection(
     edge_weight: f64,
     parent_score: f64,
     visited: &HashSet<String>,
-    results: &mut Vec<SearchResult>,
-    neighbors: &mut Vec<(String, f64)>,
-) {
+    similarities: &HashMap<String, f64>,
+    half_life_days: f64,
+) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+
     for mem in memories {
         if visited.contains(&mem.memory_id) {
             continue;
         }
 
-        let temporal_score = calculate_temporal_freshness(&mem.created_at, 30.0);
+        let temporal_score = calculate_temporal_freshness(&mem.created_at, half_life_days);
         let graph_score = calculate_graph_score(edge_weight, parent_score);
+        let semantic_sim = similarities.get(&mem.memory_id).copied();
         
-        
-        let semantic_sim = 0.5;
-        
-        let result = SearchResult::from_graph(
+        let mut result = SearchResult::from_graph(
             &mem.memory_id,
             &mem.content,
-            semantic_sim,
+            semantic_sim.unwrap_or(0.5),
             graph_score,
             temporal_score,
             1, 
             vec![edge_type.to_string()],
-        );
+        )
+        .with_salience(mem.importance, mem.certainty);
+        if semantic_sim.is_none() {
+            result = result.with_metadata(HashMap::from([
+                ("semantic_fallback".to_string(), serde_json::Value::Bool(true)),
+            ]));
+        }
 
         results.push(result);
-        neighbors.push((mem.memory_id.clone(), graph_score));
     }
+
+    results
+}
+
+
+fn select_expansion_candidates(
+    mut neighbors: Vec<(String, f64, f64)>,
+    visited: &HashSet<String>,
+    min_similarity: f64,
+    beam_width: usize,
+) -> Vec<(String, f64)> {
+    neighbors.retain(|(id, _, semantic_sim)| {
+        if *semantic_sim < min_similarity {
+            debug!("Pruning branch at {} (similarity {:.3} < {:.3})", id, semantic_sim, min_similarity);
+            return false;
+        }
+        !visited.contains(id)
+    });
+    neighbors.sort_by(|a, b| cmp_scored((a.1, &a.0), (b.1, &b.0)));
+    neighbors
+        .into_iter()
+        .take(beam_width)
+        .map(|(id, graph_score, _)| (id, graph_score))
+        .collect()
 }
 
 
@@ -362,7 +671,8 @@ pub fn rank_and_filter(
     
     let mut best_scores: std::collections::HashMap<String, SearchResult> = std::collections::HashMap::new();
     
-    for result in results {
+    for mut result in results {
+        result.combined_score = sanitize_score(result.combined_score);
         match best_scores.get(&result.memory_id) {
             Some(existing) => {
                 if result.combined_score > existing.combined_score {
@@ -382,8 +692,465 @@ pub fn rank_and_filter(
         .collect();
 
     
-    filtered_results.sort_by(|a, b| b.combined_score.partial_cmp(&a.combined_score).unwrap());
+    filtered_results.sort_by(cmp_results);
 
     info!("Phase 3 completed: {} final results", filtered_results.len());
     filtered_results
-}
\ No newline at end of file
+}
+
+
+pub async fn content_dedup_phase(
+    client: &HelixClient,
+    results: Vec<SearchResult>,
+    threshold: f64,
+) -> Vec<SearchResult> {
+    if results.len() < 2 {
+        return results;
+    }
+    let ids: HashSet<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+    let embeddings = fetch_memory_embeddings(client, ids).await;
+    let before = results.len();
+    let deduped = dedup_by_content(results, &embeddings, threshold);
+    debug!("Content dedup removed {} near-duplicates", before - deduped.len());
+    deduped
+}
+
+/// Keeps the higher-scored of any pair that is near-identical by embedding, or by
+/// normalized content when either embedding is unavailable. Expects `results` sorted best-first.
+pub fn dedup_by_content(
+    results: Vec<SearchResult>,
+    embeddings: &HashMap<String, Vec<f32>>,
+    threshold: f64,
+) -> Vec<SearchResult> {
+    let mut kept: Vec<(SearchResult, String)> = Vec::with_capacity(results.len());
+
+    for result in results {
+        let normalized = normalize_content(&result.content);
+        let duplicate = kept.iter().any(|(other, other_normalized)| {
+            if *other_normalized == normalized {
+                return true;
+            }
+            match (embeddings.get(&result.memory_id), embeddings.get(&other.memory_id)) {
+                (Some(a), Some(b)) => cosine_similarity(a, b) >= threshold,
+                _ => false,
+            }
+        });
+        if !duplicate {
+            kept.push((result, normalized));
+        }
+    }
+
+    kept.into_iter().map(|(result, _)| result).collect()
+}
+
+fn normalize_content(content: &str) -> String {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{spawn_async_stub, spawn_reply_stub, spawn_stub};
+
+    // Answers getMemoryLogicalConnections from `graph` and records which nodes were
+    // expanded; every other query gets an empty object.
+    async fn spawn_graph_stub(
+        graph: HashMap<&'static str, serde_json::Value>,
+    ) -> (Arc<HelixClient>, Arc<std::sync::Mutex<Vec<String>>>) {
+        let expanded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = Arc::clone(&expanded);
+        let stub = spawn_stub(move |query, body| {
+            if query != "getMemoryLogicalConnections" {
+                return Some(serde_json::json!({}));
+            }
+            let memory_id = body["memory_id"].as_str().unwrap_or_default();
+            log.lock().unwrap().push(memory_id.to_string());
+            Some(graph.get(memory_id).cloned().unwrap_or_else(|| serde_json::json!({})))
+        })
+        .await;
+        (stub.client(), expanded)
+    }
+
+    // Answers getMemoryLogicalConnections after `delay`, tracking the most requests in flight at once.
+    async fn spawn_counting_stub(delay: std::time::Duration) -> (Arc<HelixClient>, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let observed = Arc::clone(&peak);
+        let stub = spawn_async_stub(move |query, _| {
+            let (in_flight, peak) = (Arc::clone(&in_flight), Arc::clone(&peak));
+            async move {
+                if query == "getMemoryLogicalConnections" {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(delay).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                }
+                Some(serde_json::json!({}))
+            }
+        })
+        .await;
+        (stub.client(), observed)
+    }
+
+    fn branching_graph() -> HashMap<&'static str, serde_json::Value> {
+        let node = |id: &str| serde_json::json!({"memory_id": id, "content": format!("memory {}", id)});
+        HashMap::from([
+            ("a", serde_json::json!({"implies_out": [node("b"), node("c")]})),
+            ("b", serde_json::json!({"implies_out": [node("d"), node("e")]})),
+            ("c", serde_json::json!({"implies_out": [node("f")]})),
+        ])
+    }
+
+    async fn expanded_nodes(beam_width: usize) -> Vec<String> {
+        let (client, expanded) = spawn_graph_stub(branching_graph()).await;
+        let config = SearchConfig {
+            graph_depth: 3,
+            edge_types: None,
+            beam_width,
+            ..Default::default()
+        };
+        let seed = SearchResult::from_vector("a", "memory a", 0.9, 1.0);
+        graph_expansion_phase(client, &[seed], &[], &config).await.unwrap();
+        let nodes = expanded.lock().unwrap().clone();
+        nodes
+    }
+
+    #[tokio::test]
+    async fn test_malformed_created_at_still_returns() {
+        let client = spawn_reply_stub(serde_json::json!({"memories": [
+            {"memory_id": "bad", "content": "bad date", "score": 0.9, "created_at": "not-a-date"},
+            {"memory_id": "good", "content": "fresh", "score": 0.9, "created_at": Utc::now().to_rfc3339()},
+        ]}))
+        .await
+        .client();
+        let config = SearchConfig { min_vector_score: 0.0, ..Default::default() };
+
+        let hits = vector_search_phase(client, &[1.0, 0.0], None, Some(Utc::now() - chrono::Duration::days(1)), &config).await.unwrap();
+        let ranked = rank_and_filter(hits, 0.0);
+
+        let order: Vec<&str> = ranked.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(order, ["good", "bad"]);
+        assert_eq!(ranked[1].temporal_score, 0.0);
+        assert!(ranked.iter().all(|r| r.combined_score.is_finite()));
+    }
+
+    #[tokio::test]
+    async fn test_vector_phase_enforces_user_and_score_floor() {
+        let now = Utc::now().to_rfc3339();
+        let client = spawn_reply_stub(serde_json::json!({"memories": [
+            {"memory_id": "mine", "content": "strong match", "score": 0.9, "user_id": "alice", "created_at": now},
+            {"memory_id": "weak", "content": "weak match", "score": 0.3, "user_id": "alice", "created_at": now},
+            {"memory_id": "theirs", "content": "strong match", "score": 0.95, "user_id": "bob", "created_at": now},
+        ], "chunks": [
+            {"chunk_id": "c1", "parent_memory_id": "bob_chunked", "position": 0, "score": 0.99},
+        ], "chunk_parents": [
+            {"memory_id": "bob_chunked", "content": "long memory", "user_id": "bob", "created_at": now},
+        ]}))
+        .await
+        .client();
+        let config = SearchConfig { min_vector_score: 0.5, ..Default::default() };
+
+        let hits = vector_search_phase(Arc::clone(&client), &[1.0, 0.0], Some("alice"), None, &config).await.unwrap();
+        let ids: Vec<&str> = hits.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, ["mine"]);
+
+        let unscoped = vector_search_phase(client, &[1.0, 0.0], None, None, &config).await.unwrap();
+        assert_eq!(unscoped.len(), 3);
+        assert!(unscoped.iter().all(|r| r.vector_score >= 0.5));
+    }
+
+    #[tokio::test]
+    async fn test_vector_phase_drops_archived_unless_included() {
+        let now = Utc::now().to_rfc3339();
+        let client = spawn_reply_stub(serde_json::json!({"memories": [
+            {"memory_id": "live", "content": "tea", "score": 0.9, "created_at": now},
+            {"memory_id": "shelved", "content": "tea", "score": 0.95, "created_at": now, "archived_at": now},
+        ]}))
+        .await
+        .client();
+        let config = SearchConfig { min_vector_score: 0.0, ..Default::default() };
+
+        let hits = vector_search_phase(Arc::clone(&client), &[1.0, 0.0], None, None, &config).await.unwrap();
+        let ids: Vec<&str> = hits.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, ["live"]);
+
+        let config = SearchConfig { include_archived: true, ..config };
+        let hits = vector_search_phase(client, &[1.0, 0.0], None, None, &config).await.unwrap();
+        assert_eq!(hits.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_vector_phase_uses_real_similarities() {
+        let stub = spawn_stub(|query, body| {
+            Some(match query {
+                "smartVectorSearchWithChunks" => {
+                    let now = Utc::now().to_rfc3339();
+                    serde_json::json!({"memories": [
+                        {"memory_id": "close", "content": "close", "similarity_score": 0.9, "created_at": now},
+                        {"memory_id": "far", "content": "far", "similarity_score": 0.6, "created_at": now},
+                        {"memory_id": "unscored", "content": "unscored", "created_at": now},
+                    ]})
+                }
+                "getMemoryEmbeddingByMemoryId" if body["memory_id"] == "unscored" => {
+                    serde_json::json!({"embedding": {"data": [0.6, 0.8]}})
+                }
+                _ => serde_json::json!({}),
+            })
+        })
+        .await;
+        let client = stub.client();
+        let config = SearchConfig { min_vector_score: 0.0, ..Default::default() };
+
+        let hits = vector_search_phase(client, &[1.0, 0.0], None, None, &config).await.unwrap();
+        let scored: Vec<(&str, f64)> = hits.iter().map(|r| (r.memory_id.as_str(), r.vector_score)).collect();
+        assert_eq!(scored.iter().map(|s| s.0).collect::<Vec<_>>(), ["close", "unscored", "far"]);
+        assert!((scored[1].1 - 0.8).abs() < 1e-6);
+        assert!(hits[0].combined_score > hits[1].combined_score && hits[1].combined_score > hits[2].combined_score);
+    }
+
+    #[tokio::test]
+    async fn test_expansion_concurrency_stays_within_limit() {
+        let (client, peak) = spawn_counting_stub(std::time::Duration::from_millis(40)).await;
+        let config = SearchConfig { max_concurrent_expansions: 3, edge_types: None, ..Default::default() };
+        let seeds: Vec<SearchResult> = (0..12)
+            .map(|i| SearchResult::from_vector(format!("seed{}", i), "", 0.9, 1.0))
+            .collect();
+
+        graph_expansion_phase(client, &seeds, &[], &config).await.unwrap();
+
+        let peak = peak.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(peak <= 3, "{} expansions queried at once", peak);
+        assert!(peak >= 2);
+    }
+
+    #[test]
+    fn test_chunk_hits_roll_up_to_parent_memory() {
+        let response: VectorSearchResponse = serde_json::from_value(serde_json::json!({
+            "memories": [
+                {"memory_id": "m1", "content": "short memory", "score": 0.7},
+                {"memory_id": "m2", "content": "unrelated"}
+            ],
+            "chunks": [
+                {"chunk_id": "m1_chunk_0", "parent_memory_id": "m1", "position": 0, "score": 0.75},
+                {"chunk_id": "m1_chunk_1", "parent_memory_id": "m1", "position": 1, "score": 0.9},
+                {"chunk_id": "m3_chunk_2", "parent_memory_id": "m3", "position": 2, "score": 0.85},
+                {"chunk_id": "m3_chunk_0", "parent_memory_id": "m3", "position": 0, "score": 0.6},
+                {"chunk_id": "gone_chunk_0", "parent_memory_id": "gone", "score": 0.99}
+            ],
+            "chunk_parents": [
+                {"memory_id": "m1", "content": "short memory"},
+                {"memory_id": "m3", "content": "a long chunked memory"}
+            ]
+        }))
+        .unwrap();
+
+        let hits = rollup_vector_hits(response);
+        let summary: Vec<(&str, Option<f64>, Option<&str>)> = hits
+            .iter()
+            .map(|h| (h.memory.memory_id.as_str(), h.vector_score, h.matched_chunk.as_ref().map(|c| c.chunk_id.as_str())))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("m1", Some(0.9), Some("m1_chunk_1")),
+                ("m2", None, None),
+                ("m3", Some(0.85), Some("m3_chunk_2")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedup_by_content_keeps_higher_scored_duplicate() {
+        let mut stale = SearchResult::from_vector("old_id", "User prefers  dark mode.", 0.8, 0.2);
+        stale.combined_score = 0.6;
+        let mut fresh = SearchResult::from_vector("new_id", "user prefers dark mode", 0.8, 1.0);
+        fresh.combined_score = 0.9;
+        let other = SearchResult::from_vector("other", "User lives in Berlin", 0.7, 1.0);
+        let ranked = rank_and_filter(vec![stale, other, fresh], 0.0);
+        assert_eq!(ranked.len(), 3);
+
+        let deduped = dedup_by_content(ranked, &HashMap::new(), 0.95);
+        let ids: Vec<&str> = deduped.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, vec!["new_id", "other"]);
+
+        let embeddings = HashMap::from([
+            ("new_id".to_string(), vec![1.0, 0.0]),
+            ("other".to_string(), vec![0.99, 0.05]),
+        ]);
+        assert_eq!(dedup_by_content(deduped.clone(), &embeddings, 0.95).len(), 1);
+        assert_eq!(dedup_by_content(deduped, &embeddings, 0.9999).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_metadata_filter_drops_results_but_keeps_traversing() {
+        let (client, expanded) = spawn_graph_stub(branching_graph()).await;
+        let config = SearchConfig {
+            graph_depth: 3,
+            edge_types: None,
+            beam_width: 1,
+            metadata_filter: Some(MetadataFilter {
+                memory_type: Some(vec!["preference".to_string()]),
+                min_importance: Some(70),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let seed = SearchResult::from_vector("a", "memory a", 0.9, 1.0);
+
+        let results = graph_expansion_phase(client, &[seed], &[], &config).await.unwrap();
+
+        assert!(results.is_empty());
+        assert_eq!(*expanded.lock().unwrap(), vec!["a", "b", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_expansion_stream_yields_one_batch_per_productive_hit() {
+        let (client, _) = spawn_graph_stub(branching_graph()).await;
+        let config = SearchConfig { graph_depth: 1, edge_types: None, ..Default::default() };
+        let seeds: Vec<SearchResult> = ["a", "c", "leaf"]
+            .into_iter()
+            .map(|id| SearchResult::from_vector(id, &format!("memory {}", id), 0.9, 1.0))
+            .collect();
+
+        let batches: Vec<Vec<SearchResult>> =
+            graph_expansion_stream(Arc::clone(&client), seeds.clone(), Arc::from(Vec::new()), &config)
+                .collect()
+                .await;
+        let mut batch_ids: Vec<Vec<String>> = batches
+            .iter()
+            .map(|batch| {
+                let mut ids: Vec<String> = batch.iter().map(|r| r.memory_id.clone()).collect();
+                ids.sort();
+                ids
+            })
+            .collect();
+        batch_ids.sort();
+        assert_eq!(batch_ids, vec![vec!["b", "c"], vec!["f"]]);
+
+        let mut streamed: Vec<String> = batch_ids.into_iter().flatten().collect();
+        let mut collected: Vec<String> = graph_expansion_phase(client, &seeds, &[], &config)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.memory_id)
+            .collect();
+        streamed.sort();
+        collected.sort();
+        assert_eq!(streamed, collected);
+    }
+
+    #[tokio::test]
+    async fn test_beam_width_one_descends_linearly() {
+        assert_eq!(expanded_nodes(1).await, vec!["a", "b", "d"]);
+        assert_eq!(expanded_nodes(0).await, vec!["a"]);
+        assert_eq!(expanded_nodes(2).await, vec!["a", "b", "d", "e", "c", "f"]);
+    }
+
+    #[test]
+    fn test_branch_below_similarity_floor_is_not_expanded() {
+        let neighbors = vec![
+            ("mem_on_topic".to_string(), 0.9, 0.8),
+            ("mem_drifting".to_string(), 0.95, 0.2),
+            ("mem_borderline".to_string(), 0.5, 0.4),
+        ];
+        let visited = HashSet::new();
+
+        let selected = select_expansion_candidates(neighbors, &visited, 0.4, 3);
+        let ids: Vec<&str> = selected.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["mem_on_topic", "mem_borderline"]);
+    }
+
+    #[test]
+    fn test_expansion_candidates_respect_visited_and_beam() {
+        let neighbors = vec![
+            ("a".to_string(), 0.1, 0.5),
+            ("b".to_string(), 0.9, 0.5),
+            ("c".to_string(), 0.5, 0.5),
+            ("d".to_string(), 0.7, 0.5),
+        ];
+        let visited: HashSet<String> = ["b".to_string()].into_iter().collect();
+
+        let selected = select_expansion_candidates(neighbors, &visited, 0.0, 2);
+        let ids: Vec<&str> = selected.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["d", "c"]);
+    }
+
+    #[test]
+    fn test_expansion_beam_breaks_score_ties_on_memory_id() {
+        let neighbors = vec![
+            ("z".to_string(), 0.6, 0.5),
+            ("x".to_string(), f64::NAN, 0.5),
+            ("m".to_string(), 0.6, 0.5),
+            ("a".to_string(), 0.6, 0.5),
+        ];
+
+        let selected = select_expansion_candidates(neighbors, &HashSet::new(), 0.0, 2);
+        let ids: Vec<&str> = selected.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "m"]);
+    }
+
+    #[test]
+    fn test_collect_neighbors_honors_edge_types() {
+        let response: GraphConnectionsResponse = serde_json::from_value(serde_json::json!({
+            "implies_out": [{"memory_id": "implied", "content": "x"}],
+            "because_out": [{"memory_id": "cause", "content": "x"}],
+            "because_in": [{"memory_id": "effect", "content": "x"}],
+            "contradicts_out": [{"memory_id": "conflict", "content": "x"}],
+            "relation_out": [{"memory_id": "related", "content": "x"}],
+        })).unwrap();
+        let visited = HashSet::new();
+
+        let similarities = HashMap::new();
+
+        let allowed = vec!["because".to_string()];
+        let (results, neighbors) = collect_neighbors(&response, Some(&allowed), &similarities, 1.0, &visited, 30.0, &HashMap::new());
+        let mut ids: Vec<&str> = neighbors.iter().map(|(id, _, _)| id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["cause", "effect"]);
+        assert_eq!(results.len(), 2);
+
+        let reverse_only = vec!["BECAUSE_IN".to_string()];
+        let (_, neighbors) = collect_neighbors(&response, Some(&reverse_only), &similarities, 1.0, &visited, 30.0, &HashMap::new());
+        assert_eq!(neighbors.len(), 1);
+        assert_eq!(neighbors[0].0, "effect");
+
+        let (_, neighbors) = collect_neighbors(&response, None, &similarities, 1.0, &visited, 30.0, &HashMap::new());
+        assert_eq!(neighbors.len(), 5);
+    }
+
+    #[test]
+    fn test_graph_results_use_semantic_similarity() {
+        let response: GraphConnectionsResponse = serde_json::from_value(serde_json::json!({
+            "implies_out": [
+                {"memory_id": "relevant", "content": "x"},
+                {"memory_id": "irrelevant", "content": "x"},
+                {"memory_id": "unknown", "content": "x"},
+            ],
+        })).unwrap();
+        let similarities = HashMap::from([
+            ("relevant".to_string(), 0.95),
+            ("irrelevant".to_string(), 0.1),
+        ]);
+
+        let (results, neighbors) = collect_neighbors(&response, None, &similarities, 1.0, &HashSet::new(), 30.0, &HashMap::new());
+        let by_id = |id: &str| results.iter().find(|r| r.memory_id == id).unwrap();
+
+        assert!((by_id("relevant").vector_score - 0.95).abs() < 1e-9);
+        assert!(by_id("relevant").combined_score > by_id("irrelevant").combined_score);
+        assert!(by_id("relevant").metadata.is_none());
+
+        let fallback = by_id("unknown");
+        assert!((fallback.vector_score - 0.5).abs() < 1e-9);
+        assert_eq!(fallback.metadata.as_ref().unwrap()["semantic_fallback"], serde_json::json!(true));
+
+        let irrelevant = neighbors.iter().find(|(id, _, _)| id == "irrelevant").unwrap();
+        assert!((irrelevant.2 - 0.1).abs() < 1e-9);
+    }
+}
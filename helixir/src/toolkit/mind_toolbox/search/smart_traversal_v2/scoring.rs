@@ -1,7 +1,14 @@
 
-
 use chrono::{DateTime, Utc};
 
+use super::models::ScoreWeights;
+
+
+/// Clamp a score to `[0, 1]`, mapping NaN and infinities to 0 so one bad record can't poison a ranking.
+pub fn sanitize_score(score: f64) -> f64 {
+    if score.is_finite() { score.clamp(0.0, 1.0) } else { 0.0 }
+}
+
 
 pub fn cosine_similarity(vec1: &[f32], vec2: &[f32]) -> f64 {
     if vec1.is_empty() || vec2.is_empty() || vec1.len() != vec2.len() {
@@ -18,11 +25,13 @@ pub fn cosine_similarity(vec1: &[f32], vec2: &[f32]) -> f64 {
 
     let similarity = f64::from(dot_product / (mag1 * mag2));
     
-    ((similarity + 1.0) / 2.0).clamp(0.0, 1.0)
+    sanitize_score((similarity + 1.0) / 2.0)
 }
 
 
-pub fn calculate_temporal_freshness(created_at: &str, decay_days: f64) -> f64 {
+/// Exponential decay: a memory `half_life_days` old scores 0.5, twice that 0.25.
+/// Unparseable timestamps score 0.
+pub fn calculate_temporal_freshness(created_at: &str, half_life_days: f64) -> f64 {
     let created = match DateTime::parse_from_rfc3339(created_at) {
         Ok(dt) => dt.with_timezone(&Utc),
         Err(_) => {
@@ -30,7 +39,7 @@ pub fn calculate_temporal_freshness(created_at: &str, decay_days: f64) -> f64 {
             if let Ok(dt) = created_at.replace('Z', "+00:00").parse::<DateTime<Utc>>() {
                 dt
             } else {
-                return 0.5; 
+                return 0.0;
             }
         }
     };
@@ -40,13 +49,13 @@ pub fn calculate_temporal_freshness(created_at: &str, decay_days: f64) -> f64 {
     let days_old = duration.num_seconds() as f64 / 86400.0;
 
     
-    let freshness = (-days_old / decay_days).exp();
-    freshness.clamp(0.0, 1.0)
+    let freshness = (-std::f64::consts::LN_2 * days_old / half_life_days.max(f64::EPSILON)).exp();
+    sanitize_score(freshness)
 }
 
 
 pub fn calculate_vector_combined_score(vector_score: f64, temporal_score: f64) -> f64 {
-    (vector_score * 0.7 + temporal_score * 0.3).clamp(0.0, 1.0)
+    sanitize_score(ScoreWeights::VECTOR_DEFAULT.combine(vector_score, 0.0, temporal_score))
 }
 
 
@@ -55,12 +64,12 @@ pub fn calculate_graph_combined_score(
     graph_score: f64,
     temporal_score: f64,
 ) -> f64 {
-    (semantic_sim * 0.3 + graph_score * 0.5 + temporal_score * 0.2).clamp(0.0, 1.0)
+    sanitize_score(ScoreWeights::GRAPH_DEFAULT.combine(semantic_sim, graph_score, temporal_score))
 }
 
 
 pub fn calculate_graph_score(edge_weight: f64, parent_score: f64) -> f64 {
-    (edge_weight * parent_score).clamp(0.0, 1.0)
+    sanitize_score(edge_weight * parent_score)
 }
 
 #[cfg(test)]
@@ -104,7 +113,25 @@ mod tests {
         let old = (Utc::now() - chrono::Duration::days(90)).to_rfc3339();
         let freshness = calculate_temporal_freshness(&old, 30.0);
         
-        assert!(freshness < 0.1);
+        assert!((freshness - 0.125).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_temporal_freshness_at_half_life() {
+        let at_half_life = (Utc::now() - chrono::Duration::days(14)).to_rfc3339();
+        assert!((calculate_temporal_freshness(&at_half_life, 14.0) - 0.5).abs() < 0.01);
+
+        let two_half_lives = (Utc::now() - chrono::Duration::days(28)).to_rfc3339();
+        assert!((calculate_temporal_freshness(&two_half_lives, 14.0) - 0.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_non_finite_inputs_score_zero() {
+        assert_eq!(calculate_temporal_freshness("not-a-date", 30.0), 0.0);
+        assert_eq!(calculate_temporal_freshness(&(Utc::now() + chrono::Duration::days(3)).to_rfc3339(), 30.0), 1.0);
+        assert_eq!(cosine_similarity(&[f32::MAX, f32::MAX], &[f32::MAX, f32::MAX]), 0.0);
+        assert_eq!(calculate_graph_score(f64::NAN, 0.9), 0.0);
+        assert_eq!(calculate_vector_combined_score(f64::INFINITY, 1.0), 0.0);
     }
 
     #[test]
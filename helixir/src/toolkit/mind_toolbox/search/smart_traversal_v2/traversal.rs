@@ -1,19 +1,28 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use lru::LruCache;
 use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
 use sha2::{Sha256, Digest};
 use tracing::{debug, info, warn};
 use super::models::{SearchResult, SearchConfig, TraversalStats};
-use super::phases::{vector_search_phase, graph_expansion_phase, rank_and_filter, TraversalError};
+use super::phases::{
+    vector_search_phase, graph_expansion_phase, graph_expansion_stream, rank_and_filter, content_dedup_phase,
+    TraversalError,
+};
 use crate::db::HelixClient;
+use crate::toolkit::mind_toolbox::search::cache::GenerationCounter;
 
 pub struct SmartTraversalV2 {
     client: Arc<HelixClient>,
     cache: RwLock<LruCache<String, Vec<SearchResult>>>,
     cache_ttl: Duration,
     stats: RwLock<TraversalStats>,
+    normalize_queries: bool,
+    generations: GenerationCounter,
 }
 
 impl SmartTraversalV2 {
@@ -25,9 +34,23 @@ impl SmartTraversalV2 {
             )),
             cache_ttl: Duration::from_secs(cache_ttl_secs),
             stats: RwLock::new(TraversalStats::default()),
+            normalize_queries: false,
+            generations: Default::default(),
         }
     }
     
+    /// Normalize query embeddings before search, for embedders that don't already do it
+    pub fn with_query_normalization(mut self, normalize: bool) -> Self {
+        self.normalize_queries = normalize;
+        self
+    }
+    
+    /// Makes every cached result for `user_id`, and every unscoped one, unreachable; call
+    /// after writing their memories.
+    pub fn bump_generation(&self, user_id: &str) {
+        self.generations.bump(user_id);
+    }
+
     pub async fn search(
         &self,
         query: &str,
@@ -36,21 +59,56 @@ impl SmartTraversalV2 {
         config: SearchConfig,
         temporal_cutoff: Option<DateTime<Utc>>,
     ) -> Result<Vec<SearchResult>, TraversalError> {
-        let cache_key = Self::make_cache_key(query_embedding, user_id, &config);
+        self.search_with_stats(query, query_embedding, user_id, config, temporal_cutoff)
+            .await
+            .map(|(results, _)| results)
+    }
+    
+    
+    pub async fn search_with_stats(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        user_id: Option<&str>,
+        config: SearchConfig,
+        temporal_cutoff: Option<DateTime<Utc>>,
+    ) -> Result<(Vec<SearchResult>, TraversalStats), TraversalError> {
+        let start_time = Instant::now();
+        let normalized;
+        let query_embedding = if self.normalize_queries {
+            let mut vector = query_embedding.to_vec();
+            crate::llm::embeddings::l2_normalize(&mut vector);
+            normalized = vector;
+            &normalized[..]
+        } else {
+            query_embedding
+        };
+        let cache_key = self.make_cache_key(query_embedding, user_id, &config);
         
         
         {
             let mut cache = self.cache.write().await;
             if let Some(cached_results) = cache.get(&cache_key) {
+                let cached_results = cached_results.clone();
+                let cache_size = cache.len();
+                drop(cache);
                 let mut stats = self.stats.write().await;
                 stats.cache_hits += 1;
                 stats.cache_hit_rate = stats.cache_hits as f64 / (stats.cache_hits + stats.cache_misses) as f64;
+                let call_stats = TraversalStats {
+                    cache_size,
+                    cache_hits: stats.cache_hits,
+                    cache_misses: stats.cache_misses,
+                    cache_hit_rate: stats.cache_hit_rate,
+                    total_duration_ms: elapsed_ms(start_time),
+                    served_from_cache: true,
+                    ..Default::default()
+                };
                 debug!("Cache hit for query: {}", query);
-                return Ok(cached_results.clone());
+                return Ok((cached_results, call_stats));
             }
         }
         
-        let start_time = Instant::now();
         info!("Starting smart traversal search for query: {}", query);
         
         
@@ -66,32 +124,26 @@ impl SmartTraversalV2 {
             Arc::clone(&self.client),
             query_embedding,
             user_id,
-            config.vector_top_k,
-            config.min_vector_score,
             temporal_cutoff,
+            &config,
         ).await?;
-        let phase1_duration = phase1_start.elapsed();
+        let phase1_ms = elapsed_ms(phase1_start);
         
         if vector_hits.is_empty() {
             info!("No vector hits found, returning empty results");
-            let total_duration = start_time.elapsed();
-            let mut stats = self.stats.write().await;
-            stats.phase1_duration_ms = phase1_duration.as_millis() as f64;
-            stats.total_duration_ms = total_duration.as_millis() as f64;
-            return Ok(vec![]);
+            let stats = self.record_timings(phase1_ms, 0.0, 0.0, elapsed_ms(start_time)).await;
+            return Ok((vec![], stats));
         }
         
         
         let phase2_start = Instant::now();
-        let edge_types = config.edge_types.as_deref().unwrap_or(&[]);
         let graph_results = graph_expansion_phase(
             Arc::clone(&self.client),
             &vector_hits,
             query_embedding,
-            config.graph_depth,
-            edge_types,
+            &config,
         ).await?;
-        let phase2_duration = phase2_start.elapsed();
+        let phase2_ms = elapsed_ms(phase2_start);
         
         
         let mut all_results = vector_hits;
@@ -99,20 +151,11 @@ impl SmartTraversalV2 {
         
         
         let phase3_start = Instant::now();
-        let final_results = rank_and_filter(all_results, config.min_combined_score);
-        let phase3_duration = phase3_start.elapsed();
-        
-        let total_duration = start_time.elapsed();
-        
-        
-        {
-            let mut stats = self.stats.write().await;
-            stats.phase1_duration_ms = phase1_duration.as_millis() as f64;
-            stats.phase2_duration_ms = phase2_duration.as_millis() as f64;
-            stats.phase3_duration_ms = phase3_duration.as_millis() as f64;
-            stats.total_duration_ms = total_duration.as_millis() as f64;
-            stats.cache_size = self.cache.read().await.len();
+        let mut final_results = rank_and_filter(all_results, config.min_combined_score);
+        if let Some(threshold) = config.dedup_content_threshold {
+            final_results = content_dedup_phase(&self.client, final_results, threshold).await;
         }
+        let phase3_ms = elapsed_ms(phase3_start);
         
         
         {
@@ -120,19 +163,68 @@ impl SmartTraversalV2 {
             cache.put(cache_key, final_results.clone());
         }
         
+        let total_ms = elapsed_ms(start_time);
+        let stats = self.record_timings(phase1_ms, phase2_ms, phase3_ms, total_ms).await;
+        
         info!("Smart traversal search completed in {:.2}ms with {} results", 
-              total_duration.as_millis(), final_results.len());
+              total_ms, final_results.len());
         
-        Ok(final_results)
+        Ok((final_results, stats))
     }
     
-    pub fn get_stats(&self) -> TraversalStats {
-        
-        
-        TraversalStats::default()
+    /// Vector hits as the first batch, then one batch per finished expansion task.
+    /// Bypasses the result cache and skips final ranking and content dedup.
+    pub fn search_stream(
+        &self,
+        query_embedding: &[f32],
+        user_id: Option<&str>,
+        config: SearchConfig,
+        temporal_cutoff: Option<DateTime<Utc>>,
+    ) -> impl Stream<Item = Result<Vec<SearchResult>, TraversalError>> + Send + use<> {
+        let client = Arc::clone(&self.client);
+        let mut query_embedding = query_embedding.to_vec();
+        if self.normalize_queries {
+            crate::llm::embeddings::l2_normalize(&mut query_embedding);
+        }
+        let query_embedding: Arc<[f32]> = query_embedding.into();
+        let user_id = user_id.map(str::to_string);
+
+        futures::stream::once(async move {
+            let hits = vector_search_phase(
+                Arc::clone(&client),
+                &query_embedding,
+                user_id.as_deref(),
+                temporal_cutoff,
+                &config,
+            ).await;
+            (hits, client, query_embedding, config)
+        })
+        .flat_map(|(hits, client, query_embedding, config)| match hits {
+            Ok(hits) => {
+                let expansions = graph_expansion_stream(client, hits.clone(), query_embedding, &config).map(Ok);
+                futures::stream::once(futures::future::ready(Ok(hits))).chain(expansions).left_stream()
+            }
+            Err(e) => futures::stream::once(futures::future::ready(Err(e))).right_stream(),
+        })
+    }
+    
+    async fn record_timings(&self, phase1_ms: f64, phase2_ms: f64, phase3_ms: f64, total_ms: f64) -> TraversalStats {
+        let cache_size = self.cache.read().await.len();
+        let mut stats = self.stats.write().await;
+        stats.phase1_duration_ms = phase1_ms;
+        stats.phase2_duration_ms = phase2_ms;
+        stats.phase3_duration_ms = phase3_ms;
+        stats.total_duration_ms = total_ms;
+        stats.cache_size = cache_size;
+        stats.clone()
+    }
+    
+    pub async fn get_stats(&self) -> TraversalStats {
+        self.stats.read().await.clone()
     }
     
     fn make_cache_key(
+        &self,
         query_embedding: &[f32],
         user_id: Option<&str>,
         config: &SearchConfig,
@@ -148,12 +240,31 @@ impl SmartTraversalV2 {
         if let Some(uid) = user_id {
             hasher.update(uid.as_bytes());
         }
+        hasher.update(self.generations.get(user_id.unwrap_or("")).to_le_bytes());
         
         
         hasher.update(config.vector_top_k.to_le_bytes());
         hasher.update(config.graph_depth.to_le_bytes());
         hasher.update(config.min_vector_score.to_le_bytes());
         hasher.update(config.min_combined_score.to_le_bytes());
+        hasher.update(config.expansion_min_similarity.to_le_bytes());
+        hasher.update(config.temporal_half_life_days.to_le_bytes());
+        hasher.update(config.beam_width.to_le_bytes());
+        hasher.update(config.importance_boost.to_le_bytes());
+        hasher.update([config.include_archived as u8]);
+        hasher.update(serde_json::to_vec(&config.combine_weights).unwrap_or_default());
+        if let Some(threshold) = config.dedup_content_threshold {
+            hasher.update(threshold.to_le_bytes());
+        }
+        let mut reverse_overrides: Vec<_> = config.reverse_edge_multipliers.iter().collect();
+        reverse_overrides.sort_by(|a, b| a.0.cmp(b.0));
+        for (edge_type, multiplier) in reverse_overrides {
+            hasher.update(edge_type.to_uppercase().as_bytes());
+            hasher.update(multiplier.to_le_bytes());
+        }
+        if let Some(filter) = &config.metadata_filter {
+            hasher.update(serde_json::to_vec(filter).unwrap_or_default());
+        }
         
         if let Some(edge_types) = &config.edge_types {
             for edge_type in edge_types {
@@ -163,4 +274,59 @@ impl SmartTraversalV2 {
         
         format!("{:x}", hasher.finalize())
     }
-}
\ No newline at end of file
+}
+
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search_with_stats_tracks_cache_usage() {
+        let traversal = SmartTraversalV2::new(Arc::new(HelixClient::new("127.0.0.1", 1).unwrap()), 10, 60);
+        let embedding = [0.1_f32, 0.2, 0.3];
+        let cached = SearchResult::from_vector("mem_1", "cached memory", 0.9, 1.0);
+        let key = traversal.make_cache_key(&embedding, Some("alice"), &SearchConfig::default());
+        traversal.cache.write().await.put(key, vec![cached]);
+
+        let (results, stats) = traversal
+            .search_with_stats("q", &embedding, Some("alice"), SearchConfig::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(stats.cache_hits, 1);
+        assert_eq!(stats.cache_size, 1);
+
+        // A different user misses the cache and fails against the offline client.
+        assert!(traversal
+            .search_with_stats("q", &embedding, Some("bob"), SearchConfig::default(), None)
+            .await
+            .is_err());
+
+        let totals = traversal.get_stats().await;
+        assert_eq!((totals.cache_hits, totals.cache_misses), (1, 1));
+        assert!((totals.cache_hit_rate - 0.5).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_bump_generation_strands_cached_traversals() {
+        let traversal = SmartTraversalV2::new(Arc::new(HelixClient::new("127.0.0.1", 1).unwrap()), 10, 60);
+        let embedding = [0.1_f32, 0.2, 0.3];
+        let config = SearchConfig::default();
+        for user_id in [Some("alice"), Some("bob"), None] {
+            let key = traversal.make_cache_key(&embedding, user_id, &config);
+            traversal.cache.write().await.put(key, vec![SearchResult::from_vector("mem_1", "cached", 0.9, 1.0)]);
+        }
+
+        traversal.bump_generation("alice");
+
+        let search = |user_id| traversal.search("q", &embedding, user_id, config.clone(), None);
+        assert!(search(Some("alice")).await.is_err());
+        assert!(search(None).await.is_err());
+        assert_eq!(search(Some("bob")).await.unwrap().len(), 1);
+    }
+}
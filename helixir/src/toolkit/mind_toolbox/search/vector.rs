@@ -2,12 +2,16 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use tracing::{debug, info, warn};
+use tracing::{debug, info};
 
-use super::models::{SearchResult, SearchMethod};
-use super::cache::SearchCache;
+use super::models::{SearchResult, SearchMethod, MetadataFilter};
+use super::cache::{SearchCache, CacheStats, ExpirationPolicy, GenerationCounter};
+use super::smart_traversal_v2::{cosine_similarity, fetch_memory_embeddings};
 use crate::db::HelixClient;
 
+pub const DEFAULT_MMR_LAMBDA: f64 = 0.7;
+const MMR_OVERFETCH_FACTOR: usize = 3;
+
 #[derive(Error, Debug)]
 pub enum VectorSearchError {
     #[error("Database error: {0}")]
@@ -35,6 +39,12 @@ struct VectorSearchMemory {
     updated_at: String,
     valid_from: String,
     valid_until: Option<String>,
+    #[serde(default)]
+    importance: Option<i64>,
+    #[serde(default)]
+    context_tags: String,
+    #[serde(default)]
+    archived_at: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -45,13 +55,15 @@ struct VectorSearchOutput {
 pub struct VectorSearch {
     client: Arc<HelixClient>,
     cache: SearchCache<Vec<SearchResult>>,
+    generations: GenerationCounter,
 }
 
 impl VectorSearch {
-    pub fn new(client: Arc<HelixClient>, cache_size: usize, cache_ttl: u64) -> Self {
+    pub fn new(client: Arc<HelixClient>, cache_size: usize, cache_ttl: u64, cache_expiration: ExpirationPolicy) -> Self {
         Self {
             client,
-            cache: SearchCache::new(cache_size, cache_ttl),
+            cache: SearchCache::new(cache_size, cache_ttl, cache_expiration),
+            generations: Default::default(),
         }
     }
 
@@ -59,10 +71,21 @@ impl VectorSearch {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
         
-        let key_data = format!("{}|{}|{}|{}", query, user_id.unwrap_or(""), limit, min_score);
+        let user = user_id.unwrap_or("");
+        let generation = self.generations.get(user);
+        let key_data = format!("{}|{}|{}|{}|{}", query, user, generation, limit, min_score);
         let mut hasher = DefaultHasher::new();
         key_data.hash(&mut hasher);
-        format!("{:x}", hasher.finish())[..16].to_string()
+        format!("{:016x}", hasher.finish())
+    }
+
+    /// Makes every cached result for `user_id`, and every unscoped one, unreachable; call after
+    /// writing their memories.
+    ///
+    /// Nothing is evicted: stale entries keep their cache slots until LRU pressure or the TTL
+    /// removes them, trading some memory for an O(1) invalidation.
+    pub fn bump_generation(&self, user_id: &str) {
+        self.generations.bump(user_id);
     }
 
     pub async fn search(
@@ -95,8 +118,23 @@ impl VectorSearch {
 
         let mut results = Vec::new();
         for item in result.memories {
+            // vectorSearch ranks the shared index without looking at user_id, so ownership is checked here.
+            if user_id.is_some_and(|u| !item.user_id.is_empty() && item.user_id != u) {
+                continue;
+            }
             let mut metadata = HashMap::new();
             metadata.insert("embedding_distance".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(0.0).unwrap()));
+            metadata.insert("memory_type".to_string(), serde_json::json!(item.memory_type));
+            if let Some(importance) = item.importance {
+                metadata.insert("importance".to_string(), serde_json::json!(importance));
+            }
+            metadata.insert("context_tags".to_string(), serde_json::json!(item.context_tags));
+            if !item.user_id.is_empty() {
+                metadata.insert("user_id".to_string(), serde_json::json!(item.user_id));
+            }
+            if !item.archived_at.is_empty() {
+                metadata.insert("archived_at".to_string(), serde_json::json!(item.archived_at));
+            }
             
             let search_result = SearchResult {
                 memory_id: item.memory_id.clone(),
@@ -117,4 +155,219 @@ impl VectorSearch {
         info!("Vector search returned {} results", results.len());
         Ok(results)
     }
-}
\ No newline at end of file
+
+    pub async fn search_filtered(
+        &self,
+        query: &str,
+        user_id: Option<&str>,
+        limit: usize,
+        min_score: f64,
+        filter: &MetadataFilter,
+    ) -> Result<Vec<SearchResult>, VectorSearchError> {
+        let mut results = self.search(query, user_id, limit, min_score, true).await?;
+        if !filter.is_empty() {
+            results.retain(|r| filter.matches_metadata(&r.metadata));
+            debug!("Metadata filter kept {} vector results", results.len());
+        }
+        Ok(results)
+    }
+
+    pub async fn search_diverse(
+        &self,
+        query: &str,
+        user_id: Option<&str>,
+        limit: usize,
+        lambda: f64,
+    ) -> Result<Vec<SearchResult>, VectorSearchError> {
+        let candidates = self
+            .search(query, user_id, limit.saturating_mul(MMR_OVERFETCH_FACTOR), 0.0, true)
+            .await?;
+        if candidates.len() <= 1 {
+            return Ok(candidates.into_iter().take(limit).collect());
+        }
+
+        let ids = candidates.iter().map(|r| r.memory_id.as_str()).collect();
+        let embeddings = fetch_memory_embeddings(&self.client, ids).await;
+        let results = mmr_rerank(candidates, &embeddings, limit, lambda);
+
+        info!("Diverse vector search returned {} results (lambda={})", results.len(), lambda);
+        Ok(results)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn seed_cache(
+        &self,
+        query: &str,
+        user_id: Option<&str>,
+        limit: usize,
+        min_score: f64,
+        results: Vec<SearchResult>,
+    ) {
+        let cache_key = self.make_cache_key(query, user_id, limit, min_score);
+        self.cache.set(&cache_key, results);
+    }
+}
+
+/// Whether a vector hit belongs to an archived memory.
+pub(crate) fn is_archived(result: &SearchResult) -> bool {
+    result.metadata.get("archived_at").and_then(|v| v.as_str()).is_some_and(|at| !at.is_empty())
+}
+
+
+// Greedy Maximal Marginal Relevance. The search score stands in for sim(query, doc);
+// candidates without a stored embedding are never penalised for redundancy.
+pub(crate) fn mmr_rerank(
+    mut candidates: Vec<SearchResult>,
+    embeddings: &HashMap<String, Vec<f32>>,
+    limit: usize,
+    lambda: f64,
+) -> Vec<SearchResult> {
+    let lambda = lambda.clamp(0.0, 1.0);
+    let mut selected: Vec<SearchResult> = Vec::with_capacity(limit.min(candidates.len()));
+
+    while selected.len() < limit && !candidates.is_empty() {
+        let mut best_idx = 0;
+        let mut best_score = f64::NEG_INFINITY;
+
+        for (idx, candidate) in candidates.iter().enumerate() {
+            let redundancy = embeddings
+                .get(&candidate.memory_id)
+                .map(|emb| {
+                    selected
+                        .iter()
+                        .filter_map(|s| embeddings.get(&s.memory_id))
+                        .map(|other| cosine_similarity(emb, other))
+                        .fold(0.0, f64::max)
+                })
+                .unwrap_or(0.0);
+            let mmr = lambda * candidate.score - (1.0 - lambda) * redundancy;
+            if mmr > best_score {
+                best_score = mmr;
+                best_idx = idx;
+            }
+        }
+
+        let mut chosen = candidates.remove(best_idx);
+        chosen.metadata.insert("mmr_score".to_string(), serde_json::json!(best_score));
+        selected.push(chosen);
+    }
+
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(memory_id: &str, score: f64) -> SearchResult {
+        SearchResult {
+            memory_id: memory_id.to_string(),
+            content: format!("content of {}", memory_id),
+            score,
+            method: SearchMethod::Vector,
+            metadata: HashMap::new(),
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_mmr_skips_near_duplicates() {
+        let candidates = vec![
+            candidate("tea", 0.95),
+            candidate("tea_again", 0.94),
+            candidate("coffee", 0.80),
+        ];
+        let embeddings: HashMap<String, Vec<f32>> = [
+            ("tea".to_string(), vec![1.0, 0.0, 0.0]),
+            ("tea_again".to_string(), vec![0.99, 0.01, 0.0]),
+            ("coffee".to_string(), vec![0.0, 1.0, 0.0]),
+        ]
+        .into_iter()
+        .collect();
+
+        let top = mmr_rerank(candidates, &embeddings, 2, DEFAULT_MMR_LAMBDA);
+
+        let ids: Vec<&str> = top.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, vec!["tea", "coffee"]);
+        assert!(top[0].metadata.contains_key("mmr_score"));
+    }
+
+    #[tokio::test]
+    async fn test_search_filtered_can_eliminate_all_candidates() {
+        let search = VectorSearch::new(Arc::new(HelixClient::new("127.0.0.1", 1).unwrap()), 10, 60, ExpirationPolicy::Absolute);
+        let mut preference = candidate("likes_tea", 0.9);
+        preference.metadata.insert("memory_type".to_string(), serde_json::json!("preference"));
+        preference.metadata.insert("importance".to_string(), serde_json::json!(40));
+        let mut fact = candidate("lives_in_oslo", 0.8);
+        fact.metadata.insert("memory_type".to_string(), serde_json::json!("fact"));
+        fact.metadata.insert("importance".to_string(), serde_json::json!(90));
+        search.seed_cache("tea", Some("alice"), 10, 0.0, vec![preference, fact]);
+
+        let important_preferences = MetadataFilter {
+            memory_type: Some(vec!["preference".to_string()]),
+            min_importance: Some(70),
+            ..Default::default()
+        };
+        let results = search.search_filtered("tea", Some("alice"), 10, 0.0, &important_preferences).await.unwrap();
+        assert!(results.is_empty());
+
+        let preferences = MetadataFilter { memory_type: Some(vec!["preference".to_string()]), ..Default::default() };
+        let results = search.search_filtered("tea", Some("alice"), 10, 0.0, &preferences).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory_id, "likes_tea");
+    }
+
+    #[tokio::test]
+    async fn test_bump_generation_misses_previously_cached_query() {
+        let search = VectorSearch::new(Arc::new(HelixClient::new("127.0.0.1", 1).unwrap()), 10, 60, ExpirationPolicy::Absolute);
+        search.seed_cache("tea", Some("alice"), 10, 0.0, vec![candidate("likes_tea", 0.9)]);
+        search.seed_cache("tea", Some("bob"), 10, 0.0, vec![candidate("bob_tea", 0.9)]);
+        assert_eq!(search.search("tea", Some("alice"), 10, 0.0, true).await.unwrap().len(), 1);
+
+        search.bump_generation("alice");
+        assert!(search.search("tea", Some("alice"), 10, 0.0, true).await.is_err());
+        assert_eq!(search.search("tea", Some("bob"), 10, 0.0, true).await.unwrap().len(), 1);
+
+        let stats = search.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_search_drops_other_users_memories() {
+        let memory = |memory_id: &str, user_id: &str| serde_json::json!({
+            "memory_id": memory_id, "content": "tea", "similarity_score": 0.9, "memory_type": "fact",
+            "user_id": user_id, "created_at": "", "updated_at": "", "valid_from": "",
+        });
+        let stub = crate::test_support::spawn_reply_stub(serde_json::json!({
+            "memories": [memory("mine", "alice"), memory("theirs", "bob")],
+        }))
+        .await;
+        let search = VectorSearch::new(stub.client(), 10, 60, ExpirationPolicy::Absolute);
+
+        let results = search.search("tea", Some("alice"), 10, 0.0, false).await.unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, ["mine"]);
+
+        assert_eq!(search.search("tea", None, 10, 0.0, false).await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_mmr_lambda_one_keeps_relevance_order() {
+        let candidates = vec![candidate("a", 0.9), candidate("b", 0.8), candidate("c", 0.7)];
+        let embeddings: HashMap<String, Vec<f32>> =
+            ["a", "b", "c"].iter().map(|id| (id.to_string(), vec![1.0, 0.0])).collect();
+
+        let top = mmr_rerank(candidates, &embeddings, 3, 1.0);
+
+        let ids: Vec<&str> = top.iter().map(|r| r.memory_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+    }
+}
@@ -2,5 +2,5 @@ pub mod mind_toolbox;
 pub mod tooling_manager;
 pub mod fast_think;
 
-pub use tooling_manager::{ToolingManager, AddMemoryResult, SearchMemoryResult, ToolingError};
+pub use tooling_manager::{ToolingManager, AddMemoryResult, SearchMemoryResult, ToolingError, DeleteFilter, RepairReport, MemoryStats, MemoryCursor, ConsolidationReport, ConsolidationMerge, MemoryGraph, GraphFormat, AddMemoryPlan, PlannedMemory, PlannedConceptLink, MemoryEvent, MemoryEventOp};
 pub use fast_think::{FastThinkManager, FastThinkLimits, FastThinkError};
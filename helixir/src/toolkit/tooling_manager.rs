@@ -1,6 +1,7 @@
 
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 
@@ -15,12 +16,14 @@ use crate::db::HelixClient;
 use crate::llm::decision::{LLMDecisionEngine, MemoryDecision, MemoryOperation, SimilarMemory};
 use crate::llm::extractor::LlmExtractor;
 use crate::llm::providers::base::LlmProvider;
+use crate::llm::providers::usage::{TokenUsage, UsageTrackingProvider};
 use crate::llm::EmbeddingGenerator;
 use crate::toolkit::mind_toolbox::chunking::{ChunkingManager, ChunkingError, DEFAULT_THRESHOLD};
+use crate::toolkit::mind_toolbox::integrator::similarity::cosine_similarity;
 use crate::toolkit::mind_toolbox::entity::{EntityManager, EntityEdgeType, EntityError};
 use crate::toolkit::mind_toolbox::ontology::{OntologyManager, OntologyError};
-use crate::toolkit::mind_toolbox::reasoning::{ReasoningEngine, ReasoningType, ReasoningError};
-use crate::toolkit::mind_toolbox::search::{SearchEngine, SearchEngineConfig, SearchError};
+use crate::toolkit::mind_toolbox::reasoning::{ReasoningEngine, ReasoningType, ReasoningRelation, ReasoningError, ChainSelection, ContradictionStrategy, StrengthDecay};
+use crate::toolkit::mind_toolbox::search::{SearchEngine, SearchEngineConfig, SearchOptions, days_to_window, SearchError, LexicalStore, QueryProcessor, Reranker, Provenance, edge_weights, cmp_results, cmp_scored};
 
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +39,37 @@ pub struct AddMemoryResult {
 }
 
 
+/// Ontology concept a planned memory would be linked to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedConceptLink {
+    pub concept_id: String,
+    pub name: String,
+    pub confidence: i32,
+}
+
+/// What `add_memory` would do with one extracted memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedMemory {
+    pub memory: crate::llm::extractor::ExtractedMemory,
+    pub decision: MemoryDecision,
+    /// Names of the extracted entities that would be linked
+    pub entity_links: Vec<String>,
+    pub concept_links: Vec<PlannedConceptLink>,
+    #[serde(skip)]
+    vector: Vec<f32>,
+}
+
+/// Result of `plan_add_memory`: the full ingestion plan, nothing written yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddMemoryPlan {
+    pub user_id: String,
+    pub context_tags: String,
+    pub memories: Vec<PlannedMemory>,
+    pub entities: Vec<crate::llm::extractor::ExtractedEntity>,
+    pub relations: Vec<crate::llm::extractor::ExtractedRelation>,
+}
+
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchMemoryResult {
     pub memory_id: String,
@@ -44,6 +78,37 @@ pub struct SearchMemoryResult {
     pub method: String,
     pub metadata: HashMap<String, serde_json::Value>,
     pub created_at: String,
+    /// Set for results that came through `SearchEngine`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+
+/// What the ingestion pipeline did to a memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::IntoStaticStr)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum MemoryEventOp {
+    Added,
+    Updated,
+    Superseded,
+    Contradicted,
+    Archived,
+}
+
+/// One append-only history entry, stored as a `HistoryEvent` node under the memory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryEvent {
+    pub memory_id: String,
+    #[serde(alias = "action")]
+    pub op: MemoryEventOp,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub confidence: u8,
+    pub timestamp: String,
+    #[serde(default)]
+    pub source_message_preview: String,
 }
 
 
@@ -64,6 +129,14 @@ pub struct ToolingReasoningChain {
 }
 
 
+/// Position after the last memory of a `list_memories_page` page; the next page resumes strictly after it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryCursor {
+    pub created_at: String,
+    pub memory_id: String,
+}
+
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChainNode {
     pub memory_id: String,
@@ -73,6 +146,642 @@ pub struct ChainNode {
 }
 
 
+const USER_MEMORY_SCAN_LIMIT: i64 = 10_000;
+/// Sorts after every stored `created_at`, so the first listing page starts at the newest memory.
+const LIST_START: &str = "9999-12-31T23:59:59Z";
+const BATCH_PARALLELISM: usize = 4;
+const SIMILAR_MEMORY_LIMIT: usize = 5;
+const CONTRADICTION_SCAN_LIMIT: usize = 200;
+const DEFAULT_EXPORT_GRAPH_DEPTH: usize = 2;
+const DEFAULT_GRAPH_SEEDS: usize = 10;
+const CONTRADICTION_SIMILARITY_THRESHOLD: f64 = 0.8;
+const CONSOLIDATION_SCAN_LIMIT: usize = 200;
+const DEFAULT_RELATION_STRENGTH: i32 = 80;
+const CONSOLIDATION_SYSTEM_PROMPT: &str = "You merge near-duplicate memories about one user into a single canonical memory. \
+Keep every distinct detail, drop repetition, and do not invent anything. Respond with only the merged memory text.";
+const EVENT_PREVIEW_CHARS: usize = 120;
+const ENTITY_LINK_SALIENCE: i32 = 50;
+
+
+// Newest first, in the stored `created_at` order the listing queries use; memory_id breaks
+// ties so a page resumes exactly after `before` however many memories were added since.
+fn paginate_memories(
+    memories: Vec<UserMemoryNode>,
+    before: Option<&MemoryCursor>,
+    limit: usize,
+) -> (Vec<SearchMemoryResult>, Option<MemoryCursor>) {
+    let after_cursor = |m: &UserMemoryNode| match before {
+        Some(cursor) => {
+            m.created_at < cursor.created_at || (m.created_at == cursor.created_at && m.memory_id > cursor.memory_id)
+        }
+        None => true,
+    };
+    let mut live: Vec<UserMemoryNode> = memories
+        .into_iter()
+        .filter(|m| m.is_deleted == 0 && m.archived_at.is_empty() && after_cursor(m))
+        .collect();
+    live.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.memory_id.cmp(&b.memory_id)));
+    live.dedup_by(|a, b| a.memory_id == b.memory_id);
+
+    let has_more = live.len() > limit;
+    live.truncate(limit);
+    let next = match live.last() {
+        Some(last) if has_more => Some(MemoryCursor { created_at: last.created_at.clone(), memory_id: last.memory_id.clone() }),
+        _ => None,
+    };
+    (live.into_iter().map(|m| m.into_result(1.0, "listing")).collect(), next)
+}
+
+
+fn parse_relation_type(relation_type: &str) -> Result<ReasoningType, ToolingError> {
+    ReasoningType::from_edge_name(relation_type).ok_or_else(|| {
+        ReasoningError::Invalid(format!(
+            "unknown relation type '{}' (expected IMPLIES, BECAUSE, CONTRADICTS or SUPPORTS)",
+            relation_type
+        ))
+        .into()
+    })
+}
+
+fn contradiction_pair_key(a: &str, b: &str) -> (String, String) {
+    if a <= b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) }
+}
+
+// Greedy single-link clustering over pairs at or above `threshold`, most similar first.
+// Two clusters never join while any of their members contradict each other.
+fn consolidation_clusters(
+    vectors: &[(&str, &[f32])],
+    contradicting: &HashSet<(String, String)>,
+    threshold: f64,
+) -> Vec<Vec<usize>> {
+    let mut cluster_of: Vec<usize> = (0..vectors.len()).collect();
+    let mut members: Vec<Vec<usize>> = (0..vectors.len()).map(|i| vec![i]).collect();
+
+    for (i, j, _) in contradiction_candidates(vectors, &HashSet::new(), threshold, usize::MAX) {
+        let (a, b) = (cluster_of[i], cluster_of[j]);
+        if a == b {
+            continue;
+        }
+        let conflict = members[a].iter().any(|&x| {
+            members[b].iter().any(|&y| contradicting.contains(&contradiction_pair_key(vectors[x].0, vectors[y].0)))
+        });
+        if conflict {
+            continue;
+        }
+        let moved = std::mem::take(&mut members[b]);
+        for &m in &moved {
+            cluster_of[m] = a;
+        }
+        members[a].extend(moved);
+    }
+
+    members
+        .into_iter()
+        .filter(|cluster| cluster.len() > 1)
+        .map(|mut cluster| {
+            cluster.sort_unstable();
+            cluster
+        })
+        .collect()
+}
+
+// Pairs above `threshold` that are not already linked, most similar first; each memory
+// joins at most `per_memory` pairs so a dense cluster cannot blow up the LLM budget.
+fn contradiction_candidates(
+    vectors: &[(&str, &[f32])],
+    known: &HashSet<(String, String)>,
+    threshold: f64,
+    per_memory: usize,
+) -> Vec<(usize, usize, f64)> {
+    let mut pairs = Vec::new();
+    for (i, (id_a, vec_a)) in vectors.iter().enumerate() {
+        for (j, (id_b, vec_b)) in vectors.iter().enumerate().skip(i + 1) {
+            let similarity = cosine_similarity(vec_a, vec_b);
+            if similarity >= threshold && !known.contains(&contradiction_pair_key(id_a, id_b)) {
+                pairs.push((i, j, similarity));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| {
+        cmp_scored((a.2, vectors[a.0].0), (b.2, vectors[b.0].0)).then_with(|| vectors[a.1].0.cmp(vectors[b.1].0))
+    });
+
+    let mut joined = vec![0usize; vectors.len()];
+    pairs
+        .into_iter()
+        .filter(|&(i, j, _)| {
+            if joined[i] >= per_memory || joined[j] >= per_memory {
+                return false;
+            }
+            joined[i] += 1;
+            joined[j] += 1;
+            true
+        })
+        .collect()
+}
+
+
+#[derive(Debug, Default)]
+struct PipelineTally {
+    added: Vec<String>,
+    updated: Vec<String>,
+    deleted: Vec<String>,
+    skipped: usize,
+    entities_linked: usize,
+    relations_created: usize,
+    chunks_created: usize,
+}
+
+impl PipelineTally {
+    fn into_result(self, metadata: HashMap<String, serde_json::Value>) -> AddMemoryResult {
+        AddMemoryResult {
+            added: self.added,
+            updated: self.updated,
+            deleted: self.deleted,
+            skipped: self.skipped,
+            entities_extracted: self.entities_linked,
+            reasoning_relations_created: self.relations_created,
+            chunks_created: self.chunks_created,
+            metadata,
+        }
+    }
+}
+
+
+#[derive(Debug, Clone)]
+struct StagedMemory {
+    user_id: String,
+    memory_id: String,
+    content: String,
+    vector: Vec<f32>,
+}
+
+
+fn merge_staged_candidates(
+    similar: &mut Vec<SimilarMemory>,
+    staged: &[StagedMemory],
+    user_id: &str,
+    vector: &[f32],
+) {
+    for staged_memory in staged.iter().filter(|m| m.user_id == user_id) {
+        let score = cosine_similarity(vector, &staged_memory.vector);
+        match similar.iter_mut().find(|m| m.id == staged_memory.memory_id) {
+            Some(existing) => existing.score = existing.score.max(score),
+            None => similar.push(SimilarMemory {
+                id: staged_memory.memory_id.clone(),
+                content: staged_memory.content.clone(),
+                score,
+                created_at: None,
+            }),
+        }
+    }
+    similar.sort_by(cmp_results);
+    similar.truncate(SIMILAR_MEMORY_LIMIT);
+}
+
+
+fn above_similarity_floor(similar: &[SimilarMemory], floor: f64) -> Vec<SimilarMemory> {
+    similar.iter().filter(|m| m.score >= floor).cloned().collect()
+}
+
+
+#[derive(Debug, Clone, Deserialize)]
+struct UserMemoryNode {
+    #[serde(default)]
+    id: String,
+    memory_id: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    memory_type: String,
+    #[serde(default)]
+    certainty: i64,
+    #[serde(default)]
+    importance: i64,
+    #[serde(default)]
+    created_at: String,
+    #[serde(default)]
+    is_deleted: i64,
+    #[serde(default)]
+    archived_at: String,
+}
+
+impl UserMemoryNode {
+    fn into_result(self, score: f64, method: &str) -> SearchMemoryResult {
+        let mut metadata = HashMap::new();
+        metadata.insert("memory_type".to_string(), serde_json::json!(self.memory_type));
+        metadata.insert("certainty".to_string(), serde_json::json!(self.certainty));
+        metadata.insert("importance".to_string(), serde_json::json!(self.importance));
+        SearchMemoryResult {
+            memory_id: self.memory_id,
+            content: self.content,
+            score,
+            method: method.to_string(),
+            metadata,
+            created_at: self.created_at,
+            provenance: None,
+        }
+    }
+}
+
+
+#[derive(Debug, Default, Deserialize)]
+struct LogicalConnections {
+    #[serde(default)]
+    implies_out: Vec<UserMemoryNode>,
+    #[serde(default)]
+    implies_in: Vec<UserMemoryNode>,
+    #[serde(default)]
+    because_out: Vec<UserMemoryNode>,
+    #[serde(default)]
+    because_in: Vec<UserMemoryNode>,
+    #[serde(default)]
+    contradicts_out: Vec<UserMemoryNode>,
+    #[serde(default)]
+    contradicts_in: Vec<UserMemoryNode>,
+    #[serde(default)]
+    relation_out: Vec<UserMemoryNode>,
+    #[serde(default)]
+    relation_in: Vec<UserMemoryNode>,
+}
+
+impl LogicalConnections {
+    fn all(&self) -> impl Iterator<Item = &UserMemoryNode> {
+        self.implies_out.iter()
+            .chain(&self.implies_in)
+            .chain(&self.because_out)
+            .chain(&self.because_in)
+            .chain(&self.contradicts_out)
+            .chain(&self.contradicts_in)
+            .chain(&self.relation_out)
+            .chain(&self.relation_in)
+    }
+
+    fn edge_count(&self) -> usize {
+        self.all().count()
+    }
+
+    fn dangling_ids(&self) -> Vec<String> {
+        self.all()
+            .filter(|n| n.is_deleted != 0 && !n.memory_id.is_empty())
+            .map(|n| n.memory_id.clone())
+            .collect()
+    }
+}
+
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryGraph {
+    pub nodes: Vec<serde_json::Value>,
+    pub edges: Vec<serde_json::Value>,
+    pub edge_type_counts: HashMap<String, usize>,
+    #[serde(skip)]
+    edge_keys: HashSet<(String, String, String)>,
+}
+
+impl MemoryGraph {
+    /// Edges reached from both endpoints during traversal, or stored in both directions,
+    /// are recorded once, in the direction first seen.
+    pub fn add_edge(&mut self, source: &str, target: &str, edge_type: &str, weight: f64) {
+        if !self.edge_keys.insert(canonical_edge_key(source, target, edge_type)) {
+            return;
+        }
+        self.edges.push(serde_json::json!({
+            "source": source,
+            "target": target,
+            "type": edge_type,
+            "weight": weight,
+        }));
+        *self.edge_type_counts.entry(edge_type.to_string()).or_insert(0) += 1;
+    }
+
+    
+    pub fn export(&self, format: GraphFormat) -> String {
+        let (nodes, edges) = self.export_view();
+        match format {
+            GraphFormat::JsonGraph => render_json_graph(&nodes, &edges),
+            GraphFormat::GraphMl => render_graphml(&nodes, &edges),
+        }
+    }
+
+    // Sorted for stable output; edge endpoints beyond the traversal depth get bare nodes.
+    fn export_view(&self) -> (Vec<ExportNode>, Vec<ExportEdge>) {
+        let text = |value: &serde_json::Value, key: &str| value[key].as_str().unwrap_or_default().to_string();
+
+        let mut nodes: std::collections::BTreeMap<String, ExportNode> = self
+            .nodes
+            .iter()
+            .map(|n| {
+                let id = text(n, "id");
+                (id.clone(), ExportNode { id, content: text(n, "content"), node_type: text(n, "type") })
+            })
+            .filter(|(id, _)| !id.is_empty())
+            .collect();
+
+        let mut edges: Vec<ExportEdge> = self
+            .edges
+            .iter()
+            .map(|e| ExportEdge {
+                source: text(e, "source"),
+                target: text(e, "target"),
+                edge_type: text(e, "type"),
+                weight: e["weight"].as_f64().unwrap_or(1.0),
+            })
+            .collect();
+        edges.sort_by(|a, b| (&a.source, &a.target, &a.edge_type).cmp(&(&b.source, &b.target, &b.edge_type)));
+
+        for edge in &edges {
+            for id in [&edge.source, &edge.target] {
+                nodes.entry(id.clone()).or_insert_with(|| ExportNode {
+                    id: id.clone(),
+                    content: String::new(),
+                    node_type: "memory".to_string(),
+                });
+            }
+        }
+
+        (nodes.into_values().collect(), edges)
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphFormat {
+    /// JSON Graph Format (jsongraphformat.info), as read by Cytoscape
+    JsonGraph,
+    /// GraphML XML, as read by Gephi and yEd
+    GraphMl,
+}
+
+
+struct ExportNode {
+    id: String,
+    content: String,
+    node_type: String,
+}
+
+
+struct ExportEdge {
+    source: String,
+    target: String,
+    edge_type: String,
+    weight: f64,
+}
+
+
+fn render_json_graph(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let nodes: serde_json::Map<String, serde_json::Value> = nodes
+        .iter()
+        .map(|n| {
+            (
+                n.id.clone(),
+                serde_json::json!({"label": n.content, "metadata": {"type": n.node_type}}),
+            )
+        })
+        .collect();
+    let edges: Vec<serde_json::Value> = edges
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "source": e.source,
+                "target": e.target,
+                "relation": e.edge_type,
+                "metadata": {"weight": e.weight},
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&serde_json::json!({
+        "graph": {"directed": true, "type": "helixir-memory", "nodes": nodes, "edges": edges}
+    }))
+    .unwrap_or_default()
+}
+
+
+fn render_graphml(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        "  <key id=\"content\" for=\"node\" attr.name=\"content\" attr.type=\"string\"/>\n",
+        "  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n",
+        "  <key id=\"relation\" for=\"edge\" attr.name=\"relation\" attr.type=\"string\"/>\n",
+        "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n",
+        "  <graph id=\"memories\" edgedefault=\"directed\">\n",
+    ));
+    for n in nodes {
+        out.push_str(&format!(
+            "    <node id=\"{}\">\n      <data key=\"content\">{}</data>\n      <data key=\"type\">{}</data>\n    </node>\n",
+            xml_escape(&n.id),
+            xml_escape(&n.content),
+            xml_escape(&n.node_type),
+        ));
+    }
+    for (i, e) in edges.iter().enumerate() {
+        out.push_str(&format!(
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n      <data key=\"relation\">{}</data>\n      <data key=\"weight\">{}</data>\n    </edge>\n",
+            i,
+            xml_escape(&e.source),
+            xml_escape(&e.target),
+            xml_escape(&e.edge_type),
+            e.weight,
+        ));
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab/newline/CR are not allowed in XML 1.0.
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+
+// Direction-free identity of an edge, so A->B and B->A of one type count as one.
+fn canonical_edge_key(a: &str, b: &str, edge_type: &str) -> (String, String, String) {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    (low.to_string(), high.to_string(), edge_type.to_string())
+}
+
+// Edge properties returned next to the neighbour lists by getMemoryLogicalConnections.
+#[derive(Debug, Default, Deserialize)]
+struct GraphEdges {
+    #[serde(default, rename = "implies_out_edges")]
+    implies_out: Vec<GraphEdge>,
+    #[serde(default, rename = "implies_in_edges")]
+    implies_in: Vec<GraphEdge>,
+    #[serde(default, rename = "because_out_edges")]
+    because_out: Vec<GraphEdge>,
+    #[serde(default, rename = "because_in_edges")]
+    because_in: Vec<GraphEdge>,
+    #[serde(default, rename = "relation_out_edges")]
+    relation_out: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphEdge {
+    #[serde(default)]
+    from_node: String,
+    #[serde(default)]
+    to_node: String,
+    #[serde(default)]
+    strength: Option<i64>,
+    #[serde(default)]
+    probability: Option<i64>,
+    #[serde(default)]
+    relation_type: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+}
+
+// Edge to or from `neighbor` (an internal node id), looked up on the far end of the edge.
+fn find_graph_edge<'a>(edges: &'a [GraphEdge], neighbor: &str, incoming: bool) -> Option<&'a GraphEdge> {
+    if neighbor.is_empty() {
+        return None;
+    }
+    edges.iter().find(|e| if incoming { e.from_node == neighbor } else { e.to_node == neighbor })
+}
+
+// Strength/probability are stored as 0-100 and fade with edge age under `decay`; edges
+// without one fall back to the type's traversal weight.
+fn graph_edge_weight(
+    edge: Option<&GraphEdge>,
+    edge_type: &str,
+    decay: StrengthDecay,
+    now: chrono::DateTime<chrono::Utc>,
+) -> f64 {
+    edge.and_then(|e| e.strength.or(e.probability).map(|value| (e, value)))
+        .map_or_else(
+            || edge_weights::get_weight(edge_type),
+            |(e, value)| decay.effective(value.clamp(0, 100) as f64 / 100.0, e.created_at.as_deref(), now),
+        )
+}
+
+
+/// Live-memory counts for one user, as returned by `memory_stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    pub total: usize,
+    pub by_type: HashMap<String, usize>,
+    pub oldest_created_at: Option<String>,
+    pub newest_created_at: Option<String>,
+}
+
+impl MemoryStats {
+    fn from_memories<'a>(memories: impl IntoIterator<Item = &'a UserMemoryNode>) -> Self {
+        use crate::toolkit::mind_toolbox::search::parse_datetime_utc;
+
+        let mut stats = Self::default();
+        let mut dated = Vec::new();
+        for memory in memories.into_iter().filter(|m| m.is_deleted == 0) {
+            stats.total += 1;
+            *stats.by_type.entry(memory.memory_type.clone()).or_insert(0) += 1;
+            if let Some(at) = parse_datetime_utc(&memory.created_at) {
+                dated.push((at, &memory.created_at));
+            }
+        }
+        stats.oldest_created_at = dated.iter().min().map(|(_, at)| at.to_string());
+        stats.newest_created_at = dated.iter().max().map(|(_, at)| at.to_string());
+        stats
+    }
+}
+
+/// A cluster of near-duplicate memories and the canonical memory that replaces it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsolidationMerge {
+    pub source_ids: Vec<String>,
+    pub content: String,
+    pub memory_type: String,
+    /// Highest importance in the cluster.
+    pub importance: i64,
+    /// The merged memory once written; `None` on dry runs.
+    pub memory_id: Option<String>,
+}
+
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsolidationReport {
+    pub memories_scanned: usize,
+    pub merges: Vec<ConsolidationMerge>,
+    pub dry_run: bool,
+}
+
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepairReport {
+    pub dangling_edges: usize,
+    /// Embeddings still attached to this user's deleted memories
+    pub orphaned_embeddings: usize,
+    pub orphaned_entity_links: usize,
+    pub memories_checked: usize,
+    pub dry_run: bool,
+}
+
+
+#[derive(Deserialize)]
+struct EmbeddingNode {
+    id: String,
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct DeleteFilter {
+    pub memory_types: Option<Vec<String>>,
+    pub older_than: Option<chrono::Duration>,
+    pub min_certainty: Option<i64>,
+    pub dry_run: bool,
+}
+
+impl DeleteFilter {
+    
+    pub fn has_criteria(&self) -> bool {
+        self.memory_types.is_some() || self.older_than.is_some() || self.min_certainty.is_some()
+    }
+
+    
+    pub fn matches(
+        &self,
+        memory_type: &str,
+        certainty: i64,
+        created_at: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        if let Some(types) = &self.memory_types {
+            if !types.iter().any(|t| t.eq_ignore_ascii_case(memory_type)) {
+                return false;
+            }
+        }
+
+        if let Some(min) = self.min_certainty {
+            if certainty < min {
+                return false;
+            }
+        }
+
+        if let Some(age) = self.older_than {
+            match crate::toolkit::mind_toolbox::search::parse_datetime_utc(created_at) {
+                Some(created) if created <= now - age => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+
 #[derive(Debug, thiserror::Error)]
 pub enum ToolingError {
     #[error("Embedding failed: {0}")]
@@ -96,10 +805,13 @@ pub enum ToolingError {
 }
 
 
+type IdempotencyLocks = HashMap<(String, String), Arc<tokio::sync::Mutex<()>>>;
+
 pub struct ToolingManager {
     db: Arc<HelixClient>,
     embedder: Arc<EmbeddingGenerator>,
     llm_provider: Arc<dyn LlmProvider>,
+    llm_usage: Arc<UsageTrackingProvider>,
     extractor: LlmExtractor<Arc<dyn LlmProvider>>,
     decision_engine: LLMDecisionEngine,
     chunking_manager: ChunkingManager,
@@ -107,6 +819,10 @@ pub struct ToolingManager {
     ontology_manager: parking_lot::RwLock<OntologyManager>,
     reasoning_engine: ReasoningEngine,
     search_engine: SearchEngine,
+    lexical_index: Arc<LexicalStore>,
+    /// One lock per in-flight `(user_id, idempotency_key)`, so concurrent retries run the
+    /// pipeline once and the rest replay its recorded result.
+    idempotency_locks: parking_lot::Mutex<IdempotencyLocks>,
 }
 
 impl ToolingManager {
@@ -118,6 +834,9 @@ impl ToolingManager {
     ) -> Self {
         info!("ToolingManager initialized with full pipeline");
         
+        let llm_usage = Arc::new(UsageTrackingProvider::new(llm_provider));
+        let llm_provider: Arc<dyn LlmProvider> = llm_usage.clone();
+        
         
         let extractor = LlmExtractor::new(Arc::clone(&llm_provider));
         
@@ -144,16 +863,19 @@ impl ToolingManager {
         );
         
         
+        let lexical_index = Arc::new(LexicalStore::new());
         let search_engine = SearchEngine::new(
             Arc::clone(&db),
             Arc::clone(&embedder),
             SearchEngineConfig::default(),
-        );
+        )
+        .with_lexical_store(Arc::clone(&lexical_index));
         
         Self { 
             db, 
             embedder, 
             llm_provider, 
+            llm_usage,
             extractor,
             decision_engine,
             chunking_manager,
@@ -161,9 +883,53 @@ impl ToolingManager {
             ontology_manager,
             reasoning_engine,
             search_engine,
+            lexical_index,
+            idempotency_locks: Default::default(),
         }
     }
 
+    
+    pub fn llm_usage(&self) -> TokenUsage {
+        self.llm_usage.usage()
+    }
+
+    
+    pub fn ontology_loaded(&self) -> bool {
+        self.ontology_manager.read().is_loaded()
+    }
+
+    
+    pub fn with_strength_decay(mut self, decay: StrengthDecay) -> Self {
+        self.reasoning_engine = self.reasoning_engine.with_strength_decay(decay);
+        self
+    }
+
+    
+    pub fn with_search_config(mut self, config: SearchEngineConfig) -> Self {
+        self.search_engine = SearchEngine::new(Arc::clone(&self.db), Arc::clone(&self.embedder), config)
+            .with_lexical_store(Arc::clone(&self.lexical_index));
+        self
+    }
+
+    /// Rerank search results; apply after `with_search_config`, which rebuilds the engine.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.search_engine = self.search_engine.with_reranker(reranker);
+        self
+    }
+
+    /// Caches extraction results for repeated messages; see `LlmExtractor::with_cache`.
+    pub fn with_extraction_cache(mut self, capacity: usize, ttl_secs: u64) -> Self {
+        self.extractor = self.extractor.with_cache(capacity, ttl_secs);
+        self
+    }
+
+    
+    pub fn with_lexical_index_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.lexical_index = Arc::new(LexicalStore::open(path));
+        self.search_engine = self.search_engine.with_lexical_store(Arc::clone(&self.lexical_index));
+        self
+    }
+
     pub async fn initialize(&self) -> Result<(), ToolingError> {
         info!("Initializing ToolingManager - loading ontology");
         
@@ -187,6 +953,7 @@ impl ToolingManager {
     }
 
     
+    #[allow(clippy::too_many_arguments)]
     pub async fn add_memory(
         &self,
         message: &str,
@@ -194,225 +961,648 @@ impl ToolingManager {
         _agent_id: Option<&str>,
         _metadata: Option<HashMap<String, serde_json::Value>>,
         context_tags: Option<&str>,
+        idempotency_key: Option<&str>,
+        chunk_threshold: Option<usize>,
     ) -> Result<AddMemoryResult, ToolingError> {
         
         let preview: String = message.chars().take(50).collect();
         let tags = context_tags.unwrap_or("");
+        let usage_before = self.llm_usage();
         info!("Adding memory for user={}: {}... [tags={}]", user_id, preview, tags);
 
+        let _claim = match idempotency_key {
+            Some(key) => Some(self.claim_idempotency_key(user_id, key).await),
+            None => None,
+        };
+        if let Some(key) = idempotency_key {
+            if let Some(prior) = self.find_idempotent_result(user_id, key).await? {
+                info!("Idempotency key '{}' already applied for user={}, returning prior result", key, user_id);
+                return Ok(prior);
+            }
+        }
+
         
         debug!("Step 1: LLM extraction");
-        let extraction = self
-            .extractor
-            .extract(message, user_id, true, true)
-            .await
-            .map_err(|e| ToolingError::Extraction(e.to_string()))?;
+        let extraction = self.extract_for_storage(message, user_id).await?;
 
-        info!(
-            "Extracted {} memories, {} entities, {} relations",
-            extraction.memories.len(),
-            extraction.entities.len(),
-            extraction.relations.len()
-        );
+        let mut tally = PipelineTally::default();
+        let mut stored = Vec::new();
 
-        let mut added_ids = Vec::new();
-        let mut updated_ids = Vec::new();
-        let mut skipped = 0usize;
-        let mut entities_linked = 0usize;
-        let mut relations_created = 0usize;
-        let mut chunks_created = 0usize;
+        let texts: Vec<&str> = extraction.memories.iter().map(|memory| memory.text.as_str()).collect();
+        let vectors = self.embedder.generate_batch_each(&texts, true).await;
 
         
-        let memories_to_store = if extraction.memories.is_empty() {
-            debug!("No memories extracted, storing original message");
-            vec![crate::llm::extractor::ExtractedMemory {
-                text: message.to_string(),
-                memory_type: "fact".to_string(),
-                certainty: 50,
-                importance: 50,
-                entities: vec![],
-            }]
-        } else {
-            extraction.memories
-        };
+        let processed: Result<(), ToolingError> = async {
+            for (memory, vector) in extraction.memories.iter().zip(vectors) {
+                debug!("Processing memory: {}...", safe_truncate(&memory.text, 30));
 
-        
-        for memory in &memories_to_store {
-            debug!("Processing memory: {}...", safe_truncate(&memory.text, 30));
+                let vector = vector.map_err(|e| ToolingError::Embedding(e.to_string()))?;
 
-            
-            let vector = self
-                .embedder
-                .generate(&memory.text, true)
-                .await
-                .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+                let similar_memories = self.find_similar_memories(&memory.text, &vector, user_id).await;
 
-            
-            let similar_results = self.search_engine
-                .search(&memory.text, &vector, user_id, 5, "contextual", None)
-                .await
-                .unwrap_or_default();
-
-            let similar_memories: Vec<SimilarMemory> = similar_results
-                .iter()
-                .map(|r| SimilarMemory {
-                    id: r.memory_id.clone(),
-                    content: r.content.clone(),
-                    score: r.score as f64,
-                    created_at: None,
-                })
-                .collect();
+                if let Some(memory_id) = self
+                    .store_with_decision(memory, &vector, &similar_memories, user_id, tags, &extraction.entities, chunk_threshold, &mut tally)
+                    .await?
+                {
+                    stored.push((memory.text.clone(), memory_id));
+                }
+            }
+            Ok(())
+        }
+        .await;
+        if processed.is_err() {
+            // Memories earlier in the batch may already be written.
+            self.search_engine.bump_generation(user_id);
+        }
+        processed?;
 
-            
-            let decision = self.decision_engine
-                .decide(&memory.text, &similar_memories, user_id)
-                .await;
+        self.link_extracted_relations(&stored, &extraction.relations, &mut tally).await;
 
-            debug!(
-                "Decision: {:?} (confidence={}, target={:?})",
-                decision.operation, decision.confidence, decision.target_memory_id
-            );
+        info!(
+            "Memory pipeline complete: {} added, {} updated, {} skipped, {} entities, {} relations",
+            tally.added.len(),
+            tally.updated.len(),
+            tally.skipped,
+            tally.entities_linked,
+            tally.relations_created
+        );
+        let usage = self.llm_usage().since(&usage_before);
+        info!(
+            "add_memory LLM usage: {} calls, {} tokens ({} prompt, {} completion){}",
+            usage.calls,
+            usage.total,
+            usage.prompt_tokens,
+            usage.completion_tokens,
+            if usage.estimated { " [estimated]" } else { "" }
+        );
 
-            
-            let memory_id = match decision.operation {
-                MemoryOperation::Noop => {
-                    debug!("NOOP: skipping duplicate memory");
-                    skipped += 1;
-                    continue;
-                }
-                MemoryOperation::Update => {
-                    
-                    if let (Some(target_id), Some(merged)) = (&decision.target_memory_id, &decision.merged_content) {
-                        debug!("UPDATE: updating {} with merged content", target_id);
-                        self.update_memory_internal(target_id, merged, &vector).await?;
-                        updated_ids.push(target_id.to_string());
-                        target_id.to_string()
-                    } else {
-                        
-                        let (new_id, new_chunks) = self.store_new_memory(&memory, user_id, &vector, tags).await?;
-                        chunks_created += new_chunks;
-                        new_id
-                    }
-                }
-                MemoryOperation::Supersede => {
+        self.search_engine.bump_generation(user_id);
+
+        let result = tally.into_result(self.pipeline_metadata(user_id));
+        if let Some(key) = idempotency_key {
+            self.record_idempotent_result(user_id, key, &result).await;
+        }
+        Ok(result)
+    }
+
+    /// Dry run of `add_memory`: extraction, similarity search and decisions, but no writes.
+    pub async fn plan_add_memory(
+        &self,
+        message: &str,
+        user_id: &str,
+        context_tags: Option<&str>,
+    ) -> Result<AddMemoryPlan, ToolingError> {
+        info!("Planning memory for user={}: {}...", user_id, safe_truncate(message, 50));
+        let crate::llm::extractor::ExtractionResult { memories: extracted, entities, relations } =
+            self.extract_for_storage(message, user_id).await?;
+
+        let texts: Vec<&str> = extracted.iter().map(|memory| memory.text.as_str()).collect();
+        let vectors = self.embedder.generate_batch_each(&texts, true).await;
+
+        let mut memories = Vec::with_capacity(extracted.len());
+        for (memory, vector) in extracted.into_iter().zip(vectors) {
+            let vector = vector.map_err(|e| ToolingError::Embedding(e.to_string()))?;
+            let similar_memories = self.find_similar_memories(&memory.text, &vector, user_id).await;
+            let decision = self.decide_storage(&memory, &similar_memories, user_id).await;
+
+            let (entity_links, concept_links) = if decision.operation == MemoryOperation::Noop {
+                (Vec::new(), Vec::new())
+            } else {
+                let names = memory
+                    .entities
+                    .iter()
+                    .filter_map(|id| entities.iter().find(|e| &e.id == id))
+                    .map(|e| e.name.clone())
+                    .collect();
+                (names, self.concept_links_for(&memory))
+            };
+            memories.push(PlannedMemory { memory, decision, entity_links, concept_links, vector });
+        }
+
+        Ok(AddMemoryPlan {
+            user_id: user_id.to_string(),
+            context_tags: context_tags.unwrap_or_default().to_string(),
+            memories,
+            entities,
+            relations,
+        })
+    }
+
+    /// Applies a plan from `plan_add_memory` as-is, without re-running the decision engine.
+    pub async fn execute_add_memory_plan(&self, plan: &AddMemoryPlan) -> Result<AddMemoryResult, ToolingError> {
+        info!("Executing add plan with {} memories for user={}", plan.memories.len(), plan.user_id);
+        let mut tally = PipelineTally::default();
+        let mut stored = Vec::new();
+
+        for planned in &plan.memories {
+            // Plans that went through serde lose their vectors.
+            let embedded;
+            let vector = if planned.vector.is_empty() {
+                embedded = self
+                    .embedder
+                    .generate(&planned.memory.text, true)
+                    .await
+                    .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+                &embedded
+            } else {
+                &planned.vector
+            };
+
+            if let Some(memory_id) = self
+                .apply_decision(
+                    &planned.memory,
+                    &planned.decision,
+                    vector,
+                    &plan.user_id,
+                    &plan.context_tags,
+                    &plan.entities,
+                    &planned.concept_links,
+                    None,
+                    &mut tally,
+                )
+                .await?
+            {
+                stored.push((planned.memory.text.clone(), memory_id));
+            }
+        }
+
+        self.link_extracted_relations(&stored, &plan.relations, &mut tally).await;
+        self.search_engine.bump_generation(&plan.user_id);
+        Ok(tally.into_result(self.pipeline_metadata(&plan.user_id)))
+    }
+
+    /// Waits until no other `add_memory` holds `key` for `user_id`; the returned guard keeps
+    /// it until dropped. Only serializes callers sharing this manager.
+    async fn claim_idempotency_key(&self, user_id: &str, key: &str) -> tokio::sync::OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.idempotency_locks.lock();
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            Arc::clone(locks.entry((user_id.to_string(), key.to_string())).or_default())
+        };
+        lock.lock_owned().await
+    }
+
+    async fn find_idempotent_result(&self, user_id: &str, key: &str) -> Result<Option<AddMemoryResult>, ToolingError> {
+        #[derive(Deserialize)]
+        struct IdempotencyRecord {
+            #[serde(default)]
+            result: String,
+        }
+        #[derive(Deserialize)]
+        struct RecordResult {
+            #[serde(default)]
+            record: Option<IdempotencyRecord>,
+        }
+
+        let response: RecordResult = self.db
+            .execute_query(
+                "getIdempotencyRecord",
+                &serde_json::json!({"user_id": user_id, "idempotency_key": key}),
+            )
+            .await
+            .map_err(|e| ToolingError::Database(e.to_string()))?;
+
+        let Some(record) = response.record.filter(|r| !r.result.is_empty()) else {
+            return Ok(None);
+        };
+        let mut prior: AddMemoryResult = serde_json::from_str(&record.result)
+            .map_err(|e| ToolingError::Database(format!("Corrupt idempotency record for '{}': {}", key, e)))?;
+        prior.metadata.insert("idempotent_replay".to_string(), serde_json::Value::Bool(true));
+        Ok(Some(prior))
+    }
+
+    /// Stores the whole result, whatever the pipeline decided, so a retry replays it as-is.
+    async fn record_idempotent_result(&self, user_id: &str, key: &str, result: &AddMemoryResult) {
+        let serialized = match serde_json::to_string(result) {
+            Ok(serialized) => serialized,
+            Err(e) => {
+                warn!("Failed to serialize result for idempotency key '{}': {}", key, e);
+                return;
+            }
+        };
+        if let Err(e) = self.db
+            .execute_mutation::<serde_json::Value, _>(
+                "addIdempotencyRecord",
+                &serde_json::json!({
+                    "user_id": user_id,
+                    "idempotency_key": key,
+                    "result": serialized,
+                    "created_at": chrono::Utc::now().to_rfc3339(),
+                }),
+            )
+            .await
+        {
+            warn!("Failed to record idempotency key '{}' for user={}: {}", key, user_id, e);
+        }
+    }
+
+    
+    pub async fn add_memories_batch(
+        &self,
+        messages: &[(&str, &str)],
+        context_tags: Option<&str>,
+    ) -> Result<Vec<AddMemoryResult>, ToolingError> {
+        use futures::{StreamExt, TryStreamExt};
+
+        if messages.is_empty() {
+            return Ok(Vec::new());
+        }
+        let tags = context_tags.unwrap_or("");
+        info!("Adding batch of {} messages [tags={}]", messages.len(), tags);
+
+        
+        let extractions: Vec<crate::llm::extractor::ExtractionResult> = futures::stream::iter(
+            messages.iter().map(|(message, user_id)| self.extract_for_storage(message, user_id)),
+        )
+        .buffered(BATCH_PARALLELISM)
+        .try_collect()
+        .await?;
+
+        
+        let jobs: Vec<(usize, &crate::llm::extractor::ExtractedMemory)> = extractions
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, extraction)| extraction.memories.iter().map(move |memory| (idx, memory)))
+            .collect();
+        let texts: Vec<&str> = jobs.iter().map(|(_, memory)| memory.text.as_str()).collect();
+        let vectors = self
+            .embedder
+            .generate_batch(&texts, true)
+            .await
+            .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+
+        
+        let db_similar: Vec<Vec<SimilarMemory>> = futures::stream::iter(
+            jobs.iter().zip(&vectors).map(|((idx, memory), vector)| {
+                self.find_similar_memories(&memory.text, vector, messages[*idx].1)
+            }),
+        )
+        .buffered(BATCH_PARALLELISM)
+        .collect()
+        .await;
+
+        // Decisions run in input order so each one sees the memories staged before it.
+        let mut tallies: Vec<PipelineTally> = messages.iter().map(|_| PipelineTally::default()).collect();
+        let mut stored: Vec<Vec<(String, String)>> = messages.iter().map(|_| Vec::new()).collect();
+        let mut staged: Vec<StagedMemory> = Vec::new();
+
+        for (((idx, memory), vector), mut similar_memories) in jobs.into_iter().zip(vectors).zip(db_similar) {
+            let user_id = messages[idx].1;
+            merge_staged_candidates(&mut similar_memories, &staged, user_id, &vector);
+
+            if let Some(memory_id) = self
+                .store_with_decision(memory, &vector, &similar_memories, user_id, tags, &extractions[idx].entities, None, &mut tallies[idx])
+                .await?
+            {
+                stored[idx].push((memory.text.clone(), memory_id.clone()));
+                staged.push(StagedMemory {
+                    user_id: user_id.to_string(),
+                    memory_id,
+                    content: memory.text.clone(),
+                    vector,
+                });
+            }
+        }
+
+        let mut results = Vec::with_capacity(messages.len());
+        for (idx, mut tally) in tallies.into_iter().enumerate() {
+            self.link_extracted_relations(&stored[idx], &extractions[idx].relations, &mut tally).await;
+            results.push(tally.into_result(self.pipeline_metadata(messages[idx].1)));
+        }
+        for user_id in messages.iter().map(|(_, user_id)| *user_id).collect::<HashSet<_>>() {
+            self.search_engine.bump_generation(user_id);
+        }
+
+        info!(
+            "Batch pipeline complete: {} messages, {} memories added",
+            results.len(),
+            results.iter().map(|r| r.added.len()).sum::<usize>()
+        );
+
+        Ok(results)
+    }
+
+    
+    async fn extract_for_storage(
+        &self,
+        message: &str,
+        user_id: &str,
+    ) -> Result<crate::llm::extractor::ExtractionResult, ToolingError> {
+        let mut extraction = self
+            .extractor
+            .extract(message, user_id, true, true)
+            .await
+            .map_err(|e| ToolingError::Extraction(e.to_string()))?;
+
+        info!(
+            "Extracted {} memories, {} entities, {} relations",
+            extraction.memories.len(),
+            extraction.entities.len(),
+            extraction.relations.len()
+        );
+
+        
+        if extraction.memories.is_empty() {
+            debug!("No memories extracted, storing original message");
+            extraction.memories.push(crate::llm::extractor::ExtractedMemory {
+                text: message.to_string(),
+                memory_type: "fact".to_string(),
+                certainty: 50,
+                importance: 50,
+                entities: vec![],
+            });
+        }
+
+        Ok(extraction)
+    }
+
+    
+    async fn find_similar_memories(&self, text: &str, vector: &[f32], user_id: &str) -> Vec<SimilarMemory> {
+        self.search_engine
+            .search(text, vector, user_id, SIMILAR_MEMORY_LIMIT, "contextual", None)
+            .await
+            .unwrap_or_default()
+            .iter()
+            .map(|r| SimilarMemory {
+                id: r.memory_id.clone(),
+                content: r.content.clone(),
+                score: r.score as f64,
+                created_at: None,
+            })
+            .collect()
+    }
+
+    
+    #[allow(clippy::too_many_arguments)]
+    async fn store_with_decision(
+        &self,
+        memory: &crate::llm::extractor::ExtractedMemory,
+        vector: &[f32],
+        similar_memories: &[SimilarMemory],
+        user_id: &str,
+        tags: &str,
+        entities: &[crate::llm::extractor::ExtractedEntity],
+        chunk_threshold: Option<usize>,
+        tally: &mut PipelineTally,
+    ) -> Result<Option<String>, ToolingError> {
+        let decision = self.decide_storage(memory, similar_memories, user_id).await;
+        let concept_links = self.concept_links_for(memory);
+        self.apply_decision(memory, &decision, vector, user_id, tags, entities, &concept_links, chunk_threshold, tally)
+            .await
+    }
+
+    
+    async fn decide_storage(
+        &self,
+        memory: &crate::llm::extractor::ExtractedMemory,
+        similar_memories: &[SimilarMemory],
+        user_id: &str,
+    ) -> MemoryDecision {
+        let floor = self.search_engine.decision_similarity_floor();
+        let close_memories = above_similarity_floor(similar_memories, floor);
+        let decision = if close_memories.is_empty() {
+            MemoryDecision::add(100, format!("No similar memories above {} floor, adding as new.", floor))
+        } else {
+            self.decision_engine
+                .decide(&memory.text, &close_memories, user_id)
+                .await
+        };
+
+        debug!(
+            "Decision: {:?} (confidence={}, target={:?})",
+            decision.operation, decision.confidence, decision.target_memory_id
+        );
+        decision
+    }
+
+    
+    fn concept_links_for(&self, memory: &crate::llm::extractor::ExtractedMemory) -> Vec<PlannedConceptLink> {
+        let ontology = self.ontology_manager.read();
+        if !ontology.is_loaded() {
+            return Vec::new();
+        }
+        ontology
+            .map_memory_to_concepts(&memory.text, Some(&memory.memory_type))
+            .into_iter()
+            .map(|m| PlannedConceptLink {
+                concept_id: m.concept.id.clone(),
+                name: m.concept.name.clone(),
+                confidence: (m.confidence * 100.0) as i32,
+            })
+            .collect()
+    }
+
+    
+    #[allow(clippy::too_many_arguments)]
+    async fn apply_decision(
+        &self,
+        memory: &crate::llm::extractor::ExtractedMemory,
+        decision: &MemoryDecision,
+        vector: &[f32],
+        user_id: &str,
+        tags: &str,
+        entities: &[crate::llm::extractor::ExtractedEntity],
+        concept_links: &[PlannedConceptLink],
+        chunk_threshold: Option<usize>,
+        tally: &mut PipelineTally,
+    ) -> Result<Option<String>, ToolingError> {
+        let mut events: Vec<(String, MemoryEventOp)> = Vec::new();
+        let memory_id = match decision.operation {
+            MemoryOperation::Noop => {
+                debug!("NOOP: skipping duplicate memory");
+                tally.skipped += 1;
+                return Ok(None);
+            }
+            MemoryOperation::Update => {
+                
+                if let (Some(target_id), Some(merged)) = (&decision.target_memory_id, &decision.merged_content) {
+                    debug!("UPDATE: updating {} with merged content", target_id);
+                    self.update_memory_internal(target_id, merged, vector).await?;
+                    tally.updated.push(target_id.to_string());
+                    events.push((target_id.to_string(), MemoryEventOp::Updated));
+                    target_id.to_string()
+                } else {
                     
-                    let (new_id, new_chunks) = self.store_new_memory(&memory, user_id, &vector, tags).await?;
-                    chunks_created += new_chunks;
-                    if let Some(old_id) = &decision.supersedes_memory_id {
-                        debug!("SUPERSEDE: {} supersedes {}", new_id, old_id);
-                        
-                        let _ = self.reasoning_engine
-                            .add_relation(&new_id, old_id, ReasoningType::Supports, 90, None)
-                            .await;
-                    }
-                    added_ids.push(new_id.clone());
+                    let (new_id, new_chunks) = self.store_new_memory(memory, user_id, vector, tags, chunk_threshold).await?;
+                    tally.chunks_created += new_chunks;
+                    events.push((new_id.clone(), MemoryEventOp::Added));
                     new_id
                 }
-                MemoryOperation::Contradict => {
+            }
+            MemoryOperation::Supersede => {
+                
+                let (new_id, new_chunks) = self.store_new_memory(memory, user_id, vector, tags, chunk_threshold).await?;
+                tally.chunks_created += new_chunks;
+                events.push((new_id.clone(), MemoryEventOp::Added));
+                if let Some(old_id) = &decision.supersedes_memory_id {
+                    debug!("SUPERSEDE: {} supersedes {}", new_id, old_id);
+                    events.push((old_id.clone(), MemoryEventOp::Superseded));
                     
-                    let (new_id, new_chunks) = self.store_new_memory(&memory, user_id, &vector, tags).await?;
-                    chunks_created += new_chunks;
-                    if let Some(contra_id) = &decision.contradicts_memory_id {
-                        debug!("CONTRADICT: {} contradicts {}", new_id, contra_id);
-                        let _ = self.reasoning_engine
-                            .add_relation(&new_id, contra_id, ReasoningType::Contradicts, 80, None)
-                            .await;
-                    }
-                    added_ids.push(new_id.clone());
-                    new_id
+                    let _ = self.reasoning_engine
+                        .add_relation(&new_id, old_id, ReasoningType::Supports, 90, None)
+                        .await;
                 }
-                MemoryOperation::Delete => {
-                    
-                    if let Some(target_id) = &decision.target_memory_id {
-                        debug!("DELETE: removing {} before adding new", target_id);
-                        let _ = self.delete_memory(target_id).await;
-                    }
-                    let (new_id, new_chunks) = self.store_new_memory(&memory, user_id, &vector, tags).await?;
-                    chunks_created += new_chunks;
-                    added_ids.push(new_id.clone());
-                    new_id
+                tally.added.push(new_id.clone());
+                new_id
+            }
+            MemoryOperation::Contradict => {
+                
+                let (new_id, new_chunks) = self.store_new_memory(memory, user_id, vector, tags, chunk_threshold).await?;
+                tally.chunks_created += new_chunks;
+                events.push((new_id.clone(), MemoryEventOp::Added));
+                if let Some(contra_id) = &decision.contradicts_memory_id {
+                    debug!("CONTRADICT: {} contradicts {}", new_id, contra_id);
+                    events.push((contra_id.clone(), MemoryEventOp::Contradicted));
+                    let _ = self.reasoning_engine
+                        .add_relation(&new_id, contra_id, ReasoningType::Contradicts, 80, None)
+                        .await;
                 }
-                MemoryOperation::Add => {
-                    
-                    let (new_id, new_chunks) = self.store_new_memory(&memory, user_id, &vector, tags).await?;
-                    chunks_created += new_chunks;
-                    added_ids.push(new_id.clone());
-                    new_id
+                tally.added.push(new_id.clone());
+                new_id
+            }
+            MemoryOperation::Delete => {
+                
+                if let Some(target_id) = &decision.target_memory_id {
+                    debug!("DELETE: archiving {} before adding new", target_id);
+                    if let Ok(true) = self.archive_memory(target_id).await {
+                        tally.deleted.push(target_id.clone());
+                        events.push((target_id.clone(), MemoryEventOp::Archived));
+                    }
                 }
-            };
+                let (new_id, new_chunks) = self.store_new_memory(memory, user_id, vector, tags, chunk_threshold).await?;
+                tally.chunks_created += new_chunks;
+                tally.added.push(new_id.clone());
+                events.push((new_id.clone(), MemoryEventOp::Added));
+                new_id
+            }
+            MemoryOperation::Add => {
+                
+                let (new_id, new_chunks) = self.store_new_memory(memory, user_id, vector, tags, chunk_threshold).await?;
+                tally.chunks_created += new_chunks;
+                tally.added.push(new_id.clone());
+                events.push((new_id.clone(), MemoryEventOp::Added));
+                new_id
+            }
+        };
+
+        for (event_memory_id, op) in events {
+            self.record_memory_event(&event_memory_id, op, decision, &memory.text).await;
+        }
 
+        
+        for entity_id in &memory.entities {
             
-            for entity_id in &memory.entities {
+            if let Some(entity) = entities.iter().find(|e| &e.id == entity_id) {
                 
-                if let Some(entity) = extraction.entities.iter().find(|e| &e.id == entity_id) {
-                    
-                    match self.entity_manager.get_or_create_entity(
-                        &entity.name,
-                        &entity.entity_type, 
-                        None, 
-                    ).await {
-                        Ok(db_entity) => {
-                            
-                            if let Err(e) = self.entity_manager.link_to_memory(
-                                &db_entity.entity_id,
-                                &memory_id,
-                                EntityEdgeType::ExtractedEntity,
-                                80,  
-                                50,  
-                                "neutral", 
-                            ).await {
-                                warn!("Failed to link entity {} to memory {}: {}", db_entity.entity_id, memory_id, e);
-                            } else {
-                                entities_linked += 1;
-                                debug!("Linked entity '{}' to memory {}", entity.name, memory_id);
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Failed to get/create entity '{}': {}", entity.name, e);
+                match self.entity_manager.get_or_create_entity(
+                    &entity.name,
+                    &entity.entity_type, 
+                    None, 
+                ).await {
+                    Ok(db_entity) => {
+                        
+                        if let Err(e) = self.entity_manager.link_to_memory(
+                            &db_entity.entity_id,
+                            &memory_id,
+                            EntityEdgeType::ExtractedEntity,
+                            entity.link_confidence(),
+                            ENTITY_LINK_SALIENCE,
+                            entity.link_sentiment(),
+                        ).await {
+                            warn!("Failed to link entity {} to memory {}: {}", db_entity.entity_id, memory_id, e);
+                        } else {
+                            tally.entities_linked += 1;
+                            debug!(
+                                "Linked entity '{}' to memory {} (confidence={})",
+                                entity.name, memory_id, entity.link_confidence()
+                            );
                         }
                     }
+                    Err(e) => {
+                        warn!("Failed to get/create entity '{}': {}", entity.name, e);
+                    }
                 }
             }
+        }
 
+        
+        for link in concept_links {
             
-            let concept_links: Vec<(String, String, i32)> = {
-                let ontology = self.ontology_manager.read();
-                if ontology.is_loaded() {
-                    ontology.map_memory_to_concepts(&memory.text, Some(&memory.memory_type))
-                        .into_iter()
-                        .map(|m| (m.concept.id.clone(), m.concept.name.clone(), (m.confidence * 100.0) as i32))
-                        .collect()
-                } else {
-                    Vec::new()
-                }
-            };
-            
-            for (concept_id, concept_name, confidence) in concept_links {
-                
-                if let Err(e) = self.link_memory_to_concept(&memory_id, &concept_id, confidence).await {
-                    warn!("Failed to link concept {}: {}", concept_id, e);
-                } else {
-                    debug!("Linked memory {} to concept '{}'", memory_id, concept_name);
-                }
+            if let Err(e) = self.link_memory_to_concept(&memory_id, &link.concept_id, link.confidence).await {
+                warn!("Failed to link concept {}: {}", link.concept_id, e);
+            } else {
+                debug!("Linked memory {} to concept '{}'", memory_id, link.name);
             }
         }
 
-        
-        let mut memory_content_to_id: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-        for (idx, mem) in memories_to_store.iter().enumerate() {
-            if idx < added_ids.len() {
-                
-                let normalized = mem.text.to_lowercase();
-                memory_content_to_id.insert(normalized.clone(), added_ids[idx].clone());
-                
-                let short_key: String = normalized.chars().take(100).collect();
-                if short_key.len() < normalized.len() {
-                    memory_content_to_id.insert(short_key, added_ids[idx].clone());
-                }
+        Ok(Some(memory_id))
+    }
+
+    // History is best-effort: a failed write is logged, never surfaced to the caller.
+    async fn record_memory_event(&self, memory_id: &str, op: MemoryEventOp, decision: &MemoryDecision, source: &str) {
+        let new_value = match op {
+            MemoryEventOp::Updated => decision.merged_content.as_deref().unwrap_or_default(),
+            _ => "",
+        };
+        let action: &'static str = op.into();
+        let result = self.db
+            .execute_mutation::<serde_json::Value, _>(
+                "addMemoryHistoryEvent",
+                &serde_json::json!({
+                    "event_id": format!("evt_{}", uuid::Uuid::new_v4().simple()),
+                    "memory_id": memory_id,
+                    "action": action,
+                    "new_value": new_value,
+                    "timestamp": chrono::Utc::now().to_rfc3339(),
+                    "actor": "decision_engine",
+                    "reason": decision.reasoning,
+                    "confidence": decision.confidence as i64,
+                    "source_message_preview": safe_truncate(source, EVENT_PREVIEW_CHARS),
+                }),
+            )
+            .await;
+        if let Err(e) = result {
+            warn!("Failed to record {} event for memory {}: {}", action, memory_id, e);
+        }
+    }
+
+    /// Everything the ingestion pipeline did to `memory_id`, oldest first.
+    pub async fn get_memory_history(&self, memory_id: &str) -> Result<Vec<MemoryEvent>, ToolingError> {
+        #[derive(Deserialize)]
+        struct HistoryResult {
+            #[serde(default)]
+            events: Vec<MemoryEvent>,
+        }
+
+        let mut history: HistoryResult = self.db
+            .execute_query("getMemoryHistory", &serde_json::json!({"memory_id": memory_id}))
+            .await
+            .map_err(|e| ToolingError::Database(e.to_string()))?;
+
+        history.events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        Ok(history.events)
+    }
+
+    
+    async fn link_extracted_relations(
+        &self,
+        stored: &[(String, String)],
+        relations: &[crate::llm::extractor::ExtractedRelation],
+        tally: &mut PipelineTally,
+    ) {
+        let mut memory_content_to_id: HashMap<String, String> = HashMap::new();
+        for (text, memory_id) in stored {
+            
+            let normalized = text.to_lowercase();
+            memory_content_to_id.insert(normalized.clone(), memory_id.clone());
+            
+            let short_key: String = normalized.chars().take(100).collect();
+            if short_key.len() < normalized.len() {
+                memory_content_to_id.insert(short_key, memory_id.clone());
             }
         }
 
-        for relation in &extraction.relations {
+        for relation in relations {
             debug!(
                 "Processing relation: '{}' --{}-> '{}'",
                 safe_truncate(&relation.from_memory_content, 30),
@@ -461,7 +1651,7 @@ impl ToolingManager {
                     None, 
                 ).await {
                     Ok(rel) => {
-                        relations_created += 1;
+                        tally.relations_created += 1;
                         debug!("Created {} relation: {} -> {}", rel.relation_type.edge_name(), from, to);
                     }
                     Err(e) => {
@@ -476,17 +1666,10 @@ impl ToolingManager {
                 );
             }
         }
+    }
 
-        info!(
-            "Memory pipeline complete: {} added, {} updated, {} skipped, {} entities, {} relations",
-            added_ids.len(),
-            updated_ids.len(),
-            skipped,
-            entities_linked,
-            relations_created
-        );
-
-        
+    
+    fn pipeline_metadata(&self, user_id: &str) -> HashMap<String, serde_json::Value> {
         let mut metadata = HashMap::new();
         metadata.insert(
             "provider".to_string(),
@@ -500,17 +1683,7 @@ impl ToolingManager {
             "user_id".to_string(),
             serde_json::Value::String(user_id.to_string()),
         );
-
-        Ok(AddMemoryResult {
-            added: added_ids,
-            updated: updated_ids,
-            deleted: vec![],
-            skipped,
-            entities_extracted: entities_linked,
-            reasoning_relations_created: relations_created,
-            chunks_created,
-            metadata,
-        })
+        metadata
     }
 
     
@@ -520,7 +1693,12 @@ impl ToolingManager {
         user_id: &str,
         vector: &[f32],
         context_tags: &str,
+        chunk_threshold: Option<usize>,
     ) -> Result<(String, usize), ToolingError> {
+        self.embedder
+            .validate_dimension(vector)
+            .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+
         let memory_id = format!(
             "mem_{}",
             uuid::Uuid::new_v4()
@@ -573,7 +1751,7 @@ impl ToolingManager {
         }
         
         let response: AddMemoryResponse = self.db
-            .execute_query("addMemory", &input)
+            .execute_mutation("addMemory", &input)
             .await
             .map_err(|e| ToolingError::Database(e.to_string()))?;
         
@@ -597,7 +1775,7 @@ impl ToolingManager {
         };
         
         if let Err(e) = self.db
-            .execute_query::<serde_json::Value, _>("addMemoryEmbedding", &embed_input)
+            .execute_mutation::<serde_json::Value, _>("addMemoryEmbedding", &embed_input)
             .await 
         {
             warn!("Failed to add embedding for {}: {}", memory_id, e);
@@ -614,7 +1792,7 @@ impl ToolingManager {
         }
 
         let _ = self.db
-            .execute_query::<serde_json::Value, _>("linkUserToMemory", &LinkUserInput {
+            .execute_mutation::<serde_json::Value, _>("linkUserToMemory", &LinkUserInput {
                 user_id: user_id.to_string(),
                 memory_id: memory_id.clone(),
                 context: "created".to_string(),
@@ -623,7 +1801,7 @@ impl ToolingManager {
         
 
         let mut chunk_count = 0usize;
-        if self.chunking_manager.should_chunk(&memory.text) {
+        if self.chunking_manager.should_chunk_with(&memory.text, chunk_threshold) {
             info!(
                 "📦 Content exceeds threshold ({} chars), creating chunks",
                 memory.text.chars().count()
@@ -638,6 +1816,7 @@ impl ToolingManager {
                 "llm_extraction",
                 "",
                 "{}",
+                chunk_threshold,
             ).await {
                 Ok(result) => {
                     chunk_count = result.chunk_count;
@@ -649,6 +1828,8 @@ impl ToolingManager {
             }
         }
 
+        self.index_memory(user_id, &memory_id, &memory.text);
+
         debug!("Stored new memory: {}", memory_id);
         Ok((memory_id, chunk_count))
     }
@@ -660,6 +1841,10 @@ impl ToolingManager {
         new_content: &str,
         vector: &[f32],
     ) -> Result<(), ToolingError> {
+        self.embedder
+            .validate_dimension(vector)
+            .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+
         #[derive(Serialize)]
         struct UpdateInput {
             memory_id: String,
@@ -676,25 +1861,101 @@ impl ToolingManager {
             .await
             .map_err(|e| ToolingError::Database(e.to_string()))?;
 
+        self.lexical_index.update(|index| index.update_content(memory_id, new_content));
+
         debug!("Updated memory: {}", memory_id);
         Ok(())
     }
 
     
-    async fn link_memory_to_concept(
-        &self,
-        memory_id: &str,
-        concept_id: &str,
-        confidence: i32,
-    ) -> Result<(), ToolingError> {
-        #[derive(serde::Deserialize)]
-        struct LinkResponse {
+    // Only users whose index is already built are updated incrementally; the rest are
+    // built in full from the database on their first hybrid search.
+    fn index_memory(&self, user_id: &str, memory_id: &str, content: &str) {
+        self.lexical_index.update(|index| {
+            if !index.has_user(user_id) {
+                return false;
+            }
+            index.upsert(user_id, memory_id, content);
+            index.set_created_at(memory_id, &chrono::Utc::now().to_rfc3339());
+            true
+        });
+    }
+
+    
+    pub async fn rebuild_lexical_index(&self, user_id: &str) -> Result<usize, ToolingError> {
+        Ok(self.search_engine.rebuild_lexical_index(user_id).await?)
+    }
+
+    /// Writes any debounced lexical index changes to disk.
+    pub async fn flush_lexical_index(&self) -> Result<(), ToolingError> {
+        self.lexical_index.flush().await.map_err(|e| ToolingError::Database(e.to_string()))
+    }
+
+    
+    async fn hybrid_search_memory(
+        &self,
+        query: &str,
+        user_id: &str,
+        limit: usize,
+        include_archived: bool,
+    ) -> Result<Vec<SearchMemoryResult>, ToolingError> {
+        let limit = self.search_engine.bounded_limit(limit);
+
+        let expansion_terms = if self.search_engine.ontology_expansion_enabled() {
+            QueryProcessor::new(None, false, 0)
+                .with_ontology_expansion(true)
+                .process_with_ontology(query, &self.ontology_manager.read())
+                .expansion_terms
+        } else {
+            Vec::new()
+        };
+        let lexical_query = if expansion_terms.is_empty() {
+            query.to_string()
+        } else {
+            format!("{} {}", query, expansion_terms.join(" "))
+        };
+
+        let documents = self.search_engine.lexical_candidates(user_id, &lexical_query, limit, None).await;
+        debug!(
+            "Hybrid search: {} lexical candidates for user={} ({} expansion terms)",
+            documents.len(), user_id, expansion_terms.len()
+        );
+
+        let results = self
+            .search_engine
+            .hybrid_search_expanded(query, Some(user_id), Some(&documents), limit, &expansion_terms, include_archived)
+            .await
+            .map_err(SearchError::from)?;
+
+        Ok(results
+            .into_iter()
+            .map(|r| SearchMemoryResult {
+                provenance: Some(Provenance::from_hybrid(&r)),
+                memory_id: r.memory_id,
+                content: r.content,
+                score: r.score,
+                method: "hybrid".to_string(),
+                metadata: r.metadata,
+                created_at: r.created_at,
+            })
+            .collect())
+    }
+
+    
+    async fn link_memory_to_concept(
+        &self,
+        memory_id: &str,
+        concept_id: &str,
+        confidence: i32,
+    ) -> Result<(), ToolingError> {
+        #[derive(serde::Deserialize)]
+        struct LinkResponse {
             #[serde(default)]
             link: serde_json::Value,
         }
         
         self.db
-            .execute_query::<LinkResponse, _>(
+            .execute_mutation::<LinkResponse, _>(
                 "linkMemoryToInstanceOf",
                 &serde_json::json!({
                     "memory_id": memory_id,
@@ -710,6 +1971,7 @@ impl ToolingManager {
     }
 
     
+    #[allow(clippy::too_many_arguments)]
     pub async fn search_memory(
         &self,
         query: &str,
@@ -718,23 +1980,37 @@ impl ToolingManager {
         mode: &str,
         temporal_days: Option<f64>,
         _graph_depth: Option<usize>,
+        include_archived: bool,
     ) -> Result<Vec<SearchMemoryResult>, ToolingError> {
         info!(
             "Searching: '{}...' [mode={}, limit={:?}, temporal_days={:?}]", 
             safe_truncate(query, 50), mode, limit, temporal_days
         );
 
-        
-        let query_embedding = self
-            .embedder
-            .generate(query, true)
-            .await
-            .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+        let limit = limit.unwrap_or(10);
+        let lexical = !include_archived && self.search_engine.routes_lexically(query, mode);
+        if mode.eq_ignore_ascii_case("hybrid") && !lexical {
+            return self.hybrid_search_memory(query, user_id, limit, include_archived).await;
+        }
+
+        // Lexical lookups never reach vector search, so their embedding would go unused.
+        let query_embedding = if lexical {
+            Vec::new()
+        } else {
+            self.embedder
+                .generate_query(query, true)
+                .await
+                .map_err(|e| ToolingError::Embedding(e.to_string()))?
+        };
 
         
+        let options = SearchOptions {
+            temporal_window: temporal_days.map(days_to_window),
+            include_archived,
+        };
         let results = self
             .search_engine
-            .search(query, &query_embedding, user_id, limit.unwrap_or(10), mode, temporal_days)
+            .search_with(query, &query_embedding, user_id, limit, mode, options)
             .await?;
 
         info!("Found {} memories via SearchEngine [method={}]", 
@@ -752,6 +2028,41 @@ impl ToolingManager {
                 method: r.method,
                 metadata: r.metadata,
                 created_at: r.created_at,
+                provenance: Some(r.provenance),
+            })
+            .collect())
+    }
+
+    
+    pub async fn search_multimode(
+        &self,
+        query: &str,
+        user_id: &str,
+        modes: &[&str],
+        weights: &[f64],
+        limit: usize,
+    ) -> Result<Vec<SearchMemoryResult>, ToolingError> {
+        let query_embedding = self
+            .embedder
+            .generate_query(query, true)
+            .await
+            .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+
+        let results = self
+            .search_engine
+            .search_multimode(query, &query_embedding, user_id, modes, weights, limit)
+            .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|r| SearchMemoryResult {
+                memory_id: r.memory_id,
+                content: r.content,
+                score: r.score as f64,
+                method: r.method,
+                metadata: r.metadata,
+                created_at: r.created_at,
+                provenance: Some(r.provenance),
             })
             .collect())
     }
@@ -802,6 +2113,7 @@ impl ToolingManager {
                 method: "tag_search".to_string(),
                 metadata: HashMap::new(),
                 created_at: m.created_at,
+                provenance: None,
             })
             .collect())
     }
@@ -811,7 +2123,7 @@ impl ToolingManager {
         &self,
         memory_id: &str,
         new_content: &str,
-        _user_id: &str,
+        user_id: &str,
     ) -> Result<bool, ToolingError> {
         info!("Updating memory: {}", memory_id);
 
@@ -871,6 +2183,9 @@ impl ToolingManager {
         
         debug!("Memory {} (id={}) updated successfully", memory_id, internal_id);
 
+        self.lexical_index.update(|index| index.update_content(memory_id, new_content));
+        self.search_engine.bump_generation(user_id);
+
         
         #[derive(serde::Deserialize)]
         struct MemoryResult {
@@ -896,7 +2211,7 @@ impl ToolingManager {
                         embedding: serde_json::Value,
                     }
 
-                    let _ = self.db.execute_query::<EmbeddingResult, _>(
+                    let _ = self.db.execute_mutation::<EmbeddingResult, _>(
                         "addMemoryEmbedding",
                         &serde_json::json!({
                             "memory_id": mem.id,
@@ -915,6 +2230,7 @@ impl ToolingManager {
     
     pub async fn delete_memory(&self, memory_id: &str) -> Result<bool, ToolingError> {
         info!("Deleting memory: {}", memory_id);
+        let owner = self.memory_owner(memory_id).await;
 
         #[derive(Serialize)]
         struct DeleteInput {
@@ -928,387 +2244,2022 @@ impl ToolingManager {
             .await
             .map_err(|e| ToolingError::Database(e.to_string()))?;
 
+        self.lexical_index.update(|index| index.remove(memory_id));
+        if let Some(user_id) = owner {
+            self.search_engine.bump_generation(&user_id);
+        }
+
         Ok(true)
     }
 
-    
-    pub async fn get_memory_graph(
-        &self,
-        user_id: &str,
-        memory_id: Option<&str>,
-        depth: usize,
-    ) -> Result<(Vec<serde_json::Value>, Vec<serde_json::Value>), ToolingError> {
-        info!("Getting memory graph for user={}, memory={:?}, depth={}", user_id, memory_id, depth);
+    // None when the memory is unknown or the lookup fails; there is then no cache to invalidate.
+    async fn memory_owner(&self, memory_id: &str) -> Option<String> {
+        #[derive(Deserialize)]
+        struct OwnerResult {
+            #[serde(default)]
+            memory: Option<OwnerNode>,
+        }
+        #[derive(Deserialize)]
+        struct OwnerNode {
+            #[serde(default)]
+            user_id: String,
+        }
 
-        let mut nodes = Vec::new();
-        let mut edges = Vec::new();
-        let mut visited = std::collections::HashSet::new();
+        let result: OwnerResult = self.db
+            .execute_query("getMemory", &serde_json::json!({"memory_id": memory_id}))
+            .await
+            .ok()?;
+        result.memory.map(|m| m.user_id).filter(|user_id| !user_id.is_empty())
+    }
 
-        
-        let start_ids: Vec<String> = if let Some(mid) = memory_id {
-            vec![mid.to_string()]
-        } else {
-            
-            #[derive(serde::Deserialize)]
-            struct UserMemoriesResult {
-                #[serde(default)]
-                memories: Vec<MemoryNode>,
-            }
-            #[derive(serde::Deserialize)]
-            struct MemoryNode {
-                memory_id: String,
-                #[serde(default)]
-                content: String,
-            }
+    /// Hides a memory from search and listings without dropping it; `restore_memory` undoes it.
+    pub async fn archive_memory(&self, memory_id: &str) -> Result<bool, ToolingError> {
+        info!("Archiving memory: {}", memory_id);
+        self.set_archived_at(memory_id, &chrono::Utc::now().to_rfc3339()).await
+    }
 
-            match self.db.execute_query::<UserMemoriesResult, _>(
-                "getUserMemories",
-                &serde_json::json!({"user_id": user_id, "limit": 10i64}),
-            ).await {
-                Ok(result) => result.memories.into_iter().map(|m| m.memory_id).collect(),
-                Err(_) => Vec::new(),
-            }
-        };
+    
+    pub async fn restore_memory(&self, memory_id: &str) -> Result<bool, ToolingError> {
+        info!("Restoring memory: {}", memory_id);
+        self.set_archived_at(memory_id, "").await
+    }
 
-        if start_ids.is_empty() {
-            return Ok((nodes, edges));
+    async fn set_archived_at(&self, memory_id: &str, archived_at: &str) -> Result<bool, ToolingError> {
+        #[derive(Deserialize)]
+        struct ArchiveResult {
+            #[serde(default)]
+            updated: serde_json::Value,
         }
 
-        
-        let mut current_ids = start_ids;
-        let mut current_depth = 0;
-
-        while current_depth < depth && !current_ids.is_empty() {
-            let mut next_ids = Vec::new();
-
-            for mid in &current_ids {
-                if visited.contains(mid) {
-                    continue;
-                }
-                visited.insert(mid.clone());
+        let result: ArchiveResult = self.db
+            .execute_query(
+                "setMemoryArchivedAt",
+                &serde_json::json!({"memory_id": memory_id, "archived_at": archived_at}),
+            )
+            .await
+            .map_err(|e| ToolingError::Database(e.to_string()))?;
 
-                
-                #[derive(serde::Deserialize)]
-                struct MemoryResult {
-                    #[serde(default)]
-                    memory: Option<MemoryData>,
-                }
-                #[derive(serde::Deserialize)]
-                struct MemoryData {
-                    memory_id: String,
-                    #[serde(default)]
-                    content: String,
-                    #[serde(default)]
-                    memory_type: String,
+        let updated = &result.updated;
+        if let Some(user_id) = updated.get("user_id").and_then(|u| u.as_str()) {
+            self.search_engine.bump_generation(user_id);
+            let field = |name: &str| updated.get(name).and_then(|v| v.as_str()).unwrap_or_default();
+            // The lexical index holds live memories only.
+            self.lexical_index.update(|index| {
+                if !archived_at.is_empty() {
+                    return index.remove(memory_id);
                 }
-
-                if let Ok(result) = self.db.execute_query::<MemoryResult, _>(
-                    "getMemory",
-                    &serde_json::json!({"memory_id": mid}),
-                ).await {
-                    if let Some(mem) = result.memory {
-                        nodes.push(serde_json::json!({
-                            "id": mem.memory_id,
-                            "content": mem.content,
-                            "type": mem.memory_type,
-                        }));
-                    }
+                if !index.has_user(user_id) || field("content").is_empty() {
+                    return false;
                 }
+                index.upsert(user_id, memory_id, field("content"));
+                index.set_created_at(memory_id, field("created_at"));
+                true
+            });
+        }
+        Ok(!updated.is_null())
+    }
 
-                
-                #[derive(serde::Deserialize, Default)]
-                struct ConnectionsResult {
-                    #[serde(default)]
-                    implies_out: Vec<ConnectedMemory>,
-                    #[serde(default)]
-                    implies_in: Vec<ConnectedMemory>,
-                    #[serde(default)]
-                    because_out: Vec<ConnectedMemory>,
-                    #[serde(default)]
-                    because_in: Vec<ConnectedMemory>,
-                    #[serde(default)]
-                    contradicts_out: Vec<ConnectedMemory>,
-                    #[serde(default)]
-                    contradicts_in: Vec<ConnectedMemory>,
-                    #[serde(default)]
-                    relation_out: Vec<ConnectedMemory>,
-                    #[serde(default)]
-                    relation_in: Vec<ConnectedMemory>,
-                }
-                #[derive(serde::Deserialize)]
-                struct ConnectedMemory {
-                    memory_id: String,
-                    #[serde(default)]
-                    content: String,
-                }
+    /// Archived memories for `user_id`, most recently archived first.
+    pub async fn list_archived(&self, user_id: &str) -> Result<Vec<SearchMemoryResult>, ToolingError> {
+        let mut memories = self.fetch_archived_memories(user_id).await?;
+        memories.sort_by(|a, b| b.archived_at.cmp(&a.archived_at).then_with(|| a.memory_id.cmp(&b.memory_id)));
 
-                if let Ok(conns) = self.db.execute_query::<ConnectionsResult, _>(
-                    "getMemoryLogicalConnections",
-                    &serde_json::json!({"memory_id": mid}),
-                ).await {
-                    
-                    for conn in conns.implies_out {
-                        edges.push(serde_json::json!({
-                            "source": mid,
-                            "target": conn.memory_id,
-                            "type": "IMPLIES",
-                            "weight": 1.0,
-                        }));
-                        next_ids.push(conn.memory_id);
-                    }
-                    for conn in conns.implies_in {
-                        edges.push(serde_json::json!({
-                            "source": conn.memory_id,
-                            "target": mid,
-                            "type": "IMPLIES",
-                            "weight": 1.0,
-                        }));
-                        next_ids.push(conn.memory_id);
-                    }
-                    
-                    for conn in conns.because_out {
-                        edges.push(serde_json::json!({
-                            "source": mid,
-                            "target": conn.memory_id,
-                            "type": "BECAUSE",
-                            "weight": 1.0,
-                        }));
-                        next_ids.push(conn.memory_id);
-                    }
-                    for conn in conns.because_in {
-                        edges.push(serde_json::json!({
-                            "source": conn.memory_id,
-                            "target": mid,
-                            "type": "BECAUSE",
-                            "weight": 1.0,
-                        }));
-                        next_ids.push(conn.memory_id);
-                    }
-                    
-                    for conn in conns.contradicts_out {
-                        edges.push(serde_json::json!({
-                            "source": mid,
-                            "target": conn.memory_id,
-                            "type": "CONTRADICTS",
-                            "weight": 1.0,
-                        }));
-                        next_ids.push(conn.memory_id);
-                    }
-                    
-                    for conn in conns.relation_out {
-                        edges.push(serde_json::json!({
-                            "source": mid,
-                            "target": conn.memory_id,
-                            "type": "SUPPORTS",
-                            "weight": 1.0,
-                        }));
-                        next_ids.push(conn.memory_id);
-                    }
-                }
-            }
+        Ok(memories
+            .into_iter()
+            .map(|m| {
+                let archived_at = serde_json::json!(m.archived_at);
+                let mut result = m.into_result(1.0, "archived");
+                result.metadata.insert("archived_at".to_string(), archived_at);
+                result
+            })
+            .collect())
+    }
 
-            current_ids = next_ids;
-            current_depth += 1;
+    async fn fetch_archived_memories(&self, user_id: &str) -> Result<Vec<UserMemoryNode>, ToolingError> {
+        #[derive(Deserialize)]
+        struct ArchivedMemoriesResult {
+            #[serde(default)]
+            memories: Vec<UserMemoryNode>,
         }
 
-        info!("Graph built: {} nodes, {} edges", nodes.len(), edges.len());
-        Ok((nodes, edges))
+        let result: ArchivedMemoriesResult = self.db
+            .execute_query("getArchivedMemories", &serde_json::json!({"user_id": user_id}))
+            .await
+            .map_err(|e| ToolingError::Database(e.to_string()))?;
+
+        Ok(result.memories.into_iter().filter(|m| !m.archived_at.is_empty()).collect())
     }
 
-    
-    pub async fn search_reasoning_chain(
+    /// Asserts a `relation_type` edge (case-insensitive edge name) from `from_id` to `to_id`;
+    /// an existing edge has its strength merged rather than duplicated.
+    pub async fn relate(
         &self,
-        query: &str,
-        user_id: &str,
-        chain_mode: &str,
-        max_depth: usize,
-        limit: usize,
-    ) -> Result<ReasoningChainSearchResult, ToolingError> {
-        info!("Reasoning chain search: '{}...' mode={} depth={} limit={}", 
-            safe_truncate(query, 30), chain_mode, max_depth, limit);
+        from_id: &str,
+        to_id: &str,
+        relation_type: &str,
+        strength: Option<i32>,
+    ) -> Result<ReasoningRelation, ToolingError> {
+        let relation_type = parse_relation_type(relation_type)?;
+        info!("Relating {} -{}-> {}", from_id, relation_type.edge_name(), to_id);
+        Ok(self
+            .reasoning_engine
+            .add_relation(from_id, to_id, relation_type, strength.unwrap_or(DEFAULT_RELATION_STRENGTH), None)
+            .await?)
+    }
 
-        
-        let query_embedding = self
+    /// Removes a relation added by `relate` or the pipeline; false if there was none.
+    pub async fn unrelate(&self, from_id: &str, to_id: &str, relation_type: &str) -> Result<bool, ToolingError> {
+        let relation_type = parse_relation_type(relation_type)?;
+        info!("Unrelating {} -{}-> {}", from_id, relation_type.edge_name(), to_id);
+        Ok(self.reasoning_engine.delete_relation(from_id, to_id, relation_type).await?)
+    }
+
+    /// Resolves a contradiction between two of `user_id`'s memories, archiving the loser per
+    /// `strategy`; returns the archived ids.
+    pub async fn resolve_contradiction(
+        &self,
+        user_id: &str,
+        from_id: &str,
+        to_id: &str,
+        resolution: &str,
+        strategy: ContradictionStrategy,
+    ) -> Result<Vec<String>, ToolingError> {
+        let removed = self
+            .reasoning_engine
+            .resolve_contradiction(from_id, to_id, resolution, strategy)
+            .await?;
+
+        self.search_engine.bump_generation(user_id);
+        self.lexical_index.update(|index| {
+            removed.iter().fold(false, |changed, memory_id| index.remove(memory_id) | changed)
+        });
+
+        Ok(removed)
+    }
+
+    
+    /// Unresolved CONTRADICTS edges plus LLM-confirmed conflicts among highly similar memories.
+    pub async fn find_contradictions(
+        &self,
+        user_id: &str,
+        limit: usize,
+    ) -> Result<Vec<(SearchMemoryResult, SearchMemoryResult, f64)>, ToolingError> {
+        use crate::toolkit::mind_toolbox::search::parse_datetime_utc;
+        use crate::toolkit::mind_toolbox::search::smart_traversal_v2::fetch_memory_embeddings;
+
+        info!("Scanning for contradictions: user={} limit={}", user_id, limit);
+
+        #[derive(Deserialize)]
+        struct ContradictionEdge {
+            #[serde(default)]
+            from_node: String,
+            #[serde(default)]
+            to_node: String,
+            #[serde(default)]
+            resolved: i64,
+        }
+
+        #[derive(Deserialize)]
+        struct UserContradictionsResult {
+            #[serde(default)]
+            memories: Vec<UserMemoryNode>,
+            #[serde(default)]
+            contradictions: Vec<ContradictionEdge>,
+        }
+
+        let result: UserContradictionsResult = self.db
+            .execute_query(
+                "getUserContradictions",
+                &serde_json::json!({"user_id": user_id, "limit": USER_MEMORY_SCAN_LIMIT}),
+            )
+            .await
+            .map_err(|e| ToolingError::Database(e.to_string()))?;
+
+        let mut memories: Vec<UserMemoryNode> = result.memories.into_iter().filter(|m| m.is_deleted == 0).collect();
+        memories.sort_by(|a, b| {
+            parse_datetime_utc(&b.created_at)
+                .cmp(&parse_datetime_utc(&a.created_at))
+                .then_with(|| a.memory_id.cmp(&b.memory_id))
+        });
+        let by_node: HashMap<&str, usize> = memories.iter().enumerate().map(|(i, m)| (m.id.as_str(), i)).collect();
+
+        let mut known = HashSet::new();
+        let mut found: Vec<(usize, usize, f64)> = Vec::new();
+        for edge in &result.contradictions {
+            let (Some(&from), Some(&to)) = (by_node.get(edge.from_node.as_str()), by_node.get(edge.to_node.as_str())) else {
+                continue;
+            };
+            if known.insert(contradiction_pair_key(&memories[from].memory_id, &memories[to].memory_id)) && edge.resolved == 0 {
+                found.push((from, to, 1.0));
+            }
+        }
+        debug!("{} existing contradictions", found.len());
+
+        let scanned = &memories[..memories.len().min(CONTRADICTION_SCAN_LIMIT)];
+        let embeddings = fetch_memory_embeddings(&self.db, scanned.iter().map(|m| m.memory_id.as_str()).collect()).await;
+        let indexed: Vec<(usize, &[f32])> = scanned
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| embeddings.get(&m.memory_id).map(|v| (i, v.as_slice())))
+            .collect();
+        let vectors: Vec<(&str, &[f32])> = indexed.iter().map(|&(i, v)| (memories[i].memory_id.as_str(), v)).collect();
+        let candidates = contradiction_candidates(&vectors, &known, CONTRADICTION_SIMILARITY_THRESHOLD, SIMILAR_MEMORY_LIMIT);
+
+        let as_similar = |m: &UserMemoryNode, score: f64| SimilarMemory {
+            id: m.memory_id.clone(),
+            content: m.content.clone(),
+            score,
+            created_at: Some(m.created_at.clone()).filter(|c| !c.is_empty()),
+        };
+        let mut checked = 0;
+        for (a, b, similarity) in candidates {
+            if found.len() >= limit {
+                break;
+            }
+            let (first, second) = (&memories[indexed[a].0], &memories[indexed[b].0]);
+            checked += 1;
+            if let Some(confidence) = self
+                .decision_engine
+                .check_contradiction(&as_similar(first, similarity), &as_similar(second, similarity))
+                .await
+            {
+                found.push((indexed[a].0, indexed[b].0, f64::from(confidence) / 100.0));
+            }
+        }
+
+        let id = |i: usize| memories[i].memory_id.as_str();
+        found.sort_by(|a, b| cmp_scored((a.2, id(a.0)), (b.2, id(b.0))).then_with(|| id(a.1).cmp(id(b.1))));
+        found.truncate(limit);
+        info!("Contradiction scan complete: {} found, {} pairs checked by LLM", found.len(), checked);
+
+        Ok(found
+            .into_iter()
+            .map(|(a, b, confidence)| {
+                (
+                    memories[a].clone().into_result(confidence, "contradiction"),
+                    memories[b].clone().into_result(confidence, "contradiction"),
+                    confidence,
+                )
+            })
+            .collect())
+    }
+
+    /// Folds clusters of near-duplicate memories into one LLM-written memory each.
+    ///
+    /// Memories linked by CONTRADICTS never share a cluster. Each merged memory keeps the
+    /// cluster's highest importance and certainty, and its sources are superseded and
+    /// archived, so `restore_memory` can bring them back. `dry_run` still asks the LLM for
+    /// the merged text but writes nothing.
+    pub async fn consolidate(
+        &self,
+        user_id: &str,
+        similarity_threshold: f64,
+        dry_run: bool,
+    ) -> Result<ConsolidationReport, ToolingError> {
+        use crate::toolkit::mind_toolbox::search::parse_datetime_utc;
+        use crate::toolkit::mind_toolbox::search::smart_traversal_v2::fetch_memory_embeddings;
+
+        info!("Consolidating memories: user={} threshold={} dry_run={}", user_id, similarity_threshold, dry_run);
+
+        #[derive(Deserialize)]
+        struct ContradictionEdge {
+            #[serde(default)]
+            from_node: String,
+            #[serde(default)]
+            to_node: String,
+        }
+
+        #[derive(Deserialize)]
+        struct UserContradictionsResult {
+            #[serde(default)]
+            memories: Vec<UserMemoryNode>,
+            #[serde(default)]
+            contradictions: Vec<ContradictionEdge>,
+        }
+
+        let result: UserContradictionsResult = self.db
+            .execute_query(
+                "getUserContradictions",
+                &serde_json::json!({"user_id": user_id, "limit": USER_MEMORY_SCAN_LIMIT}),
+            )
+            .await
+            .map_err(|e| ToolingError::Database(e.to_string()))?;
+
+        let by_node: HashMap<&str, &str> =
+            result.memories.iter().map(|m| (m.id.as_str(), m.memory_id.as_str())).collect();
+        let contradicting: HashSet<(String, String)> = result
+            .contradictions
+            .iter()
+            .filter_map(|edge| Some(contradiction_pair_key(by_node.get(edge.from_node.as_str())?, by_node.get(edge.to_node.as_str())?)))
+            .collect();
+
+        let mut memories: Vec<&UserMemoryNode> =
+            result.memories.iter().filter(|m| m.is_deleted == 0 && m.archived_at.is_empty()).collect();
+        memories.sort_by(|a, b| {
+            parse_datetime_utc(&b.created_at)
+                .cmp(&parse_datetime_utc(&a.created_at))
+                .then_with(|| a.memory_id.cmp(&b.memory_id))
+        });
+        memories.truncate(CONSOLIDATION_SCAN_LIMIT);
+
+        let embeddings = fetch_memory_embeddings(&self.db, memories.iter().map(|m| m.memory_id.as_str()).collect()).await;
+        let embedded: Vec<&UserMemoryNode> =
+            memories.iter().copied().filter(|m| embeddings.contains_key(&m.memory_id)).collect();
+        let vectors: Vec<(&str, &[f32])> =
+            embedded.iter().map(|m| (m.memory_id.as_str(), embeddings[&m.memory_id].as_slice())).collect();
+
+        let mut report = ConsolidationReport { memories_scanned: memories.len(), dry_run, ..Default::default() };
+        for cluster in consolidation_clusters(&vectors, &contradicting, similarity_threshold) {
+            let members: Vec<&UserMemoryNode> = cluster.iter().map(|&i| embedded[i]).collect();
+            let Some(content) = self.synthesize_cluster(&members).await else {
+                continue;
+            };
+            let Some(lead) = members.iter().max_by_key(|m| (m.importance, m.certainty)) else {
+                continue;
+            };
+            let mut merge = ConsolidationMerge {
+                source_ids: members.iter().map(|m| m.memory_id.clone()).collect(),
+                content,
+                memory_type: lead.memory_type.clone(),
+                importance: lead.importance,
+                memory_id: None,
+            };
+            if !dry_run {
+                let certainty = members.iter().map(|m| m.certainty).max().unwrap_or_default();
+                merge.memory_id = Some(self.write_consolidation(user_id, &merge, certainty).await?);
+            }
+            report.merges.push(merge);
+        }
+
+        info!(
+            "Consolidation complete: {} memories scanned, {} clusters merged",
+            report.memories_scanned,
+            report.merges.len()
+        );
+        Ok(report)
+    }
+
+    // None when the LLM fails or answers with nothing; the cluster is then left alone.
+    async fn synthesize_cluster(&self, members: &[&UserMemoryNode]) -> Option<String> {
+        let listing: Vec<String> = members.iter().enumerate().map(|(i, m)| format!("{}. {}", i + 1, m.content)).collect();
+        let prompt = format!("Merge these memories into one:\n{}", listing.join("\n"));
+
+        match self.llm_provider.generate(CONSOLIDATION_SYSTEM_PROMPT, &prompt, None).await {
+            Ok((response, _)) => Some(response.trim().trim_matches('"').trim().to_string()).filter(|text| !text.is_empty()),
+            Err(e) => {
+                warn!("Consolidation LLM call failed for {} memories: {}", members.len(), e);
+                None
+            }
+        }
+    }
+
+    async fn write_consolidation(&self, user_id: &str, merge: &ConsolidationMerge, certainty: i64) -> Result<String, ToolingError> {
+        let vector = self
             .embedder
-            .generate(query, true)
+            .generate(&merge.content, true)
             .await
             .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+        let memory = crate::llm::extractor::ExtractedMemory {
+            text: merge.content.clone(),
+            memory_type: merge.memory_type.clone(),
+            certainty: certainty as i32,
+            importance: merge.importance as i32,
+            entities: Vec::new(),
+        };
+        let reason = format!("Consolidated {} near-duplicate memories", merge.source_ids.len());
+        let concept_links = self.concept_links_for(&memory);
+        let mut tally = PipelineTally::default();
 
-        let seed_results = self
-            .search_engine
-            .search(query, &query_embedding, user_id, limit, "contextual", None)
-            .await?;
+        let memory_id = self
+            .apply_decision(&memory, &MemoryDecision::add(100, &reason), &vector, user_id, "", &[], &concept_links, None, &mut tally)
+            .await?
+            .ok_or_else(|| ToolingError::Memory("consolidated memory was not stored".to_string()))?;
 
-        if seed_results.is_empty() {
-            debug!("No seed memories found for query");
-            return Ok(ReasoningChainSearchResult {
-                chains: Vec::new(),
-                total_memories: 0,
-                deepest_chain: 0,
-            });
+        for source_id in &merge.source_ids {
+            if let Err(e) = self.reasoning_engine.add_relation(&memory_id, source_id, ReasoningType::Supports, 90, None).await {
+                warn!("Failed to link {} to superseded {}: {}", memory_id, source_id, e);
+            }
+            let decision = MemoryDecision::supersede(source_id, 100, &reason);
+            self.record_memory_event(source_id, MemoryEventOp::Superseded, &decision, &merge.content).await;
+            self.set_archived_at(source_id, &chrono::Utc::now().to_rfc3339()).await?;
         }
+        self.search_engine.bump_generation(user_id);
 
-        
-        let mut all_chains = Vec::new();
-        let mut max_chain_depth = 0;
-        let mut total_memories = 0;
+        debug!("Consolidated {:?} into {}", merge.source_ids, memory_id);
+        Ok(memory_id)
+    }
 
-        for seed in &seed_results {
-            match self.reasoning_engine.get_chain(&seed.memory_id, chain_mode, max_depth).await {
-                Ok(chain) => {
-                    if !chain.relations.is_empty() {
-                        let chain_depth = chain.depth;
-                        max_chain_depth = max_chain_depth.max(chain_depth);
-                        total_memories += chain.relations.len();
+    
+    pub async fn delete_where(
+        &self,
+        user_id: &str,
+        filter: &DeleteFilter,
+    ) -> Result<Vec<String>, ToolingError> {
+        if !filter.has_criteria() {
+            return Err(ToolingError::Memory(
+                "DeleteFilter must set at least one criterion".to_string(),
+            ));
+        }
 
-                        all_chains.push(ToolingReasoningChain {
-                            seed: SearchMemoryResult {
-                                memory_id: seed.memory_id.clone(),
-                                content: seed.content.clone(),
-                                score: seed.score as f64,
-                                method: seed.method.clone(),
-                                metadata: seed.metadata.clone(),
-                                created_at: seed.created_at.clone(),
-                            },
-                            nodes: chain.relations.iter().map(|r| ChainNode {
-                                memory_id: r.to_memory_id.clone(),
-                                content: r.to_memory_content.clone(),
-                                relation: r.relation_type.edge_name().to_string(),
-                                depth: 0,
-                            }).collect(),
-                            chain_type: chain.chain_type.clone(),
-                            reasoning_trail: chain.reasoning_trail.clone(),
-                        });
-                    }
-                }
-                Err(e) => {
-                    debug!("Failed to get chain for {}: {}", seed.memory_id, e);
-                }
-            }
+        info!("Bulk delete for user={}: {:?}", user_id, filter);
+
+        let memories = self.fetch_user_memories(user_id).await?;
+
+        let now = chrono::Utc::now();
+        let matched: Vec<String> = memories
+            .into_iter()
+            .filter(|m| filter.matches(&m.memory_type, m.certainty, &m.created_at, now))
+            .map(|m| m.memory_id)
+            .collect();
+
+        if filter.dry_run {
+            info!("Bulk delete dry run: {} memories would be deleted", matched.len());
+            return Ok(matched);
         }
 
-        info!("Found {} chains, max_depth={}, total_memories={}", 
-            all_chains.len(), max_chain_depth, total_memories);
+        let mut deleted = Vec::with_capacity(matched.len());
+        for memory_id in matched {
+            match self.delete_memory(&memory_id).await {
+                Ok(_) => deleted.push(memory_id),
+                Err(e) => warn!("Failed to delete memory {}: {}", memory_id, e),
+            }
+        }
 
-        Ok(ReasoningChainSearchResult {
-            chains: all_chains,
-            total_memories,
-            deepest_chain: max_chain_depth,
-        })
+        info!("Bulk delete complete: {} memories deleted", deleted.len());
+        Ok(deleted)
     }
 
     
-    pub async fn search_by_concept(
+    async fn fetch_user_memories(&self, user_id: &str) -> Result<Vec<UserMemoryNode>, ToolingError> {
+        #[derive(Deserialize)]
+        struct UserMemoriesResult {
+            #[serde(default)]
+            memories: Vec<UserMemoryNode>,
+        }
+
+        let result: UserMemoriesResult = self.db
+            .execute_query(
+                "getUserMemories",
+                &serde_json::json!({"user_id": user_id, "limit": USER_MEMORY_SCAN_LIMIT}),
+            )
+            .await
+            .map_err(|e| ToolingError::Database(e.to_string()))?;
+
+        Ok(result.memories)
+    }
+
+    /// Totals per `memory_type` and the `created_at` span, aggregated by `countUserMemories`.
+    ///
+    /// When the aggregation is unavailable this falls back to scanning the user's memories
+    /// (one `getUserMemories` round-trip of up to `USER_MEMORY_SCAN_LIMIT` rows), which is
+    /// slow for large stores and undercounts past the cap.
+    pub async fn memory_stats(&self, user_id: &str) -> Result<MemoryStats, ToolingError> {
+        #[derive(Deserialize)]
+        struct CountResult {
+            #[serde(default)]
+            count: Option<usize>,
+            #[serde(default)]
+            by_type: Vec<TypeCount>,
+            #[serde(default)]
+            oldest: Option<UserMemoryNode>,
+            #[serde(default)]
+            newest: Option<UserMemoryNode>,
+        }
+        #[derive(Deserialize)]
+        struct TypeCount {
+            #[serde(default)]
+            memory_type: String,
+            #[serde(default)]
+            count: usize,
+        }
+
+        match self.db
+            .execute_query::<CountResult, _>("countUserMemories", &serde_json::json!({"user_id": user_id}))
+            .await
+        {
+            // A non-zero total without its breakdown is a query that can't group; scan instead.
+            Ok(CountResult { count: Some(total), by_type, oldest, newest }) if total == 0 || !by_type.is_empty() => {
+                let mut stats = MemoryStats { total, ..Default::default() };
+                for group in by_type {
+                    *stats.by_type.entry(group.memory_type).or_insert(0) += group.count;
+                }
+                stats.oldest_created_at = oldest.map(|m| m.created_at).filter(|at| !at.is_empty());
+                stats.newest_created_at = newest.map(|m| m.created_at).filter(|at| !at.is_empty());
+                return Ok(stats);
+            }
+            Ok(_) => debug!("countUserMemories returned no breakdown for user={}, scanning", user_id),
+            Err(e) => debug!("countUserMemories failed for user={}: {}, scanning", user_id, e),
+        }
+
+        let memories = self.fetch_user_memories(user_id).await?;
+        Ok(MemoryStats::from_memories(&memories))
+    }
+
+    /// Up to `limit` of the user's live memories, newest first, after skipping the newest
+    /// `offset`. Offsets shift when memories are added; `list_memories_page` pages stably.
+    pub async fn list_memories(
         &self,
-        query: &str,
         user_id: &str,
-        concept_type: Option<&str>,
-        tags: Option<&str>,
-        mode: &str,
         limit: usize,
+        offset: usize,
     ) -> Result<Vec<SearchMemoryResult>, ToolingError> {
-        info!("Concept search: '{}...' type={:?} tags={:?}", 
-            safe_truncate(query, 30), concept_type, tags);
+        let (memories, _) = self.list_memories_page(user_id, offset.saturating_add(limit), None).await?;
+        Ok(memories.into_iter().skip(offset).collect())
+    }
 
-        
-        let query_embedding = self
-            .embedder
-            .generate(query, true)
-            .await
-            .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+    /// One page of the user's live memories, newest first, and the cursor for the next page
+    /// (`None` on the last one). Only the page, plus memories sharing the cursor's timestamp,
+    /// is read from the database.
+    pub async fn list_memories_page(
+        &self,
+        user_id: &str,
+        limit: usize,
+        before: Option<&MemoryCursor>,
+    ) -> Result<(Vec<SearchMemoryResult>, Option<MemoryCursor>), ToolingError> {
+        #[derive(Deserialize)]
+        struct UserMemoriesResult {
+            #[serde(default)]
+            memories: Vec<UserMemoryNode>,
+        }
 
-        let candidates = self
-            .search_engine
-            .search(query, &query_embedding, user_id, limit * 3, mode, None)
-            .await?;
+        debug!("Listing memories for user={} (limit={}, before={:?})", user_id, limit, before);
 
-        if candidates.is_empty() {
-            return Ok(Vec::new());
+        let mut memories = Vec::new();
+        if let Some(cursor) = before {
+            let ties: UserMemoriesResult = self.db
+                .execute_query(
+                    "getUserMemoriesAt",
+                    &serde_json::json!({"user_id": user_id, "created_at": cursor.created_at}),
+                )
+                .await
+                .map_err(|e| ToolingError::Database(e.to_string()))?;
+            memories.extend(ties.memories);
         }
+        let older: UserMemoriesResult = self.db
+            .execute_query(
+                "getUserMemoriesBefore",
+                &serde_json::json!({
+                    "user_id": user_id,
+                    "before": before.map_or(LIST_START, |cursor| cursor.created_at.as_str()),
+                    // One extra row tells whether another page follows.
+                    "limit": limit as i64 + 1,
+                }),
+            )
+            .await
+            .map_err(|e| ToolingError::Database(e.to_string()))?;
+        memories.extend(older.memories);
+
+        let (page, next) = paginate_memories(memories, before, limit);
+        debug!("Listed {} memories for user={}", page.len(), user_id);
+        Ok((page, next))
+    }
+
+    
+    pub async fn repair(&self, user_id: &str, dry_run: bool) -> Result<RepairReport, ToolingError> {
+        info!("Repairing memory store for user={} (dry_run={})", user_id, dry_run);
+
+        let memories = self.fetch_user_memories(user_id).await?;
+        let mut report = RepairReport {
+            memories_checked: memories.len(),
+            dry_run,
+            ..Default::default()
+        };
 
         
-        let mut results = Vec::new();
-        
-        for candidate in candidates {
-            
-            #[derive(serde::Deserialize)]
-            struct ConceptsResult {
-                #[serde(default)]
-                instance_of: Vec<ConceptNode>,
-                #[serde(default)]
-                belongs_to: Vec<ConceptNode>,
+        let mut dead_ids: std::collections::BTreeSet<String> = memories
+            .iter()
+            .filter(|m| m.is_deleted != 0)
+            .map(|m| m.memory_id.clone())
+            .collect();
+
+        for memory in memories.iter().filter(|m| m.is_deleted == 0) {
+            if let Ok(conns) = self.db.execute_query::<LogicalConnections, _>(
+                "getMemoryLogicalConnections",
+                &serde_json::json!({"memory_id": memory.memory_id}),
+            ).await {
+                let dangling = conns.dangling_ids();
+                if !dangling.is_empty() {
+                    debug!("Memory {} has {} dangling reasoning edges", memory.memory_id, dangling.len());
+                }
+                dead_ids.extend(dangling);
             }
-            
-            #[derive(serde::Deserialize)]
-            struct ConceptNode {
+        }
+
+        for dead_id in &dead_ids {
+            let edge_count = self.db
+                .execute_query::<LogicalConnections, _>(
+                    "getMemoryLogicalConnections",
+                    &serde_json::json!({"memory_id": dead_id}),
+                )
+                .await
+                .map(|c| c.edge_count())
+                .unwrap_or(0);
+
+            #[derive(Deserialize, Default)]
+            struct EntityLinks {
                 #[serde(default)]
-                concept_id: String,
+                entities: Vec<serde_json::Value>,
                 #[serde(default)]
-                name: String,
+                mentions: Vec<serde_json::Value>,
             }
 
-            if let Ok(concepts) = self.db
-                .execute_query::<ConceptsResult, _>(
-                    "getMemoryConcepts",
-                    &serde_json::json!({"memory_id": candidate.memory_id}),
+            let link_count = self.db
+                .execute_query::<EntityLinks, _>(
+                    "getMemoryEntities",
+                    &serde_json::json!({"memory_id": dead_id}),
                 )
                 .await
-            {
-                
-                let matches_type = match concept_type {
-                    Some(ct) => {
-                        let has_db_link = concepts.instance_of.iter().any(|c| 
-                            c.name.to_lowercase() == ct.to_lowercase() ||
-                            c.concept_id.to_lowercase().contains(&ct.to_lowercase())
-                        );
-                        
-                        if has_db_link {
-                            true
-                        } else {
-                            let ontology = self.ontology_manager.read();
-                            if ontology.is_loaded() {
-                                let mapped = ontology.map_memory_to_concepts(&candidate.content, None);
-                                mapped.iter().any(|m| 
-                                    m.concept.name.to_lowercase() == ct.to_lowercase() ||
-                                    m.concept.id.to_lowercase() == ct.to_lowercase()
-                                )
-                            } else {
-                                false
-                            }
-                        }
-                    }
-                    None => true,
-                };
+                .map(|l| l.entities.len() + l.mentions.len())
+                .unwrap_or(0);
 
-                
-                let matches_tags = match tags {
-                    Some(t) => {
-                        let tag_list: Vec<&str> = t.split(',').map(|s| s.trim()).collect();
-                        tag_list.iter().any(|tag| 
-                            candidate.content.to_lowercase().contains(&tag.to_lowercase())
-                        )
-                    }
-                    None => true,
-                };
+            report.dangling_edges += edge_count;
+            report.orphaned_entity_links += link_count;
 
-                if matches_type && matches_tags {
-                    results.push(SearchMemoryResult {
-                        memory_id: candidate.memory_id,
-                        content: candidate.content,
-                        score: candidate.score as f64,
-                        method: format!("concept_search_{}", mode),
-                        metadata: candidate.metadata,
-                        created_at: candidate.created_at,
-                    });
-
-                    if results.len() >= limit {
-                        break;
-                    }
-                }
+            if dry_run {
+                continue;
             }
-        }
 
-        info!("Concept search found {} results", results.len());
-        Ok(results)
+            if edge_count > 0 {
+                if let Err(e) = self.db.execute_query::<serde_json::Value, _>(
+                    "deleteMemoryEdges",
+                    &serde_json::json!({"memory_id": dead_id}),
+                ).await {
+                    warn!("Failed to remove dangling edges of {}: {}", dead_id, e);
+                }
+            }
+            if link_count > 0 {
+                if let Err(e) = self.db.execute_query::<serde_json::Value, _>(
+                    "deleteMemoryEntityLinks",
+                    &serde_json::json!({"memory_id": dead_id}),
+                ).await {
+                    warn!("Failed to remove entity links of {}: {}", dead_id, e);
+                }
+            }
+        }
+
+        // Embeddings carry no owner, so only those still hanging off this user's deleted
+        // memories are in scope; parentless ones are left to `purge_orphaned_embeddings`.
+        for memory in memories.iter().filter(|m| m.is_deleted != 0) {
+            let Some(embedding) = self.memory_embedding_node(&memory.memory_id).await else {
+                continue;
+            };
+            report.orphaned_embeddings += 1;
+            if !dry_run {
+                self.delete_embedding_node(&embedding.id).await;
+            }
+        }
+
+        info!(
+            "Repair complete: {} dangling edges, {} orphaned embeddings, {} orphaned entity links",
+            report.dangling_edges, report.orphaned_embeddings, report.orphaned_entity_links
+        );
+        Ok(report)
+    }
+
+    /// Store-wide maintenance: counts (and unless `dry_run`, deletes) every `MemoryEmbedding`
+    /// with neither a memory nor a chunk parent. Embeddings record no owner, so this cannot be
+    /// scoped to a user and belongs to operators, not per-tenant callers.
+    pub async fn purge_orphaned_embeddings(&self, dry_run: bool) -> Result<usize, ToolingError> {
+        #[derive(Deserialize, Default)]
+        struct OrphanedEmbeddings {
+            #[serde(default)]
+            embeddings: Vec<EmbeddingNode>,
+        }
+
+        let orphans = self.db
+            .execute_query::<OrphanedEmbeddings, _>("findOrphanedMemoryEmbeddings", &serde_json::json!({}))
+            .await
+            .map_err(|e| ToolingError::Database(e.to_string()))?;
+        info!("Found {} orphaned embeddings (dry_run={})", orphans.embeddings.len(), dry_run);
+
+        if !dry_run {
+            for embedding in &orphans.embeddings {
+                self.delete_embedding_node(&embedding.id).await;
+            }
+        }
+        Ok(orphans.embeddings.len())
+    }
+
+    async fn memory_embedding_node(&self, memory_id: &str) -> Option<EmbeddingNode> {
+        #[derive(Deserialize)]
+        struct MemoryEmbedding {
+            embedding: Option<EmbeddingNode>,
+        }
+
+        self.db
+            .execute_query::<MemoryEmbedding, _>(
+                "getMemoryEmbeddingByMemoryId",
+                &serde_json::json!({"memory_id": memory_id}),
+            )
+            .await
+            .ok()
+            .and_then(|result| result.embedding)
+    }
+
+    async fn delete_embedding_node(&self, embedding_id: &str) {
+        if let Err(e) = self.db.execute_query::<serde_json::Value, _>(
+            "deleteEmbeddingById",
+            &serde_json::json!({"embedding_id": embedding_id}),
+        ).await {
+            warn!("Failed to remove embedding {}: {}", embedding_id, e);
+        }
+    }
+
+    
+    /// Breadth-first graph around `memory_id`, or around the user's `max_seeds` most recent
+    /// memories (default `DEFAULT_GRAPH_SEEDS`) when no start memory is given.
+    pub async fn get_memory_graph(
+        &self,
+        user_id: &str,
+        memory_id: Option<&str>,
+        depth: usize,
+        max_seeds: Option<usize>,
+    ) -> Result<MemoryGraph, ToolingError> {
+        info!("Getting memory graph for user={}, memory={:?}, depth={}", user_id, memory_id, depth);
+
+        let mut graph = MemoryGraph::default();
+        // Memories that were expanded or are queued for the next layer.
+        let mut visited = HashSet::new();
+
+        
+        let start_ids: Vec<String> = if let Some(mid) = memory_id {
+            vec![mid.to_string()]
+        } else {
+            
+            #[derive(serde::Deserialize)]
+            struct UserMemoriesResult {
+                #[serde(default)]
+                memories: Vec<MemoryNode>,
+            }
+            #[derive(serde::Deserialize)]
+            struct MemoryNode {
+                memory_id: String,
+                #[serde(default)]
+                content: String,
+            }
+
+            match self.db.execute_query::<UserMemoriesResult, _>(
+                "getUserMemories",
+                &serde_json::json!({"user_id": user_id, "limit": max_seeds.unwrap_or(DEFAULT_GRAPH_SEEDS) as i64}),
+            ).await {
+                Ok(result) => result.memories.into_iter().map(|m| m.memory_id).collect(),
+                Err(_) => Vec::new(),
+            }
+        };
+
+        let mut current_ids: Vec<String> = start_ids.into_iter().filter(|id| visited.insert(id.clone())).collect();
+        if current_ids.is_empty() {
+            return Ok(graph);
+        }
+
+        let mut current_depth = 0;
+
+        while current_depth < depth && !current_ids.is_empty() {
+            let mut next_ids = Vec::new();
+
+            for mid in &current_ids {
+                
+                #[derive(serde::Deserialize)]
+                struct MemoryResult {
+                    #[serde(default)]
+                    memory: Option<MemoryData>,
+                }
+                #[derive(serde::Deserialize)]
+                struct MemoryData {
+                    memory_id: String,
+                    #[serde(default)]
+                    content: String,
+                    #[serde(default)]
+                    memory_type: String,
+                }
+
+                if let Ok(result) = self.db.execute_query::<MemoryResult, _>(
+                    "getMemory",
+                    &serde_json::json!({"memory_id": mid}),
+                ).await {
+                    if let Some(mem) = result.memory {
+                        graph.nodes.push(serde_json::json!({
+                            "id": mem.memory_id,
+                            "content": mem.content,
+                            "type": mem.memory_type,
+                        }));
+                    }
+                }
+
+                
+                #[derive(serde::Deserialize, Default)]
+                struct ConnectionsResult {
+                    #[serde(default)]
+                    implies_out: Vec<ConnectedMemory>,
+                    #[serde(default)]
+                    implies_in: Vec<ConnectedMemory>,
+                    #[serde(default)]
+                    because_out: Vec<ConnectedMemory>,
+                    #[serde(default)]
+                    because_in: Vec<ConnectedMemory>,
+                    #[serde(default)]
+                    contradicts_out: Vec<ConnectedMemory>,
+                    #[serde(default)]
+                    contradicts_in: Vec<ConnectedMemory>,
+                    #[serde(default)]
+                    relation_out: Vec<ConnectedMemory>,
+                    #[serde(default)]
+                    relation_in: Vec<ConnectedMemory>,
+                    #[serde(flatten)]
+                    edges: GraphEdges,
+                }
+                #[derive(serde::Deserialize)]
+                struct ConnectedMemory {
+                    #[serde(default)]
+                    id: String,
+                    memory_id: String,
+                    #[serde(default)]
+                    content: String,
+                }
+
+                if let Ok(conns) = self.db.execute_query::<ConnectionsResult, _>(
+                    "getMemoryLogicalConnections",
+                    &serde_json::json!({"memory_id": mid}),
+                ).await {
+                    let edges = &conns.edges;
+                    let (decay, now) = (self.reasoning_engine.strength_decay(), chrono::Utc::now());
+                    
+                    for conn in conns.implies_out {
+                        let weight = graph_edge_weight(find_graph_edge(&edges.implies_out, &conn.id, false), "IMPLIES", decay, now);
+                        graph.add_edge(mid, &conn.memory_id, "IMPLIES", weight);
+                        next_ids.push(conn.memory_id);
+                    }
+                    for conn in conns.implies_in {
+                        let weight = graph_edge_weight(find_graph_edge(&edges.implies_in, &conn.id, true), "IMPLIES", decay, now);
+                        graph.add_edge(&conn.memory_id, mid, "IMPLIES", weight);
+                        next_ids.push(conn.memory_id);
+                    }
+                    
+                    for conn in conns.because_out {
+                        let weight = graph_edge_weight(find_graph_edge(&edges.because_out, &conn.id, false), "BECAUSE", decay, now);
+                        graph.add_edge(mid, &conn.memory_id, "BECAUSE", weight);
+                        next_ids.push(conn.memory_id);
+                    }
+                    for conn in conns.because_in {
+                        let weight = graph_edge_weight(find_graph_edge(&edges.because_in, &conn.id, true), "BECAUSE", decay, now);
+                        graph.add_edge(&conn.memory_id, mid, "BECAUSE", weight);
+                        next_ids.push(conn.memory_id);
+                    }
+                    
+                    for conn in conns.contradicts_out {
+                        graph.add_edge(mid, &conn.memory_id, "CONTRADICTS", graph_edge_weight(None, "CONTRADICTS", decay, now));
+                        next_ids.push(conn.memory_id);
+                    }
+                    
+                    for conn in conns.relation_out {
+                        let edge = find_graph_edge(&edges.relation_out, &conn.id, false);
+                        let relation = edge
+                            .and_then(|e| e.relation_type.as_deref())
+                            .filter(|r| !r.is_empty())
+                            .map_or_else(|| "SUPPORTS".to_string(), str::to_uppercase);
+                        graph.add_edge(mid, &conn.memory_id, &relation, graph_edge_weight(edge, "MEMORY_RELATION", decay, now));
+                        next_ids.push(conn.memory_id);
+                    }
+                }
+            }
+
+            current_ids = next_ids.into_iter().filter(|id| visited.insert(id.clone())).collect();
+            current_depth += 1;
+        }
+
+        info!(
+            "Graph built: {} nodes, {} edges {:?}",
+            graph.nodes.len(), graph.edges.len(), graph.edge_type_counts
+        );
+        Ok(graph)
+    }
+
+    
+    pub async fn export_graph(&self, user_id: &str, format: GraphFormat) -> Result<String, ToolingError> {
+        let graph = self.get_memory_graph(user_id, None, DEFAULT_EXPORT_GRAPH_DEPTH, None).await?;
+        Ok(graph.export(format))
+    }
+
+    
+    pub async fn search_reasoning_chain(
+        &self,
+        query: &str,
+        user_id: &str,
+        chain_mode: &str,
+        max_depth: usize,
+        limit: usize,
+        edge_types: Option<&[ReasoningType]>,
+    ) -> Result<ReasoningChainSearchResult, ToolingError> {
+        info!("Reasoning chain search: '{}...' mode={} depth={} limit={}", 
+            safe_truncate(query, 30), chain_mode, max_depth, limit);
+
+        
+        let query_embedding = self
+            .embedder
+            .generate_query(query, true)
+            .await
+            .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+
+        let seed_results = self
+            .search_engine
+            .search(query, &query_embedding, user_id, limit, "contextual", None)
+            .await?;
+
+        if seed_results.is_empty() {
+            debug!("No seed memories found for query");
+            return Ok(ReasoningChainSearchResult {
+                chains: Vec::new(),
+                total_memories: 0,
+                deepest_chain: 0,
+            });
+        }
+
+        
+        let mut all_chains = Vec::new();
+        let mut max_chain_depth = 0;
+        // Chains from neighbouring seeds walk the same edges; each is counted once.
+        let mut seen_edges = HashSet::new();
+
+        for seed in &seed_results {
+            match self.reasoning_engine.get_chain(&seed.memory_id, chain_mode, max_depth, edge_types, ChainSelection::LlmGuided).await {
+                Ok(chain) => {
+                    if !chain.relations.is_empty() {
+                        let chain_depth = chain.depth;
+                        max_chain_depth = max_chain_depth.max(chain_depth);
+                        seen_edges.extend(chain.relations.iter().map(|r| {
+                            canonical_edge_key(&r.from_memory_id, &r.to_memory_id, r.relation_type.edge_name())
+                        }));
+
+                        all_chains.push(ToolingReasoningChain {
+                            seed: SearchMemoryResult {
+                                memory_id: seed.memory_id.clone(),
+                                content: seed.content.clone(),
+                                score: seed.score as f64,
+                                method: seed.method.clone(),
+                                metadata: seed.metadata.clone(),
+                                created_at: seed.created_at.clone(),
+                                provenance: None,
+                            },
+                            nodes: chain.relations.iter().map(|r| ChainNode {
+                                memory_id: r.to_memory_id.clone(),
+                                content: r.to_memory_content.clone(),
+                                relation: r.relation_type.edge_name().to_string(),
+                                depth: 0,
+                            }).collect(),
+                            chain_type: chain.chain_type.clone(),
+                            reasoning_trail: chain.reasoning_trail.clone(),
+                        });
+                    }
+                }
+                Err(e) => {
+                    debug!("Failed to get chain for {}: {}", seed.memory_id, e);
+                }
+            }
+        }
+
+        let total_memories = seen_edges.len();
+        info!("Found {} chains, max_depth={}, total_memories={}", 
+            all_chains.len(), max_chain_depth, total_memories);
+
+        Ok(ReasoningChainSearchResult {
+            chains: all_chains,
+            total_memories,
+            deepest_chain: max_chain_depth,
+        })
+    }
+
+    
+    /// Candidates come from one hybrid search; the concept filter is one `getConceptMemories`
+    /// round-trip per accepted concept instead of a `getMemoryConcepts` lookup per candidate.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_by_concept(
+        &self,
+        query: &str,
+        user_id: &str,
+        concept_type: Option<&str>,
+        tags: Option<&str>,
+        mode: &str,
+        limit: usize,
+        include_subconcepts: bool,
+    ) -> Result<Vec<SearchMemoryResult>, ToolingError> {
+        info!("Concept search: '{}...' type={:?} tags={:?}", 
+            safe_truncate(query, 30), concept_type, tags);
+
+        let (accepted_types, concept_ids) = self.accepted_concepts(concept_type, include_subconcepts);
+
+        
+        let query_embedding = self
+            .embedder
+            .generate_query(query, true)
+            .await
+            .map_err(|e| ToolingError::Embedding(e.to_string()))?;
+
+        let candidates = self
+            .search_engine
+            .search(query, &query_embedding, user_id, self.search_engine.candidate_fetch(limit, 3), mode, None)
+            .await?;
+
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let linked = match concept_type {
+            Some(_) => self.concept_linked_memories(&concept_ids).await,
+            None => None,
+        };
+        if concept_type.is_some() && linked.is_none() {
+            debug!("Concepts {:?} not in graph, checking candidates one by one", concept_ids);
+        }
+
+        let tag_list: Option<Vec<String>> = tags.map(|t| t.split(',').map(|s| s.trim().to_lowercase()).collect());
+        
+        let mut results = Vec::new();
+        
+        for candidate in candidates {
+            let matches_type = match (concept_type, &linked) {
+                (None, _) => true,
+                (Some(_), Some(linked)) => {
+                    linked.contains(&candidate.memory_id)
+                        || self.mapped_concept_matches(&candidate.content, &accepted_types)
+                }
+                (Some(_), None) => {
+                    let Some(has_db_link) = self.candidate_concept_link(&candidate.memory_id, &accepted_types).await else {
+                        continue;
+                    };
+                    has_db_link || self.mapped_concept_matches(&candidate.content, &accepted_types)
+                }
+            };
+
+            
+            let matches_tags = match &tag_list {
+                Some(tag_list) => {
+                    let content = candidate.content.to_lowercase();
+                    tag_list.iter().any(|tag| content.contains(tag.as_str()))
+                }
+                None => true,
+            };
+
+            if matches_type && matches_tags {
+                results.push(SearchMemoryResult {
+                    memory_id: candidate.memory_id,
+                    content: candidate.content,
+                    score: candidate.score as f64,
+                    method: format!("concept_search_{}", mode),
+                    metadata: candidate.metadata,
+                    created_at: candidate.created_at,
+                    provenance: None,
+                });
+
+                if results.len() >= limit {
+                    break;
+                }
+            }
+        }
+
+        info!("Concept search found {} results", results.len());
+        Ok(results)
+    }
+
+    /// Memories linked to the entity called `entity_name` through EXTRACTED_ENTITY edges,
+    /// strongest link first and newest first among equals. Several entities may share a
+    /// name; `entity_type` picks one of them, otherwise all are merged. An unknown entity
+    /// yields no results rather than an error.
+    pub async fn memories_for_entity(
+        &self,
+        entity_name: &str,
+        entity_type: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SearchMemoryResult>, ToolingError> {
+        #[derive(Deserialize)]
+        struct EntityMemoriesResult {
+            #[serde(default)]
+            memories: Vec<UserMemoryNode>,
+            #[serde(default)]
+            memory_edges: Vec<EntityLinkEdge>,
+        }
+
+        #[derive(Deserialize)]
+        struct EntityLinkEdge {
+            #[serde(default)]
+            from_node: String,
+            #[serde(default)]
+            confidence: Option<i64>,
+        }
+
+        let entities = self.entity_manager.find_by_name(entity_name, entity_type).await?;
+        if entities.is_empty() {
+            debug!("No entity named '{}' ({:?})", entity_name, entity_type);
+            return Ok(Vec::new());
+        }
+
+        let lookups = entities.iter().map(|entity| async move {
+            let linked = self
+                .db
+                .execute_query::<EntityMemoriesResult, _>(
+                    "getEntityMemories",
+                    &serde_json::json!({"entity_id": entity.entity_id}),
+                )
+                .await
+                .map_err(|e| ToolingError::Database(e.to_string()))?;
+            Ok::<_, ToolingError>((entity, linked))
+        });
+
+        let mut by_memory: HashMap<String, SearchMemoryResult> = HashMap::new();
+        for lookup in futures::future::join_all(lookups).await {
+            let (entity, linked) = lookup?;
+            for memory in linked.memories {
+                if memory.is_deleted != 0 || !memory.archived_at.is_empty() {
+                    continue;
+                }
+                let confidence = linked
+                    .memory_edges
+                    .iter()
+                    .find(|e| !memory.id.is_empty() && e.from_node == memory.id)
+                    .and_then(|e| e.confidence)
+                    .unwrap_or(i64::from(crate::llm::extractor::DEFAULT_ENTITY_CONFIDENCE))
+                    .clamp(0, 100);
+
+                let mut result = memory.into_result(confidence as f64 / 100.0, "entity");
+                result.metadata.insert("entity_id".to_string(), serde_json::json!(entity.entity_id));
+                result.metadata.insert("entity_type".to_string(), serde_json::json!(entity.entity_type.to_string()));
+                result.metadata.insert("link_confidence".to_string(), serde_json::json!(confidence));
+                match by_memory.get(&result.memory_id) {
+                    Some(existing) if existing.score >= result.score => {}
+                    _ => {
+                        by_memory.insert(result.memory_id.clone(), result);
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<SearchMemoryResult> = by_memory.into_values().collect();
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.created_at.cmp(&a.created_at))
+                .then_with(|| a.memory_id.cmp(&b.memory_id))
+        });
+        results.truncate(limit);
+        info!("Entity '{}' links {} memories", entity_name, results.len());
+        Ok(results)
+    }
+
+    /// Folds the entity `alias_id` into `canonical_id`; see `EntityManager::merge_entities`.
+    pub async fn merge_entities(&self, canonical_id: &str, alias_id: &str) -> Result<usize, ToolingError> {
+        Ok(self.entity_manager.merge_entities(canonical_id, alias_id).await?)
+    }
+
+    /// Lowercased names to match against, plus the concept ids to look up in the graph.
+    fn accepted_concepts(&self, concept_type: Option<&str>, include_subconcepts: bool) -> (Vec<String>, Vec<String>) {
+        let Some(ct) = concept_type else {
+            return (Vec::new(), Vec::new());
+        };
+        let ontology = self.ontology_manager.read();
+        let Some(root) = ontology.resolve_concept(ct) else {
+            return (vec![ct.to_lowercase()], vec![ct.to_string()]);
+        };
+
+        let mut concepts = vec![root.clone()];
+        if include_subconcepts {
+            concepts.extend(ontology.descendants(&root.concept_id));
+        }
+        let mut types = vec![ct.to_lowercase()];
+        types.extend(concepts.iter().skip(1).map(|c| c.name.to_lowercase()));
+        (types, concepts.into_iter().map(|c| c.concept_id).collect())
+    }
+
+    /// Memories with an INSTANCE_OF edge to any of `concept_ids`; `None` when none of them is in the graph.
+    async fn concept_linked_memories(&self, concept_ids: &[String]) -> Option<HashSet<String>> {
+        #[derive(serde::Deserialize)]
+        struct ConceptMemoriesResult {
+            #[serde(default)]
+            concept: Option<serde_json::Value>,
+            #[serde(default)]
+            memories: Vec<MemoryRef>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct MemoryRef {
+            #[serde(default)]
+            memory_id: String,
+        }
+
+        let lookups = concept_ids.iter().map(|concept_id| async move {
+            self.db
+                .execute_query::<ConceptMemoriesResult, _>(
+                    "getConceptMemories",
+                    &serde_json::json!({"concept_id": concept_id}),
+                )
+                .await
+        });
+
+        let mut found = false;
+        let mut linked = HashSet::new();
+        for response in futures::future::join_all(lookups).await.into_iter().flatten() {
+            if response.concept.is_some_and(|c| !c.is_null()) {
+                found = true;
+                linked.extend(response.memories.into_iter().map(|m| m.memory_id));
+            }
+        }
+        found.then_some(linked)
+    }
+
+    /// Per-candidate fallback; `None` when the lookup itself fails.
+    async fn candidate_concept_link(&self, memory_id: &str, accepted_types: &[String]) -> Option<bool> {
+        #[derive(serde::Deserialize)]
+        struct ConceptsResult {
+            #[serde(default)]
+            instance_of: Vec<ConceptNode>,
+        }
+        
+        #[derive(serde::Deserialize)]
+        struct ConceptNode {
+            #[serde(default)]
+            concept_id: String,
+            #[serde(default)]
+            name: String,
+        }
+
+        let concepts = self.db
+            .execute_query::<ConceptsResult, _>(
+                "getMemoryConcepts",
+                &serde_json::json!({"memory_id": memory_id}),
+            )
+            .await
+            .ok()?;
+
+        Some(concepts.instance_of.iter().any(|c| accepted_types.iter().any(|ct|
+            c.name.to_lowercase() == *ct ||
+            c.concept_id.to_lowercase().contains(ct.as_str())
+        )))
+    }
+
+    fn mapped_concept_matches(&self, content: &str, accepted_types: &[String]) -> bool {
+        let ontology = self.ontology_manager.read();
+        if !ontology.is_loaded() {
+            return false;
+        }
+        ontology.map_memory_to_concepts(content, None).iter().any(|m| accepted_types.iter().any(|ct|
+            m.concept.name.to_lowercase() == *ct ||
+            m.concept.id.to_lowercase() == *ct
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{ollama_embedder, spawn_stub, StubLlm, StubServer};
+
+    fn staged(user_id: &str, memory_id: &str, vector: Vec<f32>) -> StagedMemory {
+        StagedMemory {
+            user_id: user_id.to_string(),
+            memory_id: memory_id.to_string(),
+            content: format!("content of {}", memory_id),
+            vector,
+        }
+    }
+
+    fn listed(memory_id: &str, created_at: &str, is_deleted: i64) -> UserMemoryNode {
+        serde_json::from_value(serde_json::json!({
+            "memory_id": memory_id,
+            "content": format!("content of {}", memory_id),
+            "created_at": created_at,
+            "is_deleted": is_deleted,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_paginate_memories_newest_first_without_overlap() {
+        let memories = vec![
+            listed("m1", "2024-01-01T00:00:00Z", 0),
+            listed("m3", "2024-03-01T00:00:00Z", 0),
+            listed("m2b", "2024-02-01T00:00:00Z", 0),
+            listed("m2a", "2024-02-01T00:00:00Z", 0),
+            listed("gone", "2024-04-01T00:00:00Z", 1),
+        ];
+
+        let (first, cursor) = paginate_memories(memories.clone(), None, 2);
+        assert_eq!(cursor, Some(MemoryCursor { created_at: "2024-02-01T00:00:00Z".to_string(), memory_id: "m2a".to_string() }));
+
+        // A memory added between pages shifts nothing after the cursor.
+        let mut memories = memories;
+        memories.push(listed("m4", "2024-05-01T00:00:00Z", 0));
+        let (second, cursor) = paginate_memories(memories, cursor.as_ref(), 2);
+
+        let ids: Vec<&str> = first.iter().chain(&second).map(|m| m.memory_id.as_str()).collect();
+        assert_eq!(ids, vec!["m3", "m2a", "m2b", "m1"]);
+        assert_eq!(cursor, None);
+        assert!(first.iter().all(|m| m.method == "listing"));
+    }
+
+    #[tokio::test]
+    async fn test_list_memories_skips_offset_newest() {
+        let stub = spawn_stub(|path, body| match path {
+            "getUserMemoriesBefore" => {
+                assert_eq!(body["limit"], 4);
+                Some(serde_json::json!({"memories": [
+                    {"memory_id": "m1", "content": "one", "created_at": "2024-01-01T00:00:00Z"},
+                    {"memory_id": "m3", "content": "three", "created_at": "2024-03-01T00:00:00Z"},
+                    {"memory_id": "m2", "content": "two", "created_at": "2024-02-01T00:00:00Z"},
+                ]}))
+            }
+            _ => None,
+        })
+        .await;
+        let manager = stub_manager(&stub, counting_llm());
+
+        let page = manager.list_memories("alice", 2, 1).await.unwrap();
+        assert_eq!(page.iter().map(|m| m.memory_id.as_str()).collect::<Vec<_>>(), ["m2", "m1"]);
+    }
+
+    #[test]
+    fn test_contradiction_candidates_stay_within_clusters() {
+        let tea = [1.0f32, 0.0, 0.0];
+        let tea_too = [0.95f32, 0.05, 0.0];
+        let tea_again = [0.9f32, 0.1, 0.0];
+        let work = [0.0f32, 1.0, 0.0];
+        let vectors: Vec<(&str, &[f32])> = vec![("m1", &tea), ("m2", &tea_too), ("m3", &tea_again), ("m4", &work)];
+
+        let pairs = contradiction_candidates(&vectors, &HashSet::new(), 0.8, 5);
+        let ids: Vec<(usize, usize)> = pairs.iter().map(|&(a, b, _)| (a, b)).collect();
+        assert_eq!(ids, vec![(0, 1), (1, 2), (0, 2)]);
+
+        let known = HashSet::from([contradiction_pair_key("m2", "m1")]);
+        let pairs = contradiction_candidates(&vectors, &known, 0.8, 1);
+        assert_eq!(pairs.iter().map(|&(a, b, _)| (a, b)).collect::<Vec<_>>(), vec![(1, 2)]);
+    }
+
+    // Extraction always comes back empty, so the pipeline stores the raw message.
+    fn counting_llm() -> Arc<StubLlm> {
+        Arc::new(StubLlm::replying("{}"))
+    }
+
+    // Serves both the embedding endpoint and the HelixDB queries the add pipeline needs,
+    // keeping memory_id -> user_id and the idempotency records so retries see earlier writes.
+    async fn spawn_ingest_stub() -> (StubServer, Arc<parking_lot::Mutex<HashMap<String, String>>>) {
+        let memories: Arc<parking_lot::Mutex<HashMap<String, String>>> = Arc::default();
+        let stored = Arc::clone(&memories);
+        let archived: Arc<parking_lot::Mutex<HashMap<String, String>>> = Arc::default();
+        let history: Arc<parking_lot::Mutex<Vec<serde_json::Value>>> = Arc::default();
+        let records: Arc<parking_lot::Mutex<HashMap<(String, String), String>>> = Arc::default();
+
+        let stub = spawn_stub(move |path, body| {
+            let field = |key: &str| body[key].as_str().unwrap_or_default().to_string();
+            Some(match path {
+                "api/embeddings" => serde_json::json!({"embedding": [0.1, 0.2, 0.3]}),
+                "addMemory" => {
+                    memories.lock().insert(field("memory_id"), field("user_id"));
+                    serde_json::json!({"memory": {"id": format!("n_{}", field("memory_id"))}})
+                }
+                "addIdempotencyRecord" => {
+                    records.lock().insert((field("user_id"), field("idempotency_key")), field("result"));
+                    serde_json::json!({"record": body})
+                }
+                "getIdempotencyRecord" => {
+                    let result = records.lock().get(&(field("user_id"), field("idempotency_key"))).cloned();
+                    serde_json::json!({"record": result.map(|result| serde_json::json!({"result": result}))})
+                }
+                "setMemoryArchivedAt" => {
+                    let memory_id = field("memory_id");
+                    let owner = memories.lock().get(&memory_id).cloned();
+                    match owner {
+                        None => serde_json::json!({}),
+                        Some(owner) => {
+                            match field("archived_at") {
+                                at if at.is_empty() => archived.lock().remove(&memory_id),
+                                at => archived.lock().insert(memory_id.clone(), at),
+                            };
+                            serde_json::json!({"updated": {"memory_id": memory_id, "user_id": owner}})
+                        }
+                    }
+                }
+                "addMemoryHistoryEvent" => {
+                    history.lock().push(body.clone());
+                    serde_json::json!({"event": body})
+                }
+                "getMemoryHistory" => {
+                    let memory_id = field("memory_id");
+                    let events: Vec<serde_json::Value> =
+                        history.lock().iter().filter(|e| e["memory_id"] == memory_id).cloned().collect();
+                    serde_json::json!({"events": events})
+                }
+                "getArchivedMemories" => {
+                    let user_id = field("user_id");
+                    let archived = archived.lock();
+                    let listed: Vec<serde_json::Value> = memories
+                        .lock()
+                        .iter()
+                        .filter(|(memory_id, owner)| **owner == user_id && archived.contains_key(*memory_id))
+                        .map(|(memory_id, _)| serde_json::json!({"memory_id": memory_id, "archived_at": archived[memory_id]}))
+                        .collect();
+                    serde_json::json!({"memories": listed})
+                }
+                "vectorSearch" => {
+                    let user_id = field("user_id");
+                    let archived = archived.lock();
+                    let mut hits: Vec<serde_json::Value> = memories
+                        .lock()
+                        .iter()
+                        .filter(|(_, owner)| **owner == user_id)
+                        .map(|(memory_id, _)| serde_json::json!({
+                            "memory_id": memory_id,
+                            "content": format!("content of {}", memory_id),
+                            "similarity_score": 0.9,
+                            "memory_type": "fact",
+                            "user_id": user_id,
+                            "created_at": "2024-01-01T00:00:00Z",
+                            "updated_at": "2024-01-01T00:00:00Z",
+                            "valid_from": "2024-01-01T00:00:00Z",
+                            "archived_at": archived.get(memory_id).cloned().unwrap_or_default(),
+                        }))
+                        .collect();
+                    hits.truncate(body["limit"].as_u64().unwrap_or(10) as usize);
+                    serde_json::json!({"memories": hits})
+                }
+                "smartVectorSearchWithChunks" => {
+                    let hits: Vec<serde_json::Value> = memories
+                        .lock()
+                        .iter()
+                        .map(|(memory_id, owner)| serde_json::json!({
+                            "memory_id": memory_id,
+                            "content": format!("content of {}", memory_id),
+                            "score": 0.95,
+                            "user_id": owner,
+                            "created_at": chrono::Utc::now().to_rfc3339(),
+                        }))
+                        .collect();
+                    serde_json::json!({"memories": hits})
+                }
+                "getEntitiesByName" if field("name") == "Alice" => serde_json::json!({"entities": [
+                    {"entity_id": "e_alice_person", "name": "Alice", "entity_type": "person"},
+                    {"entity_id": "e_alice_org", "name": "Alice", "entity_type": "organization"},
+                ]}),
+                "getEntityMemories" if field("entity_id") == "e_alice_person" => serde_json::json!({
+                    "entity": {"entity_id": "e_alice_person"},
+                    "memories": [
+                        {"id": "n1", "memory_id": "m_passing", "content": "Met Alice once", "created_at": "2024-03-01T00:00:00Z"},
+                        {"id": "n2", "memory_id": "m_moved", "content": "Alice moved to Lisbon", "created_at": "2024-01-01T00:00:00Z"},
+                        {"id": "n3", "memory_id": "m_old", "content": "Alice lived in Porto", "created_at": "2023-01-01T00:00:00Z", "archived_at": "2024-01-01T00:00:00Z"},
+                    ],
+                    "memory_edges": [
+                        {"from_node": "n1", "to_node": "e1", "confidence": 30},
+                        {"from_node": "n2", "to_node": "e1", "confidence": 90},
+                        {"from_node": "n3", "to_node": "e1", "confidence": 95},
+                    ],
+                }),
+                "getEntityMemories" if field("entity_id") == "e_alice_org" => serde_json::json!({
+                    "entity": {"entity_id": "e_alice_org"},
+                    "memories": [{"id": "n4", "memory_id": "m_company", "content": "Alice Corp raised a round", "created_at": "2024-02-01T00:00:00Z"}],
+                    "memory_edges": [{"from_node": "n4", "to_node": "e2", "confidence": 60}],
+                }),
+                // g_a -IMPLIES-> g_b -BECAUSE-> g_c -IMPLIES-> g_a, listed from both ends.
+                "getUserMemories" if field("user_id") == "graph_user" => {
+                    let mut seeds: Vec<serde_json::Value> =
+                        ["g_a", "g_b", "g_c"].iter().map(|id| serde_json::json!({"memory_id": id})).collect();
+                    seeds.truncate(body["limit"].as_u64().unwrap_or(10) as usize);
+                    serde_json::json!({"memories": seeds})
+                }
+                "countUserMemories" if field("user_id") == "stats_user" => serde_json::json!({
+                    "count": 5,
+                    "by_type": [{"memory_type": "fact", "count": 3}, {"memory_type": "preference", "count": 2}],
+                    "oldest": {"memory_id": "s1", "created_at": "2024-01-01T00:00:00Z"},
+                    "newest": {"memory_id": "s5", "created_at": "2024-06-01T00:00:00Z"},
+                }),
+                "countUserMemories" if field("user_id") == "scan_user" => serde_json::json!({"count": 3}),
+                "getUserMemories" if field("user_id") == "scan_user" => serde_json::json!({"memories": [
+                    {"memory_id": "s1", "memory_type": "fact", "created_at": "2024-03-01T00:00:00+02:00"},
+                    {"memory_id": "s2", "memory_type": "goal", "created_at": "2024-02-01T00:00:00Z"},
+                    {"memory_id": "s3", "memory_type": "fact", "created_at": "2024-05-01T00:00:00Z"},
+                    {"memory_id": "s4", "memory_type": "fact", "created_at": "2023-01-01T00:00:00Z", "is_deleted": 1},
+                ]}),
+                // d_tea and d_tea2 repeat each other; d_coffee contradicts d_tea.
+                "getUserContradictions" if field("user_id") == "dup_user" => serde_json::json!({
+                    "memories": [
+                        {"id": "n_tea", "memory_id": "d_tea", "content": "Likes tea", "memory_type": "preference", "importance": 40, "certainty": 70},
+                        {"id": "n_tea2", "memory_id": "d_tea2", "content": "Enjoys tea", "memory_type": "fact", "importance": 90, "certainty": 60},
+                        {"id": "n_coffee", "memory_id": "d_coffee", "content": "Hates tea, drinks coffee", "importance": 50},
+                        {"id": "n_work", "memory_id": "d_work", "content": "Works in Oslo", "importance": 50},
+                    ],
+                    "contradictions": [{"from_node": "n_coffee", "to_node": "n_tea"}],
+                }),
+                "getMemoryEmbeddingByMemoryId" if field("memory_id").starts_with("d_") => {
+                    let data = match field("memory_id").as_str() {
+                        "d_tea" => [1.0, 0.0, 0.0],
+                        "d_tea2" => [0.99, 0.1, 0.0],
+                        "d_coffee" => [0.98, -0.15, 0.0],
+                        _ => [0.0, 0.0, 1.0],
+                    };
+                    serde_json::json!({"embedding": {"data": data}})
+                }
+                "getMemory" if field("memory_id").starts_with("c_") => serde_json::json!({
+                    "memory": {"memory_id": field("memory_id"), "created_at": match field("memory_id").as_str() {
+                        "c_new" => "2024-06-01T00:00:00Z",
+                        _ => "2024-01-01T00:00:00Z",
+                    }},
+                }),
+                "getMemory" if field("memory_id").starts_with("g_") => serde_json::json!({
+                    "memory": {"memory_id": field("memory_id"), "content": "graph node", "memory_type": "fact"},
+                }),
+                "getMemoryLogicalConnections" => {
+                    let node = |id: &str| serde_json::json!([{"memory_id": id}]);
+                    match field("memory_id").as_str() {
+                        "g_a" => serde_json::json!({"implies_out": node("g_b"), "implies_in": node("g_c")}),
+                        "g_b" => serde_json::json!({"implies_in": node("g_a"), "because_out": node("g_c")}),
+                        "g_c" => serde_json::json!({"because_in": node("g_b"), "implies_out": node("g_a")}),
+                        // g_x and g_y imply each other.
+                        "g_x" => serde_json::json!({"implies_out": node("g_y"), "implies_in": node("g_y")}),
+                        "g_y" => serde_json::json!({"implies_out": node("g_x"), "implies_in": node("g_x")}),
+                        _ => serde_json::json!({}),
+                    }
+                }
+                "getConceptMemories" if field("concept_id") == "Preference" => serde_json::json!({
+                    "concept": {"concept_id": "Preference"},
+                    "memories": [{"memory_id": "m_pref"}],
+                }),
+                _ => serde_json::json!({}),
+            })
+        })
+        .await;
+        (stub, stored)
+    }
+
+    fn stub_manager(stub: &StubServer, llm: Arc<dyn LlmProvider>) -> ToolingManager {
+        ToolingManager::new(stub.client(), Arc::new(ollama_embedder(stub.url())), llm)
+    }
+
+    #[tokio::test]
+    async fn test_add_memory_replays_result_for_repeated_idempotency_key() {
+        let (stub, _) = spawn_ingest_stub().await;
+        let llm = counting_llm();
+        let manager = stub_manager(&stub, llm.clone());
+        let calls = || llm.calls();
+
+        let first = manager
+            .add_memory("Alice moved to Lisbon", "alice", None, None, None, Some("ingest-42"), None)
+            .await
+            .unwrap();
+        assert_eq!(first.added.len(), 1);
+        assert_eq!(calls(), 1);
+
+        let retry = manager
+            .add_memory("Alice moved to Lisbon", "alice", None, None, None, Some("ingest-42"), None)
+            .await
+            .unwrap();
+        assert_eq!(retry.added, first.added);
+        assert_eq!(retry.metadata.get("idempotent_replay"), Some(&serde_json::Value::Bool(true)));
+        assert_eq!(calls(), 1);
+
+        let other_user = manager
+            .add_memory("Bob moved to Lisbon", "bob", None, None, None, Some("ingest-42"), None)
+            .await
+            .unwrap();
+        assert_ne!(other_user.added, first.added);
+        assert_eq!(calls(), 2);
+    }
+
+    /// Answers every prompt with a NOOP decision; extraction then falls back to the raw message.
+    fn noop_llm() -> Arc<StubLlm> {
+        Arc::new(StubLlm::replying(r#"{"operation": "NOOP", "confidence": 95, "reasoning": "already known"}"#))
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_replay_covers_every_outcome_and_concurrent_retries() {
+        let (stub, _) = spawn_ingest_stub().await;
+        let llm = noop_llm();
+        let manager = stub_manager(&stub, llm.clone());
+        let calls = || llm.calls();
+        let add = |key| manager.add_memory("Alice moved to Lisbon", "alice", None, None, None, key, None);
+        let outcome = |result: &AddMemoryResult| {
+            let mut result = serde_json::to_value(result).unwrap();
+            result["metadata"].as_object_mut().unwrap().remove("idempotent_replay");
+            result
+        };
+
+        add(None).await.unwrap();
+        // The same message again meets its earlier copy and is skipped, adding nothing.
+        let (first, concurrent) = tokio::join!(add(Some("ingest-7")), add(Some("ingest-7")));
+        let (first, concurrent) = (first.unwrap(), concurrent.unwrap());
+        assert_eq!((first.added.len(), first.skipped), (0, 1));
+        let calls_after_first = calls();
+        assert_eq!(outcome(&concurrent), outcome(&first));
+        assert_eq!(stub.count("addIdempotencyRecord"), 1);
+
+        let retry = add(Some("ingest-7")).await.unwrap();
+        assert_eq!(outcome(&retry), outcome(&first));
+        assert_eq!(retry.metadata.get("idempotent_replay"), Some(&serde_json::Value::Bool(true)));
+        assert_eq!(calls(), calls_after_first);
+    }
+
+    #[tokio::test]
+    async fn test_memories_for_entity_orders_links_and_disambiguates_by_type() {
+        let (stub, _) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+        let ids = |results: &[SearchMemoryResult]| results.iter().map(|r| r.memory_id.clone()).collect::<Vec<_>>();
+
+        let person = manager.memories_for_entity("Alice", Some("person"), 10).await.unwrap();
+        assert_eq!(ids(&person), ["m_moved", "m_passing"]);
+        assert_eq!(person[0].metadata["link_confidence"], serde_json::json!(90));
+        assert!((person[1].score - 0.3).abs() < 1e-9);
+
+        let any = manager.memories_for_entity("Alice", None, 10).await.unwrap();
+        assert_eq!(ids(&any), ["m_moved", "m_company", "m_passing"]);
+        assert_eq!(ids(&manager.memories_for_entity("Alice", None, 1).await.unwrap()), ["m_moved"]);
+
+        assert!(manager.memories_for_entity("Nobody", None, 10).await.unwrap().is_empty());
+        assert!(manager.memories_for_entity("Alice", Some("location"), 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_archive_hides_memory_from_search_until_restored() {
+        let (stub, stored) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+        for memory_id in ["m1", "m2"] {
+            stored.lock().insert(memory_id.to_string(), "alice".to_string());
+        }
+        let search = |include_archived| manager.search_memory("tea", "alice", Some(5), "vector", None, None, include_archived);
+        let ids = |results: Vec<SearchMemoryResult>| {
+            let mut ids: Vec<String> = results.into_iter().map(|r| r.memory_id).collect();
+            ids.sort();
+            ids
+        };
+
+        assert!(manager.archive_memory("m1").await.unwrap());
+        assert!(!manager.archive_memory("missing").await.unwrap());
+        assert_eq!(ids(search(false).await.unwrap()), ["m2"]);
+        assert_eq!(ids(manager.list_archived("alice").await.unwrap()), ["m1"]);
+        assert_eq!(ids(search(true).await.unwrap()), ["m1", "m2"]);
+
+        assert!(manager.restore_memory("m1").await.unwrap());
+        assert!(manager.list_archived("alice").await.unwrap().is_empty());
+        assert_eq!(ids(search(false).await.unwrap()), ["m1", "m2"]);
+    }
+
+    #[tokio::test]
+    async fn test_add_memory_invalidates_cached_search() {
+        let (stub, _) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+        let search = || manager.search_memory("tea", "alice", Some(5), "vector", None, None, false);
+
+        assert!(search().await.unwrap().is_empty());
+        let added = manager.add_memory("Alice drinks green tea", "alice", None, None, None, None, None).await.unwrap();
+        assert_eq!(added.added.len(), 1);
+
+        let ids: Vec<String> = search().await.unwrap().into_iter().map(|r| r.memory_id).collect();
+        assert_eq!(ids, added.added);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_contradiction_invalidates_cached_search() {
+        let (stub, stored) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+        for memory_id in ["c_old", "c_new"] {
+            stored.lock().insert(memory_id.to_string(), "alice".to_string());
+        }
+        let search = || manager.search_memory("tea", "alice", Some(5), "vector", None, None, false);
+        assert_eq!(search().await.unwrap().len(), 2);
+
+        let archived = manager
+            .resolve_contradiction("alice", "c_new", "c_old", "moved on", ContradictionStrategy::KeepNewer)
+            .await
+            .unwrap();
+        assert_eq!(archived, ["c_old"]);
+
+        let ids: Vec<String> = search().await.unwrap().into_iter().map(|r| r.memory_id).collect();
+        assert_eq!(ids, ["c_new"]);
+    }
+
+    #[tokio::test]
+    async fn test_memory_graph_visits_each_node_and_edge_once() {
+        let (stub, _) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+        let edges = |graph: &MemoryGraph| {
+            let mut edges: Vec<String> =
+                graph.edges.iter().map(|e| format!("{}-{}->{}", e["source"], e["type"], e["target"]).replace('"', "")).collect();
+            edges.sort();
+            edges
+        };
+
+        let full = manager.get_memory_graph("graph_user", None, 3, None).await.unwrap();
+        assert_eq!(full.nodes.len(), 3);
+        assert_eq!(edges(&full), ["g_a-IMPLIES->g_b", "g_b-BECAUSE->g_c", "g_c-IMPLIES->g_a"]);
+        assert_eq!(full.edge_type_counts["IMPLIES"], 2);
+
+        let one_seed = manager.get_memory_graph("graph_user", None, 1, Some(1)).await.unwrap();
+        assert_eq!(one_seed.nodes.len(), 1);
+        assert_eq!(edges(&one_seed), ["g_a-IMPLIES->g_b", "g_c-IMPLIES->g_a"]);
+
+        let reciprocal = manager.get_memory_graph("graph_user", Some("g_x"), 2, None).await.unwrap();
+        assert_eq!(reciprocal.nodes.len(), 2);
+        assert_eq!(edges(&reciprocal), ["g_x-IMPLIES->g_y"]);
+        assert_eq!(reciprocal.edge_type_counts["IMPLIES"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_stats_uses_grouped_counts_then_scans() {
+        let (stub, _) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+
+        let grouped = manager.memory_stats("stats_user").await.unwrap();
+        assert_eq!(grouped.total, 5);
+        assert_eq!(grouped.by_type, HashMap::from([("fact".to_string(), 3), ("preference".to_string(), 2)]));
+        assert_eq!(grouped.oldest_created_at.as_deref(), Some("2024-01-01T00:00:00Z"));
+        assert_eq!(grouped.newest_created_at.as_deref(), Some("2024-06-01T00:00:00Z"));
+
+        let scanned = manager.memory_stats("scan_user").await.unwrap();
+        assert_eq!(scanned.total, 3);
+        assert_eq!(scanned.by_type, HashMap::from([("fact".to_string(), 2), ("goal".to_string(), 1)]));
+        assert_eq!(scanned.oldest_created_at.as_deref(), Some("2024-02-01T00:00:00Z"));
+        assert_eq!(scanned.newest_created_at.as_deref(), Some("2024-05-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_consolidation_clusters_never_join_contradictions() {
+        let (a, b, c, d) = ([1.0f32, 0.0, 0.0], [0.99f32, 0.1, 0.0], [0.98f32, 0.15, 0.0], [0.0f32, 1.0, 0.0]);
+        let vectors: Vec<(&str, &[f32])> = vec![("a", &a), ("b", &b), ("c", &c), ("d", &d)];
+
+        assert_eq!(consolidation_clusters(&vectors, &HashSet::new(), 0.95), vec![vec![0, 1, 2]]);
+        let contradicting = HashSet::from([contradiction_pair_key("c", "a")]);
+        assert_eq!(consolidation_clusters(&vectors, &contradicting, 0.95), vec![vec![1, 2]]);
+        assert!(consolidation_clusters(&vectors, &HashSet::new(), 0.9999).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_consolidate_merges_cluster_keeping_highest_importance() {
+        let (stub, stored) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, Arc::new(StubLlm::replying("  \"Likes and enjoys tea\"\n")));
+
+        let plan = manager.consolidate("dup_user", 0.95, true).await.unwrap();
+        assert_eq!(plan.memories_scanned, 4);
+        assert_eq!(plan.merges.len(), 1);
+        let merge = &plan.merges[0];
+        assert_eq!(merge.source_ids, ["d_tea", "d_tea2"]);
+        assert_eq!((merge.content.as_str(), merge.memory_type.as_str(), merge.importance), ("Likes and enjoys tea", "fact", 90));
+        assert_eq!(merge.memory_id, None);
+        assert!(stored.lock().is_empty());
+
+        let applied = manager.consolidate("dup_user", 0.95, false).await.unwrap();
+        let memory_id = applied.merges[0].memory_id.clone().unwrap();
+        assert_eq!(stored.lock().get(&memory_id).map(String::as_str), Some("dup_user"));
+        let history = manager.get_memory_history("d_tea").await.unwrap();
+        assert_eq!(history.iter().map(|e| e.op).collect::<Vec<_>>(), [MemoryEventOp::Superseded]);
+    }
+
+    #[tokio::test]
+    async fn test_concept_filter_uses_graph_links_and_subconcepts() {
+        use crate::toolkit::mind_toolbox::ontology::{Concept, ConceptType};
+
+        let (stub, _) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+        let concept = |id: &str, parent: Option<&str>| {
+            let concept = Concept::new(id.into(), id.into(), ConceptType::Abstract, String::new(), parent.map(Into::into), 3);
+            (id.to_string(), concept)
+        };
+        manager.ontology_manager.write().load_concepts(
+            [concept("Attribute", None), concept("Preference", Some("Attribute")), concept("Taste", Some("Preference"))]
+                .into_iter()
+                .collect(),
+            Vec::new(),
+        );
+
+        let (types, ids) = manager.accepted_concepts(Some("preference"), true);
+        assert_eq!(types, ["preference", "taste"]);
+        assert_eq!(ids, ["Preference", "Taste"]);
+        assert_eq!(manager.accepted_concepts(Some("preference"), false).1, ["Preference"]);
+
+        let linked = manager.concept_linked_memories(&ids).await.unwrap();
+        assert_eq!(linked, HashSet::from(["m_pref".to_string()]));
+        assert!(manager.concept_linked_memories(&["Taste".to_string()]).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plan_add_memory_writes_nothing_until_executed() {
+        let (stub, stored) = spawn_ingest_stub().await;
+        let llm = counting_llm();
+        let manager = stub_manager(&stub, llm.clone());
+
+        let plan = manager.plan_add_memory("Alice moved to Lisbon", "alice", Some("travel")).await.unwrap();
+        assert_eq!(plan.memories.len(), 1);
+        assert_eq!(plan.memories[0].decision.operation, MemoryOperation::Add);
+        assert_eq!(plan.context_tags, "travel");
+        assert!(stored.lock().is_empty());
+
+        let replayed: AddMemoryPlan = serde_json::from_value(serde_json::to_value(&plan).unwrap()).unwrap();
+        let result = manager.execute_add_memory_plan(&replayed).await.unwrap();
+        assert_eq!(result.added.len(), 1);
+        assert_eq!(stored.lock().len(), 1);
+        assert_eq!(llm.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_supersede_records_history_for_both_memories() {
+        let (stub, stored) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+        stored.lock().insert("mem_old".to_string(), "alice".to_string());
+
+        let mut plan = manager.plan_add_memory("Alice moved to Porto", "alice", None).await.unwrap();
+        plan.memories[0].decision = MemoryDecision::supersede("mem_old", 85, "Moved again");
+        let result = manager.execute_add_memory_plan(&plan).await.unwrap();
+        let new_id = &result.added[0];
+
+        let added = manager.get_memory_history(new_id).await.unwrap();
+        let superseded = manager.get_memory_history("mem_old").await.unwrap();
+        assert_eq!(added.iter().map(|e| e.op).collect::<Vec<_>>(), [MemoryEventOp::Added]);
+        assert_eq!(superseded.iter().map(|e| e.op).collect::<Vec<_>>(), [MemoryEventOp::Superseded]);
+        assert_eq!((superseded[0].confidence, superseded[0].reason.as_str()), (85, "Moved again"));
+        assert_eq!(superseded[0].source_message_preview, "Alice moved to Porto");
+    }
+
+    #[tokio::test]
+    async fn test_delete_decision_reports_archived_target() {
+        let (stub, stored) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+        stored.lock().insert("mem_old".to_string(), "alice".to_string());
+
+        let mut plan = manager.plan_add_memory("Alice moved to Porto", "alice", None).await.unwrap();
+        plan.memories[0].decision = MemoryDecision {
+            operation: MemoryOperation::Delete,
+            target_memory_id: Some("mem_old".to_string()),
+            ..MemoryDecision::add(90, "Outdated")
+        };
+        let result = crate::core::helixir_client::AddMemoryResult::from(manager.execute_add_memory_plan(&plan).await.unwrap());
+
+        assert_eq!((result.memories_added, result.memories_deleted), (1, 1));
+        assert_eq!(result.deleted_ids, ["mem_old"]);
+    }
+
+    #[test]
+    fn test_graph_export_is_stable_weighted_and_escaped() {
+        let mut graph = MemoryGraph::default();
+        graph.nodes.push(serde_json::json!({"id": "m2", "content": "Uses <Rust> & \"Go\"", "type": "skill"}));
+        graph.nodes.push(serde_json::json!({"id": "m1", "content": "Likes tea", "type": "preference"}));
+        graph.add_edge("m1", "m2", "IMPLIES", 0.75);
+        graph.add_edge("m1", "m2", "IMPLIES", 0.75);
+        graph.add_edge("m2", "m3", "CONTRADICTS", 1.0);
+        assert_eq!(graph.edge_type_counts.get("IMPLIES"), Some(&1));
+
+        let graphml = graph.export(GraphFormat::GraphMl);
+        assert!(graphml.contains("<data key=\"content\">Uses &lt;Rust&gt; &amp; &quot;Go&quot;</data>"));
+        assert!(graphml.contains("<edge id=\"e0\" source=\"m1\" target=\"m2\">"));
+        assert!(graphml.contains("<data key=\"weight\">0.75</data>"));
+        assert!(graphml.find("<node id=\"m1\">") < graphml.find("<node id=\"m2\">"));
+        assert!(graphml.contains("<node id=\"m3\">"));
+
+        let json: serde_json::Value = serde_json::from_str(&graph.export(GraphFormat::JsonGraph)).unwrap();
+        assert_eq!(json["graph"]["nodes"]["m1"]["label"], "Likes tea");
+        assert_eq!(json["graph"]["edges"][0]["metadata"]["weight"], 0.75);
+        assert_eq!(json["graph"]["edges"].as_array().unwrap().len(), 2);
+        assert_eq!(graph.export(GraphFormat::JsonGraph), graph.clone().export(GraphFormat::JsonGraph));
+    }
+
+    #[test]
+    fn test_graph_edge_weight_uses_stored_strength() {
+        let edges: GraphEdges = serde_json::from_value(serde_json::json!({
+            "implies_out": [{"id": "n1", "memory_id": "m1"}],
+            "implies_out_edges": [{"from_node": "n0", "to_node": "n1", "probability": 80}],
+            "because_in_edges": [{"from_node": "n2", "to_node": "n0", "strength": 35}],
+            "relation_out_edges": [{"from_node": "n0", "to_node": "n4", "relation_type": "supports"}],
+        }))
+        .unwrap();
+        let (decay, now) = (StrengthDecay::default(), chrono::Utc::now());
+
+        assert_eq!(graph_edge_weight(find_graph_edge(&edges.implies_out, "n1", false), "IMPLIES", decay, now), 0.8);
+        assert_eq!(graph_edge_weight(find_graph_edge(&edges.because_in, "n2", true), "BECAUSE", decay, now), 0.35);
+        assert_eq!(graph_edge_weight(find_graph_edge(&edges.relation_out, "n4", false), "MEMORY_RELATION", decay, now), 0.7);
+        assert_eq!(graph_edge_weight(None, "CONTRADICTS", decay, now), 0.4);
+        assert!(find_graph_edge(&edges.implies_out, "n2", false).is_none());
+    }
+
+    #[test]
+    fn test_above_similarity_floor_drops_weak_candidates() {
+        let candidate = |id: &str, score: f64| SimilarMemory {
+            id: id.to_string(),
+            content: format!("content of {}", id),
+            score,
+            created_at: None,
+        };
+        let similar = vec![candidate("close", 0.82), candidate("edge", 0.6), candidate("far", 0.31)];
+
+        let ids: Vec<String> = above_similarity_floor(&similar, 0.6).into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["close", "edge"]);
+        assert!(above_similarity_floor(&similar, 0.9).is_empty());
+    }
+
+    #[test]
+    fn test_merge_staged_candidates_surfaces_in_batch_duplicates() {
+        let mut similar = vec![SimilarMemory {
+            id: "db-1".to_string(),
+            content: "older memory".to_string(),
+            score: 0.4,
+            created_at: None,
+        }];
+        let staged = vec![
+            staged("alice", "batch-1", vec![1.0, 0.0, 0.0]),
+            staged("bob", "batch-2", vec![1.0, 0.0, 0.0]),
+            staged("alice", "batch-3", vec![0.0, 1.0, 0.0]),
+        ];
+
+        merge_staged_candidates(&mut similar, &staged, "alice", &[0.99, 0.05, 0.0]);
+
+        assert_eq!(similar[0].id, "batch-1");
+        assert!(similar[0].score > 0.99);
+        assert!(similar.iter().all(|m| m.id != "batch-2"));
+        assert_eq!(similar.len(), 3);
+    }
+
+    #[test]
+    fn test_merge_staged_candidates_keeps_best_score_and_limit() {
+        let mut similar = vec![SimilarMemory {
+            id: "batch-0".to_string(),
+            content: "content of batch-0".to_string(),
+            score: 0.1,
+            created_at: None,
+        }];
+        let staged: Vec<StagedMemory> = (0..8)
+            .map(|i| staged("alice", &format!("batch-{}", i), vec![1.0, i as f32 * 0.1]))
+            .collect();
+
+        merge_staged_candidates(&mut similar, &staged, "alice", &[1.0, 0.0]);
+
+        assert_eq!(similar.len(), SIMILAR_MEMORY_LIMIT);
+        assert_eq!(similar[0].id, "batch-0");
+        assert!(similar[0].score > 0.99);
+        assert!(similar.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[tokio::test]
+    async fn test_relate_rejects_unknown_relation_type() {
+        assert_eq!(parse_relation_type(" supports ").unwrap(), ReasoningType::Supports);
+
+        let (stub, _) = spawn_ingest_stub().await;
+        let manager = stub_manager(&stub, counting_llm());
+
+        let err = manager.relate("m1", "m2", "CAUSES", Some(70)).await.unwrap_err();
+        assert!(matches!(err, ToolingError::Reasoning(ReasoningError::Invalid(_))));
+        assert!(err.to_string().contains("unknown relation type 'CAUSES'"));
+        assert!(matches!(manager.unrelate("m1", "m2", "").await, Err(ToolingError::Reasoning(ReasoningError::Invalid(_)))));
+    }
+
+    #[test]
+    fn test_delete_filter_requires_criteria() {
+        assert!(!DeleteFilter::default().has_criteria());
+        assert!(DeleteFilter { min_certainty: Some(10), ..Default::default() }.has_criteria());
+    }
+
+    #[test]
+    fn test_delete_filter_matches_type_and_age() {
+        let now = chrono::Utc::now();
+        let filter = DeleteFilter {
+            memory_types: Some(vec!["event".to_string()]),
+            older_than: Some(chrono::Duration::days(90)),
+            ..Default::default()
+        };
+
+        let old = (now - chrono::Duration::days(120)).to_rfc3339();
+        let recent = (now - chrono::Duration::days(10)).to_rfc3339();
+
+        assert!(filter.matches("event", 50, &old, now));
+        assert!(filter.matches("EVENT", 50, &old, now));
+        assert!(!filter.matches("event", 50, &recent, now));
+        assert!(!filter.matches("fact", 50, &old, now));
+        assert!(!filter.matches("event", 50, "", now));
+    }
+
+    #[test]
+    fn test_logical_connections_dangling_ids() {
+        let conns: LogicalConnections = serde_json::from_value(serde_json::json!({
+            "implies_out": [{"memory_id": "mem_live", "content": "a"}],
+            "because_in": [{"memory_id": "mem_dead", "content": "b", "is_deleted": 1}],
+            "relation_out": [{"memory_id": "mem_other", "is_deleted": 0}],
+        })).unwrap();
+
+        assert_eq!(conns.edge_count(), 3);
+        assert_eq!(conns.dangling_ids(), vec!["mem_dead".to_string()]);
+    }
+
+    #[test]
+    fn test_delete_filter_min_certainty() {
+        let now = chrono::Utc::now();
+        let filter = DeleteFilter { min_certainty: Some(70), ..Default::default() };
+
+        assert!(filter.matches("fact", 70, "", now));
+        assert!(!filter.matches("fact", 69, "", now));
+    }
+
+    #[test]
+    fn test_memory_graph_edge_type_counts() {
+        let mut graph = MemoryGraph::default();
+        graph.add_edge("a", "b", "IMPLIES", 1.0);
+        graph.add_edge("b", "c", "IMPLIES", 1.0);
+        graph.add_edge("c", "a", "BECAUSE", 1.0);
+        graph.add_edge("b", "a", "IMPLIES", 1.0);
+        graph.add_edge("a", "c", "BECAUSE", 1.0);
+
+        assert_eq!(graph.edges.len(), 3);
+        assert_eq!(graph.edge_type_counts.get("IMPLIES"), Some(&2));
+        assert_eq!(graph.edge_type_counts.get("BECAUSE"), Some(&1));
+        assert_eq!(graph.edge_type_counts.get("CONTRADICTS"), None);
     }
 }